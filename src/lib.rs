@@ -8,12 +8,31 @@
 mod pdf;
 pub use pdf::Version;
 pub use pdf::MediaBox;
+pub use pdf::Rectangle;
+pub use pdf::Date;
 pub use pdf::doc::Doc;
-pub use pdf::page::Page;
+pub use pdf::loaded_doc::LoadedDoc;
+pub use pdf::error::Error;
+pub use pdf::object;
+pub use pdf::operation;
+pub use pdf::optimize;
+pub use pdf::page::{Page, Rotation};
+pub use pdf::paper_size::{Orientation, PaperSize};
 pub use pdf::font::Font;
 pub use pdf::Pos;
+pub use pdf::annotation;
 pub use pdf::colour;
+pub use pdf::form;
 pub use pdf::function;
+pub use pdf::ext_g_state;
+pub use pdf::impose;
+pub use pdf::marks;
+pub use pdf::pattern;
+pub use pdf::redact;
+pub use pdf::shading;
+pub use pdf::split;
+pub use pdf::text;
+pub use pdf::xobject;
 
 #[cfg(test)]
 mod tests {
@@ -47,7 +66,6 @@ mod tests {
         page.resources().add_font("F0", font);
 
         // CMYKOGV color space
-        let names: Vec<&str> = vec!["Cyan", "Magenta", "Yellow", "Black"];
         let in_domain = [(0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (0.0, 1.0)];
         let out_range = [(0.0, 1.0), (0.0, 1.0), (0.0, 1.0), (0.0, 1.0)];
         let sample_sizes = [1, 1, 1, 1, 1, 1, 1];
@@ -57,41 +75,43 @@ mod tests {
         let process_component_names: Vec<&str> = vec!["Cyan", "Magenta", "Yellow", "Black"];
         let orange = colour::space::Separation::new(
             "Orange",
-            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905),
+            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905).unwrap(),
             function::Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0));
         let green = colour::space::Separation::new(
             "Green",
-            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905),
+            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905).unwrap(),
             function::Type2::new([100.0, 0.0, 0.0], [60.0, -75.0, 0.0], 1.0));
         let violet = colour::space::Separation::new(
             "Violet",
-            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905),
+            colour::space::Lab::new_with_white(0.964203, 1.0, 0.824905).unwrap(),
             function::Type2::new([100.0, 0.0, 0.0], [22.0, 47.0, -56.0], 1.0));
         let mut colorants: HashMap<&str, colour::space::Separation> = HashMap::new();
         colorants.insert("Orange", orange);
         colorants.insert("Green", green);
         colorants.insert("Violet", violet);
-        let nchannel = colour::space::NChannel::new(colorants, process_component_names);
+        let nchannel = colour::space::DeviceNAttributes::nchannel(colorants, colour::space::DeviceCMYK {}, process_component_names);
         let names = vec!["Cyan", "Magenta", "Yellow", "Black", "Orange", "Green", "Violet"];
-        let devicen = colour::space::DeviceN::new(names, type0, nchannel);
-        page.resources().add_color_space("CS0", devicen);
+        let mut devicen = colour::space::DeviceN::new(names, colour::space::DeviceCMYK {}, type0);
+        devicen.set_attributes(nchannel);
+        let devicen = colour::space::ColorSpace::from(devicen);
+        page.resources().add_color_space("CS0", devicen.clone());
 
         // Contents
         page.contents().fill_text("F0", 32, Pos {x: 0, y: 760}, "Hello");
-        page.contents().set_fill_color_space("CS0");
-        page.contents().set_fill_color_space_color([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        page.contents().set_fill_color_space("CS0", &devicen);
+        page.contents().set_fill_color_space_color([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
         page.contents().fill_rect(0, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        page.contents().set_fill_color_space_color([0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
         page.contents().fill_rect(50, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        page.contents().set_fill_color_space_color([0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
         page.contents().fill_rect(100, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]).unwrap();
         page.contents().fill_rect(150, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]).unwrap();
         page.contents().fill_rect(200, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]).unwrap();
         page.contents().fill_rect(250, 700, 50, 50);
-        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        page.contents().set_fill_color_space_color([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]).unwrap();
         page.contents().fill_rect(300, 700, 50, 50);
 
         doc.push_page(page);
@@ -101,4 +121,120 @@ mod tests {
         let path = dir.join("cmykogv.pdf");
         doc.write_to_file(path.to_str().unwrap().to_string());
     }
+
+    #[test]
+    fn annotations() {
+        use annotation::*;
+        use colour::DeviceColour;
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::Letter);
+        let font = Font::new("Helvetica");
+        page.resources().add_font("F0", font);
+        page.contents().fill_text("F0", 12, Pos {x: 72, y: 700}, "Please review this line.");
+
+        let date = Date::new(2025, 1, 1, 9, 0, 0);
+        let mut annots = vec![
+            Annotation::new(Rectangle::new(72.0, 740.0, 92.0, 760.0), Text::new(TextIcon::Comment)),
+            Annotation::new(Rectangle::new(72.0, 640.0, 272.0, 680.0), FreeText::new(12.0)),
+            Annotation::new(
+                Rectangle::new(72.0, 697.0, 200.0, 712.0),
+                TextMarkup::highlight(vec![Quad::from_rect(&Rectangle::new(72.0, 697.0, 200.0, 712.0))])),
+            Annotation::new(Rectangle::new(72.0, 500.0, 172.0, 560.0), Square::new()),
+            Annotation::new(Rectangle::new(200.0, 500.0, 300.0, 560.0), Circle::new()),
+            Annotation::new(Rectangle::new(320.0, 500.0, 420.0, 560.0), Line::new((325.0, 505.0), (415.0, 555.0))),
+            Annotation::new(
+                Rectangle::new(72.0, 380.0, 172.0, 460.0),
+                Polygon::new(vec![(72.0, 380.0), (172.0, 380.0), (122.0, 460.0)])),
+            Annotation::new(
+                Rectangle::new(200.0, 380.0, 300.0, 460.0),
+                Ink::new(vec![vec![(200.0, 400.0), (250.0, 450.0), (300.0, 400.0)]])),
+            Annotation::new(Rectangle::new(320.0, 400.0, 520.0, 450.0), Stamp::new(StampName::Approved)),
+        ];
+        for a in &mut annots {
+            a.set_author("Reviewer");
+            a.set_contents("Looks good");
+            a.set_creation_date(date);
+        }
+        annots[3].set_opacity(0.5);
+        annots[3].set_colour(DeviceColour::Cmyk(0.0, 1.0, 1.0, 0.0));
+        for a in annots {
+            page.add_annotation(a);
+        }
+
+        doc.push_page(page);
+
+        let exe_path = std::env::current_exe().unwrap();
+        let dir = exe_path.parent().unwrap();
+        let path = dir.join("annotations.pdf");
+        doc.write_to_file(path.to_str().unwrap().to_string());
+    }
+
+    #[test]
+    fn acro_form() {
+        use form::*;
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        page.contents().fill_text("F0", 12, Pos {x: 72, y: 760}, "Name");
+        page.contents().fill_text("F0", 12, Pos {x: 72, y: 720}, "Postcode");
+        doc.push_page(page);
+
+        let mut name = TextField::new();
+        name.set_value("Taro Yamada");
+        let mut name = Field::new("name", name);
+        name.set_flags(FieldFlags::REQUIRED);
+        name.add_widget(Widget::new(0, Rectangle::new(160.0, 755.0, 400.0, 775.0)));
+
+        let mut postcode = TextField::new();
+        postcode.set_max_len(7);
+        postcode.set_comb(true);
+        postcode.set_value("1000001");
+        let mut postcode = Field::new("postcode", postcode);
+        postcode.add_widget(Widget::new(0, Rectangle::new(160.0, 715.0, 300.0, 735.0)));
+
+        let mut agree = CheckBox::new();
+        agree.set_checked(true);
+        let mut agree = Field::new("agree", agree);
+        agree.add_widget(Widget::new(0, Rectangle::new(72.0, 680.0, 86.0, 694.0)));
+
+        let mut payment = RadioGroup::new(vec!["Cash", "Card"]);
+        payment.select("Card");
+        let mut payment = Field::new("payment", payment);
+        payment.add_widget(Widget::new(0, Rectangle::new(72.0, 650.0, 86.0, 664.0)));
+        payment.add_widget(Widget::new(0, Rectangle::new(100.0, 650.0, 114.0, 664.0)));
+
+        let mut colour = Choice::combo_box(vec![ChoiceOption::new("Red"), ChoiceOption::new("Green")]);
+        colour.set_value("Green");
+        let mut colour = Field::new("colour", colour);
+        colour.add_widget(Widget::new(0, Rectangle::new(72.0, 600.0, 200.0, 620.0)));
+
+        let mut sizes = Choice::list_box(vec![ChoiceOption::new("S"), ChoiceOption::new("M"), ChoiceOption::new("L")]);
+        sizes.set_multi_select(true);
+        sizes.set_values(&["S", "L"]);
+        let mut sizes = Field::new("sizes", sizes);
+        sizes.add_widget(Widget::new(0, Rectangle::new(220.0, 560.0, 300.0, 620.0)));
+
+        let mut submit = Field::new("submit", PushButton::new("Submit"));
+        submit.add_widget(Widget::new(0, Rectangle::new(72.0, 520.0, 150.0, 545.0)));
+
+        let mut applicant = Field::new_group("applicant");
+        applicant.add_child(name);
+        applicant.add_child(postcode);
+
+        let form = doc.acro_form();
+        form.add_field(applicant);
+        form.add_field(agree);
+        form.add_field(payment);
+        form.add_field(colour);
+        form.add_field(sizes);
+        form.add_field(submit);
+        assert!(form.field("applicant.postcode").is_some());
+
+        let exe_path = std::env::current_exe().unwrap();
+        let dir = exe_path.parent().unwrap();
+        let path = dir.join("acro_form.pdf");
+        doc.write_to_file(path.to_str().unwrap().to_string());
+    }
 }
@@ -0,0 +1,129 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::Style;
+use super::super::colour::DeviceColour;
+use super::super::font::{approx_text_width, Font};
+use super::super::utils::to_text_string;
+use super::super::xobject::Form;
+
+/// Text justification (`/Q`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Justification {
+    Left = 0,
+    Centre = 1,
+    Right = 2,
+}
+
+/// PDF32000-1:2008 12.5.6.6
+///
+/// Text displayed directly on the page. The text is the `/Contents` of the
+/// annotation and is written with Helvetica.
+///
+/// ```text
+/// << /Subtype /FreeText
+///    /DA (/Helv 12 Tf 0 g)
+///    /Q 0
+/// >>
+/// ```
+pub struct FreeText {
+    font_size: f64,
+    text_colour: DeviceColour,
+    justification: Justification,
+}
+
+impl FreeText {
+    pub const FONT_NAME: &'static str = "Helv";
+
+    pub fn new(font_size: f64) -> Self {
+        Self {
+            font_size,
+            text_colour: DeviceColour::black(),
+            justification: Justification::Left,
+        }
+    }
+
+    pub fn set_text_colour(&mut self, colour: DeviceColour) {
+        self.text_colour = colour;
+    }
+
+    pub fn set_justification(&mut self, justification: Justification) {
+        self.justification = justification;
+    }
+
+    fn default_appearance(&self) -> String {
+        format!("/{} {} Tf {}", Self::FONT_NAME, self.font_size, self.text_colour.to_fill_operator())
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("DA", to_text_string(&self.default_appearance())),
+            ("Q", (self.justification as u8).to_string()),
+        ]
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        form.resources().add_font(Self::FONT_NAME, Font::new("Helvetica"));
+
+        let rect = style.rect;
+        let padding = style.border_width + 2.0;
+        let leading = self.font_size * 1.2;
+        let c = form.contents();
+
+        if style.border_width > 0.0 {
+            let w = style.border_width;
+            c.set_line_width(w);
+            c.set_stroke_device_color(&style.colour);
+            c.rect(rect.llx + w / 2.0, rect.lly + w / 2.0, rect.width() - w, rect.height() - w);
+            c.stroke();
+        }
+
+        let text = style.contents.unwrap_or("");
+        let mut y = rect.ury - padding - self.font_size;
+        c.begin_text();
+        c.set_font(Self::FONT_NAME, self.font_size);
+        c.set_fill_device_color(&self.text_colour);
+        let mut prev_x = 0.0;
+        let mut prev_y = 0.0;
+        for line in text.lines() {
+            if y < rect.lly {
+                break;
+            }
+            let width = approx_text_width(line, self.font_size);
+            let x = match self.justification {
+                Justification::Left => rect.llx + padding,
+                Justification::Centre => rect.llx + (rect.width() - width) / 2.0,
+                Justification::Right => rect.urx - padding - width,
+            };
+            c.move_text(x - prev_x, y - prev_y);
+            c.show_text(line);
+            prev_x = x;
+            prev_y = y;
+            y -= leading;
+        }
+        c.end_text();
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let mut t = FreeText::new(12.0);
+        t.set_justification(Justification::Right);
+        assert_eq!(t.entries(), vec![
+            ("DA", String::from("(/Helv 12 Tf 0 g)")),
+            ("Q", String::from("2")),
+        ]);
+    }
+}
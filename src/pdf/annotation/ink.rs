@@ -0,0 +1,67 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::{points_to_pdf_string, Style};
+use super::super::xobject::Form;
+
+/// PDF32000-1:2008 12.5.6.13
+///
+/// Freehand strokes, each given as a list of points.
+///
+/// ```text
+/// << /Subtype /Ink
+///    /InkList [[10 10 20 15 30 10] [10 20 30 20]]
+/// >>
+/// ```
+pub struct Ink {
+    strokes: Vec<Vec<(f64, f64)>>,
+}
+
+impl Ink {
+    pub fn new(strokes: Vec<Vec<(f64, f64)>>) -> Self {
+        Self { strokes }
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let list = self.strokes.iter()
+            .map(|s| points_to_pdf_string(s))
+            .collect::<Vec<String>>()
+            .join(" ");
+        vec![("InkList", format!("[{list}]"))]
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let c = form.contents();
+        c.set_line_width(style.border_width);
+        c.set_stroke_device_color(&style.colour);
+        for stroke in &self.strokes {
+            let Some(first) = stroke.first() else {
+                continue;
+            };
+            c.move_to(first.0, first.1);
+            for p in &stroke[1..] {
+                c.line_to(p.0, p.1);
+            }
+        }
+        c.stroke();
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let ink = Ink::new(vec![vec![(10.0, 10.0), (20.0, 15.0)], vec![(10.0, 20.0), (30.0, 20.0)]]);
+        assert_eq!(ink.entries(), vec![("InkList", String::from("[[10 10 20 15] [10 20 30 20]]"))]);
+    }
+}
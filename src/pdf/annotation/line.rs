@@ -0,0 +1,142 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::shape::interior_entries;
+use super::{points_to_pdf_string, Style};
+use super::super::colour::DeviceColour;
+use super::super::xobject::Form;
+
+/// Line ending styles (`/LE`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEnding {
+    None,
+    OpenArrow,
+    ClosedArrow,
+    Butt,
+}
+
+impl LineEnding {
+    pub fn to_pdf_string(self) -> String {
+        match self {
+            Self::None => "/None",
+            Self::OpenArrow => "/OpenArrow",
+            Self::ClosedArrow => "/ClosedArrow",
+            Self::Butt => "/Butt",
+        }.to_string()
+    }
+}
+
+/// PDF32000-1:2008 12.5.6.7
+///
+/// ```text
+/// << /Subtype /Line
+///    /L [100 100 200 150]
+///    /LE [/None /OpenArrow]
+/// >>
+/// ```
+pub struct Line {
+    start: (f64, f64),
+    end: (f64, f64),
+    endings: (LineEnding, LineEnding),
+    interior_colour: Option<DeviceColour>,
+}
+
+impl Line {
+    pub fn new(start: (f64, f64), end: (f64, f64)) -> Self {
+        Self {
+            start,
+            end,
+            endings: (LineEnding::None, LineEnding::None),
+            interior_colour: None,
+        }
+    }
+
+    pub fn set_endings(&mut self, start: LineEnding, end: LineEnding) {
+        self.endings = (start, end);
+    }
+
+    /// Sets the fill colour of closed arrow heads (`/IC`).
+    pub fn set_interior_colour(&mut self, colour: DeviceColour) {
+        self.interior_colour = Some(colour);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("L", points_to_pdf_string(&[self.start, self.end])),
+            ("LE", format!("[{} {}]", self.endings.0.to_pdf_string(), self.endings.1.to_pdf_string())),
+        ];
+        entries.append(&mut interior_entries(&self.interior_colour));
+        entries
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let w = style.border_width.max(0.5);
+        let c = form.contents();
+        c.set_line_width(w);
+        c.set_stroke_device_color(&style.colour);
+        c.set_fill_device_color(self.interior_colour.as_ref().unwrap_or(&style.colour));
+        c.move_to(self.start.0, self.start.1);
+        c.line_to(self.end.0, self.end.1);
+        c.stroke();
+
+        draw_ending(form, self.endings.0, self.start, self.end, w);
+        draw_ending(form, self.endings.1, self.end, self.start, w);
+    }
+}
+
+/// Draws the ending at `tip` of a line coming from `from`.
+fn draw_ending(form: &mut Form, ending: LineEnding, tip: (f64, f64), from: (f64, f64), width: f64) {
+    let len = ((tip.0 - from.0).powi(2) + (tip.1 - from.1).powi(2)).sqrt();
+    if len == 0.0 {
+        return;
+    }
+    let (ux, uy) = ((tip.0 - from.0) / len, (tip.1 - from.1) / len);
+    let (nx, ny) = (-uy, ux);
+    let size = width * 4.0 + 3.0;
+    let c = form.contents();
+
+    match ending {
+        LineEnding::None => {}
+        LineEnding::OpenArrow | LineEnding::ClosedArrow => {
+            let bx = tip.0 - ux * size;
+            let by = tip.1 - uy * size;
+            c.move_to(bx + nx * size / 2.0, by + ny * size / 2.0);
+            c.line_to(tip.0, tip.1);
+            c.line_to(bx - nx * size / 2.0, by - ny * size / 2.0);
+            if ending == LineEnding::ClosedArrow {
+                c.close_path();
+                c.fill_stroke();
+            } else {
+                c.stroke();
+            }
+        }
+        LineEnding::Butt => {
+            c.move_to(tip.0 + nx * size / 2.0, tip.1 + ny * size / 2.0);
+            c.line_to(tip.0 - nx * size / 2.0, tip.1 - ny * size / 2.0);
+            c.stroke();
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let mut l = Line::new((100.0, 100.0), (200.0, 150.0));
+        l.set_endings(LineEnding::None, LineEnding::OpenArrow);
+        assert_eq!(l.entries(), vec![
+            ("L", String::from("[100 100 200 150]")),
+            ("LE", String::from("[/None /OpenArrow]")),
+        ]);
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+mod free_text;
+mod ink;
+mod line;
+mod polygon;
+mod shape;
+mod stamp;
+mod text;
+mod text_markup;
+
+pub use free_text::{FreeText, Justification};
+pub use ink::Ink;
+pub use line::{Line, LineEnding};
+pub use polygon::Polygon;
+pub use shape::{Circle, Square};
+pub use stamp::{Stamp, StampName};
+pub use text::{Text, TextIcon};
+pub use text_markup::{Quad, TextMarkup, TextMarkupType};
+
+use super::base::*;
+use super::colour::DeviceColour;
+use super::utils::{dict_string, to_text_string, ToPdfString};
+use super::xobject::Form;
+
+/// Annotation subtypes with their subtype specific entries.
+pub enum AnnotationKind {
+    Text(Text),
+    FreeText(FreeText),
+    TextMarkup(TextMarkup),
+    Square(Square),
+    Circle(Circle),
+    Line(Line),
+    Polygon(Polygon),
+    Ink(Ink),
+    Stamp(Stamp),
+}
+
+macro_rules! impl_from_kind {
+    ($($t:ident),*) => {
+        $(
+            impl From<$t> for AnnotationKind {
+                fn from(v: $t) -> Self {
+                    Self::$t(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_kind!(Text, FreeText, TextMarkup, Square, Circle, Line, Polygon, Ink, Stamp);
+
+impl AnnotationKind {
+    fn subtype(&self) -> &str {
+        match self {
+            Self::Text(_) => "Text",
+            Self::FreeText(_) => "FreeText",
+            Self::TextMarkup(v) => v.subtype(),
+            Self::Square(_) => "Square",
+            Self::Circle(_) => "Circle",
+            Self::Line(_) => "Line",
+            Self::Polygon(_) => "Polygon",
+            Self::Ink(_) => "Ink",
+            Self::Stamp(_) => "Stamp",
+        }
+    }
+
+    fn default_colour(&self) -> DeviceColour {
+        match self {
+            Self::Text(_) => DeviceColour::Rgb(1.0, 0.82, 0.0),
+            Self::FreeText(_) => DeviceColour::black(),
+            Self::TextMarkup(v) => v.default_colour(),
+            _ => DeviceColour::Rgb(1.0, 0.0, 0.0),
+        }
+    }
+
+    fn flags(&self) -> u32 {
+        match self {
+            // Print, NoZoom, NoRotate
+            Self::Text(_) => 4 | 8 | 16,
+            // Print
+            _ => 4,
+        }
+    }
+
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Text(v) => v.entries(),
+            Self::FreeText(v) => v.entries(),
+            Self::TextMarkup(v) => v.entries(),
+            Self::Square(v) => v.entries(),
+            Self::Circle(v) => v.entries(),
+            Self::Line(v) => v.entries(),
+            Self::Polygon(v) => v.entries(),
+            Self::Ink(v) => v.entries(),
+            Self::Stamp(v) => v.entries(),
+        }
+    }
+
+    fn draw(&self, form: &mut Form, style: &Style) {
+        match self {
+            Self::Text(v) => v.draw(form, style),
+            Self::FreeText(v) => v.draw(form, style),
+            Self::TextMarkup(v) => v.draw(form, style),
+            Self::Square(v) => v.draw(form, style),
+            Self::Circle(v) => v.draw(form, style),
+            Self::Line(v) => v.draw(form, style),
+            Self::Polygon(v) => v.draw(form, style),
+            Self::Ink(v) => v.draw(form, style),
+            Self::Stamp(v) => v.draw(form, style),
+        }
+    }
+}
+
+/// Properties shared by all subtypes which the appearance depends on.
+pub(crate) struct Style<'a> {
+    pub rect: Rectangle,
+    pub colour: DeviceColour,
+    pub border_width: f64,
+    pub contents: Option<&'a str>,
+}
+
+/// PDF32000-1:2008 12.5
+///
+/// ```text
+/// 20 0 obj
+/// << /Type /Annot
+///    /Subtype /Square
+///    /Rect [100 100 200 150]
+///    /P 3 0 R
+///    /F 4
+///    /C [1 0 0]
+///    /CA 0.5
+///    /T (Reviewer)
+///    /Contents (Check this)
+///    /CreationDate (D:20250101120000Z)
+///    /M (D:20250101120000Z)
+///    /BS << /W 1 >>
+///    /AP << /N 21 0 R >>
+/// >>
+/// endobj
+/// ```
+///
+/// The normal appearance stream is generated from the properties when the
+/// document is written.
+pub struct Annotation {
+    id: Id,
+    page_id: Id,
+    rect: Rectangle,
+    kind: AnnotationKind,
+    author: Option<String>,
+    contents: Option<String>,
+    colour: DeviceColour,
+    opacity: f64,
+    creation_date: Option<Date>,
+    border_width: f64,
+    appearance: Option<Form>,
+}
+
+impl Annotation {
+    pub fn new(rect: Rectangle, kind: impl Into<AnnotationKind>) -> Self {
+        let kind = kind.into();
+        Self {
+            id: Id::new_0(),
+            page_id: Id::new_0(),
+            rect,
+            colour: kind.default_colour(),
+            kind,
+            author: None,
+            contents: None,
+            opacity: 1.0,
+            creation_date: None,
+            border_width: 1.0,
+            appearance: None,
+        }
+    }
+
+    pub fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    pub fn kind(&self) -> &AnnotationKind {
+        &self.kind
+    }
+
+    /// Sets the author shown as the title of the pop-up window (`/T`).
+    pub fn set_author(&mut self, author: &str) {
+        self.author = Some(author.to_string());
+    }
+
+    /// Sets the text of the annotation (`/Contents`).
+    pub fn set_contents(&mut self, contents: &str) {
+        self.contents = Some(contents.to_string());
+    }
+
+    /// Sets the colour (`/C`).
+    pub fn set_colour(&mut self, colour: DeviceColour) {
+        self.colour = colour;
+    }
+
+    /// Sets the constant opacity from 0.0 to 1.0 (`/CA`).
+    pub fn set_opacity(&mut self, opacity: f64) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn set_creation_date(&mut self, date: Date) {
+        self.creation_date = Some(date);
+    }
+
+    /// Sets the border width in points (`/BS /W`).
+    pub fn set_border_width(&mut self, width: f64) {
+        self.border_width = width.max(0.0);
+    }
+
+    pub(crate) fn set_page_id(&mut self, page_id: Id) {
+        self.page_id = page_id;
+    }
+
+    fn create_appearance(&self) -> Form {
+        let mut form = Form::new(self.rect);
+        let style = Style {
+            rect: self.rect,
+            colour: self.colour,
+            border_width: self.border_width,
+            contents: self.contents.as_deref(),
+        };
+        self.kind.draw(&mut form, &style);
+        form
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let mut entries = vec![
+            ("Type", String::from("/Annot")),
+            ("Subtype", format!("/{}", self.kind.subtype())),
+            ("Rect", self.rect.to_pdf_string()),
+            ("P", self.page_id.to_ref_string()),
+            ("F", self.kind.flags().to_string()),
+            ("C", self.colour.to_pdf_string()),
+        ];
+        if self.opacity < 1.0 {
+            entries.push(("CA", self.opacity.to_pdf_string()));
+        }
+        if let Some(author) = &self.author {
+            entries.push(("T", to_text_string(author)));
+        }
+        if let Some(contents) = &self.contents {
+            entries.push(("Contents", to_text_string(contents)));
+        }
+        if let Some(date) = &self.creation_date {
+            entries.push(("CreationDate", date.to_pdf_string()));
+            entries.push(("M", date.to_pdf_string()));
+        }
+        entries.push(("BS", format!("<< /W {} >>", self.border_width)));
+        entries.append(&mut self.kind.entries());
+        if let Some(ap) = &self.appearance {
+            entries.push(("AP", format!("<< /N {} >>", ap.id().to_ref_string())));
+        }
+
+        format!(concat!(
+            "{} obj\n",
+            "{}\n",
+            "endobj"),
+            self.id,
+            dict_string(&entries),
+        )
+    }
+}
+
+impl PdfObject for Annotation {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        let mut appearance = self.create_appearance();
+        appearance.assign_ids(id_factory);
+        self.appearance = Some(appearance);
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        if let Some(ap) = &self.appearance {
+            list.append(&mut ap.get_objects());
+        }
+        list
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Formats points as a flat number array, e.g. `[x1 y1 x2 y2]`.
+pub(crate) fn points_to_pdf_string(points: &[(f64, f64)]) -> String {
+    let v: Vec<f64> = points.iter().flat_map(|p| [p.0, p.1]).collect();
+    v.to_pdf_string()
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string() {
+        let mut a = Annotation::new(Rectangle::new(10.0, 10.0, 30.0, 20.0), Square::new());
+        a.set_author("Reviewer");
+        a.set_contents("Check (this)");
+        a.set_opacity(0.5);
+        a.set_creation_date(Date::new(2025, 1, 2, 3, 4, 5));
+
+        let mut id_factory = IdFactory::new();
+        a.assign_ids(&mut id_factory);
+
+        let ok = concat!(
+            "1 0 obj\n",
+            "<< /Type /Annot\n",
+            "   /Subtype /Square\n",
+            "   /Rect [10 10 30 20]\n",
+            "   /P 0 0 R\n",
+            "   /F 4\n",
+            "   /C [1 0 0]\n",
+            "   /CA 0.5\n",
+            "   /T (Reviewer)\n",
+            "   /Contents (Check \\(this\\))\n",
+            "   /CreationDate (D:20250102030405Z)\n",
+            "   /M (D:20250102030405Z)\n",
+            "   /BS << /W 1 >>\n",
+            "   /AP << /N 2 0 R >>\n",
+            ">>\n",
+            "endobj",
+        );
+
+        assert_eq!(a.to_string(0), ok);
+        assert_eq!(a.get_objects().len(), 3);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::shape::{interior_entries, paint};
+use super::{points_to_pdf_string, Style};
+use super::super::colour::DeviceColour;
+use super::super::xobject::Form;
+
+/// PDF32000-1:2008 12.5.6.9
+///
+/// ```text
+/// << /Subtype /Polygon
+///    /Vertices [100 100 200 100 150 180]
+/// >>
+/// ```
+pub struct Polygon {
+    vertices: Vec<(f64, f64)>,
+    interior_colour: Option<DeviceColour>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<(f64, f64)>) -> Self {
+        Self { vertices, interior_colour: None }
+    }
+
+    /// Sets the fill colour (`/IC`). Without it the polygon is not filled.
+    pub fn set_interior_colour(&mut self, colour: DeviceColour) {
+        self.interior_colour = Some(colour);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![("Vertices", points_to_pdf_string(&self.vertices))];
+        entries.append(&mut interior_entries(&self.interior_colour));
+        entries
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let Some(first) = self.vertices.first() else {
+            return;
+        };
+
+        let c = form.contents();
+        c.move_to(first.0, first.1);
+        for v in &self.vertices[1..] {
+            c.line_to(v.0, v.1);
+        }
+        c.close_path();
+        paint(form, style, &self.interior_colour);
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let p = Polygon::new(vec![(100.0, 100.0), (200.0, 100.0), (150.0, 180.0)]);
+        assert_eq!(p.entries(), vec![("Vertices", String::from("[100 100 200 100 150 180]"))]);
+    }
+}
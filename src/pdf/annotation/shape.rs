@@ -0,0 +1,121 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::Style;
+use super::super::colour::DeviceColour;
+use super::super::xobject::Form;
+
+/// PDF32000-1:2008 12.5.6.8
+///
+/// A rectangle inscribed in the annotation rectangle.
+///
+/// ```text
+/// << /Subtype /Square
+///    /IC [1 1 0]
+/// >>
+/// ```
+#[derive(Default)]
+pub struct Square {
+    interior_colour: Option<DeviceColour>,
+}
+
+impl Square {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fill colour (`/IC`). Without it the shape is not filled.
+    pub fn set_interior_colour(&mut self, colour: DeviceColour) {
+        self.interior_colour = Some(colour);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        interior_entries(&self.interior_colour)
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let r = style.rect.expand(-style.border_width / 2.0);
+        let c = form.contents();
+        c.rect(r.llx, r.lly, r.width(), r.height());
+        paint(form, style, &self.interior_colour);
+    }
+}
+
+/// PDF32000-1:2008 12.5.6.8
+///
+/// An ellipse inscribed in the annotation rectangle.
+///
+/// ```text
+/// << /Subtype /Circle
+///    /IC [1 1 0]
+/// >>
+/// ```
+#[derive(Default)]
+pub struct Circle {
+    interior_colour: Option<DeviceColour>,
+}
+
+impl Circle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fill colour (`/IC`). Without it the shape is not filled.
+    pub fn set_interior_colour(&mut self, colour: DeviceColour) {
+        self.interior_colour = Some(colour);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        interior_entries(&self.interior_colour)
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let r = style.rect.expand(-style.border_width / 2.0);
+        form.contents().ellipse(&r);
+        paint(form, style, &self.interior_colour);
+    }
+}
+
+pub(crate) fn interior_entries(interior_colour: &Option<DeviceColour>) -> Vec<(&'static str, String)> {
+    match interior_colour {
+        Some(ic) => vec![("IC", ic.to_pdf_string())],
+        None => Vec::new(),
+    }
+}
+
+/// Strokes and optionally fills the current path.
+pub(crate) fn paint(form: &mut Form, style: &Style, interior_colour: &Option<DeviceColour>) {
+    let c = form.contents();
+    c.set_line_width(style.border_width);
+    c.set_stroke_device_color(&style.colour);
+    match interior_colour {
+        Some(ic) => {
+            c.set_fill_device_color(ic);
+            if style.border_width > 0.0 { c.fill_stroke() } else { c.fill() }
+        }
+        None => {
+            if style.border_width > 0.0 { c.stroke() } else { c.end_path() }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let mut s = Circle::new();
+        assert!(s.entries().is_empty());
+        s.set_interior_colour(DeviceColour::Gray(0.5));
+        assert_eq!(s.entries(), vec![("IC", String::from("[0.5]"))]);
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::Style;
+use super::super::font::{approx_text_width, Font};
+use super::super::xobject::Form;
+
+/// Standard rubber stamp names (`/Name`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StampName {
+    Approved,
+    Experimental,
+    NotApproved,
+    AsIs,
+    Expired,
+    NotForPublicRelease,
+    Confidential,
+    Final,
+    Sold,
+    Departmental,
+    ForComment,
+    TopSecret,
+    Draft,
+    ForPublicRelease,
+}
+
+impl StampName {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Approved => "Approved",
+            Self::Experimental => "Experimental",
+            Self::NotApproved => "NotApproved",
+            Self::AsIs => "AsIs",
+            Self::Expired => "Expired",
+            Self::NotForPublicRelease => "NotForPublicRelease",
+            Self::Confidential => "Confidential",
+            Self::Final => "Final",
+            Self::Sold => "Sold",
+            Self::Departmental => "Departmental",
+            Self::ForComment => "ForComment",
+            Self::TopSecret => "TopSecret",
+            Self::Draft => "Draft",
+            Self::ForPublicRelease => "ForPublicRelease",
+        }
+    }
+
+    /// Returns the text painted in the appearance, e.g. `NOT APPROVED`.
+    pub fn label(self) -> String {
+        let mut label = String::new();
+        for (i, c) in self.name().chars().enumerate() {
+            if i > 0 && c.is_ascii_uppercase() {
+                label.push(' ');
+            }
+            label.push(c.to_ascii_uppercase());
+        }
+        label
+    }
+}
+
+/// PDF32000-1:2008 12.5.6.12
+///
+/// ```text
+/// << /Subtype /Stamp
+///    /Name /Approved
+/// >>
+/// ```
+pub struct Stamp {
+    name: StampName,
+}
+
+impl Stamp {
+    pub const FONT_NAME: &'static str = "HeBo";
+
+    pub fn new(name: StampName) -> Self {
+        Self { name }
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![("Name", format!("/{}", self.name.name()))]
+    }
+
+    /// Draws the label in a frame, scaled to fit the rectangle.
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        form.resources().add_font(Self::FONT_NAME, Font::new("Helvetica-Bold"));
+
+        let rect = style.rect;
+        let w = style.border_width.max(1.0) * 2.0;
+        let label = self.name.label();
+        let font_size = (rect.height() * 0.6).min((rect.width() - w * 4.0) / approx_text_width(&label, 1.2));
+        let text_width = approx_text_width(&label, font_size * 1.2);
+
+        let c = form.contents();
+        c.set_line_width(w);
+        c.set_stroke_device_color(&style.colour);
+        c.rect(rect.llx + w / 2.0, rect.lly + w / 2.0, rect.width() - w, rect.height() - w);
+        c.stroke();
+
+        c.begin_text();
+        c.set_font(Self::FONT_NAME, font_size);
+        c.set_fill_device_color(&style.colour);
+        c.move_text(
+            rect.llx + (rect.width() - text_width) / 2.0,
+            rect.lly + (rect.height() - font_size * 0.7) / 2.0);
+        c.show_text(&label);
+        c.end_text();
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label() {
+        assert_eq!(StampName::NotForPublicRelease.label(), "NOT FOR PUBLIC RELEASE");
+        assert_eq!(Stamp::new(StampName::Draft).entries(), vec![("Name", String::from("/Draft"))]);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::Style;
+use super::super::colour::DeviceColour;
+use super::super::xobject::Form;
+
+/// Icons of the text annotation (`/Name`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextIcon {
+    Comment,
+    Key,
+    Note,
+    Help,
+    NewParagraph,
+    Paragraph,
+    Insert,
+}
+
+impl TextIcon {
+    pub fn to_pdf_string(self) -> String {
+        match self {
+            Self::Comment => "/Comment",
+            Self::Key => "/Key",
+            Self::Note => "/Note",
+            Self::Help => "/Help",
+            Self::NewParagraph => "/NewParagraph",
+            Self::Paragraph => "/Paragraph",
+            Self::Insert => "/Insert",
+        }.to_string()
+    }
+}
+
+/// PDF32000-1:2008 12.5.6.4
+///
+/// A sticky note.
+///
+/// ```text
+/// << /Subtype /Text
+///    /Name /Comment
+///    /Open false
+/// >>
+/// ```
+pub struct Text {
+    icon: TextIcon,
+    open: bool,
+}
+
+impl Text {
+    pub fn new(icon: TextIcon) -> Self {
+        Self { icon, open: false }
+    }
+
+    /// Sets whether the pop-up is initially open.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Name", self.icon.to_pdf_string()),
+            ("Open", self.open.to_string()),
+        ]
+    }
+
+    /// Draws a note sheet with a folded corner in the lower-left square of the rectangle.
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        let size = style.rect.width().min(style.rect.height());
+        let x = style.rect.llx;
+        let y = style.rect.lly;
+        let fold = size * 0.3;
+        let c = form.contents();
+
+        c.set_line_width(1.0);
+        c.set_fill_device_color(&style.colour);
+        c.set_stroke_device_color(&DeviceColour::black());
+        c.move_to(x + 0.5, y + fold);
+        c.line_to(x + 0.5, y + size - 0.5);
+        c.line_to(x + size - 0.5, y + size - 0.5);
+        c.line_to(x + size - 0.5, y + 0.5);
+        c.line_to(x + fold, y + 0.5);
+        c.close_path();
+        c.fill_stroke();
+
+        // folded corner
+        c.move_to(x + fold, y + 0.5);
+        c.line_to(x + fold, y + fold);
+        c.line_to(x + 0.5, y + fold);
+        c.stroke();
+
+        // text lines
+        c.set_line_width(size / 20.0);
+        for i in 1..4 {
+            let ly = y + size - size * 0.22 * i as f64;
+            c.move_to(x + size * 0.2, ly);
+            c.line_to(x + size * 0.8, ly);
+        }
+        c.stroke();
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let mut t = Text::new(TextIcon::Note);
+        t.set_open(true);
+        assert_eq!(t.entries(), vec![
+            ("Name", String::from("/Note")),
+            ("Open", String::from("true")),
+        ]);
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::{points_to_pdf_string, Style};
+use super::super::base::Rectangle;
+use super::super::colour::DeviceColour;
+use super::super::ext_g_state::{BlendMode, ExtGState};
+use super::super::xobject::Form;
+
+/// A quadrilateral given in the `/QuadPoints` order:
+/// upper-left, upper-right, lower-left, lower-right.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quad {
+    pub points: [(f64, f64); 4],
+}
+
+impl Quad {
+    pub fn new(points: [(f64, f64); 4]) -> Self {
+        Self { points }
+    }
+
+    /// Creates a quad covering an upright rectangle.
+    pub fn from_rect(rect: &Rectangle) -> Self {
+        Self::new([
+            (rect.llx, rect.ury),
+            (rect.urx, rect.ury),
+            (rect.llx, rect.lly),
+            (rect.urx, rect.lly),
+        ])
+    }
+
+    fn upper_left(&self) -> (f64, f64) {
+        self.points[0]
+    }
+
+    fn upper_right(&self) -> (f64, f64) {
+        self.points[1]
+    }
+
+    fn lower_left(&self) -> (f64, f64) {
+        self.points[2]
+    }
+
+    fn lower_right(&self) -> (f64, f64) {
+        self.points[3]
+    }
+
+    /// Height measured along the left edge.
+    fn height(&self) -> f64 {
+        let (x0, y0) = self.lower_left();
+        let (x1, y1) = self.upper_left();
+        ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt()
+    }
+}
+
+/// Text markup subtypes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextMarkupType {
+    Highlight,
+    Underline,
+    StrikeOut,
+    Squiggly,
+}
+
+/// PDF32000-1:2008 12.5.6.10
+///
+/// ```text
+/// << /Subtype /Highlight
+///    /QuadPoints [72 720 144 720 72 708 144 708]
+/// >>
+/// ```
+pub struct TextMarkup {
+    markup_type: TextMarkupType,
+    quads: Vec<Quad>,
+}
+
+impl TextMarkup {
+    pub fn new(markup_type: TextMarkupType, quads: Vec<Quad>) -> Self {
+        Self { markup_type, quads }
+    }
+
+    pub fn highlight(quads: Vec<Quad>) -> Self {
+        Self::new(TextMarkupType::Highlight, quads)
+    }
+
+    pub fn underline(quads: Vec<Quad>) -> Self {
+        Self::new(TextMarkupType::Underline, quads)
+    }
+
+    pub fn strike_out(quads: Vec<Quad>) -> Self {
+        Self::new(TextMarkupType::StrikeOut, quads)
+    }
+
+    pub fn squiggly(quads: Vec<Quad>) -> Self {
+        Self::new(TextMarkupType::Squiggly, quads)
+    }
+
+    pub fn quads(&self) -> &Vec<Quad> {
+        &self.quads
+    }
+
+    pub(crate) fn subtype(&self) -> &str {
+        match self.markup_type {
+            TextMarkupType::Highlight => "Highlight",
+            TextMarkupType::Underline => "Underline",
+            TextMarkupType::StrikeOut => "StrikeOut",
+            TextMarkupType::Squiggly => "Squiggly",
+        }
+    }
+
+    pub(crate) fn default_colour(&self) -> DeviceColour {
+        match self.markup_type {
+            TextMarkupType::Highlight => DeviceColour::Rgb(1.0, 1.0, 0.0),
+            TextMarkupType::Underline => DeviceColour::Rgb(0.0, 0.0, 1.0),
+            _ => DeviceColour::Rgb(1.0, 0.0, 0.0),
+        }
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let points: Vec<(f64, f64)> = self.quads.iter().flat_map(|q| q.points).collect();
+        vec![("QuadPoints", points_to_pdf_string(&points))]
+    }
+
+    pub(crate) fn draw(&self, form: &mut Form, style: &Style) {
+        if self.markup_type == TextMarkupType::Highlight {
+            let mut gs = ExtGState::new();
+            gs.set_blend_mode(BlendMode::Multiply);
+            form.resources().add_ext_g_state("GS0", gs);
+        }
+
+        let c = form.contents();
+        match self.markup_type {
+            TextMarkupType::Highlight => {
+                c.set_ext_g_state("GS0");
+                c.set_fill_device_color(&style.colour);
+                for q in &self.quads {
+                    let (ulx, uly) = q.upper_left();
+                    let (urx, ury) = q.upper_right();
+                    let (llx, lly) = q.lower_left();
+                    let (lrx, lry) = q.lower_right();
+                    c.move_to(llx, lly);
+                    c.line_to(lrx, lry);
+                    c.line_to(urx, ury);
+                    c.line_to(ulx, uly);
+                    c.close_path();
+                }
+                c.fill();
+            }
+            TextMarkupType::Underline | TextMarkupType::StrikeOut => {
+                c.set_stroke_device_color(&style.colour);
+                for q in &self.quads {
+                    // relative position of the line from the bottom to the top
+                    let t = if self.markup_type == TextMarkupType::Underline { 0.07 } else { 0.5 };
+                    let (sx, sy) = lerp(q.lower_left(), q.upper_left(), t);
+                    let (ex, ey) = lerp(q.lower_right(), q.upper_right(), t);
+                    c.set_line_width((q.height() / 14.0).max(0.5));
+                    c.move_to(sx, sy);
+                    c.line_to(ex, ey);
+                    c.stroke();
+                }
+            }
+            TextMarkupType::Squiggly => {
+                c.set_stroke_device_color(&style.colour);
+                for q in &self.quads {
+                    let h = q.height();
+                    let (sx, sy) = lerp(q.lower_left(), q.upper_left(), 0.05);
+                    let (ex, ey) = lerp(q.lower_right(), q.upper_right(), 0.05);
+                    let len = ((ex - sx).powi(2) + (ey - sy).powi(2)).sqrt();
+                    if len == 0.0 {
+                        continue;
+                    }
+                    // unit vectors along and across the baseline
+                    let (ux, uy) = ((ex - sx) / len, (ey - sy) / len);
+                    let (nx, ny) = (-uy, ux);
+                    let step = (h / 6.0).max(1.0);
+                    let amp = h / 12.0;
+
+                    c.set_line_width((h / 20.0).max(0.5));
+                    c.move_to(sx, sy);
+                    let mut d = step;
+                    let mut up = true;
+                    while d < len {
+                        let a = if up { amp } else { 0.0 };
+                        c.line_to(sx + ux * d + nx * a, sy + uy * d + ny * a);
+                        d += step;
+                        up = !up;
+                    }
+                    c.stroke();
+                }
+            }
+        }
+    }
+}
+
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let quad = Quad::from_rect(&Rectangle::new(72.0, 708.0, 144.0, 720.0));
+        let m = TextMarkup::highlight(vec![quad]);
+        assert_eq!(m.subtype(), "Highlight");
+        assert_eq!(m.entries(), vec![
+            ("QuadPoints", String::from("[72 720 144 720 72 708 144 708]")),
+        ]);
+    }
+}
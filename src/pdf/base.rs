@@ -16,7 +16,7 @@ impl Pos {
     }
 }
 
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Name {
     name: String,
 }
@@ -27,11 +27,27 @@ impl Name {
             name: name.to_string(),
         }
     }
+
+    /// Returns the name without the leading slash and escapes.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
 }
 
 impl std::fmt::Display for Name {
+    /// Writes the name with the characters outside the regular characters
+    /// escaped as `#xx` (PDF32000-1:2008 7.3.5).
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "/{}", self.name)
+        write!(f, "/")?;
+        for b in self.name.bytes() {
+            let is_regular = (b'!'..=b'~').contains(&b) && !b"#()<>[]{}/%".contains(&b);
+            if is_regular {
+                write!(f, "{}", b as char)?;
+            } else {
+                write!(f, "#{:02X}", b)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -70,7 +86,8 @@ impl Version {
 // MediaBox
 //------------------------------------------------------------------------------
 
-/// MediaBox types.
+/// MediaBox types. See `PaperSize` for other sizes.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MediaBox {
     /// 0, 0, 612, 792
     Letter,
@@ -79,14 +96,211 @@ pub enum MediaBox {
     Custom(u32, u32, u32, u32),
 }
 
-impl MediaBox {
-    /// Returns the string in PDF.
-    pub fn to_string(&self) -> String {
+impl std::fmt::Display for MediaBox {
+    /// Writes the string in PDF.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Letter => "[0 0 612 792]".to_string(), // pt = 1 / 72 inch
-            Self::A4 => "[0 0 595 842]".to_string(),
-            Self::Custom(v1, v2,v3, v4 ) => format!("[{v1} {v2} {v3} {v4}]"),
+            Self::Letter => write!(f, "[0 0 612 792]"), // pt = 1 / 72 inch
+            Self::A4 => write!(f, "[0 0 595 842]"),
+            Self::Custom(v1, v2,v3, v4 ) => write!(f, "[{v1} {v2} {v3} {v4}]"),
+        }
+    }
+}
+
+impl From<MediaBox> for Rectangle {
+    fn from(media_box: MediaBox) -> Self {
+        match media_box {
+            MediaBox::Letter => Rectangle::new(0.0, 0.0, 612.0, 792.0),
+            MediaBox::A4 => Rectangle::new(0.0, 0.0, 595.0, 842.0),
+            MediaBox::Custom(v1, v2, v3, v4) => Rectangle::new(v1 as f64, v2 as f64, v3 as f64, v4 as f64),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Rectangle
+//------------------------------------------------------------------------------
+
+/// PDF32000-1:2008 7.9.5
+///
+/// A rectangle given by its lower-left and upper-right corners in default
+/// user space units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rectangle {
+    pub llx: f64,
+    pub lly: f64,
+    pub urx: f64,
+    pub ury: f64,
+}
+
+impl Rectangle {
+    /// Creates a new rectangle. The corners are normalised so that the
+    /// lower-left corner is always the smaller one.
+    pub fn new(llx: f64, lly: f64, urx: f64, ury: f64) -> Self {
+        Self {
+            llx: llx.min(urx),
+            lly: lly.min(ury),
+            urx: llx.max(urx),
+            ury: lly.max(ury),
+        }
+    }
+
+    /// Creates a new rectangle from the origin and the size.
+    pub fn from_size(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self::new(x, y, x + width, y + height)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.urx - self.llx
+    }
+
+    pub fn height(&self) -> f64 {
+        self.ury - self.lly
+    }
+
+    /// Returns the rectangle grown by `d` on every side.
+    pub fn expand(&self, d: f64) -> Self {
+        Self::new(self.llx - d, self.lly - d, self.urx + d, self.ury + d)
+    }
+
+    /// Returns true if the other rectangle is inside this one or on its edges.
+    pub fn contains(&self, other: &Rectangle) -> bool {
+        // Tolerates the rounding of the sizes converted from millimetres
+        const EPSILON: f64 = 1e-6;
+        other.llx >= self.llx - EPSILON
+            && other.lly >= self.lly - EPSILON
+            && other.urx <= self.urx + EPSILON
+            && other.ury <= self.ury + EPSILON
+    }
+
+    /// Returns the rectangle with the longer side vertical, keeping the lower-left corner.
+    pub fn portrait(&self) -> Self {
+        if self.width() > self.height() { self.rotated() } else { *self }
+    }
+
+    /// Returns the rectangle with the longer side horizontal, keeping the lower-left corner.
+    pub fn landscape(&self) -> Self {
+        if self.width() < self.height() { self.rotated() } else { *self }
+    }
+
+    fn rotated(&self) -> Self {
+        Self::from_size(self.llx, self.lly, self.height(), self.width())
+    }
+
+    /// Returns the smallest rectangle enclosing all points.
+    pub fn bounding(points: &[(f64, f64)]) -> Option<Self> {
+        let first = points.first()?;
+        let mut r = Self::new(first.0, first.1, first.0, first.1);
+        for p in points {
+            r.llx = r.llx.min(p.0);
+            r.lly = r.lly.min(p.1);
+            r.urx = r.urx.max(p.0);
+            r.ury = r.ury.max(p.1);
         }
+        Some(r)
+    }
+
+    pub fn to_pdf_string(&self) -> String {
+        // Rounded to keep the sizes converted from millimetres short
+        let n = |v: f64| {
+            let v = (v * 10000.0).round() / 10000.0;
+            if v == 0.0 { 0.0 } else { v }
+        };
+        format!("[{} {} {} {}]", n(self.llx), n(self.lly), n(self.urx), n(self.ury))
+    }
+}
+
+//------------------------------------------------------------------------------
+// Date
+//------------------------------------------------------------------------------
+
+/// PDF32000-1:2008 7.9.4
+///
+/// `(D:YYYYMMDDHHmmSSOHH'mm)`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    /// Offset from UT in minutes.
+    utc_offset: i16,
+}
+
+impl Date {
+    /// Creates a new date in UT.
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        Self { year, month, day, hour, minute, second, utc_offset: 0 }
+    }
+
+    /// Returns the date with the offset from UT in minutes.
+    pub fn with_utc_offset(mut self, minutes: i16) -> Self {
+        self.utc_offset = minutes;
+        self
+    }
+
+    /// Returns the current time in UT.
+    pub fn now() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self::from_unix_time(secs)
+    }
+
+    /// Converts seconds since 1970-01-01T00:00:00Z to a date.
+    pub fn from_unix_time(secs: u64) -> Self {
+        let days = (secs / 86400) as i64;
+        let rem = secs % 86400;
+
+        // Howard Hinnant's civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self::new(
+            year as u16, month as u8, day as u8,
+            (rem / 3600) as u8, (rem % 3600 / 60) as u8, (rem % 60) as u8)
+    }
+
+    /// Returns the date as ISO 8601, e.g. `2025-01-01T09:00:00+09:00`.
+    pub fn to_iso8601_string(&self) -> String {
+        let offset = if self.utc_offset == 0 {
+            String::from("Z")
+        } else {
+            let sign = if self.utc_offset < 0 { '-' } else { '+' };
+            let m = self.utc_offset.unsigned_abs();
+            format!("{}{:02}:{:02}", sign, m / 60, m % 60)
+        };
+
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+            self.year, self.month, self.day,
+            self.hour, self.minute, self.second,
+            offset)
+    }
+
+    /// Returns the PDF string including the parentheses.
+    pub fn to_pdf_string(&self) -> String {
+        let offset = if self.utc_offset == 0 {
+            String::from("Z")
+        } else {
+            let sign = if self.utc_offset < 0 { '-' } else { '+' };
+            let m = self.utc_offset.unsigned_abs();
+            format!("{}{:02}'{:02}'", sign, m / 60, m % 60)
+        };
+
+        format!("(D:{:04}{:02}{:02}{:02}{:02}{:02}{})",
+            self.year, self.month, self.day,
+            self.hour, self.minute, self.second,
+            offset)
     }
 }
 
@@ -94,7 +308,7 @@ impl MediaBox {
 // Id
 //------------------------------------------------------------------------------
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Id {
     id: u32,
     generation: u32,
@@ -103,7 +317,7 @@ pub struct Id {
 impl Id {
     /// Creates a new Id.
     pub fn new(id: u32, generation: u32) -> Id {
-        Id { id: id, generation }
+        Id { id, generation }
     }
 
     /// Creates a new 0 Id.
@@ -111,14 +325,25 @@ impl Id {
         Id { id: 0, generation: 0 }
     }
 
-    /// Converts to a simple string
-    pub fn to_string(&self) -> String {
-        format!("{} {}", self.id, self.generation)
+    /// Returns the object number.
+    pub fn number(self) -> u32 {
+        self.id
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
     }
 
     /// Converts to a reference string
-    pub fn to_ref_string(&self) -> String {
-        format!("{} R", self.to_string())
+    pub fn to_ref_string(self) -> String {
+        format!("{} R", self)
+    }
+}
+
+impl std::fmt::Display for Id {
+    /// Writes a simple string
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.id, self.generation)
     }
 }
 
@@ -127,20 +352,28 @@ impl Id {
 //------------------------------------------------------------------------------
 
 pub struct IdFactory {
-    page_list_id: Id,
+    page_list_id: Option<Id>,
     next_id: u32,
 }
 
 impl IdFactory {
     pub fn new() -> Self {
         IdFactory {
-            page_list_id: Id::new(1, 0),
-            next_id: 2
+            page_list_id: None,
+            next_id: 1,
         }
     }
 
-    pub fn page_list_id(&self) -> &Id {
-        &self.page_list_id
+    /// Returns the id of the page tree root, creating it on first use.
+    pub fn page_list_id(&mut self) -> Id {
+        match self.page_list_id {
+            Some(id) => id,
+            None => {
+                let id = self.next_id();
+                self.page_list_id = Some(id);
+                id
+            }
+        }
     }
 
     /// Creates new Id and increments a next id number from the initial_id.
@@ -151,6 +384,12 @@ impl IdFactory {
     }
 }
 
+impl Default for IdFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub trait PdfObject {
     fn id(&self) -> &Id;
     fn assign_ids(&mut self, id_factory: &mut IdFactory);
@@ -175,6 +414,16 @@ mod tests {
         }
     }
 
+    mod name {
+        use super::super::*;
+
+        #[test]
+        fn escape() {
+            assert_eq!(Name::new("Orange").to_string(), "/Orange");
+            assert_eq!(Name::new("Pantone 185 C#").to_string(), "/Pantone#20185#20C#23");
+        }
+    }
+
     mod version {
         //use crate::prspdf::base::Version;
         use super::super::*;
@@ -196,6 +445,66 @@ mod tests {
         }
     }
 
+    mod rectangle {
+        use crate::Rectangle;
+
+        #[test]
+        fn new() {
+            let r = Rectangle::new(10.0, 20.0, 0.0, 5.0);
+            assert_eq!(r.to_pdf_string(), "[0 5 10 20]");
+            assert_eq!(r.width(), 10.0);
+            assert_eq!(r.height(), 15.0);
+        }
+
+        #[test]
+        fn bounding() {
+            let r = Rectangle::bounding(&[(1.0, 2.0), (-1.0, 5.0), (3.0, 0.5)]).unwrap();
+            assert_eq!(r, Rectangle::new(-1.0, 0.5, 3.0, 5.0));
+            assert!(Rectangle::bounding(&[]).is_none());
+        }
+
+        #[test]
+        fn contains() {
+            let r = Rectangle::new(0.0, 0.0, 100.0, 50.0);
+            assert!(r.contains(&r));
+            assert!(r.contains(&Rectangle::new(10.0, 10.0, 20.0, 20.0)));
+            assert!(!r.contains(&Rectangle::new(-1.0, 10.0, 20.0, 20.0)));
+        }
+
+        #[test]
+        fn orientation() {
+            let r = Rectangle::new(10.0, 10.0, 110.0, 60.0);
+            assert_eq!(r.portrait(), Rectangle::new(10.0, 10.0, 60.0, 110.0));
+            assert_eq!(r.landscape(), r);
+            assert_eq!(Rectangle::new(0.0, 0.0, 1.0 / 3.0, 1.0).to_pdf_string(), "[0 0 0.3333 1]");
+        }
+    }
+
+    mod date {
+        use crate::pdf::Date;
+
+        #[test]
+        fn to_pdf_string() {
+            let d = Date::new(2025, 3, 4, 5, 6, 7);
+            assert_eq!(d.to_pdf_string(), "(D:20250304050607Z)");
+            assert_eq!(d.with_utc_offset(9 * 60).to_pdf_string(), "(D:20250304050607+09'00')");
+            assert_eq!(d.with_utc_offset(-150).to_pdf_string(), "(D:20250304050607-02'30')");
+        }
+
+        #[test]
+        fn to_iso8601_string() {
+            let d = Date::new(2025, 3, 4, 5, 6, 7);
+            assert_eq!(d.to_iso8601_string(), "2025-03-04T05:06:07Z");
+            assert_eq!(d.with_utc_offset(9 * 60).to_iso8601_string(), "2025-03-04T05:06:07+09:00");
+        }
+
+        #[test]
+        fn from_unix_time() {
+            assert_eq!(Date::from_unix_time(0), Date::new(1970, 1, 1, 0, 0, 0));
+            assert_eq!(Date::from_unix_time(1709251199), Date::new(2024, 2, 29, 23, 59, 59));
+        }
+    }
+
     mod id {
         use crate::pdf::Id;
 
@@ -221,9 +530,11 @@ mod tests {
         fn new_id() {
 
             let mut id_factory = IdFactory::new();
+            assert_eq!(id_factory.next_id().to_string(), "1 0");
             assert_eq!(id_factory.next_id().to_string(), "2 0");
-            assert_eq!(id_factory.next_id().to_string(), "3 0");
+            assert_eq!(id_factory.page_list_id().to_string(), "3 0");
             assert_eq!(id_factory.next_id().to_string(), "4 0");
+            assert_eq!(id_factory.page_list_id().to_string(), "3 0");
         }
     }
 }
@@ -0,0 +1,266 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! Approximate conversion of colours in a colour space to CIE XYZ, Lab,
+//! sRGB and CMYK, e.g. for swatch previews.
+//!
+//! CIE-based spaces are converted with their parameters and chromatically
+//! adapted to D50 with the Bradford transform. Device spaces are taken as
+//! sRGB, DeviceCMYK as a naive conversion without ink limits. ICCBased
+//! spaces are converted through their alternate as profiles are not
+//! evaluated. Separation and DeviceN colours go through the tint transform
+//! to the alternate space. Black points are ignored.
+//!
+//! ```
+//! use prspdf::colour::convert;
+//! use prspdf::colour::space::{ColorSpace, Lab, Separation};
+//! use prspdf::function::Type2;
+//!
+//! let lab = Lab::new_with_white(0.9642, 1.0, 0.8249).unwrap();
+//! let orange = Separation::new("Orange", lab, Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0));
+//! let rgb = convert::to_srgb(&ColorSpace::from(orange), &[1.0]).unwrap();
+//! assert!(rgb[0] > 0.9 && rgb[2] < 0.1);
+//! ```
+
+use super::space::{ColorSpace, D50, D65};
+use super::super::error::Error;
+
+/// Returns CIE XYZ relative to D50, Y of the white being 1.0.
+pub fn to_xyz(space: &ColorSpace, components: &[f64]) -> Result<[f64; 3], Error> {
+    Ok(match resolve(space, components)? {
+        Value::Rgb(rgb) => adapt(&srgb_to_xyz(&rgb), &D65, &D50),
+        Value::Xyz(xyz, white) => adapt(&xyz, &white, &D50),
+    })
+}
+
+/// Returns CIE L*a*b* relative to D50.
+pub fn to_lab(space: &ColorSpace, components: &[f64]) -> Result<[f64; 3], Error> {
+    Ok(xyz_to_lab(&to_xyz(space, components)?, &D50))
+}
+
+/// Returns sRGB clipped to 0.0 to 1.0.
+pub fn to_srgb(space: &ColorSpace, components: &[f64]) -> Result<[f64; 3], Error> {
+    let rgb = match resolve(space, components)? {
+        Value::Rgb(rgb) => rgb,
+        Value::Xyz(xyz, white) => xyz_to_srgb(&adapt(&xyz, &white, &D65)),
+    };
+    Ok(rgb.map(|v| v.clamp(0.0, 1.0)))
+}
+
+/// Returns CMYK. Colours of DeviceCMYK, or resolving to it through the
+/// tint transforms, the alternates and the palettes, are returned as they
+/// are, the others are converted naively from sRGB.
+pub fn to_cmyk(space: &ColorSpace, components: &[f64]) -> Result<[f64; 4], Error> {
+    check_components(space, components)?;
+    match space {
+        ColorSpace::DeviceCMYK => Ok([components[0], components[1], components[2], components[3]].map(|v| v.clamp(0.0, 1.0))),
+        ColorSpace::IccBased(icc) if icc.alternate().is_some_and(|a| matches!(a, ColorSpace::DeviceCMYK))
+            || (icc.alternate().is_none() && icc.components() == 4) => {
+            to_cmyk(&ColorSpace::DeviceCMYK, components)
+        }
+        ColorSpace::Indexed(indexed) => to_cmyk(indexed.base(), &lookup(indexed, components[0])),
+        ColorSpace::Separation(separation) => {
+            to_cmyk(separation.alt_space(), &separation.tint_transform().evaluate(components))
+        }
+        ColorSpace::DeviceN(devicen) => to_cmyk(devicen.alt_space(), &devicen.tint_transform().evaluate(components)),
+        _ => {
+            let [r, g, b] = to_srgb(space, components)?;
+            let k = 1.0 - r.max(g).max(b);
+            if k >= 1.0 {
+                return Ok([0.0, 0.0, 0.0, 1.0]);
+            }
+            Ok([(1.0 - r - k) / (1.0 - k), (1.0 - g - k) / (1.0 - k), (1.0 - b - k) / (1.0 - k), k])
+        }
+    }
+}
+
+/// Returns CIE L*a*b* of CIE XYZ relative to the white.
+pub fn xyz_to_lab(xyz: &[f64; 3], white: &[f64; 3]) -> [f64; 3] {
+    let f = |t: f64| if t > EPSILON { t.cbrt() } else { (KAPPA * t + 16.0) / 116.0 };
+    let fx = f(xyz[0] / white[0]);
+    let fy = f(xyz[1] / white[1]);
+    let fz = f(xyz[2] / white[2]);
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Returns CIE XYZ relative to the white of CIE L*a*b*.
+pub fn lab_to_xyz(lab: &[f64; 3], white: &[f64; 3]) -> [f64; 3] {
+    let fy = (lab[0] + 16.0) / 116.0;
+    let fx = fy + lab[1] / 500.0;
+    let fz = fy - lab[2] / 200.0;
+    let f_inv = |f: f64| if f * f * f > EPSILON { f * f * f } else { (116.0 * f - 16.0) / KAPPA };
+    let y = if lab[0] > KAPPA * EPSILON { fy * fy * fy } else { lab[0] / KAPPA };
+    [white[0] * f_inv(fx), white[1] * y, white[2] * f_inv(fz)]
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// CIE constants of L*a*b*
+const EPSILON: f64 = 216.0 / 24389.0;
+const KAPPA: f64 = 24389.0 / 27.0;
+
+/// Bradford cone response matrix and its inverse
+const BRADFORD: [f64; 9] = [0.8951, 0.2664, -0.1614, -0.7502, 1.7135, 0.0367, 0.0389, -0.0685, 1.0296];
+const BRADFORD_INV: [f64; 9] = [0.9869929, -0.1470543, 0.1599627, 0.4323053, 0.5183603, 0.0492912, -0.0085287, 0.0400428, 0.9684867];
+
+/// Linear sRGB to XYZ (D65) and the inverse
+const SRGB_TO_XYZ: [f64; 9] = [0.4124, 0.3576, 0.1805, 0.2126, 0.7152, 0.0722, 0.0193, 0.1192, 0.9505];
+const XYZ_TO_SRGB: [f64; 9] = [3.2406, -1.5372, -0.4986, -0.9689, 1.8758, 0.0415, 0.0557, -0.2040, 1.0570];
+
+/// A colour resolved to sRGB, or to CIE XYZ relative to the white.
+enum Value {
+    Rgb([f64; 3]),
+    Xyz([f64; 3], [f64; 3]),
+}
+
+fn resolve(space: &ColorSpace, components: &[f64]) -> Result<Value, Error> {
+    check_components(space, components)?;
+    let c = |i: usize| components[i].clamp(0.0, 1.0);
+    Ok(match space {
+        ColorSpace::DeviceGray => Value::Rgb([c(0); 3]),
+        ColorSpace::DeviceRGB => Value::Rgb([c(0), c(1), c(2)]),
+        ColorSpace::DeviceCMYK => {
+            let k = 1.0 - c(3);
+            Value::Rgb([(1.0 - c(0)) * k, (1.0 - c(1)) * k, (1.0 - c(2)) * k])
+        }
+        ColorSpace::CalGray(space) => {
+            let a = c(0).powf(space.gamma());
+            Value::Xyz(space.white().map(|w| w * a), *space.white())
+        }
+        ColorSpace::CalRGB(space) => {
+            let g = space.gamma();
+            let abc = [c(0).powf(g[0]), c(1).powf(g[1]), c(2).powf(g[2])];
+            let m = space.matrix();
+            let xyz = [0, 1, 2].map(|i| m[i] * abc[0] + m[3 + i] * abc[1] + m[6 + i] * abc[2]);
+            Value::Xyz(xyz, *space.white())
+        }
+        ColorSpace::Lab(space) => {
+            let r = space.range();
+            let lab = [components[0].clamp(0.0, 100.0), components[1].clamp(r[0], r[1]), components[2].clamp(r[2], r[3])];
+            Value::Xyz(lab_to_xyz(&lab, space.white()), *space.white())
+        }
+        ColorSpace::IccBased(icc) => match icc.alternate() {
+            Some(alternate) => resolve(alternate, components)?,
+            None => match icc.components() {
+                1 => resolve(&ColorSpace::DeviceGray, components)?,
+                3 => resolve(&ColorSpace::DeviceRGB, components)?,
+                _ => resolve(&ColorSpace::DeviceCMYK, components)?,
+            },
+        },
+        ColorSpace::Indexed(indexed) => resolve(indexed.base(), &lookup(indexed, components[0]))?,
+        ColorSpace::Separation(separation) => {
+            resolve(separation.alt_space(), &separation.tint_transform().evaluate(components))?
+        }
+        ColorSpace::DeviceN(devicen) => resolve(devicen.alt_space(), &devicen.tint_transform().evaluate(components))?,
+        ColorSpace::Pattern(_) => return Err(Error::Unsupported(String::from("conversion of Pattern colours"))),
+    })
+}
+
+fn check_components(space: &ColorSpace, components: &[f64]) -> Result<(), Error> {
+    if components.len() != space.components() {
+        return Err(Error::InvalidValue(format!(
+            "{} components for {} colour space of {}", components.len(), space.family(), space.components())));
+    }
+    Ok(())
+}
+
+/// Returns the components in the base space of the palette entry.
+fn lookup(indexed: &super::space::Indexed, index: f64) -> Vec<f64> {
+    let base = indexed.base();
+    let n = base.components();
+    let index = (index.round().max(0.0) as usize).min(indexed.hival() as usize);
+    let bytes = &indexed.lookup()[index * n..(index + 1) * n];
+    bytes.iter().zip(base.default_decode(8)).map(|(b, (min, max))| min + *b as f64 * (max - min) / 255.0).collect()
+}
+
+fn multiply(m: &[f64; 9], v: &[f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|i| m[i * 3] * v[0] + m[i * 3 + 1] * v[1] + m[i * 3 + 2] * v[2])
+}
+
+/// Adapts XYZ under the white `from` to the white `to`.
+fn adapt(xyz: &[f64; 3], from: &[f64; 3], to: &[f64; 3]) -> [f64; 3] {
+    let cone = multiply(&BRADFORD, xyz);
+    let from = multiply(&BRADFORD, from);
+    let to = multiply(&BRADFORD, to);
+    multiply(&BRADFORD_INV, &[0, 1, 2].map(|i| cone[i] * to[i] / from[i]))
+}
+
+fn srgb_to_xyz(rgb: &[f64; 3]) -> [f64; 3] {
+    let linear = rgb.map(|v| if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) });
+    multiply(&SRGB_TO_XYZ, &linear)
+}
+
+fn xyz_to_srgb(xyz: &[f64; 3]) -> [f64; 3] {
+    multiply(&XYZ_TO_SRGB, xyz).map(|v| {
+        let v = v.clamp(0.0, 1.0);
+        if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+    })
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::pdf::colour::space::*;
+    use crate::pdf::function::{Type0, Type2};
+
+    fn assert_near<const N: usize>(a: [f64; N], b: [f64; N], tolerance: f64) {
+        assert!(a.iter().zip(&b).all(|(a, b)| (a - b).abs() < tolerance), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn device() {
+        assert_near(to_srgb(&ColorSpace::DeviceCMYK, &[0.0, 1.0, 1.0, 0.0]).unwrap(), [1.0, 0.0, 0.0], 1e-9);
+        assert_near(to_lab(&ColorSpace::DeviceRGB, &[1.0, 1.0, 1.0]).unwrap(), [100.0, 0.0, 0.0], 0.05);
+        assert_near(to_xyz(&ColorSpace::DeviceGray, &[0.0]).unwrap(), [0.0; 3], 1e-9);
+        assert_near(to_cmyk(&ColorSpace::DeviceRGB, &[0.0, 0.0, 1.0]).unwrap(), [1.0, 1.0, 0.0, 0.0], 1e-9);
+        assert!(to_srgb(&ColorSpace::DeviceRGB, &[1.0]).is_err());
+        assert!(to_srgb(&ColorSpace::Pattern(None), &[]).is_err());
+    }
+
+    #[test]
+    fn cie() {
+        // sRGB red through the approximating CalRGB
+        let red = to_srgb(&CalRGB::srgb().into(), &[1.0, 0.0, 0.0]).unwrap();
+        assert_near(red, [1.0, 0.0, 0.0], 0.01);
+        assert_near(to_lab(&CalGray::srgb().into(), &[1.0]).unwrap(), [100.0, 0.0, 0.0], 0.05);
+
+        let lab = Lab::new(D50, [0.0; 3], [-128.0, 127.0, -128.0, 127.0]).unwrap();
+        let space = ColorSpace::from(lab);
+        assert_near(to_lab(&space, &[50.0, 20.0, -30.0]).unwrap(), [50.0, 20.0, -30.0], 1e-4);
+        assert_near(to_xyz(&space, &[100.0, 0.0, 0.0]).unwrap(), D50, 1e-6);
+    }
+
+    #[test]
+    fn tint_transform() {
+        let lab = Lab::new_with_white(0.964203, 1.0, 0.824905).unwrap();
+        let orange = Separation::new("Orange", lab, Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0));
+        let orange = ColorSpace::from(orange);
+        assert_near(to_lab(&orange, &[1.0]).unwrap(), [65.0, 58.0, 88.0], 0.05);
+        assert_near(to_lab(&orange, &[0.0]).unwrap(), [100.0, 0.0, 0.0], 0.05);
+
+        let spot = Separation::new("Spot", DeviceCMYK {}, Type2::new([0.0; 4], [0.0, 0.5, 1.0, 0.0], 1.0));
+        let tints = ColorSpace::from(Indexed::new(spot, 1, vec![0, 255]).unwrap());
+        assert_near(to_cmyk(&tints, &[1.0]).unwrap(), [0.0, 0.5, 1.0, 0.0], 1e-9);
+
+        // CMYKOGV to CMYK, Orange as M+Y
+        let samples = vec![0, 0, 0, 0, 0, 255, 255, 0];
+        let type0 = Type0::new([(0.0, 1.0); 7], [(0.0, 1.0); 4], [1, 1, 1, 1, 2, 1, 1], 8, samples);
+        let mut devicen = DeviceN::new(vec!["Cyan", "Magenta", "Yellow", "Black", "Orange", "Green", "Violet"], DeviceCMYK {}, type0);
+        devicen.set_attributes(DeviceNAttributes::nchannel(HashMap::new(), DeviceCMYK {}, vec!["Cyan", "Magenta", "Yellow", "Black"]));
+        let devicen = ColorSpace::from(devicen);
+        assert_near(to_cmyk(&devicen, &[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]).unwrap(), [0.0, 1.0, 1.0, 0.0], 1e-9);
+        assert_near(to_srgb(&devicen, &[0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0]).unwrap(), [1.0, 0.5, 0.5], 1e-2);
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use crate::pdf::operation::Operation;
+
+/// A colour in one of the device colour spaces.
+///
+/// Used where PDF takes a bare colour array whose length selects the space,
+/// e.g. the `/C` and `/IC` entries of annotations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeviceColour {
+    Gray(f64),
+    Rgb(f64, f64, f64),
+    Cmyk(f64, f64, f64, f64),
+}
+
+impl DeviceColour {
+    pub fn black() -> Self {
+        Self::Gray(0.0)
+    }
+
+    pub fn white() -> Self {
+        Self::Gray(1.0)
+    }
+
+    pub fn components(&self) -> Vec<f64> {
+        match *self {
+            Self::Gray(g) => vec![g],
+            Self::Rgb(r, g, b) => vec![r, g, b],
+            Self::Cmyk(c, m, y, k) => vec![c, m, y, k],
+        }
+    }
+
+    /// Returns the colour array, e.g. `[1 0 0]`.
+    pub fn to_pdf_string(&self) -> String {
+        let c = self.components().iter().map(|e| e.to_string()).collect::<Vec<String>>().join(" ");
+        format!("[{c}]")
+    }
+
+    /// Returns the operator setting this colour for stroking, e.g. `1 0 0 RG`.
+    pub fn to_stroke_operator(&self) -> String {
+        self.to_operator(true)
+    }
+
+    /// Returns the operator setting this colour for filling, e.g. `1 0 0 rg`.
+    pub fn to_fill_operator(&self) -> String {
+        self.to_operator(false)
+    }
+
+    /// Returns the `G`, `RG` or `K` operation.
+    pub fn to_stroke_operation(&self) -> Operation {
+        match *self {
+            Self::Gray(g) => Operation::SetStrokeGray(g),
+            Self::Rgb(r, g, b) => Operation::SetStrokeRgb(r, g, b),
+            Self::Cmyk(c, m, y, k) => Operation::SetStrokeCmyk(c, m, y, k),
+        }
+    }
+
+    /// Returns the `g`, `rg` or `k` operation.
+    pub fn to_fill_operation(&self) -> Operation {
+        match *self {
+            Self::Gray(g) => Operation::SetFillGray(g),
+            Self::Rgb(r, g, b) => Operation::SetFillRgb(r, g, b),
+            Self::Cmyk(c, m, y, k) => Operation::SetFillCmyk(c, m, y, k),
+        }
+    }
+
+    fn to_operator(self, stroke: bool) -> String {
+        let op = match (self, stroke) {
+            (Self::Gray(_), true) => "G",
+            (Self::Gray(_), false) => "g",
+            (Self::Rgb(..), true) => "RG",
+            (Self::Rgb(..), false) => "rg",
+            (Self::Cmyk(..), true) => "K",
+            (Self::Cmyk(..), false) => "k",
+        };
+        let c = self.components().iter().map(|e| e.to_string()).collect::<Vec<String>>().join(" ");
+        format!("{c} {op}")
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_pdf_string() {
+        assert_eq!(DeviceColour::Gray(0.5).to_pdf_string(), "[0.5]");
+        assert_eq!(DeviceColour::Rgb(1.0, 0.0, 0.25).to_pdf_string(), "[1 0 0.25]");
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(DeviceColour::Cmyk(0.0, 0.0, 0.0, 1.0).to_fill_operator(), "0 0 0 1 k");
+        assert_eq!(DeviceColour::Rgb(1.0, 0.0, 0.0).to_stroke_operator(), "1 0 0 RG");
+    }
+}
@@ -5,4 +5,8 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+mod device_colour;
+pub use device_colour::DeviceColour;
+
+pub mod convert;
 pub mod space;
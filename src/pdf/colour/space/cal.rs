@@ -0,0 +1,182 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::cie::{self, D65};
+use super::super::super::error::Error;
+use super::super::super::utils::ToPdfString;
+
+/// PDF32000-1:2008 8.6.5.2
+///
+/// ```text
+/// [
+///   /CalGray
+///   <<
+///     /WhitePoint [0.9505 1.0 1.089]
+///     /BlackPoint [0.0 0.0 0.0]
+///     /Gamma 2.2
+///   >>
+/// ]
+/// ```
+#[derive(Clone)]
+pub struct CalGray {
+    white: [f64; 3],
+    black: [f64; 3],
+    gamma: f64,
+}
+
+impl CalGray {
+    /// Fails if the points are invalid or the gamma is not positive.
+    pub fn new(white: [f64; 3], black: [f64; 3], gamma: f64) -> Result<Self, Error> {
+        cie::check_points(&white, &black)?;
+        check_gamma(&[gamma])?;
+        Ok(Self { white, black, gamma })
+    }
+
+    /// Returns the gray of gamma 2.2 under D65 approximating sRGB.
+    pub fn srgb() -> Self {
+        Self { white: D65, black: [0.0; 3], gamma: 2.2 }
+    }
+
+    pub fn white(&self) -> &[f64; 3] {
+        &self.white
+    }
+
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let entries = [("Gamma", self.gamma.to_pdf_string())];
+        cie::to_pdf_string("CalGray", &self.white, &self.black, &entries, indent_depth)
+    }
+}
+
+/// PDF32000-1:2008 8.6.5.3
+///
+/// ```text
+/// [
+///   /CalRGB
+///   <<
+///     /WhitePoint [0.9505 1.0 1.089]
+///     /BlackPoint [0.0 0.0 0.0]
+///     /Gamma [1.8 1.8 1.8]
+///     /Matrix [0.4497 0.2446 0.0252 0.3163 0.672 0.1412 0.1845 0.0833 0.9227]
+///   >>
+/// ]
+/// ```
+#[derive(Clone)]
+pub struct CalRGB {
+    white: [f64; 3],
+    black: [f64; 3],
+    gamma: [f64; 3],
+    matrix: [f64; 9],
+}
+
+impl CalRGB {
+    /// Creates the space with the gamma of each component and the matrix of
+    /// the XYZ of red, green and blue at full intensity, in the order
+    /// `XA YA ZA XB YB ZB XC YC ZC`.
+    ///
+    /// Fails if the points are invalid or any gamma is not positive.
+    pub fn new(white: [f64; 3], black: [f64; 3], gamma: [f64; 3], matrix: [f64; 9]) -> Result<Self, Error> {
+        cie::check_points(&white, &black)?;
+        check_gamma(&gamma)?;
+        Ok(Self { white, black, gamma, matrix })
+    }
+
+    /// Returns the space of the sRGB primaries under D65 with gamma 2.2, an
+    /// approximation of sRGB whose curve has a linear part near black.
+    pub fn srgb() -> Self {
+        Self {
+            white: D65,
+            black: [0.0; 3],
+            gamma: [2.2; 3],
+            matrix: [0.4124, 0.2126, 0.0193, 0.3576, 0.7152, 0.1192, 0.1805, 0.0722, 0.9505],
+        }
+    }
+
+    pub fn white(&self) -> &[f64; 3] {
+        &self.white
+    }
+
+    pub fn gamma(&self) -> &[f64; 3] {
+        &self.gamma
+    }
+
+    pub fn matrix(&self) -> &[f64; 9] {
+        &self.matrix
+    }
+
+    pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let entries = [
+            ("Gamma", self.gamma.to_pdf_string()),
+            ("Matrix", self.matrix.to_pdf_string()),
+        ];
+        cie::to_pdf_string("CalRGB", &self.white, &self.black, &entries, indent_depth)
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn check_gamma(gamma: &[f64]) -> Result<(), Error> {
+    if gamma.iter().any(|g| *g <= 0.0) {
+        return Err(Error::InvalidValue(format!("Gamma {}", gamma.to_vec().to_pdf_string())));
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::D50;
+
+    #[test]
+    fn cal_gray() {
+        let gray = CalGray::new(D50, [0.0; 3], 1.8).unwrap();
+        let ok = concat!(
+            "[\n",
+            "  /CalGray\n",
+            "  <<\n",
+            "    /WhitePoint [0.9642 1 0.8249]\n",
+            "    /BlackPoint [0 0 0]\n",
+            "    /Gamma 1.8\n",
+            "  >>\n",
+            "]",
+        );
+        assert_eq!(gray.to_pdf_string(0), ok);
+
+        assert!(CalGray::new([0.9642, 0.9, 0.8249], [0.0; 3], 1.8).is_err());
+        assert!(CalGray::new(D50, [-0.1, 0.0, 0.0], 1.8).is_err());
+        assert!(CalGray::new(D50, [0.0; 3], 0.0).is_err());
+    }
+
+    #[test]
+    fn cal_rgb() {
+        let ok = concat!(
+            "  [\n",
+            "    /CalRGB\n",
+            "    <<\n",
+            "      /WhitePoint [0.9505 1 1.089]\n",
+            "      /BlackPoint [0 0 0]\n",
+            "      /Gamma [2.2 2.2 2.2]\n",
+            "      /Matrix [0.4124 0.2126 0.0193 0.3576 0.7152 0.1192 0.1805 0.0722 0.9505]\n",
+            "    >>\n",
+            "  ]",
+        );
+        assert_eq!(CalRGB::srgb().to_pdf_string(1), ok);
+
+        let matrix = [0.4497, 0.2446, 0.0252, 0.3163, 0.672, 0.1412, 0.1845, 0.0833, 0.9227];
+        assert!(CalRGB::new(D65, [0.0; 3], [1.8; 3], matrix).is_ok());
+        assert!(CalRGB::new(D65, [0.0; 3], [1.8, -1.0, 1.8], matrix).is_err());
+    }
+}
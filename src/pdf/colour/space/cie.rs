@@ -0,0 +1,56 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! The parts shared by the CIE-based spaces, `CalGray`, `CalRGB` and `Lab`.
+
+use super::super::super::error::Error;
+use super::super::super::utils::{indent, ToPdfString};
+
+/// The CIE 1931 XYZ of the D50 illuminant, the white of the ICC PCS.
+pub const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// The CIE 1931 XYZ of the D65 illuminant, the white of sRGB.
+pub const D65: [f64; 3] = [0.9505, 1.0, 1.089];
+
+/// Checks the WhitePoint and the BlackPoint (PDF32000-1:2008 Table 63).
+/// X and Z of the white shall be positive and Y 1.0, the black shall not be
+/// negative.
+pub(super) fn check_points(white: &[f64; 3], black: &[f64; 3]) -> Result<(), Error> {
+    if white[0] <= 0.0 || white[1] != 1.0 || white[2] <= 0.0 {
+        return Err(Error::InvalidValue(format!("WhitePoint {}", white.to_pdf_string())));
+    }
+    if black.iter().any(|v| *v < 0.0) {
+        return Err(Error::InvalidValue(format!("BlackPoint {}", black.to_pdf_string())));
+    }
+    Ok(())
+}
+
+/// Returns the array of the space with the dictionary of the points and
+/// the other entries.
+pub(super) fn to_pdf_string(
+    family: &str,
+    white: &[f64; 3],
+    black: &[f64; 3],
+    entries: &[(&str, String)],
+    indent_depth: usize,
+) -> String {
+    let mut s = format!(concat!(
+        "[\n",
+        "  /{}\n",
+        "  <<\n",
+        "    /WhitePoint {}\n",
+        "    /BlackPoint {}\n"),
+        family,
+        white.to_pdf_string(),
+        black.to_pdf_string(),
+    );
+    for (key, value) in entries {
+        s.push_str(&format!("    /{} {}\n", key, value));
+    }
+    s.push_str("  >>\n]");
+    indent(&s, indent_depth)
+}
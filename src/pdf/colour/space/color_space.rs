@@ -0,0 +1,252 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use crate::pdf::PdfObject;
+
+use super::*;
+use super::super::super::base::*;
+use super::super::super::utils::indent;
+
+/// PDF32000-1:2008 8.6 Colour Spaces
+///
+/// Any colour space usable as a resource with `cs`/`CS`. `Separation`,
+/// `DeviceN` and `ICCBased` spaces are written as objects of their own, the
+/// others inline.
+#[derive(Clone)]
+pub enum ColorSpace {
+    DeviceGray,
+    DeviceRGB,
+    DeviceCMYK,
+    CalGray(CalGray),
+    CalRGB(CalRGB),
+    Lab(Lab),
+    IccBased(IccBased),
+    Indexed(Box<Indexed>),
+    /// The underlying space of uncoloured tiling patterns, if any
+    Pattern(Option<Box<ColorSpace>>),
+    Separation(Box<Separation>),
+    DeviceN(Box<DeviceN>),
+}
+
+impl ColorSpace {
+    /// Returns the colour space family name, e.g. `ICCBased`.
+    pub fn family(&self) -> &'static str {
+        match self {
+            Self::DeviceGray => "DeviceGray",
+            Self::DeviceRGB => "DeviceRGB",
+            Self::DeviceCMYK => "DeviceCMYK",
+            Self::CalGray(_) => "CalGray",
+            Self::CalRGB(_) => "CalRGB",
+            Self::Lab(_) => "Lab",
+            Self::IccBased(_) => "ICCBased",
+            Self::Indexed(_) => "Indexed",
+            Self::Pattern(_) => "Pattern",
+            Self::Separation(_) => "Separation",
+            Self::DeviceN(_) => "DeviceN",
+        }
+    }
+
+    /// Returns the number of colour components. A pattern space has the
+    /// components of its underlying space, none for coloured patterns.
+    pub fn components(&self) -> usize {
+        match self {
+            Self::DeviceGray | Self::CalGray(_) | Self::Indexed(_) | Self::Separation(_) => 1,
+            Self::DeviceRGB | Self::CalRGB(_) | Self::Lab(_) => 3,
+            Self::DeviceCMYK => 4,
+            Self::IccBased(space) => space.components(),
+            Self::Pattern(base) => base.as_ref().map_or(0, |b| b.components()),
+            Self::DeviceN(space) => space.names().len(),
+        }
+    }
+
+    /// Returns the default `/Decode` ranges of image samples in the space
+    /// (PDF32000-1:2008 Table 90). Patterns cannot be used for images.
+    pub fn default_decode(&self, bits_per_component: u8) -> Vec<(f64, f64)> {
+        match self {
+            Self::Lab(space) => {
+                let range = space.range();
+                vec![(0.0, 100.0), (range[0], range[1]), (range[2], range[3])]
+            }
+            Self::IccBased(space) => space.range().to_vec(),
+            Self::Indexed(_) => vec![(0.0, ((1u32 << bits_per_component.min(16)) - 1) as f64)],
+            Self::Pattern(_) => Vec::new(),
+            _ => vec![(0.0, 1.0); self.components()],
+        }
+    }
+
+    /// Returns the colour set by selecting the space with `cs`/`CS`
+    /// (PDF32000-1:2008 8.6.3). The initial colour of a pattern space paints
+    /// nothing and has no components.
+    pub fn initial_color(&self) -> Vec<f64> {
+        match self {
+            Self::DeviceCMYK => vec![0.0, 0.0, 0.0, 1.0],
+            Self::Lab(_) | Self::IccBased(_) => self.default_decode(8).iter().map(|(min, max)| 0.0f64.clamp(*min, *max)).collect(),
+            Self::Pattern(_) => Vec::new(),
+            Self::Separation(_) | Self::DeviceN(_) => vec![1.0; self.components()],
+            _ => vec![0.0; self.components()],
+        }
+    }
+
+    /// Returns the name or the array of the space, or the reference to it.
+    pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        match self {
+            Self::DeviceGray | Self::DeviceRGB | Self::DeviceCMYK | Self::Pattern(None) => {
+                indent(&format!("/{}", self.family()), indent_depth)
+            }
+            Self::CalGray(space) => space.to_pdf_string(indent_depth),
+            Self::CalRGB(space) => space.to_pdf_string(indent_depth),
+            Self::Lab(space) => space.to_pdf_string(indent_depth),
+            Self::IccBased(space) => indent(&space.to_pdf_string(), indent_depth),
+            Self::Indexed(space) => space.to_pdf_string(indent_depth),
+            Self::Pattern(Some(base)) => indent(&format!(concat!(
+                "[\n",
+                "  /Pattern\n",
+                "{}\n",
+                "]"),
+                base.to_pdf_string(1),
+            ), indent_depth),
+            Self::Separation(space) => indent(&space.id().to_ref_string(), indent_depth),
+            Self::DeviceN(space) => indent(&space.id().to_ref_string(), indent_depth),
+        }
+    }
+
+    pub fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        match self {
+            Self::IccBased(space) => space.assign_ids(id_factory),
+            Self::Indexed(space) => space.assign_ids(id_factory),
+            Self::Pattern(Some(base)) => base.assign_ids(id_factory),
+            Self::Separation(space) => space.assign_ids(id_factory),
+            Self::DeviceN(space) => space.assign_ids(id_factory),
+            _ => (),
+        }
+    }
+
+    /// Returns the objects written for the space.
+    pub fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        match self {
+            Self::IccBased(space) => space.get_objects(),
+            Self::Indexed(space) => space.get_objects(),
+            Self::Pattern(Some(base)) => base.get_objects(),
+            Self::Separation(space) => space.get_objects(),
+            Self::DeviceN(space) => space.get_objects(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl From<DeviceGray> for ColorSpace {
+    fn from(_: DeviceGray) -> Self {
+        Self::DeviceGray
+    }
+}
+
+impl From<DeviceRGB> for ColorSpace {
+    fn from(_: DeviceRGB) -> Self {
+        Self::DeviceRGB
+    }
+}
+
+impl From<DeviceCMYK> for ColorSpace {
+    fn from(_: DeviceCMYK) -> Self {
+        Self::DeviceCMYK
+    }
+}
+
+impl From<CalGray> for ColorSpace {
+    fn from(space: CalGray) -> Self {
+        Self::CalGray(space)
+    }
+}
+
+impl From<CalRGB> for ColorSpace {
+    fn from(space: CalRGB) -> Self {
+        Self::CalRGB(space)
+    }
+}
+
+impl From<Lab> for ColorSpace {
+    fn from(space: Lab) -> Self {
+        Self::Lab(space)
+    }
+}
+
+impl From<IccBased> for ColorSpace {
+    fn from(space: IccBased) -> Self {
+        Self::IccBased(space)
+    }
+}
+
+impl From<Indexed> for ColorSpace {
+    fn from(space: Indexed) -> Self {
+        Self::Indexed(Box::new(space))
+    }
+}
+
+impl From<Separation> for ColorSpace {
+    fn from(space: Separation) -> Self {
+        Self::Separation(Box::new(space))
+    }
+}
+
+impl From<DeviceN> for ColorSpace {
+    fn from(space: DeviceN) -> Self {
+        Self::DeviceN(Box::new(space))
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::function::Type2;
+
+    #[test]
+    fn components() {
+        let lab = Lab::new([0.9505, 1.0, 1.089], [0.0; 3], [-100.0, 100.0, -50.0, 50.0]).unwrap();
+        let separation = Separation::new("Orange", lab.clone(), Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0));
+        let mut icc = IccBased::new(4, Vec::new());
+        icc.set_alternate(DeviceCMYK {});
+        let indexed = Indexed::new(DeviceRGB {}, 1, vec![255, 0, 0, 0, 0, 255]).unwrap();
+
+        let spaces: Vec<ColorSpace> = vec![
+            DeviceGray {}.into(),
+            DeviceCMYK {}.into(),
+            lab.into(),
+            icc.into(),
+            indexed.into(),
+            ColorSpace::Pattern(Some(Box::new(ColorSpace::DeviceRGB))),
+            separation.into(),
+        ];
+        let components: Vec<usize> = spaces.iter().map(|s| s.components()).collect();
+        assert_eq!(components, [1, 4, 3, 4, 1, 3, 1]);
+
+        assert_eq!(spaces[1].initial_color(), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(spaces[2].initial_color(), [0.0, 0.0, 0.0]);
+        assert_eq!(spaces[2].default_decode(8), [(0.0, 100.0), (-100.0, 100.0), (-50.0, 50.0)]);
+        assert_eq!(spaces[4].default_decode(4), [(0.0, 15.0)]);
+        assert_eq!(spaces[6].initial_color(), [1.0]);
+        assert!(ColorSpace::Pattern(None).initial_color().is_empty());
+    }
+
+    #[test]
+    fn to_pdf_string() {
+        let indexed = ColorSpace::from(Indexed::new(DeviceRGB {}, 1, vec![255, 0, 0, 0, 0, 255]).unwrap());
+        let ok = concat!(
+            "[\n",
+            "  /Indexed\n",
+            "  /DeviceRGB\n",
+            "  1\n",
+            "  <ff00000000ff>\n",
+            "]",
+        );
+        assert_eq!(indexed.to_pdf_string(0), ok);
+        assert_eq!(ColorSpace::Pattern(None).to_pdf_string(0), "/Pattern");
+    }
+}
@@ -5,6 +5,17 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+#[derive(Clone)]
+pub struct DeviceGray {
+}
+
+impl DeviceGray {
+    pub fn to_pdf_string(&self) -> String {
+        String::from("/DeviceGray")
+    }
+}
+
+#[derive(Clone)]
 pub struct DeviceRGB {
 }
 
@@ -14,6 +25,7 @@ impl DeviceRGB {
     }
 }
 
+#[derive(Clone)]
 pub struct DeviceCMYK {
 }
 
@@ -9,9 +9,10 @@ use std::collections::HashMap;
 
 use crate::pdf::PdfObject;
 
-use super::{DeviceCMYK, Separation};
+use super::{ColorSpace, Separation};
 use super::super::super::base::*;
-use super::super::super::function;
+use super::super::super::error::Error;
+use super::super::super::function::Function;
 use super::super::super::utils::*;
 
 /// PDF32000-1:2008 8.6.6.5
@@ -41,28 +42,12 @@ use super::super::super::utils::*;
 ///   <<
 ///     /Subtype /NChannel
 ///     /Colorants <<
-///       /Orange [
-///         /Separation
-///         /Orange
-///         [
-///           /Lab
-///           <<
-///             /WhitePoint [0.964203 1.0 0.824905]
-///             /BlackPoint [0.0 0.0 0.0]
-///             /Range [-100 100 -100 100]
-///           >>
-///         ]
-///         <<
-///           /FunctionType 2
-///           /C0 [100.0 0.0 0.0]
-///           /C1 [65.0 58.0 88.0]
-///           /N 1.0
-///         >>
-///       ]
+///       /Orange
+///       7 0 R
 ///       /Green
-///       ...
+///       8 0 R
 ///       /Violet
-///       ...
+///       9 0 R
 ///     >>
 ///     /Process <<
 ///       /ColorSpace /DeviceCMYK
@@ -71,41 +56,69 @@ use super::super::super::utils::*;
 ///   >>
 /// ]
 /// endobj
+#[derive(Clone)]
 pub struct DeviceN {
     id: Id,
     names: Vec<Name>,
-    alt_space: DeviceCMYK,
-    tint_transform: function::Type0,
-    attributes: NChannel,
+    alt_space: ColorSpace,
+    tint_transform: Box<dyn Function>,
+    attributes: Option<DeviceNAttributes>,
 }
 
 impl DeviceN {
-    pub fn new(names: Vec<&str>, tint_transform: function::Type0, attributes: NChannel) -> Self {
+    /// Creates the space without the attributes dictionary.
+    pub fn new(names: Vec<&str>, alt_space: impl Into<ColorSpace>, tint_transform: impl Function + 'static) -> Self {
         Self {
             id: Id::new_0(),
             names: names.iter().map(|e| Name::new(e)).collect(),
-            alt_space: DeviceCMYK {},
-            tint_transform,
-            attributes
+            alt_space: alt_space.into(),
+            tint_transform: Box::new(tint_transform),
+            attributes: None,
         }
     }
 
+    /// Returns the colorant names in the order of the components.
+    pub fn names(&self) -> Vec<&str> {
+        self.names.iter().map(|n| n.as_str()).collect()
+    }
+
+    pub fn alt_space(&self) -> &ColorSpace {
+        &self.alt_space
+    }
+
+    pub fn tint_transform(&self) -> &dyn Function {
+        self.tint_transform.as_ref()
+    }
+
+    pub fn attributes(&self) -> Option<&DeviceNAttributes> {
+        self.attributes.as_ref()
+    }
+
+    pub fn set_attributes(&mut self, attributes: DeviceNAttributes) {
+        self.attributes = Some(attributes);
+    }
+
     pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let attributes = match &self.attributes {
+            Some(attributes) => format!("{}\n", attributes.to_pdf_string(1)),
+            None => String::new(),
+        };
+
         indent(&format!(concat!(
             "{} obj\n",
             "[\n",
             "  /DeviceN\n",
             "  {}\n", // names
-            "  {}\n", // alternateSpace
-            "  {}\n", // tintTransform
-            "  {}\n", // attributes
+            "{}\n", // alternateSpace
+            "{}\n", // tintTransform
+            "{}", // attributes
             "]\n",
             "endobj"),
             self.id.to_string(),
             self.names.to_pdf_string(),
-            self.alt_space.to_pdf_string(),
-            self.tint_transform.id().to_ref_string(),
-            self.attributes.to_pdf_string(indent_depth + 1)
+            self.alt_space.to_pdf_string(1),
+            self.tint_transform.to_pdf_string(1),
+            attributes,
         ), indent_depth)
     }
 }
@@ -116,12 +129,23 @@ impl PdfObject for DeviceN {
     }
 
     fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        if let Some(attributes) = &mut self.attributes {
+            attributes.assign_ids(id_factory);
+        }
+        self.alt_space.assign_ids(id_factory);
         self.tint_transform.assign_ids(id_factory);
         self.id = id_factory.next_id()
     }
 
     fn get_objects(&self) -> Vec<&dyn PdfObject> {
-        vec![&self.tint_transform, self]
+        let mut list = match &self.attributes {
+            Some(attributes) => attributes.get_objects(),
+            None => Vec::new(),
+        };
+        list.append(&mut self.alt_space.get_objects());
+        list.append(&mut self.tint_transform.get_objects());
+        list.push(self);
+        list
     }
 
     fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
@@ -129,56 +153,321 @@ impl PdfObject for DeviceN {
     }
 }
 
-pub struct NChannel {
+/// PDF32000-1:2008 Table 71
+///
+/// The attributes dictionary of a DeviceN space. The `NChannel` subtype
+/// requires the colorants of every spot component and the process space if
+/// the space has process components.
+#[derive(Clone)]
+pub struct DeviceNAttributes {
+    nchannel: bool,
     colorants: HashMap<Name, Separation>,
-    process_space: DeviceCMYK,
-    process_component_names: Vec<Name>,
+    process: Option<(ColorSpace, Vec<Name>)>,
+    mixing_hints: Option<MixingHints>,
 }
 
-impl NChannel {
-    pub fn new(colorants: HashMap<&str, Separation>, process_component_names: Vec<&str>) -> Self {
-        let mut m: HashMap<Name, Separation> = HashMap::new();
-        for c in colorants {
-            m.insert(Name::new(c.0), c.1);
+impl DeviceNAttributes {
+    /// Creates the `/DeviceN` subtype attributes.
+    pub fn new(colorants: HashMap<&str, Separation>) -> Self {
+        DeviceNAttributes {
+            nchannel: false,
+            colorants: colorants.into_iter().map(|(n, s)| (Name::new(n), s)).collect(),
+            process: None,
+            mixing_hints: None,
         }
+    }
 
-        NChannel {
-            colorants: m,
-            process_space: DeviceCMYK{},
-            process_component_names: process_component_names.iter().map(|e| Name::new(e)).collect(),
-        }
+    /// Creates the `/NChannel` subtype attributes with the process space and
+    /// the names of its components, e.g. `DeviceCMYK` and `Cyan`, ...
+    pub fn nchannel(
+        colorants: HashMap<&str, Separation>,
+        process_space: impl Into<ColorSpace>,
+        process_component_names: Vec<&str>,
+    ) -> Self {
+        let mut attributes = Self::new(colorants);
+        attributes.nchannel = true;
+        attributes.set_process(process_space, process_component_names);
+        attributes
+    }
+
+    pub fn is_nchannel(&self) -> bool {
+        self.nchannel
     }
 
-    fn get_colorants_pdf_string(&self) -> String {
-        let mut s = String::new();
-        s.push_str("<<\n");
-        for c in &self.colorants {
+    pub fn colorant(&self, name: &str) -> Option<&Separation> {
+        self.colorants.get(&Name::new(name))
+    }
+
+    pub fn set_process(&mut self, space: impl Into<ColorSpace>, component_names: Vec<&str>) {
+        let names = component_names.iter().map(|e| Name::new(e)).collect();
+        self.process = Some((space.into(), names));
+    }
+
+    pub fn mixing_hints(&self) -> Option<&MixingHints> {
+        self.mixing_hints.as_ref()
+    }
+
+    pub fn set_mixing_hints(&mut self, mixing_hints: MixingHints) {
+        self.mixing_hints = Some(mixing_hints);
+    }
+
+    pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let mut s = String::from("<<\n");
+        let subtype = if self.nchannel { "NChannel" } else { "DeviceN" };
+        s.push_str(&format!("  /Subtype /{}\n", subtype));
+        if !self.colorants.is_empty() {
+            s.push_str("  /Colorants\n");
+            s.push_str("  <<\n");
+            for (name, separation) in sorted(&self.colorants) {
+                s.push_str(&format!("    {}\n    {}\n", name, separation.id().to_ref_string()));
+            }
+            s.push_str("  >>\n");
+        }
+        if let Some((space, names)) = &self.process {
             s.push_str(&format!(concat!(
-                "  {}\n",
-                "{}\n"
-            ), c.0.to_pdf_string(), c.1.to_pdf_string(2)
-        ));
+                "  /Process\n",
+                "  <<\n",
+                "    /ColorSpace\n",
+                "{}\n",
+                "    /Components {}\n",
+                "  >>\n"),
+                space.to_pdf_string(2),
+                names.to_pdf_string(),
+            ));
+        }
+        if let Some(mixing_hints) = &self.mixing_hints {
+            s.push_str(&format!("  /MixingHints\n{}\n", mixing_hints.to_pdf_string(1)));
         }
         s.push_str(">>");
-        s
+        indent(&s, indent_depth)
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        for c in self.colorants.values_mut() {
+            c.assign_ids(id_factory);
+        }
+        if let Some((space, _)) = &mut self.process {
+            space.assign_ids(id_factory);
+        }
+        if let Some(mixing_hints) = &mut self.mixing_hints {
+            for f in mixing_hints.dot_gain.values_mut() {
+                f.assign_ids(id_factory);
+            }
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = Vec::new();
+        for (_, c) in sorted(&self.colorants) {
+            list.append(&mut c.get_objects());
+        }
+        if let Some((space, _)) = &self.process {
+            list.append(&mut space.get_objects());
+        }
+        if let Some(mixing_hints) = &self.mixing_hints {
+            for (_, f) in sorted(&mixing_hints.dot_gain) {
+                list.append(&mut f.get_objects());
+            }
+        }
+        list
+    }
+}
+
+/// PDF32000-1:2008 Table 72
+///
+/// Hints how the colorants of a DeviceN space mix when the space is
+/// rendered without the separations, e.g. for the preview.
+#[derive(Clone, Default)]
+pub struct MixingHints {
+    solidities: HashMap<Name, f64>,
+    printing_order: Vec<Name>,
+    dot_gain: HashMap<Name, Box<dyn Function>>,
+}
+
+impl MixingHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the solidity of the colorant, 0.0 (transparent) to 1.0 (opaque).
+    /// The name may be `/Default` for the colorants not listed.
+    pub fn set_solidity(&mut self, name: &str, solidity: f64) -> Result<(), Error> {
+        if !(0.0..=1.0).contains(&solidity) {
+            return Err(Error::InvalidValue(format!("solidity out of range: {}", solidity)));
+        }
+        self.solidities.insert(Name::new(name), solidity);
+        Ok(())
+    }
+
+    pub fn solidity(&self, name: &str) -> Option<f64> {
+        self.solidities.get(&Name::new(name)).copied()
+    }
+
+    /// Sets the order the colorants are printed in, the first is printed
+    /// first. Required if `/Solidities` is present.
+    pub fn set_printing_order(&mut self, names: Vec<&str>) {
+        self.printing_order = names.iter().map(|e| Name::new(e)).collect();
+    }
+
+    /// Sets the function mapping a tint to the dot gain of the colorant.
+    pub fn set_dot_gain(&mut self, name: &str, function: impl Function + 'static) {
+        self.dot_gain.insert(Name::new(name), Box::new(function));
     }
 
     pub fn to_pdf_string(&self, indent_depth: usize) -> String {
-        indent(&format!(concat!(
-            "<<\n",
-            "  /Subtype /NChannel\n",
-            "  /Colorants\n",
-            "  {}\n",
-            "  /Process\n",
+        let mut s = String::from("<<\n");
+        if !self.solidities.is_empty() {
+            s.push_str("  /Solidities\n");
+            s.push_str("  <<\n");
+            for (name, solidity) in sorted(&self.solidities) {
+                s.push_str(&format!("    {} {}\n", name, solidity.to_pdf_string()));
+            }
+            s.push_str("  >>\n");
+        }
+        if !self.printing_order.is_empty() {
+            s.push_str(&format!("  /PrintingOrder {}\n", self.printing_order.to_pdf_string()));
+        }
+        if !self.dot_gain.is_empty() {
+            s.push_str("  /DotGain\n");
+            s.push_str("  <<\n");
+            for (name, function) in sorted(&self.dot_gain) {
+                s.push_str(&format!("    {}\n{}\n", name, function.to_pdf_string(2)));
+            }
+            s.push_str("  >>\n");
+        }
+        s.push_str(">>");
+        indent(&s, indent_depth)
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Returns the entries in the name order to write the dictionaries stably.
+fn sorted<T>(map: &HashMap<Name, T>) -> Vec<(&Name, &T)> {
+    let mut list: Vec<(&Name, &T)> = map.iter().collect();
+    list.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    list
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::{IccBased, Lab};
+    use crate::pdf::function::{Type0, Type2};
+
+    #[test]
+    fn without_attributes() {
+        let tint = Type2::new([1.0, 1.0, 1.0], [0.0, 0.5, 0.0], 1.0);
+        let mut devicen = DeviceN::new(vec!["Spot1", "Spot2"], ColorSpace::DeviceRGB, tint);
+        let mut id_factory = IdFactory::new();
+        devicen.assign_ids(&mut id_factory);
+
+        let ok = concat!(
+            "1 0 obj\n",
+            "[\n",
+            "  /DeviceN\n",
+            "  [/Spot1 /Spot2]\n",
+            "  /DeviceRGB\n",
+            "  <<\n",
+            "    /FunctionType 2\n",
+            "    /Domain [0 1]\n",
+            "    /C0 [1 1 1]\n",
+            "    /C1 [0 0.5 0]\n",
+            "    /N 1\n",
+            "  >>\n",
+            "]\n",
+            "endobj",
+        );
+        assert_eq!(String::from_utf8(devicen.to_bytes(0)).unwrap(), ok);
+        assert_eq!(devicen.get_objects().len(), 1);
+    }
+
+    #[test]
+    fn attributes() {
+        let lab = Lab::new_with_white(0.964203, 1.0, 0.824905).unwrap();
+        let orange = Separation::new("Orange", lab.clone(), Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0));
+        let tint = Type0::new([(0.0, 1.0); 2], [(0.0, 100.0), (-128.0, 127.0), (-128.0, 127.0)], [2, 2], 8, vec![0; 12]);
+        let mut devicen = DeviceN::new(vec!["Orange", "Black"], lab, tint);
+
+        let mut attributes = DeviceNAttributes::new(HashMap::from([("Orange", orange)]));
+        let mut hints = MixingHints::new();
+        hints.set_solidity("Orange", 0.2).unwrap();
+        hints.set_solidity("Default", 0.0).unwrap();
+        assert!(hints.set_solidity("Black", 1.5).is_err());
+        hints.set_printing_order(vec!["Orange", "Black"]);
+        hints.set_dot_gain("Orange", Type2::new([0.0], [1.0], 0.8));
+        attributes.set_mixing_hints(hints);
+        devicen.set_attributes(attributes);
+
+        let mut id_factory = IdFactory::new();
+        devicen.assign_ids(&mut id_factory);
+        // The separation, the sampled function and the DeviceN
+        assert_eq!(devicen.get_objects().len(), 3);
+
+        let ok = concat!(
+            "  <<\n",
+            "    /Subtype /DeviceN\n",
+            "    /Colorants\n",
             "    <<\n",
-            "      /ColorSpace {}\n",
-            "      /Components {}\n",
+            "      /Orange\n",
+            "      1 0 R\n",
             "    >>\n",
-            "\n",
-            ">>"),
-            self.get_colorants_pdf_string(),
-            self.process_space.to_pdf_string(),
-            self.process_component_names.to_pdf_string(),
-        ), indent_depth)
+            "    /MixingHints\n",
+            "    <<\n",
+            "      /Solidities\n",
+            "      <<\n",
+            "        /Default 0\n",
+            "        /Orange 0.2\n",
+            "      >>\n",
+            "      /PrintingOrder [/Orange /Black]\n",
+            "      /DotGain\n",
+            "      <<\n",
+            "        /Orange\n",
+            "        <<\n",
+            "          /FunctionType 2\n",
+            "          /Domain [0 1]\n",
+            "          /C0 [0]\n",
+            "          /C1 [1]\n",
+            "          /N 0.8\n",
+            "        >>\n",
+            "      >>\n",
+            "    >>\n",
+            "  >>\n",
+            "]",
+        );
+        let s = devicen.to_pdf_string(0);
+        assert!(s.contains("  ]\n  2 0 R\n"), "{s}");
+        assert!(s.contains(ok), "{s}");
+    }
+
+    #[test]
+    fn nchannel() {
+        let icc = IccBased::new(4, vec![0; 128]);
+        let attributes = DeviceNAttributes::nchannel(HashMap::new(), icc.clone(), vec!["Cyan", "Magenta", "Yellow", "Black"]);
+        let tint = Type2::new([0.0; 4], [1.0; 4], 1.0);
+        let mut devicen = DeviceN::new(vec!["Cyan"], icc, tint);
+        devicen.set_attributes(attributes);
+
+        let mut id_factory = IdFactory::new();
+        devicen.assign_ids(&mut id_factory);
+        assert!(devicen.attributes().unwrap().is_nchannel());
+
+        let s = devicen.to_pdf_string(0);
+        assert!(s.contains("  [/ICCBased 2 0 R]\n"), "{s}");
+        assert!(s.contains(concat!(
+            "    /Subtype /NChannel\n",
+            "    /Process\n",
+            "    <<\n",
+            "      /ColorSpace\n",
+            "      [/ICCBased 1 0 R]\n",
+            "      /Components [/Cyan /Magenta /Yellow /Black]\n",
+            "    >>\n",
+        )), "{s}");
     }
 }
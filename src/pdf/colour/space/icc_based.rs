@@ -0,0 +1,308 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use crate::pdf::PdfObject;
+
+use super::{ColorSpace, DeviceCMYK, DeviceGray, DeviceRGB};
+use super::super::super::base::*;
+use super::super::super::error::Error;
+use super::super::super::utils::{dict_string, ToPdfString};
+
+/// PDF32000-1:2008 8.6.5.5
+///
+/// The profile is written as a stream object referenced by the colour space.
+///
+/// ```text
+/// 12 0 obj
+/// <<
+///   /N 4
+///   /Alternate /DeviceCMYK
+///   /Range [0 1 0 1 0 1 0 1]
+///   /Length 557168
+/// >>
+/// stream
+/// ... ICC profile
+/// endstream
+/// endobj
+///
+/// [/ICCBased 12 0 R]
+/// ```
+#[derive(Clone)]
+pub struct IccBased {
+    id: Id,
+    components: usize,
+    alternate: Option<Box<ColorSpace>>,
+    range: Vec<(f64, f64)>,
+    profile: Vec<u8>,
+}
+
+impl IccBased {
+    /// Creates the colour space of the profile data with 1, 3 or 4 components.
+    /// The profile is not checked, see [`IccBased::from_profile`].
+    pub fn new(components: usize, profile: Vec<u8>) -> Self {
+        Self {
+            id: Id::new_0(),
+            components,
+            alternate: None,
+            range: vec![(0.0, 1.0); components],
+            profile,
+        }
+    }
+
+    /// Creates the colour space of the profile, taking the number of
+    /// components from the colour space signature in the header. The
+    /// alternate is set to the device space with the same components and the
+    /// range of a Lab profile to the default of the Lab space.
+    ///
+    /// Fails if the header is broken, the profile is a device link or
+    /// abstract profile, or the data space is not Gray, RGB, CMYK or Lab.
+    pub fn from_profile(profile: Vec<u8>) -> Result<Self, Error> {
+        let header = IccHeader::parse(&profile)?;
+        if header.class == *b"link" || header.class == *b"abst" {
+            return Err(Error::InvalidValue(format!(
+                "ICC profile class '{}' cannot be used as a colour space", signature_string(&header.class))));
+        }
+        let components = header.components().ok_or_else(|| Error::Unsupported(format!(
+            "ICC profile colour space '{}'", signature_string(&header.color_space))))?;
+
+        let mut space = Self::new(components, profile);
+        match &header.color_space {
+            b"GRAY" => space.set_alternate(DeviceGray {}),
+            b"RGB " => space.set_alternate(DeviceRGB {}),
+            b"CMYK" => space.set_alternate(DeviceCMYK {}),
+            _ => space.set_range(vec![(0.0, 100.0), (-128.0, 127.0), (-128.0, 127.0)]),
+        }
+        Ok(space)
+    }
+
+    /// Parses the header of the profile and checks that it matches the
+    /// number of components of the space.
+    pub fn header(&self) -> Result<IccHeader, Error> {
+        let header = IccHeader::parse(&self.profile)?;
+        if header.components() != Some(self.components) {
+            return Err(Error::InvalidValue(format!(
+                "ICC profile colour space '{}' does not have {} components",
+                signature_string(&header.color_space), self.components)));
+        }
+        Ok(header)
+    }
+
+    /// Checks that the profile version is supported by the PDF version
+    /// (PDF32000-1:2008 Table 67), e.g. up to ICC.1:2004-10 (4.2) for 1.7.
+    pub fn check_version(&self, version: Version) -> Result<(), Error> {
+        let header = self.header()?;
+        let max = match version {
+            Version::V1_4 => (2, 3),
+            Version::V1_7 => (4, 2),
+        };
+        if (header.version.0, header.version.1) > max {
+            return Err(Error::Unsupported(format!(
+                "ICC profile version {}.{} in {}",
+                header.version.0, header.version.1, &version.to_str()[1..])));
+        }
+        Ok(())
+    }
+
+    /// Sets the space used when the profile cannot be used.
+    pub fn set_alternate(&mut self, space: impl Into<ColorSpace>) {
+        self.alternate = Some(Box::new(space.into()));
+    }
+
+    pub fn alternate(&self) -> Option<&ColorSpace> {
+        self.alternate.as_deref()
+    }
+
+    /// Sets the range of each component, `[0 1]` by default.
+    pub fn set_range(&mut self, range: Vec<(f64, f64)>) {
+        self.range = range;
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    pub fn range(&self) -> &[(f64, f64)] {
+        &self.range
+    }
+
+    pub fn to_pdf_string(&self) -> String {
+        format!("[/ICCBased {}]", self.id.to_ref_string())
+    }
+}
+
+/// The fixed 128 bytes header of an ICC profile (ICC.1:2010 7.2).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IccHeader {
+    /// Profile size in bytes
+    pub size: u32,
+    /// Major, minor and bug fix version, e.g. (4, 2, 0)
+    pub version: (u8, u8, u8),
+    /// Profile/device class, e.g. `prtr` or `mntr`
+    pub class: [u8; 4],
+    /// Colour space of the data, e.g. `CMYK` or `RGB `
+    pub color_space: [u8; 4],
+    /// Profile connection space, `XYZ ` or `Lab `
+    pub pcs: [u8; 4],
+}
+
+impl IccHeader {
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < 128 {
+            return Err(Error::InvalidValue(String::from("ICC profile shorter than its header")));
+        }
+        if &data[36..40] != b"acsp" {
+            return Err(Error::InvalidValue(String::from("ICC profile without the 'acsp' signature")));
+        }
+        let size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        if (size as usize) > data.len() {
+            return Err(Error::InvalidValue(format!("ICC profile of {} bytes truncated to {}", size, data.len())));
+        }
+
+        let signature = |pos: usize| [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        Ok(IccHeader {
+            size,
+            version: (data[8], data[9] >> 4, data[9] & 0x0f),
+            class: signature(12),
+            color_space: signature(16),
+            pcs: signature(20),
+        })
+    }
+
+    /// Returns the number of components of the data colour space if it can
+    /// be used for `ICCBased`.
+    pub fn components(&self) -> Option<usize> {
+        match &self.color_space {
+            b"GRAY" => Some(1),
+            b"RGB " | b"Lab " => Some(3),
+            b"CMYK" => Some(4),
+            _ => None,
+        }
+    }
+}
+
+impl PdfObject for IccBased {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        if let Some(alternate) = &mut self.alternate {
+            alternate.assign_ids(id_factory);
+        }
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.alternate.as_ref().map_or_else(Vec::new, |a| a.get_objects());
+        list.push(self);
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut entries = vec![("N", self.components.to_string())];
+        if let Some(alternate) = &self.alternate {
+            entries.push(("Alternate", alternate.to_pdf_string(0)));
+        }
+        if self.range.iter().any(|r| *r != (0.0, 1.0)) {
+            entries.push(("Range", self.range.to_pdf_string()));
+        }
+        entries.push(("Length", self.profile.len().to_string()));
+
+        let mut bytes = format!("{} obj\n{}\nstream\n", self.id, dict_string(&entries)).into_bytes();
+        bytes.extend_from_slice(&self.profile);
+        bytes.extend_from_slice(b"\nendstream\nendobj");
+        bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn signature_string(signature: &[u8; 4]) -> String {
+    String::from_utf8_lossy(signature).trim_end().to_string()
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::DeviceN;
+    use crate::pdf::function::Type2;
+
+    fn profile(version: [u8; 2], class: &[u8; 4], color_space: &[u8; 4]) -> Vec<u8> {
+        let mut data = vec![0; 132];
+        data[0..4].copy_from_slice(&132u32.to_be_bytes());
+        data[8..10].copy_from_slice(&version);
+        data[12..16].copy_from_slice(class);
+        data[16..20].copy_from_slice(color_space);
+        data[20..24].copy_from_slice(b"Lab ");
+        data[36..40].copy_from_slice(b"acsp");
+        data
+    }
+
+    #[test]
+    fn from_profile() {
+        let fogra = IccBased::from_profile(profile([2, 0x10], b"prtr", b"CMYK")).unwrap();
+        assert_eq!(fogra.components(), 4);
+        let header = fogra.header().unwrap();
+        assert_eq!(header.version, (2, 1, 0));
+        assert_eq!(&header.pcs, b"Lab ");
+
+        let lab = IccBased::from_profile(profile([4, 0x20], b"spac", b"Lab ")).unwrap();
+        assert_eq!(lab.range(), [(0.0, 100.0), (-128.0, 127.0), (-128.0, 127.0)]);
+
+        assert!(IccBased::from_profile(profile([4, 0x20], b"link", b"RGB ")).is_err());
+        assert!(IccBased::from_profile(profile([4, 0x20], b"scnr", b"6CLR")).is_err());
+        assert!(IccBased::from_profile(vec![0; 64]).is_err());
+        let mut truncated = profile([4, 0x20], b"mntr", b"RGB ");
+        truncated.truncate(128);
+        assert!(IccBased::from_profile(truncated).is_err());
+
+        // The number of components not matching the profile
+        assert!(IccBased::new(3, profile([2, 0x10], b"prtr", b"CMYK")).header().is_err());
+    }
+
+    #[test]
+    fn check_version() {
+        let p3 = IccBased::from_profile(profile([4, 0x30], b"mntr", b"RGB ")).unwrap();
+        assert!(p3.check_version(Version::V1_7).is_err());
+        let srgb = IccBased::from_profile(profile([4, 0x20], b"mntr", b"RGB ")).unwrap();
+        assert!(srgb.check_version(Version::V1_7).is_ok());
+        assert!(srgb.check_version(Version::V1_4).is_err());
+        let srgb = IccBased::from_profile(profile([2, 0x10], b"mntr", b"RGB ")).unwrap();
+        assert!(srgb.check_version(Version::V1_4).is_ok());
+    }
+
+    #[test]
+    fn to_bytes() {
+        let mut gracol = IccBased::from_profile(profile([2, 0x10], b"prtr", b"CMYK")).unwrap();
+        let mut id_factory = IdFactory::new();
+        gracol.assign_ids(&mut id_factory);
+
+        let bytes = gracol.to_bytes(0);
+        let s = String::from_utf8_lossy(&bytes);
+        assert!(s.starts_with(concat!(
+            "1 0 obj\n",
+            "<< /N 4\n",
+            "   /Alternate /DeviceCMYK\n",
+            "   /Length 132\n",
+            ">>\n",
+            "stream\n",
+        )), "{s}");
+        assert!(s.ends_with("\nendstream\nendobj"));
+
+        // As the alternate of a DeviceN
+        let mut devicen = DeviceN::new(vec!["Cyan", "Magenta"], gracol, Type2::new([0.0; 4], [1.0, 1.0, 0.0, 0.0], 1.0));
+        devicen.assign_ids(&mut id_factory);
+        assert_eq!(devicen.get_objects().len(), 2);
+        assert!(devicen.to_pdf_string(0).contains("  [/ICCBased 2 0 R]\n"));
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use crate::pdf::PdfObject;
+
+use super::ColorSpace;
+use super::super::super::base::*;
+use super::super::super::error::Error;
+use super::super::super::utils::{dict_string, indent};
+
+/// PDF32000-1:2008 8.6.6.3
+///
+/// The lookup table is written as a hexadecimal string, or as a stream
+/// object of its own for large palettes.
+///
+/// ```text
+/// [
+///   /Indexed
+///   /DeviceRGB
+///   1
+///   <ff00000000ff>
+/// ]
+/// ```
+#[derive(Clone)]
+pub struct Indexed {
+    id: Id,
+    base: ColorSpace,
+    hival: u8,
+    lookup: Vec<u8>,
+    lookup_stream: bool,
+}
+
+impl Indexed {
+    /// Creates the palette of `hival + 1` colours of the base space, the
+    /// lookup table holding the components of each colour as bytes.
+    ///
+    /// Fails if the base is a pattern or indexed space, or the table is not
+    /// `components * (hival + 1)` bytes long.
+    pub fn new(base: impl Into<ColorSpace>, hival: u8, lookup: Vec<u8>) -> Result<Self, Error> {
+        let base = base.into();
+        if matches!(base, ColorSpace::Indexed(_) | ColorSpace::Pattern(_)) {
+            return Err(Error::InvalidValue(format!("{} as the base of an Indexed space", base.family())));
+        }
+        let len = base.components() * (hival as usize + 1);
+        if lookup.len() != len {
+            return Err(Error::InvalidValue(format!(
+                "Indexed lookup table of {} bytes, {} expected", lookup.len(), len)));
+        }
+
+        Ok(Self { id: Id::new_0(), base, hival, lookup, lookup_stream: false })
+    }
+
+    pub fn base(&self) -> &ColorSpace {
+        &self.base
+    }
+
+    pub fn hival(&self) -> u8 {
+        self.hival
+    }
+
+    pub fn lookup(&self) -> &[u8] {
+        &self.lookup
+    }
+
+    /// Writes the lookup table as a stream instead of a string.
+    pub fn set_lookup_stream(&mut self, lookup_stream: bool) {
+        self.lookup_stream = lookup_stream;
+    }
+
+    pub fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let lookup = if self.lookup_stream {
+            self.id.to_ref_string()
+        } else {
+            format!("<{}>", self.lookup.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+        };
+        indent(&format!(concat!(
+            "[\n",
+            "  /Indexed\n",
+            "{}\n",
+            "  {}\n",
+            "  {}\n",
+            "]"),
+            self.base.to_pdf_string(1),
+            self.hival,
+            lookup,
+        ), indent_depth)
+    }
+}
+
+/// The lookup table stream if it is written as a stream.
+impl PdfObject for Indexed {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.base.assign_ids(id_factory);
+        if self.lookup_stream {
+            self.id = id_factory.next_id();
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.base.get_objects();
+        if self.lookup_stream {
+            list.push(self);
+        }
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let entries = [("Length", self.lookup.len().to_string())];
+        let mut bytes = format!("{} obj\n{}\nstream\n", self.id, dict_string(&entries)).into_bytes();
+        bytes.extend_from_slice(&self.lookup);
+        bytes.extend_from_slice(b"\nendstream\nendobj");
+        bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::{DeviceCMYK, DeviceRGB, IccBased, Separation};
+    use crate::pdf::function::Type2;
+
+    #[test]
+    fn new() {
+        assert!(Indexed::new(DeviceCMYK {}, 1, vec![0; 8]).is_ok());
+        assert!(Indexed::new(DeviceCMYK {}, 1, vec![0; 6]).is_err());
+        assert!(Indexed::new(ColorSpace::Pattern(None), 0, Vec::new()).is_err());
+
+        let rgb = Indexed::new(DeviceRGB {}, 0, vec![0; 3]).unwrap();
+        assert!(Indexed::new(rgb, 0, vec![0]).is_err());
+
+        let spot = Separation::new("Spot", DeviceCMYK {}, Type2::new([0.0; 4], [0.0, 0.5, 1.0, 0.0], 1.0));
+        let tints = Indexed::new(spot, 2, vec![0, 128, 255]).unwrap();
+        assert_eq!(tints.lookup(), [0, 128, 255]);
+    }
+
+    #[test]
+    fn lookup_stream() {
+        let mut indexed = Indexed::new(IccBased::new(3, vec![0; 128]), 1, vec![255, 0, 0, 0, 0, 255]).unwrap();
+        indexed.set_lookup_stream(true);
+        let mut id_factory = IdFactory::new();
+        indexed.assign_ids(&mut id_factory);
+        assert_eq!(indexed.get_objects().len(), 2);
+
+        let ok = concat!(
+            "[\n",
+            "  /Indexed\n",
+            "  [/ICCBased 1 0 R]\n",
+            "  1\n",
+            "  2 0 R\n",
+            "]",
+        );
+        assert_eq!(indexed.to_pdf_string(0), ok);
+
+        let mut ok = b"2 0 obj\n<< /Length 6\n>>\nstream\n".to_vec();
+        ok.extend_from_slice(&[255, 0, 0, 0, 0, 255]);
+        ok.extend_from_slice(b"\nendstream\nendobj");
+        assert_eq!(indexed.to_bytes(0), ok);
+    }
+}
@@ -5,7 +5,9 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
-use super::super::super::utils::{indent, ToPdfString};
+use super::cie;
+use super::super::super::error::Error;
+use super::super::super::utils::ToPdfString;
 
 /// PDF32000-1:2008 8.6.5.4
 ///
@@ -19,6 +21,7 @@ use super::super::super::utils::{indent, ToPdfString};
 ///   >>
 /// ]
 /// ```
+#[derive(Clone)]
 pub struct Lab {
     white: [f64; 3],
     black: [f64; 3],
@@ -26,30 +29,34 @@ pub struct Lab {
 }
 
 impl Lab {
-    pub fn new(white: [f64; 3], black: [f64;3] , range: [f64; 4]) -> Self {
-        Self {
-            white, black, range
+    /// Fails if the points are invalid or a minimum of the range is greater
+    /// than the maximum.
+    pub fn new(white: [f64; 3], black: [f64;3] , range: [f64; 4]) -> Result<Self, Error> {
+        cie::check_points(&white, &black)?;
+        if range[0] > range[1] || range[2] > range[3] {
+            return Err(Error::InvalidValue(format!("Range {}", range.to_pdf_string())));
         }
+        Ok(Self {
+            white, black, range
+        })
+    }
+
+    pub fn new_with_white(x: f64, y: f64, z: f64) -> Result<Self, Error> {
+        Self::new([x, y, z], [0.0, 0.0, 0.0], [-128.0, 127.0, -128.0, 127.0])
     }
 
-    pub fn new_with_white(X: f64, Y: f64, Z: f64) -> Self {
-        Self::new([X, Y, Z], [0.0, 0.0, 0.0], [-128.0, 127.0, -128.0, 127.0])
+    pub fn white(&self) -> &[f64; 3] {
+        &self.white
+    }
+
+    /// Returns the ranges of a* and b*: amin, amax, bmin and bmax.
+    pub fn range(&self) -> &[f64; 4] {
+        &self.range
     }
 
     pub fn to_pdf_string(&self, indent_depth: usize) -> String {
-        indent(&format!(concat!(
-            "[\n",
-            "  /Lab\n",
-            "  <<\n",
-            "    /WhitePoint {}\n",
-            "    /BlackPoint {}\n",
-            "    /Range {}\n",
-            "  >>\n",
-            "]"),
-            self.white.to_pdf_string(),
-            self.black.to_pdf_string(),
-            self.range.to_pdf_string(),
-        ), indent_depth)
+        let entries = [("Range", self.range.to_pdf_string())];
+        cie::to_pdf_string("Lab", &self.white, &self.black, &entries, indent_depth)
     }
 
     pub fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
@@ -106,4 +113,11 @@ mod tests {
 
         assert_eq!(String::from_utf8(lab.to_bytes(1)).unwrap(), expected);
     }
+
+    #[test]
+    fn new() {
+        assert!(Lab::new_with_white(0.964203, 1.0, 0.824905).is_ok());
+        assert!(Lab::new_with_white(0.0, 1.0, 0.824905).is_err());
+        assert!(Lab::new([0.9505, 1.0, 1.089], [0.0; 3], [100.0, -100.0, -100.0, 100.0]).is_err());
+    }
 }
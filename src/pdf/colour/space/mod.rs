@@ -5,13 +5,24 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+mod cal;
+mod cie;
+mod color_space;
 mod device;
 mod devicen;
+mod icc_based;
+mod indexed;
 mod lab;
 mod separation;
 
-pub use device::{DeviceRGB, DeviceCMYK};
+pub use cal::{CalGray, CalRGB};
+pub use cie::{D50, D65};
+pub use color_space::ColorSpace;
+pub use device::{DeviceGray, DeviceRGB, DeviceCMYK};
 pub use devicen::DeviceN;
-pub use devicen::NChannel;
+pub use devicen::DeviceNAttributes;
+pub use devicen::MixingHints;
+pub use icc_based::{IccBased, IccHeader};
+pub use indexed::Indexed;
 pub use lab::Lab;
 pub use separation::Separation;
@@ -5,12 +5,22 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
-use super::super::space::Lab;
-use super::super::super::function;
+use crate::pdf::PdfObject;
+
+use super::ColorSpace;
+use super::super::super::base::*;
+use super::super::super::function::{Function, Type2};
 use super::super::super::utils::indent;
 
 /// PDF32000-1:2008 8.6.6.4
 ///
+/// A single colorant, e.g. a spot colour, with one tint component. The
+/// alternate space is a device or CIE-based space (DeviceCMYK, DeviceRGB,
+/// Lab, ICCBased, ...) the tint transform maps the tint into.
+///
+/// Written as an object of its own and referenced from the resources and
+/// the `NChannel` colorants.
+///
 /// ```text
 /// [
 ///   /Separation
@@ -34,36 +44,129 @@ use super::super::super::utils::indent;
 ///   >>
 /// ]
 /// ```
+#[derive(Clone)]
 pub struct Separation {
-    name: String,
-    alt_space: Lab,
-    tint_transform: function::Type2,
+    id: Id,
+    name: Name,
+    alt_space: ColorSpace,
+    tint_transform: Box<dyn Function>,
 }
 
 impl Separation {
-    pub fn new(name: &str, alt_space: Lab, tint_transform: function::Type2) -> Separation {
+    pub fn new(name: &str, alt_space: impl Into<ColorSpace>, tint_transform: impl Function + 'static) -> Separation {
         Separation {
-            name: name.to_string(),
-            alt_space,
-            tint_transform,
+            id: Id::new_0(),
+            name: Name::new(name),
+            alt_space: alt_space.into(),
+            tint_transform: Box::new(tint_transform),
         }
     }
 
+    /// Returns the `/All` separation, painting on every colorant of the
+    /// device, e.g. for registration marks. Its alternate is DeviceCMYK.
+    pub fn all() -> Separation {
+        Self::new("All", ColorSpace::DeviceCMYK, Type2::new([0.0; 4], [1.0; 4], 1.0))
+    }
+
+    /// Returns the `/None` separation, which paints nothing.
+    pub fn none() -> Separation {
+        Self::new("None", ColorSpace::DeviceCMYK, Type2::new([0.0; 4], [0.0; 4], 1.0))
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn alt_space(&self) -> &ColorSpace {
+        &self.alt_space
+    }
+
+    pub fn tint_transform(&self) -> &dyn Function {
+        self.tint_transform.as_ref()
+    }
+
     pub fn to_pdf_string(&self, indent_depth: usize) -> String {
         indent(&format!(concat!(
             "[\n",
             "  /Separation\n",
-            "  /{}\n",
-            "  {}\n",
             "  {}\n",
+            "{}\n",
+            "{}\n",
             "]"),
             self.name,
-            self.alt_space.to_pdf_string(indent_depth + 1),
-            String::from_utf8(self.tint_transform.to_bytes(indent_depth + 1)).unwrap(),
+            self.alt_space.to_pdf_string(1),
+            self.tint_transform.to_pdf_string(1),
         ), indent_depth)
     }
+}
+
+impl PdfObject for Separation {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.alt_space.assign_ids(id_factory);
+        self.tint_transform.assign_ids(id_factory);
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.alt_space.get_objects();
+        list.append(&mut self.tint_transform.get_objects());
+        list.push(self);
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        format!("{} obj\n{}\nendobj", self.id, self.to_pdf_string(0)).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::IccBased;
+    use crate::pdf::function::Type0;
+
+    #[test]
+    fn to_bytes() {
+        let pantone = Separation::new("PANTONE 185 C", ColorSpace::DeviceCMYK, Type2::new([0.0; 4], [0.0, 0.91, 0.76, 0.0], 1.0));
+
+        let ok = concat!(
+            "0 0 obj\n",
+            "[\n",
+            "  /Separation\n",
+            "  /PANTONE#20185#20C\n",
+            "  /DeviceCMYK\n",
+            "  <<\n",
+            "    /FunctionType 2\n",
+            "    /Domain [0 1]\n",
+            "    /C0 [0 0 0 0]\n",
+            "    /C1 [0 0.91 0.76 0]\n",
+            "    /N 1\n",
+            "  >>\n",
+            "]\n",
+            "endobj",
+        );
+        assert_eq!(String::from_utf8(pantone.to_bytes(0)).unwrap(), ok);
+        assert_eq!(Separation::all().name(), "All");
+    }
+
+    #[test]
+    fn objects() {
+        let tint = Type0::new([(0.0, 1.0)], [(0.0, 1.0); 3], [2], 8, vec![255, 255, 255, 255, 0, 0]);
+        let mut separation = Separation::new("Red", IccBased::new(3, vec![0; 128]), tint);
+        let mut id_factory = IdFactory::new();
+        separation.assign_ids(&mut id_factory);
 
-    pub fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
-        self.to_pdf_string(indent_depth).into_bytes()
+        // The profile, the sampled function and the separation
+        assert_eq!(separation.get_objects().len(), 3);
+        let s = separation.to_pdf_string(0);
+        assert!(s.contains("  [/ICCBased 1 0 R]\n  2 0 R\n"), "{s}");
     }
 }
@@ -6,60 +6,349 @@
 
 
 use super::base::*;
+use super::colour::DeviceColour;
+use super::colour::space::ColorSpace;
+use super::error::Error;
+use super::operation::{self, Operation, TextItem};
 use super::utils::indent;
 
+#[derive(Clone)]
 pub struct Contents {
     pub id: Id,
-    operators: Vec<String>,
+    operations: Vec<Operation>,
+    /// Components of the stroke and fill colour spaces set with
+    /// `set_*_color_space`, `None` when unknown
+    components: ColorComponents,
+    saved_components: Vec<ColorComponents>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ColorComponents {
+    stroke: Option<SpaceComponents>,
+    fill: Option<SpaceComponents>,
+}
+
+#[derive(Clone, Copy)]
+struct SpaceComponents {
+    count: usize,
+    /// A `/Pattern` space, whose colours name the pattern
+    pattern: bool,
+}
+
+impl From<&ColorSpace> for SpaceComponents {
+    fn from(space: &ColorSpace) -> Self {
+        SpaceComponents {
+            count: space.components(),
+            pattern: matches!(space, ColorSpace::Pattern(_)),
+        }
+    }
 }
 
 impl Contents {
     pub fn new() -> Contents {
-        Contents { id: Id::new_0(), operators: Vec::new() }
+        Self::from_operations(Vec::new())
+    }
+
+    pub fn from_operations(operations: Vec<Operation>) -> Contents {
+        Contents {
+            id: Id::new_0(),
+            operations,
+            components: ColorComponents::default(),
+            saved_components: Vec::new(),
+        }
+    }
+
+    /// Parses an existing content stream, e.g. a decoded page `/Contents`.
+    pub fn parse(data: &[u8]) -> Result<Contents, Error> {
+        Ok(Self::from_operations(operation::parse(data)?))
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn operations_mut(&mut self) -> &mut Vec<Operation> {
+        &mut self.operations
+    }
+
+    pub fn push(&mut self, operation: Operation) {
+        self.operations.push(operation);
     }
 
-    pub fn set_fill_color_space(&mut self, name: &str) {
-        self.operators.push(format!("/{name} cs"));
+    /// `cs`, selects the colour space added to the resources with the name.
+    /// The following `scn` colours are validated against the space.
+    pub fn set_fill_color_space(&mut self, name: &str, space: &ColorSpace) {
+        self.components.fill = Some(space.into());
+        self.push(Operation::SetFillColorSpace(Name::new(name)));
     }
 
-    pub fn set_fill_color_space_color<
-        const COLOR_NUM: usize
-    >(&mut self, colors: [f64; COLOR_NUM]) {
-        let colors = colors.iter().map(|e|e.to_string()).collect::<Vec<String>>().join(" ");
-        self.operators.push(format!("{colors} scn"));
+    /// `scn`, fails if the number of components does not match the fill
+    /// colour space.
+    pub fn set_fill_color_space_color(&mut self, colors: impl AsRef<[f64]>) -> Result<(), Error> {
+        check_components(colors.as_ref(), self.components.fill, false)?;
+        self.push(Operation::SetFillColorN(colors.as_ref().to_vec(), None));
+        Ok(())
+    }
+
+    /// `scn` with the pattern added to the resources with the name. The
+    /// colours are the components in the underlying space for an uncoloured
+    /// pattern, empty for a coloured one. Fails if the fill colour space is
+    /// not a pattern space or the number of components does not match.
+    pub fn set_fill_pattern(&mut self, name: &str, colors: impl AsRef<[f64]>) -> Result<(), Error> {
+        check_components(colors.as_ref(), self.components.fill, true)?;
+        self.push(Operation::SetFillColorN(colors.as_ref().to_vec(), Some(Name::new(name))));
+        Ok(())
+    }
+
+    /// `CS`, selects the colour space added to the resources with the name.
+    /// The following `SCN` colours are validated against the space.
+    pub fn set_stroke_color_space(&mut self, name: &str, space: &ColorSpace) {
+        self.components.stroke = Some(space.into());
+        self.push(Operation::SetStrokeColorSpace(Name::new(name)));
+    }
+
+    /// `SCN`, fails if the number of components does not match the stroke
+    /// colour space.
+    pub fn set_stroke_color_space_color(&mut self, colors: impl AsRef<[f64]>) -> Result<(), Error> {
+        check_components(colors.as_ref(), self.components.stroke, false)?;
+        self.push(Operation::SetStrokeColorN(colors.as_ref().to_vec(), None));
+        Ok(())
+    }
+
+    /// `SCN` with the pattern added to the resources with the name, see
+    /// `set_fill_pattern`.
+    pub fn set_stroke_pattern(&mut self, name: &str, colors: impl AsRef<[f64]>) -> Result<(), Error> {
+        check_components(colors.as_ref(), self.components.stroke, true)?;
+        self.push(Operation::SetStrokeColorN(colors.as_ref().to_vec(), Some(Name::new(name))));
+        Ok(())
     }
 
     pub fn set_stroke_color(&mut self, r: f32, g: f32, b: f32) {
-        self.operators.push(format!("{r} {g} {b} RG"));
+        self.push(Operation::SetStrokeRgb(r as f64, g as f64, b as f64));
     }
 
     pub fn set_fill_color(&mut self, r: f32, g: f32, b: f32) {
-        self.operators.push(format!("{r} {g} {b} rg"));
+        self.push(Operation::SetFillRgb(r as f64, g as f64, b as f64));
     }
 
     pub fn set_fill_cmyk_color(&mut self, c: f32, m: f32, y: f32, k:f32) {
-        self.operators.push(format!("{c} {m} {y} {k} k"));
+        self.push(Operation::SetFillCmyk(c as f64, m as f64, y as f64, k as f64));
+    }
+
+    pub fn set_stroke_device_color(&mut self, colour: &DeviceColour) {
+        self.push(colour.to_stroke_operation());
+    }
+
+    pub fn set_fill_device_color(&mut self, colour: &DeviceColour) {
+        self.push(colour.to_fill_operation());
     }
 
     pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
-        self.operators.push(format!("{x} {y} {width} {height} re f"));
+        self.push(Operation::Rect(x as f64, y as f64, width as f64, height as f64));
+        self.push(Operation::Fill);
+    }
+
+    /// `q`
+    pub fn save_state(&mut self) {
+        self.saved_components.push(self.components);
+        self.push(Operation::SaveState);
+    }
+
+    /// `Q`
+    pub fn restore_state(&mut self) {
+        self.components = self.saved_components.pop().unwrap_or_default();
+        self.push(Operation::RestoreState);
+    }
+
+    /// `a b c d e f cm`
+    pub fn concat_matrix(&mut self, matrix: [f64; 6]) {
+        self.push(Operation::ConcatMatrix(matrix));
+    }
+
+    /// `w`
+    pub fn set_line_width(&mut self, width: f64) {
+        self.push(Operation::SetLineWidth(width));
+    }
+
+    /// `d`
+    pub fn set_dash(&mut self, dash: &[f64], phase: f64) {
+        self.push(Operation::SetDash(dash.to_vec(), phase));
+    }
+
+    /// `gs`
+    pub fn set_ext_g_state(&mut self, name: &str) {
+        self.push(Operation::SetExtGState(Name::new(name)));
+    }
+
+    /// `m`
+    pub fn move_to(&mut self, x: f64, y: f64) {
+        self.push(Operation::MoveTo(x, y));
+    }
+
+    /// `l`
+    pub fn line_to(&mut self, x: f64, y: f64) {
+        self.push(Operation::LineTo(x, y));
+    }
+
+    /// `c`
+    pub fn curve_to(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) {
+        self.push(Operation::CurveTo([x1, y1, x2, y2, x3, y3]));
+    }
+
+    /// `h`
+    pub fn close_path(&mut self) {
+        self.push(Operation::ClosePath);
+    }
+
+    /// `re`
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.push(Operation::Rect(x, y, width, height));
+    }
+
+    /// Appends an ellipse inscribed in the rectangle as four Bézier curves.
+    pub fn ellipse(&mut self, rect: &Rectangle) {
+        const K: f64 = 0.552_284_749_8;
+        let rx = rect.width() / 2.0;
+        let ry = rect.height() / 2.0;
+        let cx = rect.llx + rx;
+        let cy = rect.lly + ry;
+
+        self.move_to(cx + rx, cy);
+        self.curve_to(cx + rx, cy + ry * K, cx + rx * K, cy + ry, cx, cy + ry);
+        self.curve_to(cx - rx * K, cy + ry, cx - rx, cy + ry * K, cx - rx, cy);
+        self.curve_to(cx - rx, cy - ry * K, cx - rx * K, cy - ry, cx, cy - ry);
+        self.curve_to(cx + rx * K, cy - ry, cx + rx, cy - ry * K, cx + rx, cy);
+        self.close_path();
+    }
+
+    /// `S`
+    pub fn stroke(&mut self) {
+        self.push(Operation::Stroke);
+    }
+
+    /// `f`
+    pub fn fill(&mut self) {
+        self.push(Operation::Fill);
+    }
+
+    /// `B`
+    pub fn fill_stroke(&mut self) {
+        self.push(Operation::FillStroke);
+    }
+
+    /// `n`
+    pub fn end_path(&mut self) {
+        self.push(Operation::EndPath);
+    }
+
+    /// `W`, intersects the clipping path with the current path.
+    pub fn clip(&mut self) {
+        self.push(Operation::Clip);
+    }
+
+    /// `BMC`
+    pub fn begin_marked_content(&mut self, tag: &str) {
+        self.push(Operation::BeginMarkedContent(Name::new(tag)));
+    }
+
+    /// `EMC`
+    pub fn end_marked_content(&mut self) {
+        self.push(Operation::EndMarkedContent);
+    }
+
+    /// `sh`, paints the shading named in the resources over the clipping
+    /// path.
+    pub fn paint_shading(&mut self, name: &str) {
+        self.push(Operation::PaintShading(Name::new(name)));
+    }
+
+    /// `Do`, paints the XObject named in the resources.
+    pub fn draw_xobject(&mut self, name: &str) {
+        self.push(Operation::DrawXObject(Name::new(name)));
+    }
+
+    /// `BT`
+    pub fn begin_text(&mut self) {
+        self.push(Operation::BeginText);
+    }
+
+    /// `ET`
+    pub fn end_text(&mut self) {
+        self.push(Operation::EndText);
+    }
+
+    /// `Tf`
+    pub fn set_font(&mut self, font_name: &str, font_size: f64) {
+        self.push(Operation::SetFont(Name::new(font_name), font_size));
+    }
+
+    /// `TL`
+    pub fn set_leading(&mut self, leading: f64) {
+        self.push(Operation::SetLeading(leading));
+    }
+
+    /// `Td`
+    pub fn move_text(&mut self, tx: f64, ty: f64) {
+        self.push(Operation::MoveText(tx, ty));
+    }
+
+    /// `T*`
+    pub fn next_line(&mut self) {
+        self.push(Operation::NextLine);
+    }
+
+    /// `Tj`
+    pub fn show_text(&mut self, text: &str) {
+        self.push(Operation::ShowText(text.as_bytes().to_vec()));
+    }
+
+    /// `Tc`
+    pub fn set_char_spacing(&mut self, spacing: f64) {
+        self.push(Operation::SetCharSpacing(spacing));
+    }
+
+    /// `Tw`
+    pub fn set_word_spacing(&mut self, spacing: f64) {
+        self.push(Operation::SetWordSpacing(spacing));
+    }
+
+    /// `Tz`, the horizontal scaling in percent.
+    pub fn set_horizontal_scaling(&mut self, scale: f64) {
+        self.push(Operation::SetHorizontalScaling(scale));
+    }
+
+    /// `Ts`
+    pub fn set_text_rise(&mut self, rise: f64) {
+        self.push(Operation::SetTextRise(rise));
+    }
+
+    /// `Tm`
+    pub fn set_text_matrix(&mut self, matrix: [f64; 6]) {
+        self.push(Operation::SetTextMatrix(matrix));
+    }
+
+    /// `TJ`, shows the strings moving the next one left by the numbers in
+    /// thousandths of the font size.
+    pub fn show_text_adjusted(&mut self, items: &[TextItem]) {
+        self.push(Operation::ShowTextAdjusted(items.to_vec()));
     }
 
     pub fn fill_text(&mut self, font_name: &str, font_size: u32, pos: Pos, text: &str) {
-        self.operators.push(format!(concat!(
-            "BT\n",
-            "  /{} {} Tf\n",
-            "  {} {} Td\n",
-            "  0 Tr\n",
-            "  ({}) Tj\n",
-            "ET"),
-            font_name, font_size,
-            pos.x, pos.y,
-            text));
+        self.begin_text();
+        self.set_font(font_name, font_size as f64);
+        self.move_text(pos.x as f64, pos.y as f64);
+        self.push(Operation::SetTextRender(0));
+        self.show_text(text);
+        self.end_text();
     }
 
-    fn get_stream_string(&self, indent_size: usize) -> String {
-        indent(&self.operators.join("\n"), indent_size)
+    /// Returns the content stream data.
+    pub fn get_stream_bytes(&self) -> Vec<u8> {
+        operation::to_bytes(&self.operations)
+    }
+
+    pub(crate) fn get_stream_string(&self, indent_size: usize) -> String {
+        indent(&String::from_utf8_lossy(&self.get_stream_bytes()), indent_size)
     }
 
     pub fn to_string(&self, indent_size: usize) -> String {
@@ -75,7 +364,7 @@ impl Contents {
                     "endstream\n",
                     "endobj"
                 ),
-                self.id.to_string(),
+                self.id,
                 stream.len(),
                 stream
             ),
@@ -84,6 +373,12 @@ impl Contents {
     }
 }
 
+impl Default for Contents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PdfObject for Contents {
     fn id(&self) -> &Id {
         &self.id
@@ -98,7 +393,33 @@ impl PdfObject for Contents {
     }
 
     fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
-        self.to_string(indent_depth).into_bytes()
+        if indent_depth > 0 {
+            return self.to_string(indent_depth).into_bytes();
+        }
+
+        // inline image data is kept as is
+        let stream = self.get_stream_bytes();
+        let mut bytes = format!("{} obj\n<< /Length {} >>\nstream\n", self.id, stream.len()).into_bytes();
+        bytes.extend_from_slice(&stream);
+        bytes.extend_from_slice(b"\nendstream\nendobj");
+        bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn check_components(colors: &[f64], components: Option<SpaceComponents>, pattern: bool) -> Result<(), Error> {
+    match components {
+        Some(c) if c.pattern != pattern => Err(Error::InvalidValue(String::from(if pattern {
+            "pattern for a colour space other than Pattern"
+        } else {
+            "colour without a pattern for a Pattern colour space"
+        }))),
+        Some(c) if c.count != colors.len() => Err(Error::InvalidValue(
+            format!("{} colour components for a colour space with {}", colors.len(), c.count))),
+        _ => Ok(()),
     }
 }
 
@@ -126,4 +447,51 @@ mod tests {
 
         assert_eq!(c.to_string(0), ok);
     }
+
+    #[test]
+    fn path() {
+        let mut c = Contents::new();
+        c.set_line_width(0.5);
+        c.move_to(1.0, 2.0);
+        c.line_to(1.0 / 3.0, -0.00001);
+        c.stroke();
+
+        assert_eq!(c.get_stream_string(0), "0.5 w\n1 2 m\n0.3333 0 l\nS");
+    }
+
+    #[test]
+    fn text_state() {
+        let mut c = Contents::new();
+        c.set_char_spacing(0.5);
+        c.set_horizontal_scaling(80.0);
+        c.set_text_matrix([1.0, 0.0, 0.0, 1.0, 72.0, 700.0]);
+        c.show_text_adjusted(&[TextItem::from("A"), TextItem::Adjust(-250.0), TextItem::from("B")]);
+
+        assert_eq!(c.get_stream_string(0), "0.5 Tc\n80 Tz\n1 0 0 1 72 700 Tm\n[(A) -250 (B)] TJ");
+    }
+
+    #[test]
+    fn color_space_components() {
+        let mut c = Contents::new();
+        c.set_fill_color_space("CS0", &ColorSpace::DeviceCMYK);
+        assert!(c.set_fill_color_space_color([0.0, 0.0, 0.0, 1.0]).is_ok());
+        assert!(c.set_fill_color_space_color([1.0]).is_err());
+        assert!(c.set_stroke_color_space_color([1.0]).is_ok());
+
+        c.save_state();
+        c.set_fill_color_space("CS1", &ColorSpace::DeviceGray);
+        assert!(c.set_fill_color_space_color([0.5]).is_ok());
+        c.restore_state();
+        assert!(c.set_fill_color_space_color([0.5]).is_err());
+
+        assert_eq!(c.get_stream_string(0), "/CS0 cs\n0 0 0 1 scn\n1 SCN\nq\n/CS1 cs\n0.5 scn\nQ");
+    }
+
+    #[test]
+    fn show_text_escapes() {
+        let mut c = Contents::new();
+        c.show_text("a (b)");
+
+        assert_eq!(c.get_stream_string(0), "(a \\(b\\)) Tj");
+    }
 }
@@ -8,14 +8,21 @@
 use std::fs;
 
 use super::base::*;
+use super::error::Error;
+use super::form::AcroForm;
+use super::import::ImportedObjects;
+use super::loaded_doc::LoadedDoc;
 use super::page::Page;
 use super::page_list::*;
+use super::utils::indent;
 
 /// PDF document
 pub struct Doc {
     ver: Version,
     is_binary: bool,
     page_list: PageList,
+    acro_form: Option<AcroForm>,
+    imported: ImportedObjects,
 }
 
 impl Doc {
@@ -25,6 +32,8 @@ impl Doc {
             ver,
             is_binary: true,
             page_list: PageList::new(),
+            acro_form: None,
+            imported: ImportedObjects::default(),
         }
     }
 
@@ -33,6 +42,139 @@ impl Doc {
         self.page_list.push(page);
     }
 
+    /// Inserts the page at the index, shifting the following pages.
+    ///
+    /// Form widgets keep their pages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > page_count()`.
+    pub fn insert_page(&mut self, index: usize, page: Page) {
+        self.page_list.insert(index, page);
+        self.remap_widget_pages(&|i| if i >= index { i + 1 } else { i });
+    }
+
+    /// Removes and returns the page at the index.
+    ///
+    /// Form widgets on the page are no longer placed on any page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_page(&mut self, index: usize) -> Page {
+        let page = self.page_list.remove(index);
+        self.remap_widget_pages(&|i| match i.cmp(&index) {
+            std::cmp::Ordering::Less => i,
+            std::cmp::Ordering::Equal => usize::MAX,
+            std::cmp::Ordering::Greater => i - 1,
+        });
+        page
+    }
+
+    /// Moves the page at `from` so that it is at `to` afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_page(&mut self, from: usize, to: usize) {
+        assert!(to < self.page_count(), "to (is {to}) should be < len");
+        let page = self.page_list.remove(from);
+        self.page_list.insert(to, page);
+        self.remap_widget_pages(&|i| {
+            if i == from {
+                to
+            } else if from < i && i <= to {
+                i - 1
+            } else if to <= i && i < from {
+                i + 1
+            } else {
+                i
+            }
+        });
+    }
+
+    /// Swaps the pages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn swap_pages(&mut self, a: usize, b: usize) {
+        self.page_list.swap(a, b);
+        self.remap_widget_pages(&|i| if i == a { b } else if i == b { a } else { i });
+    }
+
+    /// Inserts a copy of the page right after it and returns the index of the copy.
+    /// The copy shares the resources; see `Page::duplicate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn duplicate_page(&mut self, index: usize) -> usize {
+        let page = self.page(index).expect("index out of bounds").duplicate();
+        self.insert_page(index + 1, page);
+        index + 1
+    }
+
+    /// Copies the pages at the indexes from the loaded document. The returned
+    /// pages can be added with `push_page` or `insert_page`.
+    ///
+    /// Every object the pages refer to is copied, identical objects once
+    /// across all the imports. The inherited attributes are set on the pages,
+    /// and the outline items pointing at the pages are appended to the outline
+    /// of this document. Named destinations are replaced with explicit ones.
+    ///
+    /// The copied resources and contents are written instead of
+    /// `Page::resources` and `Page::contents`; annotations can still be added.
+    pub fn import_pages(&mut self, src: &LoadedDoc, indexes: &[usize]) -> Result<Vec<Page>, Error> {
+        self.imported.import_pages(src, indexes)
+    }
+
+    /// Appends all the pages of the loaded document, see `import_pages`.
+    pub fn append(&mut self, src: &LoadedDoc) -> Result<(), Error> {
+        let indexes: Vec<usize> = (0..src.page_ids().len()).collect();
+        for page in self.import_pages(src, &indexes)? {
+            self.push_page(page);
+        }
+        Ok(())
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.page_list.len()
+    }
+
+    pub fn page(&self, index: usize) -> Option<&Page> {
+        self.page_list.get(index)
+    }
+
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut Page> {
+        self.page_list.get_mut(index)
+    }
+
+    /// Returns an iterator over the pages in order.
+    pub fn pages(&self) -> impl Iterator<Item = &Page> {
+        self.page_list.pages().iter()
+    }
+
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut Page> {
+        self.page_list.pages_mut().iter_mut()
+    }
+
+    fn remap_widget_pages(&mut self, f: &dyn Fn(usize) -> usize) {
+        if let Some(form) = &mut self.acro_form {
+            form.remap_pages(f);
+        }
+    }
+
+    /// Sets the maximum number of kids of a page tree node.
+    pub fn set_page_tree_fan_out(&mut self, fan_out: usize) {
+        self.page_list.set_fan_out(fan_out);
+    }
+
+    /// Returns the interactive form, creating it on first use.
+    pub fn acro_form(&mut self) -> &mut AcroForm {
+        self.acro_form.get_or_insert_with(AcroForm::new)
+    }
+
     fn get_header_bytes(&self) -> Vec<u8> {
         let mut bytes: Vec<u8> = Vec::new();
 
@@ -50,24 +192,47 @@ impl Doc {
     }
 
     fn get_doc_catalog_bytes(&self, id: &Id, page_list_id: &Id) -> Vec<u8> {
+        let outlines = match self.imported.outline_id() {
+            Some(outlines_id) => format!("   /Outlines {}\n", outlines_id.to_ref_string()),
+            None => String::new(),
+        };
+        let acro_form = match &self.acro_form {
+            Some(form) => format!("   /AcroForm\n{}\n", indent(&form.to_pdf_string(), 1)),
+            None => String::new(),
+        };
+
         format!(concat!(
             "{} obj\n",
             "<< /Type /Catalog\n",
             "   /Pages {}\n",
+            "{}",
+            "{}",
             ">>\n",
             "endobj"),
-            id.to_string(),
-            page_list_id.to_ref_string()
+            id,
+            page_list_id.to_ref_string(),
+            outlines,
+            acro_form,
         ).into_bytes()
     }
 
-    fn get_cross_ref_table_bytes(&self, byte_offsets: &Vec<usize>) -> Vec<u8> {
+    /// Writes the table for the `(object number, byte offset)` pairs in any order.
+    fn get_cross_ref_table_bytes(&self, byte_offsets: &[(u32, usize)]) -> Vec<u8> {
+        let size = byte_offsets.iter().map(|o| o.0).max().unwrap_or(0) + 1;
+        let mut offsets = vec![None; size as usize];
+        for (number, offset) in byte_offsets {
+            offsets[*number as usize] = Some(*offset);
+        }
+
         let mut s = String::new();
-        s.push_str(&format!("0 {}\n", byte_offsets.len() + 1));
+        s.push_str(&format!("0 {}\n", size));
         s.push_str("0000000000 65535 f \n");
 
-        for index in byte_offsets {
-            s.push_str(&format!("{:0>10} 00000 n \n", index));
+        for offset in offsets.iter().skip(1) {
+            match offset {
+                Some(offset) => s.push_str(&format!("{:0>10} 00000 n \n", offset)),
+                None => s.push_str("0000000000 00001 f \n"),
+            }
         }
 
         s.into_bytes()
@@ -86,26 +251,54 @@ impl Doc {
     pub fn to_bytes(&mut self) -> Vec<u8> {
         let mut bytes: Vec<u8> = Vec::new();
         let mut id_factory = IdFactory::new();
-        let mut byte_offsets: Vec<usize> = Vec::new();
+        let mut byte_offsets: Vec<(u32, usize)> = Vec::new();
+
+        // Objects copied from loaded documents first, as the pages refer to them
+        let roots: Vec<Id> = self.page_list.pages().iter()
+            .filter_map(|p| p.imported())
+            .flat_map(|i| i.references())
+            .collect();
+        self.imported.assign_ids(&mut id_factory, &roots);
+        for page in self.page_list.pages_mut() {
+            if let Some(imported) = page.imported_mut() {
+                imported.set_written_ids(&|id| self.imported.id(id));
+            }
+        }
 
         //
         self.page_list.assign_ids(&mut id_factory);
+        let keys: Vec<(Id, Id)> = self.page_list.pages().iter()
+            .filter_map(|p| p.imported().map(|i| (i.key, p.id)))
+            .collect();
+        self.imported.build(&keys);
+        if let Some(form) = &mut self.acro_form {
+            let page_ids: Vec<Id> = self.page_list.pages().iter().map(|p| p.id).collect();
+            let widgets = form.assign_ids(&mut id_factory, &page_ids);
+            for (i, page) in self.page_list.pages_mut().iter_mut().enumerate() {
+                let ids = widgets.iter().filter(|w| w.0 == i).map(|w| w.1).collect();
+                page.set_widget_ids(ids);
+            }
+        }
 
         // Header
         bytes.append(&mut self.get_header_bytes());
 
-        // Page list, Page
-        let objects = self.page_list.get_objects();
+        // Page list, Page, Form fields
+        let mut objects = self.page_list.get_objects();
+        if let Some(form) = &self.acro_form {
+            objects.append(&mut form.get_objects());
+        }
+        objects.append(&mut self.imported.get_objects());
         for obj in &objects {
             bytes.append(&mut "\n".to_string().into_bytes());
-            byte_offsets.push(bytes.len());
+            byte_offsets.push((obj.id().number(), bytes.len()));
             bytes.append(&mut obj.to_bytes(0));
         }
 
         // Document catalog
         bytes.append(&mut "\n".to_string().into_bytes());
-        byte_offsets.push(bytes.len());
         let doc_catalog_id = id_factory.next_id();
+        byte_offsets.push((doc_catalog_id.number(), bytes.len()));
         let b = self.get_doc_catalog_bytes(&doc_catalog_id, &self.page_list.id);
         bytes.append(&mut b.clone());
 
@@ -116,7 +309,8 @@ impl Doc {
         bytes.append(&mut self.get_cross_ref_table_bytes(&byte_offsets));
 
         // Trailer
-        bytes.append(&mut self.get_trailer_bytes(&doc_catalog_id, byte_offsets.len() + 1));
+        let size = byte_offsets.iter().map(|o| o.0).max().unwrap_or(0) as usize + 1;
+        bytes.append(&mut self.get_trailer_bytes(&doc_catalog_id, size));
 
         bytes.append(&mut "startxref\n".to_string().into_bytes());
         bytes.append(&mut format!("{}\n", cross_ref_offset).into_bytes());
@@ -134,23 +328,192 @@ impl Doc {
     }
 }
 
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
 
+#[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pdf::font::Font;
+    use crate::pdf::loaded_doc::LoadedDoc;
 
     #[test]
     fn it_works() {
-        // let mut doc = Doc::new(Version::V1_4);
-        // let mut page = Page::new(MediaBox::Letter);
-        // page.resources().add_font("F0", Font::new("Times-Italic"));
-        // page.contents().fill_text("F0", 32, Pos::new(0, 0), "Hello");
-        // doc.push_page(page);
-
-        // let exe_path = std::env::current_exe().unwrap();
-        // let dir = exe_path.parent().unwrap();
-        // let path = dir.join("hello.pdf");
-        // println!("{}", path.to_str().unwrap());
-        // doc.write_to_file(path.to_str().unwrap().to_string());
-        // //assert_eq!(String::from_utf8(doc.to_bytes()).unwrap(), "".to_string());
+        let mut doc = Doc::new(Version::V1_4);
+        let mut page = Page::new(MediaBox::Letter);
+        page.resources().add_font("F0", Font::new("Times-Italic"));
+        page.contents().fill_text("F0", 32, Pos::new(0, 0), "Hello");
+        doc.push_page(page);
+
+        let bytes = doc.to_bytes();
+        assert!(bytes.starts_with(b"%PDF-1.4\n%"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+    }
+
+    #[test]
+    fn pages() {
+        let mut doc = Doc::new(Version::V1_7);
+        for size in [MediaBox::A4, MediaBox::Letter, MediaBox::Custom(0, 0, 100, 100)] {
+            doc.push_page(Page::new(size));
+        }
+        doc.insert_page(0, Page::new(MediaBox::Custom(0, 0, 200, 200)));
+        doc.move_page(0, 3);
+        doc.swap_pages(0, 1);
+        assert_eq!(doc.remove_page(2).media_box(), MediaBox::Custom(0, 0, 100, 100).into());
+        doc.duplicate_page(0);
+
+        let sizes: Vec<Rectangle> = doc.pages().map(|p| p.media_box()).collect();
+        let expected = [MediaBox::Letter, MediaBox::Letter, MediaBox::A4, MediaBox::Custom(0, 0, 200, 200)];
+        assert_eq!(sizes, expected.map(Rectangle::from));
+        assert!(doc.page(0).unwrap().shares_resources_with(doc.page(1).unwrap()));
+        assert_eq!(doc.page_count(), 4);
+    }
+
+    #[test]
+    fn widget_pages() {
+        use crate::pdf::form::{CheckBox, Field, Widget};
+
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(Page::new(MediaBox::A4));
+        doc.push_page(Page::new(MediaBox::A4));
+        let mut field = Field::new("agree", CheckBox::new());
+        field.add_widget(Widget::new(1, Rectangle::new(0.0, 0.0, 10.0, 10.0)));
+        doc.acro_form().add_field(field);
+
+        doc.insert_page(0, Page::new(MediaBox::Letter));
+        doc.move_page(2, 0);
+        assert_eq!(doc.acro_form().field("agree").unwrap().widgets()[0].page_index(), 0);
+        doc.remove_page(0);
+        assert_eq!(doc.acro_form().field("agree").unwrap().widgets()[0].page_index(), usize::MAX);
+        doc.to_bytes();
+    }
+
+    #[test]
+    fn page_tree() {
+        let mut doc = Doc::new(Version::V1_7);
+        doc.set_page_tree_fan_out(4);
+        for i in 0..50 {
+            doc.push_page(Page::new(if i % 10 == 0 { MediaBox::Letter } else { MediaBox::A4 }));
+        }
+
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let pages = loaded.page_ids();
+        assert_eq!(pages.len(), 50);
+        assert_eq!(loaded.page_media_box(pages[10]), Rectangle::new(0.0, 0.0, 612.0, 792.0));
+        assert_eq!(loaded.page_media_box(pages[11]), Rectangle::new(0.0, 0.0, 595.0, 842.0));
+    }
+
+    /// Two pages sharing the resources, the second rotated, and an outline
+    /// with an explicit and a named destination.
+    fn source() -> LoadedDoc {
+        use crate::pdf::annotation::{Annotation, Square};
+        use crate::pdf::object::{Dictionary, Object};
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        page.contents().fill_text("F0", 12, Pos::new(72, 720), "One");
+        let mut second = page.duplicate();
+        second.set_rotation(crate::pdf::page::Rotation::Deg90);
+        for p in [&mut page, &mut second] {
+            p.add_annotation(Annotation::new(Rectangle::new(0.0, 0.0, 10.0, 10.0), Square::new()));
+        }
+        doc.push_page(page);
+        doc.push_page(second);
+
+        let mut loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let pages = loaded.page_ids();
+        let outlines = loaded.add_object(Dictionary::new());
+        let mut items = Vec::new();
+        for (title, dest) in [
+            ("One", Object::Array(vec![Object::Reference(pages[0]), Object::name("Fit")])),
+            ("Two", Object::name("second")),
+        ] {
+            let mut item = Dictionary::new();
+            item.set("Title", Object::text(title));
+            item.set("Parent", outlines);
+            item.set("Dest", dest);
+            items.push(loaded.add_object(item));
+        }
+        for (i, key) in [(0, "Next"), (1, "Prev")] {
+            let other = items[1 - i];
+            loaded.get_mut(items[i]).unwrap().as_dict_mut().unwrap().set(key, other);
+        }
+        let outlines_dict = loaded.get_mut(outlines).unwrap().as_dict_mut().unwrap();
+        outlines_dict.set("First", items[0]);
+        outlines_dict.set("Last", items[1]);
+        outlines_dict.set("Count", 2);
+
+        let mut dests = Dictionary::new();
+        dests.set("second", Object::Array(vec![Object::Reference(pages[1]), Object::name("Fit")]));
+        let catalog = loaded.catalog_id();
+        let catalog = loaded.get_mut(catalog).unwrap().as_dict_mut().unwrap();
+        catalog.set("Outlines", outlines);
+        catalog.set("Dests", dests);
+        loaded
+    }
+
+    #[test]
+    fn append() {
+        let src = source();
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(Page::new(MediaBox::Letter));
+        doc.append(&src).unwrap();
+        doc.append(&src).unwrap();
+        assert_eq!(doc.page_count(), 5);
+        assert!(doc.page(1).unwrap().is_imported());
+        assert_eq!(doc.page(2).unwrap().rotation(), crate::pdf::page::Rotation::Deg90);
+
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let pages = loaded.page_ids();
+        assert_eq!(pages.len(), 5);
+
+        // The resources are copied once
+        let resources = loaded.ids()
+            .filter(|id| loaded.get(*id).and_then(|o| o.as_dict()).is_some_and(|d| d.contains_key("Font")))
+            .count();
+        assert_eq!(resources, 1);
+        assert_eq!(loaded.page_attribute(pages[2], "Rotate").and_then(|r| r.as_i64()), Some(90));
+        assert_eq!(loaded.page_content(pages[3]).unwrap(), loaded.page_content(pages[1]).unwrap());
+
+        // The annotations refer to the new pages
+        for page in &pages[1..] {
+            let annots = loaded.page_attribute(*page, "Annots").and_then(|a| a.as_array()).unwrap();
+            let annot = loaded.resolve(&annots[0]).as_dict().unwrap();
+            assert_eq!(annot.get_reference("P"), Some(*page));
+        }
+
+        // The outline items of both imports, the named destination resolved
+        let catalog = loaded.catalog().unwrap();
+        let outlines = loaded.resolve_entry(catalog, "Outlines").and_then(|o| o.as_dict()).unwrap();
+        assert_eq!(outlines.get_i64("Count"), Some(4));
+        let mut item = outlines.get_reference("First");
+        let mut dest_pages = Vec::new();
+        while let Some(id) = item {
+            let dict = loaded.get(id).and_then(|o| o.as_dict()).unwrap();
+            let dest = dict.get("Dest").and_then(|d| d.as_array()).unwrap();
+            dest_pages.push(dest[0].as_reference().unwrap());
+            item = dict.get_reference("Next");
+        }
+        assert_eq!(dest_pages, pages[1..]);
+    }
+
+    #[test]
+    fn import_pages() {
+        let src = source();
+        let mut doc = Doc::new(Version::V1_7);
+        let pages = doc.import_pages(&src, &[1]).unwrap();
+        assert!(doc.import_pages(&src, &[2]).is_err());
+        for page in pages {
+            doc.push_page(page);
+        }
+
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let catalog = loaded.catalog().unwrap();
+        let outlines = loaded.resolve_entry(catalog, "Outlines").and_then(|o| o.as_dict()).unwrap();
+        let item = loaded.resolve_entry(outlines, "First").and_then(|o| o.as_dict()).unwrap();
+        assert_eq!(item.get("Title").and_then(|t| t.as_text()).as_deref(), Some("Two"));
+        assert_eq!(outlines.get_reference("First"), outlines.get_reference("Last"));
     }
 }
@@ -0,0 +1,53 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+/// Errors from loading and editing existing PDFs.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// Malformed syntax at the byte offset.
+    Parse { offset: usize, message: String },
+    /// A stream could not be decoded.
+    Filter { filter: String, message: String },
+    /// A feature such as encryption which is not supported.
+    Unsupported(String),
+    /// A required object, entry or field is missing.
+    NotFound(String),
+    /// A value is not acceptable, e.g. a field value not in the options.
+    InvalidValue(String),
+}
+
+impl Error {
+    pub(crate) fn parse(offset: usize, message: &str) -> Self {
+        Self::Parse { offset, message: message.to_string() }
+    }
+
+    pub(crate) fn filter(filter: &str, message: &str) -> Self {
+        Self::Filter { filter: filter.to_string(), message: message.to_string() }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Parse { offset, message } => write!(f, "parse error at {offset}: {message}"),
+            Self::Filter { filter, message } => write!(f, "{filter} error: {message}"),
+            Self::Unsupported(s) => write!(f, "unsupported: {s}"),
+            Self::NotFound(s) => write!(f, "not found: {s}"),
+            Self::InvalidValue(s) => write!(f, "invalid value: {s}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
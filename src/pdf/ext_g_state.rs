@@ -0,0 +1,111 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::utils::{dict_string, ToPdfString};
+
+/// PDF32000-1:2008 11.3.5
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+}
+
+impl BlendMode {
+    pub fn to_pdf_string(&self) -> String {
+        match self {
+            Self::Normal => "/Normal",
+            Self::Multiply => "/Multiply",
+            Self::Screen => "/Screen",
+            Self::Overlay => "/Overlay",
+            Self::Darken => "/Darken",
+            Self::Lighten => "/Lighten",
+        }.to_string()
+    }
+}
+
+/// PDF32000-1:2008 8.4.5
+///
+/// ```text
+/// << /Type /ExtGState
+///    /CA 0.5
+///    /ca 0.5
+///    /BM /Multiply
+/// >>
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtGState {
+    stroke_alpha: Option<f64>,
+    fill_alpha: Option<f64>,
+    blend_mode: Option<BlendMode>,
+}
+
+impl ExtGState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the constant opacity for stroking and non-stroking operations.
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.stroke_alpha = Some(alpha);
+        self.fill_alpha = Some(alpha);
+    }
+
+    pub fn set_stroke_alpha(&mut self, alpha: f64) {
+        self.stroke_alpha = Some(alpha);
+    }
+
+    pub fn set_fill_alpha(&mut self, alpha: f64) {
+        self.fill_alpha = Some(alpha);
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = Some(blend_mode);
+    }
+
+    pub fn to_pdf_string(&self) -> String {
+        let mut entries = vec![("Type", String::from("/ExtGState"))];
+        if let Some(a) = self.stroke_alpha {
+            entries.push(("CA", a.to_pdf_string()));
+        }
+        if let Some(a) = self.fill_alpha {
+            entries.push(("ca", a.to_pdf_string()));
+        }
+        if let Some(bm) = self.blend_mode {
+            entries.push(("BM", bm.to_pdf_string()));
+        }
+        dict_string(&entries)
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_pdf_string() {
+        let mut gs = ExtGState::new();
+        gs.set_fill_alpha(0.5);
+        gs.set_blend_mode(BlendMode::Multiply);
+
+        let ok = concat!(
+            "<< /Type /ExtGState\n",
+            "   /ca 0.5\n",
+            "   /BM /Multiply\n",
+            ">>",
+        );
+
+        assert_eq!(gs.to_pdf_string(), ok);
+    }
+}
@@ -0,0 +1,765 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! ITU-T T.81 (JPEG) baseline decoder and encoder for DCTDecode.
+
+use std::f64::consts::PI;
+
+use crate::pdf::error::Error;
+use super::flate::code_lengths;
+
+/// Decoded samples, interleaved and 8 bits per component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub components: usize,
+    pub samples: Vec<u8>,
+}
+
+/// Decodes baseline and extended sequential Huffman JPEG data.
+///
+/// `color_transform` is the `/ColorTransform` decode parameter. Without it,
+/// the transform of an Adobe APP14 marker is used, else YCbCr is assumed
+/// for 3 components only. Progressive and lossless data are unsupported.
+pub fn decode(data: &[u8], color_transform: Option<bool>) -> Result<Image, Error> {
+    let mut decoder = Decoder::default();
+    let mut pos = 2;
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return Err(Error::filter("DCTDecode", "missing SOI marker"));
+    }
+
+    loop {
+        while data.get(pos) == Some(&0xff) && data.get(pos + 1) == Some(&0xff) {
+            pos += 1;
+        }
+        let (Some(0xff), Some(&marker)) = (data.get(pos), data.get(pos + 1)) else {
+            return Err(Error::filter("DCTDecode", "missing marker"));
+        };
+        pos += 2;
+        if marker == 0xd9 {
+            break;
+        }
+        if (0xd0..=0xd7).contains(&marker) || marker == 0x01 {
+            continue;
+        }
+        let length = u16::from_be_bytes([*data.get(pos).unwrap_or(&0), *data.get(pos + 1).unwrap_or(&0)]) as usize;
+        let segment = data.get(pos + 2..pos + length.max(2))
+            .ok_or_else(|| Error::filter("DCTDecode", "truncated segment"))?;
+        pos += length;
+        match marker {
+            0xc0 | 0xc1 => decoder.read_frame(segment)?,
+            0xc2 | 0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => {
+                return Err(Error::Unsupported("progressive, lossless or arithmetic coded JPEG".to_string()));
+            }
+            0xc4 => decoder.read_huffman_tables(segment)?,
+            0xdb => decoder.read_quantization_tables(segment)?,
+            0xdd => decoder.restart_interval = u16::from_be_bytes([segment[0], segment[1]]) as usize,
+            0xee if segment.starts_with(b"Adobe") && segment.len() >= 12 => decoder.adobe_transform = Some(segment[11]),
+            0xda => pos = decoder.read_scan(segment, data, pos)?,
+            _ => (),
+        }
+    }
+
+    decoder.output(color_transform)
+}
+
+/// Encodes 8-bit samples as baseline JPEG data with the IJG quality scale
+/// (1-100), without subsampling and with Huffman tables optimized for the
+/// image. Three components are converted to YCbCr, which is the default
+/// `/ColorTransform` of DCTDecode.
+pub fn encode(image: &Image, quality: u8) -> Result<Vec<u8>, Error> {
+    if !matches!(image.components, 1 | 3 | 4) || image.width == 0 || image.height == 0
+        || image.width > 0xffff || image.height > 0xffff
+        || image.samples.len() < image.width * image.height * image.components {
+        return Err(Error::InvalidValue("JPEG image size".to_string()));
+    }
+
+    let quality = quality.clamp(1, 100) as u32;
+    let scale = if quality < 50 { 5000 / quality } else { 200 - quality * 2 };
+    let tables: Vec<[u16; 64]> = [LUMINANCE_QUANTIZATION, CHROMINANCE_QUANTIZATION].iter()
+        .map(|base| std::array::from_fn(|k| ((base[ZIGZAG[k]] as u32 * scale + 50) / 100).clamp(1, 255) as u16))
+        .collect();
+    let table_of = |c: usize| usize::from(image.components == 3 && c > 0);
+
+    // Quantized blocks in zigzag order, per component
+    let blocks_x = image.width.div_ceil(8);
+    let blocks_y = image.height.div_ceil(8);
+    let planes = planes(image);
+    let mut blocks: Vec<Vec<[i32; 64]>> = (0..image.components).map(|_| Vec::with_capacity(blocks_x * blocks_y)).collect();
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            for (c, plane) in planes.iter().enumerate() {
+                let mut block = [0.0; 64];
+                for y in 0..8 {
+                    for x in 0..8 {
+                        let px = (bx * 8 + x).min(image.width - 1);
+                        let py = (by * 8 + y).min(image.height - 1);
+                        block[y * 8 + x] = plane[py * image.width + px] - 128.0;
+                    }
+                }
+                let coefficients = fdct(&block);
+                let table = &tables[table_of(c)];
+                let quantized: [i32; 64] = std::array::from_fn(|k| (coefficients[ZIGZAG[k]] / table[k] as f64).round() as i32);
+                blocks[c].push(quantized);
+            }
+        }
+    }
+
+    // Symbol statistics, then the optimal tables
+    let table_count = if image.components == 3 { 2 } else { 1 };
+    let mut dc_freqs = vec![[0u32; 257]; table_count];
+    let mut ac_freqs = vec![[0u32; 257]; table_count];
+    for (c, component) in blocks.iter().enumerate() {
+        let mut pred = 0;
+        for block in component {
+            encode_block(block, &mut pred, |symbol, _, _| {
+                let freqs = if symbol.0 { &mut ac_freqs } else { &mut dc_freqs };
+                freqs[table_of(c)][symbol.1 as usize] += 1;
+            });
+        }
+    }
+    let dc_tables: Vec<HuffmanTable> = dc_freqs.iter_mut().map(HuffmanTable::optimal).collect();
+    let ac_tables: Vec<HuffmanTable> = ac_freqs.iter_mut().map(HuffmanTable::optimal).collect();
+
+    let mut out = vec![0xff, 0xd8];
+    for (i, table) in tables.iter().enumerate().take(table_count) {
+        let mut segment = vec![i as u8];
+        segment.extend(table.iter().map(|q| *q as u8));
+        write_segment(&mut out, 0xdb, &segment);
+    }
+
+    let mut frame = vec![8];
+    frame.extend_from_slice(&(image.height as u16).to_be_bytes());
+    frame.extend_from_slice(&(image.width as u16).to_be_bytes());
+    frame.push(image.components as u8);
+    for c in 0..image.components {
+        frame.extend_from_slice(&[c as u8 + 1, 0x11, table_of(c) as u8]);
+    }
+    write_segment(&mut out, 0xc0, &frame);
+
+    for (class, tables) in [(0u8, &dc_tables), (1, &ac_tables)] {
+        for (i, table) in tables.iter().enumerate() {
+            let mut segment = vec![class << 4 | i as u8];
+            segment.extend_from_slice(&table.counts);
+            segment.extend_from_slice(&table.symbols);
+            write_segment(&mut out, 0xc4, &segment);
+        }
+    }
+
+    let mut scan = vec![image.components as u8];
+    for c in 0..image.components {
+        scan.extend_from_slice(&[c as u8 + 1, (table_of(c) as u8) << 4 | table_of(c) as u8]);
+    }
+    scan.extend_from_slice(&[0, 63, 0]);
+    write_segment(&mut out, 0xda, &scan);
+
+    let mut writer = BitWriter { out, buf: 0, count: 0 };
+    let mut preds = vec![0; image.components];
+    for i in 0..blocks_x * blocks_y {
+        for (c, component) in blocks.iter().enumerate() {
+            let (dc, ac) = (&dc_tables[table_of(c)], &ac_tables[table_of(c)]);
+            encode_block(&component[i], &mut preds[c], |(is_ac, symbol), value, bits| {
+                let table = if is_ac { ac } else { dc };
+                let (code, length) = table.codes[symbol as usize];
+                writer.bits(code as u32, length as u32);
+                writer.bits(value, bits);
+            });
+        }
+    }
+    let mut out = writer.finish();
+    out.extend_from_slice(&[0xff, 0xd9]);
+    Ok(out)
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Zigzag index to natural (row-major) index.
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10,
+    17, 24, 32, 25, 18, 11, 4, 5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13, 6, 7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// T.81 Annex K.1, in natural order.
+const LUMINANCE_QUANTIZATION: [u8; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61,
+    12, 12, 14, 19, 26, 58, 60, 55,
+    14, 13, 16, 24, 40, 57, 69, 56,
+    14, 17, 22, 29, 51, 87, 80, 62,
+    18, 22, 37, 56, 68, 109, 103, 77,
+    24, 35, 55, 64, 81, 104, 113, 92,
+    49, 64, 78, 87, 103, 121, 120, 101,
+    72, 92, 95, 98, 112, 100, 103, 99,
+];
+
+const CHROMINANCE_QUANTIZATION: [u8; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99,
+    18, 21, 26, 66, 99, 99, 99, 99,
+    24, 26, 56, 99, 99, 99, 99, 99,
+    47, 66, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+/// `C(u) / 2 * cos((2x + 1) u pi / 16)` indexed by `[x][u]`, the basis of
+/// both the forward and the inverse DCT.
+fn basis() -> [[f64; 8]; 8] {
+    std::array::from_fn(|x| std::array::from_fn(|u| {
+        let c = if u == 0 { std::f64::consts::FRAC_1_SQRT_2 } else { 1.0 };
+        c / 2.0 * ((2 * x + 1) as f64 * u as f64 * PI / 16.0).cos()
+    }))
+}
+
+fn fdct(block: &[f64; 64]) -> [f64; 64] {
+    let basis = basis();
+    let mut rows = [0.0; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..8).map(|x| basis[x][u] * block[y * 8 + x]).sum();
+        }
+    }
+    let mut out = [0.0; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            out[v * 8 + u] = (0..8).map(|y| basis[y][v] * rows[y * 8 + u]).sum();
+        }
+    }
+    out
+}
+
+fn idct(coefficients: &[f64; 64]) -> [f64; 64] {
+    let basis = basis();
+    let mut columns = [0.0; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            columns[y * 8 + u] = (0..8).map(|v| basis[y][v] * coefficients[v * 8 + u]).sum();
+        }
+    }
+    let mut out = [0.0; 64];
+    for y in 0..8 {
+        for x in 0..8 {
+            out[y * 8 + x] = (0..8).map(|u| basis[x][u] * columns[y * 8 + u]).sum();
+        }
+    }
+    out
+}
+
+/// Returns the planes of the components to encode, converted to YCbCr for
+/// 3 components.
+fn planes(image: &Image) -> Vec<Vec<f64>> {
+    let n = image.components;
+    let pixels = image.samples.chunks_exact(n).take(image.width * image.height);
+    if n != 3 {
+        return (0..n).map(|c| pixels.clone().map(|p| p[c] as f64).collect()).collect();
+    }
+
+    let mut planes: Vec<Vec<f64>> = (0..3).map(|_| Vec::with_capacity(image.width * image.height)).collect();
+    for p in pixels {
+        let (r, g, b) = (p[0] as f64, p[1] as f64, p[2] as f64);
+        planes[0].push(0.299 * r + 0.587 * g + 0.114 * b);
+        planes[1].push(-0.168736 * r - 0.331264 * g + 0.5 * b + 128.0);
+        planes[2].push(0.5 * r - 0.418688 * g - 0.081312 * b + 128.0);
+    }
+    planes
+}
+
+fn write_segment(out: &mut Vec<u8>, marker: u8, segment: &[u8]) {
+    out.extend_from_slice(&[0xff, marker]);
+    out.extend_from_slice(&(segment.len() as u16 + 2).to_be_bytes());
+    out.extend_from_slice(segment);
+}
+
+/// Calls `emit((is_ac, symbol), value, bits)` for each symbol of the block.
+fn encode_block(block: &[i32; 64], pred: &mut i32, mut emit: impl FnMut((bool, u8), u32, u32)) {
+    let category = |v: i32| 32 - v.unsigned_abs().leading_zeros();
+    let bits = |v: i32, size: u32| {
+        let v = if v < 0 { v - 1 } else { v };
+        (v as u32) & ((1u32 << size) - 1)
+    };
+
+    let diff = block[0] - *pred;
+    *pred = block[0];
+    let size = category(diff);
+    emit((false, size as u8), bits(diff, size), size);
+
+    let mut run = 0;
+    for value in &block[1..] {
+        if *value == 0 {
+            run += 1;
+            continue;
+        }
+        while run > 15 {
+            emit((true, 0xf0), 0, 0);
+            run -= 16;
+        }
+        let size = category(*value);
+        emit((true, (run << 4) as u8 | size as u8), bits(*value, size), size);
+        run = 0;
+    }
+    if run > 0 {
+        emit((true, 0x00), 0, 0);
+    }
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    buf: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Writes the bits MSB first, stuffing a zero byte after 0xff.
+    fn bits(&mut self, value: u32, count: u32) {
+        for i in (0..count).rev() {
+            self.buf = self.buf << 1 | (value >> i) & 1;
+            self.count += 1;
+            if self.count == 8 {
+                self.out.push(self.buf as u8);
+                if self.buf == 0xff {
+                    self.out.push(0);
+                }
+                self.buf = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    /// Pads the last byte with one bits.
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bits(0xff, 8 - self.count);
+        }
+        self.out
+    }
+}
+
+#[derive(Default, Clone)]
+struct HuffmanTable {
+    /// The number of codes of each length, 1 to 16
+    counts: [u8; 16],
+    symbols: Vec<u8>,
+    /// (code, length) by symbol, for encoding
+    codes: Vec<(u16, u8)>,
+    /// The largest code of each length, -1 if none, for decoding
+    max_codes: [i32; 17],
+    /// The index in `symbols` minus the first code of each length
+    offsets: [i32; 17],
+}
+
+impl HuffmanTable {
+    fn new(counts: [u8; 16], symbols: Vec<u8>) -> Self {
+        let mut table = Self {
+            counts,
+            codes: vec![(0, 0); 256],
+            max_codes: [-1; 17],
+            ..Default::default()
+        };
+        let mut code = 0i32;
+        let mut index = 0usize;
+        for length in 1..=16 {
+            let count = counts[length - 1] as usize;
+            table.offsets[length] = index as i32 - code;
+            for symbol in symbols.iter().skip(index).take(count) {
+                table.codes[*symbol as usize] = (code as u16, length as u8);
+                code += 1;
+            }
+            index += count;
+            if count > 0 {
+                table.max_codes[length] = code - 1;
+            }
+            code <<= 1;
+        }
+        table.symbols = symbols;
+        table
+    }
+
+    /// Builds the table from the symbol frequencies. Index 256 reserves a
+    /// code so that no code is all one bits (T.81 K.2).
+    fn optimal(freqs: &mut [u32; 257]) -> Self {
+        freqs[256] = 1;
+        let mut lengths = code_lengths(freqs.as_slice(), 16);
+        let max = *lengths.iter().max().unwrap_or(&0);
+        if lengths[256] != max && let Some(i) = lengths.iter().position(|l| *l == max) {
+            lengths.swap(i, 256);
+        }
+
+        let mut counts = [0u8; 16];
+        let mut symbols = Vec::new();
+        for length in 1..=16u8 {
+            for (symbol, l) in lengths.iter().enumerate().take(256) {
+                if *l == length {
+                    counts[length as usize - 1] += 1;
+                    symbols.push(symbol as u8);
+                }
+            }
+        }
+        Self::new(counts, symbols)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+    table: usize,
+    /// Blocks per line and lines of blocks, padded to whole MCUs
+    blocks_x: usize,
+    blocks_y: usize,
+    plane: Vec<u8>,
+}
+
+struct Decoder {
+    width: usize,
+    height: usize,
+    components: Vec<Component>,
+    quantization: [[u16; 64]; 4],
+    dc_tables: [HuffmanTable; 4],
+    ac_tables: [HuffmanTable; 4],
+    restart_interval: usize,
+    adobe_transform: Option<u8>,
+    max_h: usize,
+    max_v: usize,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            components: Vec::new(),
+            quantization: [[1; 64]; 4],
+            dc_tables: Default::default(),
+            ac_tables: Default::default(),
+            restart_interval: 0,
+            adobe_transform: None,
+            max_h: 1,
+            max_v: 1,
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl BitReader<'_> {
+    fn bit(&mut self) -> u32 {
+        if self.count == 0 {
+            // Zero bits past a marker
+            let mut byte = 0;
+            if let Some(b) = self.data.get(self.pos) {
+                if *b != 0xff {
+                    byte = *b;
+                    self.pos += 1;
+                } else if self.data.get(self.pos + 1) == Some(&0) {
+                    byte = 0xff;
+                    self.pos += 2;
+                }
+            }
+            self.buf = byte as u32;
+            self.count = 8;
+        }
+        self.count -= 1;
+        (self.buf >> self.count) & 1
+    }
+
+    fn bits(&mut self, count: u32) -> i32 {
+        (0..count).fold(0, |v, _| v << 1 | self.bit() as i32)
+    }
+
+    /// Reads an additional bits value of the category (T.81 F.2.2.1).
+    fn receive_extend(&mut self, size: u32) -> i32 {
+        if size == 0 {
+            return 0;
+        }
+        let v = self.bits(size);
+        if v < 1 << (size - 1) { v - (1 << size) + 1 } else { v }
+    }
+
+    fn decode(&mut self, table: &HuffmanTable) -> Result<u8, Error> {
+        let mut code = self.bit() as i32;
+        let mut length = 1;
+        while length <= 16 && code > table.max_codes[length] {
+            code = code << 1 | self.bit() as i32;
+            length += 1;
+        }
+        if length > 16 {
+            return Err(Error::filter("DCTDecode", "invalid Huffman code"));
+        }
+        table.symbols.get((table.offsets[length] + code) as usize)
+            .copied()
+            .ok_or_else(|| Error::filter("DCTDecode", "invalid Huffman code"))
+    }
+
+    /// Skips the restart marker at the next byte boundary.
+    fn restart(&mut self) {
+        self.count = 0;
+        while self.data.get(self.pos) == Some(&0xff) && self.data.get(self.pos + 1).is_some_and(|m| *m == 0xff || (0xd0..=0xd7).contains(m)) {
+            self.pos += 2;
+        }
+    }
+}
+
+impl Decoder {
+    fn read_frame(&mut self, segment: &[u8]) -> Result<(), Error> {
+        let error = || Error::filter("DCTDecode", "invalid frame header");
+        if segment.len() < 6 || segment[0] != 8 {
+            return Err(Error::Unsupported(format!("JPEG with {} bits precision", segment.first().unwrap_or(&0))));
+        }
+        self.height = u16::from_be_bytes([segment[1], segment[2]]) as usize;
+        self.width = u16::from_be_bytes([segment[3], segment[4]]) as usize;
+        let count = segment[5] as usize;
+        if self.width == 0 || self.height == 0 || !matches!(count, 1 | 3 | 4) || segment.len() < 6 + count * 3 {
+            return Err(error());
+        }
+        for c in segment[6..6 + count * 3].chunks(3) {
+            let (h, v) = ((c[1] >> 4) as usize, (c[1] & 15) as usize);
+            if !(1..=4).contains(&h) || !(1..=4).contains(&v) || c[2] > 3 {
+                return Err(error());
+            }
+            self.components.push(Component { id: c[0], h, v, table: c[2] as usize, blocks_x: 0, blocks_y: 0, plane: Vec::new() });
+        }
+        self.max_h = self.components.iter().map(|c| c.h).max().unwrap_or(1);
+        self.max_v = self.components.iter().map(|c| c.v).max().unwrap_or(1);
+        let mcus_x = self.width.div_ceil(8 * self.max_h);
+        let mcus_y = self.height.div_ceil(8 * self.max_v);
+        for c in &mut self.components {
+            c.blocks_x = mcus_x * c.h;
+            c.blocks_y = mcus_y * c.v;
+            c.plane = vec![0; c.blocks_x * c.blocks_y * 64];
+        }
+        Ok(())
+    }
+
+    fn read_huffman_tables(&mut self, mut segment: &[u8]) -> Result<(), Error> {
+        while segment.len() >= 17 {
+            let (class, index) = (segment[0] >> 4, (segment[0] & 15) as usize);
+            let counts: [u8; 16] = segment[1..17].try_into().unwrap();
+            let total: usize = counts.iter().map(|c| *c as usize).sum();
+            let symbols = segment.get(17..17 + total)
+                .ok_or_else(|| Error::filter("DCTDecode", "truncated Huffman table"))?;
+            if index > 3 || class > 1 {
+                return Err(Error::filter("DCTDecode", "invalid Huffman table"));
+            }
+            let table = HuffmanTable::new(counts, symbols.to_vec());
+            if class == 0 {
+                self.dc_tables[index] = table;
+            } else {
+                self.ac_tables[index] = table;
+            }
+            segment = &segment[17 + total..];
+        }
+        Ok(())
+    }
+
+    fn read_quantization_tables(&mut self, mut segment: &[u8]) -> Result<(), Error> {
+        while let Some(first) = segment.first() {
+            let (precision, index) = (first >> 4, (first & 15) as usize);
+            let size = if precision == 0 { 64 } else { 128 };
+            let values = segment.get(1..1 + size)
+                .filter(|_| index < 4)
+                .ok_or_else(|| Error::filter("DCTDecode", "invalid quantization table"))?;
+            for k in 0..64 {
+                self.quantization[index][k] = if precision == 0 {
+                    values[k] as u16
+                } else {
+                    u16::from_be_bytes([values[k * 2], values[k * 2 + 1]])
+                };
+            }
+            segment = &segment[1 + size..];
+        }
+        Ok(())
+    }
+
+    /// Decodes the entropy-coded data after the scan header at `pos` and
+    /// returns the position of the next marker.
+    fn read_scan(&mut self, header: &[u8], data: &[u8], pos: usize) -> Result<usize, Error> {
+        let error = || Error::filter("DCTDecode", "invalid scan header");
+        let count = *header.first().ok_or_else(error)? as usize;
+        let mut scan = Vec::new();
+        for c in header.get(1..1 + count * 2).ok_or_else(error)?.chunks(2) {
+            let index = self.components.iter().position(|comp| comp.id == c[0]).ok_or_else(error)?;
+            scan.push((index, (c[1] >> 4) as usize & 3, (c[1] & 15) as usize & 3));
+        }
+        if scan.is_empty() {
+            return Err(error());
+        }
+
+        let mut reader = BitReader { data, pos, buf: 0, count: 0 };
+        let mut preds = vec![0i32; scan.len()];
+        // A single component scan is not interleaved: one block per MCU
+        let (mcus_x, mcus_y) = if let [(index, _, _)] = scan[..] {
+            let c = &self.components[index];
+            (
+                (self.width * c.h).div_ceil(self.max_h).div_ceil(8),
+                (self.height * c.v).div_ceil(self.max_v).div_ceil(8),
+            )
+        } else {
+            (self.width.div_ceil(8 * self.max_h), self.height.div_ceil(8 * self.max_v))
+        };
+
+        for mcu in 0..mcus_x * mcus_y {
+            if self.restart_interval > 0 && mcu > 0 && mcu % self.restart_interval == 0 {
+                reader.restart();
+                preds.fill(0);
+            }
+            let (mx, my) = (mcu % mcus_x, mcu / mcus_x);
+            for (i, (index, dc, ac)) in scan.iter().enumerate() {
+                let (h, v) = if scan.len() == 1 { (1, 1) } else { (self.components[*index].h, self.components[*index].v) };
+                for by in 0..v {
+                    for bx in 0..h {
+                        let coefficients = self.decode_block(&mut reader, *index, *dc, *ac, &mut preds[i])?;
+                        let samples = idct(&coefficients);
+                        let c = &mut self.components[*index];
+                        let (x, y) = ((mx * h + bx) * 8, (my * v + by) * 8);
+                        let stride = c.blocks_x * 8;
+                        for row in 0..8 {
+                            for column in 0..8 {
+                                let sample = (samples[row * 8 + column] + 128.0).round().clamp(0.0, 255.0) as u8;
+                                c.plane[(y + row) * stride + x + column] = sample;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Finds the marker after the entropy-coded data
+        let mut pos = reader.pos;
+        while pos + 1 < data.len() && !(data[pos] == 0xff && data[pos + 1] != 0 && !(0xd0..=0xd7).contains(&data[pos + 1])) {
+            pos += 1;
+        }
+        Ok(pos)
+    }
+
+    fn decode_block(&self, reader: &mut BitReader, index: usize, dc: usize, ac: usize, pred: &mut i32) -> Result<[f64; 64], Error> {
+        let quantization = &self.quantization[self.components[index].table];
+        let mut coefficients = [0.0; 64];
+        let size = reader.decode(&self.dc_tables[dc])? as u32;
+        *pred += reader.receive_extend(size.min(16));
+        coefficients[0] = (*pred * quantization[0] as i32) as f64;
+
+        let mut k = 1;
+        while k < 64 {
+            let rs = reader.decode(&self.ac_tables[ac])?;
+            let (run, size) = ((rs >> 4) as usize, (rs & 15) as u32);
+            if size == 0 {
+                if run != 15 {
+                    break;
+                }
+                k += 16;
+                continue;
+            }
+            k += run;
+            if k > 63 {
+                return Err(Error::filter("DCTDecode", "invalid coefficient"));
+            }
+            coefficients[ZIGZAG[k]] = (reader.receive_extend(size) * quantization[k] as i32) as f64;
+            k += 1;
+        }
+        Ok(coefficients)
+    }
+
+    fn output(self, color_transform: Option<bool>) -> Result<Image, Error> {
+        let n = self.components.len();
+        if n == 0 {
+            return Err(Error::filter("DCTDecode", "missing frame"));
+        }
+        let transform = match (color_transform, self.adobe_transform) {
+            (Some(t), _) => t && n >= 3,
+            (None, Some(t)) => t != 0 && n >= 3,
+            (None, None) => n == 3,
+        };
+
+        let mut samples = Vec::with_capacity(self.width * self.height * n);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let start = samples.len();
+                for c in &self.components {
+                    let cx = x * c.h / self.max_h;
+                    let cy = y * c.v / self.max_v;
+                    samples.push(c.plane[cy * c.blocks_x * 8 + cx]);
+                }
+                if transform {
+                    let p = &mut samples[start..];
+                    let (luma, cb, cr) = (p[0] as f64, p[1] as f64 - 128.0, p[2] as f64 - 128.0);
+                    let rgb = [luma + 1.402 * cr, luma - 0.344136 * cb - 0.714136 * cr, luma + 1.772 * cb];
+                    for (i, v) in rgb.iter().enumerate() {
+                        let v = v.round().clamp(0.0, 255.0) as u8;
+                        // YCCK converts to CMY
+                        p[i] = if n == 4 { 255 - v } else { v };
+                    }
+                }
+            }
+        }
+        Ok(Image { width: self.width, height: self.height, components: n, samples })
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: usize, height: usize, components: usize) -> Image {
+        let mut samples = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..components {
+                    samples.push(((x * 255 / width + y * 3 + c * 40) % 256) as u8);
+                }
+            }
+        }
+        Image { width, height, components, samples }
+    }
+
+    fn max_error(a: &Image, b: &Image) -> u8 {
+        a.samples.iter().zip(&b.samples).map(|(a, b)| a.abs_diff(*b)).max().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for components in [1, 3, 4] {
+            let image = gradient(21, 13, components);
+            let data = encode(&image, 95).unwrap();
+            let decoded = decode(&data, None).unwrap();
+            assert_eq!((decoded.width, decoded.height, decoded.components), (21, 13, components));
+            assert!(max_error(&image, &decoded) < 24, "{components}");
+
+            let low = encode(&image, 10).unwrap();
+            assert!(low.len() < data.len());
+        }
+    }
+
+    #[test]
+    fn flat_and_unsupported() {
+        let image = Image { width: 16, height: 16, components: 1, samples: vec![200; 256] };
+        let gray = encode(&image, 100).unwrap();
+        let decoded = decode(&gray, None).unwrap();
+        assert!(decoded.samples.iter().all(|s| s.abs_diff(200) <= 1));
+
+        let progressive = [0xff, 0xd8, 0xff, 0xc2, 0, 11, 8, 0, 1, 0, 1, 1, 1, 0x11, 0];
+        assert!(matches!(decode(&progressive, None), Err(Error::Unsupported(_))));
+    }
+}
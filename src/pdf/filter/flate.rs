@@ -0,0 +1,735 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! RFC 1950/1951 decoder, after Mark Adler's puff.c, and encoder.
+
+use super::super::error::Error;
+
+const MAX_BITS: usize = 15;
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0, buf: 0, count: 0 }
+    }
+
+    fn bits(&mut self, need: u32) -> Result<u32, Error> {
+        let mut val = self.buf;
+        while self.count < need {
+            let Some(b) = self.data.get(self.pos) else {
+                return Err(Error::filter("FlateDecode", "unexpected end of data"));
+            };
+            self.pos += 1;
+            val |= (*b as u32) << self.count;
+            self.count += 8;
+        }
+        self.buf = if need == 32 { 0 } else { val >> need };
+        self.count -= need;
+        Ok(val & ((1u64 << need) - 1) as u32)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.buf = 0;
+        self.count = 0;
+    }
+}
+
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for l in lengths {
+            counts[*l as usize] += 1;
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, l) in lengths.iter().enumerate() {
+            if *l != 0 {
+                symbols[offsets[*l as usize] as usize] = symbol as u16;
+                offsets[*l as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, Error> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+        Err(Error::filter("FlateDecode", "invalid Huffman code"))
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+fn inflate_codes(reader: &mut BitReader, out: &mut Vec<u8>, lencode: &Huffman, distcode: &Huffman) -> Result<(), Error> {
+    loop {
+        let symbol = lencode.decode(reader)?;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let symbol = (symbol - 257) as usize;
+                if symbol >= 29 {
+                    return Err(Error::filter("FlateDecode", "invalid length symbol"));
+                }
+                let len = LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+                let symbol = distcode.decode(reader)? as usize;
+                if symbol >= 30 {
+                    return Err(Error::filter("FlateDecode", "invalid distance symbol"));
+                }
+                let dist = DIST_BASE[symbol] as usize + reader.bits(DIST_EXTRA[symbol] as u32)? as usize;
+                if dist > out.len() {
+                    return Err(Error::filter("FlateDecode", "distance too far back"));
+                }
+
+                let start = out.len() - dist;
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+fn inflate_stored(reader: &mut BitReader, out: &mut Vec<u8>) -> Result<(), Error> {
+    reader.align_to_byte();
+    let data = reader.data;
+    let pos = reader.pos;
+    if pos + 4 > data.len() {
+        return Err(Error::filter("FlateDecode", "unexpected end of data"));
+    }
+    let len = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+    let nlen = u16::from_le_bytes([data[pos + 2], data[pos + 3]]) as usize;
+    if len != (!nlen & 0xffff) {
+        return Err(Error::filter("FlateDecode", "stored block length mismatch"));
+    }
+    let start = pos + 4;
+    let end = (start + len).min(data.len());
+    out.extend_from_slice(&data[start..end]);
+    reader.pos = end;
+    Ok(())
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    for (i, l) in lengths.iter_mut().enumerate() {
+        *l = match i {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (Huffman::new(&lengths), Huffman::new(&[5u8; 30]))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    const ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+    let nlen = reader.bits(5)? as usize + 257;
+    let ndist = reader.bits(5)? as usize + 1;
+    let ncode = reader.bits(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(Error::filter("FlateDecode", "bad counts"));
+    }
+
+    let mut lengths = [0u8; 320];
+    for i in ORDER.iter().take(ncode) {
+        lengths[*i] = reader.bits(3)? as u8;
+    }
+    let lencode = Huffman::new(&lengths[..19]);
+
+    let mut index = 0;
+    while index < nlen + ndist {
+        let symbol = lencode.decode(reader)?;
+        if symbol < 16 {
+            lengths[index] = symbol as u8;
+            index += 1;
+            continue;
+        }
+
+        let (value, repeat) = match symbol {
+            16 => {
+                if index == 0 {
+                    return Err(Error::filter("FlateDecode", "repeat with no first length"));
+                }
+                (lengths[index - 1], 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        if index + repeat > nlen + ndist {
+            return Err(Error::filter("FlateDecode", "too many lengths"));
+        }
+        for _ in 0..repeat {
+            lengths[index] = value;
+            index += 1;
+        }
+    }
+
+    Ok((Huffman::new(&lengths[..nlen]), Huffman::new(&lengths[nlen..nlen + ndist])))
+}
+
+/// Decodes raw deflate data. Data after a truncated block is dropped
+/// rather than failing, as many PDF writers produce slightly broken streams.
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = BitReader::new(data);
+    let mut out: Vec<u8> = Vec::with_capacity(data.len() * 4);
+
+    loop {
+        let last = match reader.bits(1) {
+            Ok(v) => v,
+            Err(e) => return if out.is_empty() { Err(e) } else { Ok(out) },
+        };
+        let result = match reader.bits(2)? {
+            0 => inflate_stored(&mut reader, &mut out),
+            1 => {
+                let (lencode, distcode) = fixed_tables();
+                inflate_codes(&mut reader, &mut out, &lencode, &distcode)
+            }
+            2 => dynamic_tables(&mut reader)
+                .and_then(|(lencode, distcode)| inflate_codes(&mut reader, &mut out, &lencode, &distcode)),
+            _ => Err(Error::filter("FlateDecode", "invalid block type")),
+        };
+
+        if let Err(e) = result {
+            return if out.is_empty() { Err(e) } else { Ok(out) };
+        }
+        if last == 1 {
+            return Ok(out);
+        }
+    }
+}
+
+/// Decodes zlib data (RFC 1950). Streams without the zlib header are
+/// decoded as raw deflate data.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let has_header = data.len() >= 2
+        && data[0] & 0x0f == 8
+        && (((data[0] as u16) << 8) | data[1] as u16).is_multiple_of(31);
+
+    if has_header {
+        inflate(&data[2..])
+    } else {
+        inflate(data)
+    }
+}
+
+//------------------------------------------------------------------------------
+// encoder
+//------------------------------------------------------------------------------
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// Candidates tried per position, as zlib's level 9.
+const MAX_CHAIN: usize = 4096;
+/// A match this long is taken without trying the next position.
+const GOOD_MATCH: usize = 32;
+const HASH_BITS: u32 = 15;
+/// Symbols per block, before choosing the block type.
+const BLOCK_SYMBOLS: usize = 1 << 15;
+
+enum Symbol {
+    Literal(u8),
+    Match { length: usize, distance: usize },
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    buf: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self { out: Vec::new(), buf: 0, count: 0 }
+    }
+
+    /// Writes the bits LSB first.
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buf |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.buf as u8);
+            self.buf >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which is packed MSB first.
+    fn code(&mut self, code: u16, length: u8) {
+        let reversed = code.reverse_bits() >> (16 - length as u32);
+        self.bits(reversed as u32, length as u32);
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.count > 0 {
+            self.bits(0, 8 - self.count);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align_to_byte();
+        self.out
+    }
+}
+
+/// Compresses the data as raw deflate data with the best compression:
+/// lazy matching over the whole window and, per block, the smallest of the
+/// dynamic, fixed and stored encodings.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    let symbols = find_matches(data);
+    if symbols.is_empty() {
+        write_block(&mut writer, &[], &[], true);
+        return writer.finish();
+    }
+
+    let mut start = 0;
+    for (i, block) in symbols.chunks(BLOCK_SYMBOLS).enumerate() {
+        let length: usize = block.iter().map(|s| match s {
+            Symbol::Literal(_) => 1,
+            Symbol::Match { length, .. } => *length,
+        }).sum();
+        let last = (i + 1) * BLOCK_SYMBOLS >= symbols.len();
+        write_block(&mut writer, block, &data[start..start + length], last);
+        start += length;
+    }
+    writer.finish()
+}
+
+/// Compresses the data as zlib data (RFC 1950), for `/FlateDecode`.
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    // CMF 0x78: deflate with a 32K window, FLG 0xda: maximum compression
+    let mut out = vec![0x78, 0xda];
+    out.extend(deflate(data));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// LZ77 with hash chains and one step of lazy matching.
+fn find_matches(data: &[u8]) -> Vec<Symbol> {
+    let hash = |i: usize| {
+        let h = ((data[i] as u32) << 10) ^ ((data[i + 1] as u32) << 5) ^ data[i + 2] as u32;
+        (h.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+    };
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; data.len()];
+    let mut inserted = 0;
+    let mut insert_to = |i: usize, head: &mut Vec<usize>, prev: &mut Vec<usize>| {
+        while inserted <= i && inserted + MIN_MATCH <= data.len() {
+            let h = hash(inserted);
+            prev[inserted] = head[h];
+            head[h] = inserted;
+            inserted += 1;
+        }
+    };
+    let longest = |i: usize, head: &[usize], prev: &[usize], chain: usize| -> (usize, usize) {
+        if i + MIN_MATCH > data.len() {
+            return (0, 0);
+        }
+        let max = MAX_MATCH.min(data.len() - i);
+        let mut best = (0, 0);
+        let mut candidate = head[hash(i)];
+        for _ in 0..chain {
+            if candidate == usize::MAX || candidate >= i || i - candidate > WINDOW_SIZE {
+                break;
+            }
+            if data[candidate + best.0.min(max - 1)] == data[i + best.0.min(max - 1)] {
+                let length = data[candidate..candidate + max].iter()
+                    .zip(&data[i..i + max])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if length > best.0 {
+                    best = (length, i - candidate);
+                    if length == max {
+                        break;
+                    }
+                }
+            }
+            candidate = prev[candidate];
+        }
+        if best.0 >= MIN_MATCH { best } else { (0, 0) }
+    };
+
+    let mut symbols = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        insert_to(i.saturating_sub(1), &mut head, &mut prev);
+        let (length, distance) = longest(i, &head, &prev, MAX_CHAIN);
+        if length == 0 {
+            symbols.push(Symbol::Literal(data[i]));
+            i += 1;
+            continue;
+        }
+        if length < GOOD_MATCH {
+            insert_to(i, &mut head, &mut prev);
+            let (next, _) = longest(i + 1, &head, &prev, MAX_CHAIN / 4);
+            if next > length {
+                symbols.push(Symbol::Literal(data[i]));
+                i += 1;
+                continue;
+            }
+        }
+        symbols.push(Symbol::Match { length, distance });
+        i += length;
+    }
+    symbols
+}
+
+fn length_symbol(length: usize) -> (usize, u32, u32) {
+    let i = LENGTH_BASE.iter().rposition(|b| *b as usize <= length).unwrap_or(0);
+    (257 + i, (length - LENGTH_BASE[i] as usize) as u32, LENGTH_EXTRA[i] as u32)
+}
+
+fn distance_symbol(distance: usize) -> (usize, u32, u32) {
+    let i = DIST_BASE.iter().rposition(|b| *b as usize <= distance).unwrap_or(0);
+    (i, (distance - DIST_BASE[i] as usize) as u32, DIST_EXTRA[i] as u32)
+}
+
+fn write_block(writer: &mut BitWriter, symbols: &[Symbol], data: &[u8], last: bool) {
+    let mut literal_freqs = [0u32; 286];
+    let mut distance_freqs = [0u32; 30];
+    literal_freqs[256] = 1;
+    for symbol in symbols {
+        match symbol {
+            Symbol::Literal(b) => literal_freqs[*b as usize] += 1,
+            Symbol::Match { length, distance } => {
+                literal_freqs[length_symbol(*length).0] += 1;
+                distance_freqs[distance_symbol(*distance).0] += 1;
+            }
+        }
+    }
+
+    let literal_lengths = code_lengths(&literal_freqs, 15);
+    let mut distance_lengths = code_lengths(&distance_freqs, 15);
+    // At least one distance code is written
+    if distance_lengths.iter().all(|l| *l == 0) {
+        distance_lengths[0] = 1;
+    }
+    let header = tree_header(&literal_lengths, &distance_lengths);
+
+    let (fixed_literal, fixed_distance) = fixed_lengths();
+    let dynamic_bits = header.bits + data_bits(symbols, &literal_lengths, &distance_lengths);
+    let fixed_bits = data_bits(symbols, &fixed_literal, &fixed_distance);
+    let stored_bits = (data.len() + 5 * data.len().div_ceil(65535).max(1)) * 8;
+
+    if !symbols.is_empty() && stored_bits < dynamic_bits.min(fixed_bits) {
+        let chunks: Vec<&[u8]> = data.chunks(65535).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            writer.bits((last && i + 1 == chunks.len()) as u32, 1);
+            writer.bits(0, 2);
+            writer.align_to_byte();
+            let length = chunk.len() as u16;
+            writer.bits(length as u32, 16);
+            writer.bits(!length as u32, 16);
+            writer.out.extend_from_slice(chunk);
+        }
+        return;
+    }
+
+    writer.bits(last as u32, 1);
+    let (literal_lengths, distance_lengths) = if fixed_bits <= dynamic_bits {
+        writer.bits(1, 2);
+        (fixed_literal, fixed_distance)
+    } else {
+        writer.bits(2, 2);
+        header.write(writer);
+        (literal_lengths, distance_lengths)
+    };
+    let literal_codes = canonical_codes(&literal_lengths);
+    let distance_codes = canonical_codes(&distance_lengths);
+    for symbol in symbols {
+        match symbol {
+            Symbol::Literal(b) => writer.code(literal_codes[*b as usize], literal_lengths[*b as usize]),
+            Symbol::Match { length, distance } => {
+                let (code, extra, extra_bits) = length_symbol(*length);
+                writer.code(literal_codes[code], literal_lengths[code]);
+                writer.bits(extra, extra_bits);
+                let (code, extra, extra_bits) = distance_symbol(*distance);
+                writer.code(distance_codes[code], distance_lengths[code]);
+                writer.bits(extra, extra_bits);
+            }
+        }
+    }
+    writer.code(literal_codes[256], literal_lengths[256]);
+}
+
+fn fixed_lengths() -> (Vec<u8>, Vec<u8>) {
+    let literal = (0..288).map(|i| match i {
+        0..=143 => 8,
+        144..=255 => 9,
+        256..=279 => 7,
+        _ => 8,
+    }).collect();
+    (literal, vec![5; 30])
+}
+
+fn data_bits(symbols: &[Symbol], literal_lengths: &[u8], distance_lengths: &[u8]) -> usize {
+    let mut bits = literal_lengths[256] as usize;
+    for symbol in symbols {
+        bits += match symbol {
+            Symbol::Literal(b) => literal_lengths[*b as usize] as usize,
+            Symbol::Match { length, distance } => {
+                let (l, _, l_extra) = length_symbol(*length);
+                let (d, _, d_extra) = distance_symbol(*distance);
+                literal_lengths[l] as usize + l_extra as usize + distance_lengths[d] as usize + d_extra as usize
+            }
+        };
+    }
+    bits
+}
+
+/// The code lengths of a dynamic block, run-length encoded (3.2.7).
+struct TreeHeader {
+    literal_count: usize,
+    distance_count: usize,
+    /// (symbol, extra bits value, extra bits)
+    runs: Vec<(usize, u32, u32)>,
+    code_length_lengths: Vec<u8>,
+    bits: usize,
+}
+
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+fn tree_header(literal_lengths: &[u8], distance_lengths: &[u8]) -> TreeHeader {
+    let literal_count = 257.max(literal_lengths.iter().rposition(|l| *l > 0).map_or(0, |i| i + 1));
+    let distance_count = 1.max(distance_lengths.iter().rposition(|l| *l > 0).map_or(0, |i| i + 1));
+    let lengths: Vec<u8> = literal_lengths[..literal_count].iter()
+        .chain(&distance_lengths[..distance_count])
+        .copied()
+        .collect();
+
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < lengths.len() {
+        let value = lengths[i];
+        let run = lengths[i..].iter().take_while(|l| **l == value).count();
+        if value == 0 && run >= 11 {
+            let n = run.min(138);
+            runs.push((18, (n - 11) as u32, 7));
+            i += n;
+        } else if value == 0 && run >= 3 {
+            runs.push((17, (run - 3) as u32, 3));
+            i += run;
+        } else if value != 0 && run >= 4 {
+            runs.push((value as usize, 0, 0));
+            let n = (run - 1).min(6);
+            runs.push((16, (n - 3) as u32, 2));
+            i += 1 + n;
+        } else {
+            runs.push((value as usize, 0, 0));
+            i += 1;
+        }
+    }
+
+    let mut freqs = [0u32; 19];
+    for (symbol, _, _) in &runs {
+        freqs[*symbol] += 1;
+    }
+    let code_length_lengths = code_lengths(&freqs, 7);
+    let count = 4.max(CODE_LENGTH_ORDER.iter().rposition(|s| code_length_lengths[*s] > 0).map_or(0, |i| i + 1));
+    let bits = 5 + 5 + 4 + count * 3 + runs.iter()
+        .map(|(symbol, _, extra)| code_length_lengths[*symbol] as usize + *extra as usize)
+        .sum::<usize>();
+    TreeHeader { literal_count, distance_count, runs, code_length_lengths, bits }
+}
+
+impl TreeHeader {
+    fn write(&self, writer: &mut BitWriter) {
+        let count = 4.max(CODE_LENGTH_ORDER.iter().rposition(|s| self.code_length_lengths[*s] > 0).map_or(0, |i| i + 1));
+        writer.bits((self.literal_count - 257) as u32, 5);
+        writer.bits((self.distance_count - 1) as u32, 5);
+        writer.bits((count - 4) as u32, 4);
+        for symbol in &CODE_LENGTH_ORDER[..count] {
+            writer.bits(self.code_length_lengths[*symbol] as u32, 3);
+        }
+        let codes = canonical_codes(&self.code_length_lengths);
+        for (symbol, extra, extra_bits) in &self.runs {
+            writer.code(codes[*symbol], self.code_length_lengths[*symbol]);
+            writer.bits(*extra, *extra_bits);
+        }
+    }
+}
+
+/// Returns the Huffman code lengths up to `limit` bits, by package-merge.
+pub(crate) fn code_lengths(freqs: &[u32], limit: usize) -> Vec<u8> {
+    let mut lengths = vec![0u8; freqs.len()];
+    let mut leaves: Vec<(u64, Vec<usize>)> = freqs.iter().enumerate()
+        .filter(|(_, f)| **f > 0)
+        .map(|(symbol, f)| (*f as u64, vec![symbol]))
+        .collect();
+    match leaves.len() {
+        0 => return lengths,
+        1 => {
+            lengths[leaves[0].1[0]] = 1;
+            return lengths;
+        }
+        _ => (),
+    }
+    leaves.sort_by_key(|(weight, _)| *weight);
+
+    let mut list = leaves.clone();
+    for _ in 1..limit {
+        let packages: Vec<(u64, Vec<usize>)> = list.chunks_exact(2)
+            .map(|pair| (pair[0].0 + pair[1].0, [pair[0].1.as_slice(), pair[1].1.as_slice()].concat()))
+            .collect();
+        let mut merged = Vec::with_capacity(leaves.len() + packages.len());
+        let (mut a, mut b) = (leaves.iter().peekable(), packages.into_iter().peekable());
+        loop {
+            match (a.peek(), b.peek()) {
+                (Some(leaf), Some(package)) if leaf.0 <= package.0 => merged.push(a.next().unwrap().clone()),
+                (_, Some(_)) => merged.push(b.next().unwrap()),
+                (Some(_), None) => merged.push(a.next().unwrap().clone()),
+                (None, None) => break,
+            }
+        }
+        list = merged;
+    }
+    for (_, symbols) in list.iter().take(2 * leaves.len() - 2) {
+        for symbol in symbols {
+            lengths[*symbol] += 1;
+        }
+    }
+    lengths
+}
+
+/// Returns the canonical codes of the lengths (3.2.2).
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let mut counts = [0u16; MAX_BITS + 1];
+    for l in lengths {
+        counts[*l as usize] += 1;
+    }
+    counts[0] = 0;
+    let mut next = [0u16; MAX_BITS + 2];
+    let mut code = 0u16;
+    for bits in 1..=MAX_BITS {
+        code = (code + counts[bits - 1]) << 1;
+        next[bits] = code;
+    }
+    lengths.iter().map(|l| {
+        if *l == 0 {
+            return 0;
+        }
+        let code = next[*l as usize];
+        next[*l as usize] += 1;
+        code
+    }).collect()
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed() {
+        // zlib.compress(b"Hello Hello Hello")
+        let data = [
+            0x78, 0x9c, 0xf3, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xf0, 0x40, 0x90, 0x00,
+            0x36, 0x0e, 0x06, 0x1d];
+        assert_eq!(decode(&data).unwrap(), b"Hello Hello Hello");
+    }
+
+    #[test]
+    fn dynamic() {
+        let data = [
+            0x78, 0xda, 0x4d, 0xc6, 0xb7, 0x15, 0x80, 0x20, 0x14, 0x00, 0xc0, 0x55, 0xfe, 0x08, 0xe6, 0x54,
+            0xfb, 0xac, 0x5d, 0x81, 0x28, 0x2a, 0x20, 0x12, 0x54, 0x9c, 0xde, 0x8e, 0xe7, 0x55, 0x37, 0x8f,
+            0x13, 0x18, 0xeb, 0x0c, 0xe5, 0x40, 0xa3, 0x46, 0x6a, 0x25, 0x20, 0x02, 0xe7, 0x0a, 0x69, 0xc0,
+            0xf2, 0x20, 0x3b, 0x78, 0xe6, 0xfc, 0x00, 0x28, 0xc1, 0x09, 0x49, 0x68, 0x02, 0x59, 0x5e, 0x94,
+            0x55, 0xdd, 0xb4, 0x5d, 0xff, 0xef, 0x1b, 0x9f, 0xfb, 0x0a, 0xde, 0xd9, 0xd3, 0x1c, 0x5a, 0xc9,
+            0x7d, 0x5b, 0xc5, 0xc2, 0x19, 0x25, 0x18, 0x7d, 0x64, 0xf3, 0x2c, 0xf6];
+        let ok = concat!(
+            "PDF prspdf dynamic huffman block test: ",
+            "aaaaaaaaaabbbbbbbbbbccccccccccdddddddddd 0123456789 0123456789 zyxwvutsrqponmlkjihgfedcba");
+        assert_eq!(decode(&data).unwrap(), ok.as_bytes());
+    }
+
+    #[test]
+    fn stored() {
+        let data = [0x78, 0x01, 0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c', 0x02, 0x4d, 0x01, 0x27];
+        assert_eq!(decode(&data).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = b"BT /F0 12 Tf 72 700 Td (Hello) Tj ET\n".repeat(200);
+        let mut noise = Vec::new();
+        let mut x = 1u32;
+        for _ in 0..70000 {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            noise.push((x >> 16) as u8);
+        }
+        for data in [&b""[..], b"a", &text, &[0u8; 100000], &noise] {
+            let encoded = encode(data);
+            assert_eq!(decode(&encoded).unwrap(), data);
+            assert_eq!(&encoded[encoded.len() - 4..], adler32(data).to_be_bytes());
+        }
+        assert!(encode(&text).len() < 200);
+        // Stored blocks for incompressible data
+        assert!(encode(&noise).len() < noise.len() + 32);
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 7.4 Filters
+
+pub mod dct;
+pub mod flate;
+
+use super::error::Error;
+use super::object::{Dictionary, Object};
+
+/// Decodes stream data with the `/Filter` and `/DecodeParms` of the stream dictionary.
+///
+/// Decoding stops at the first image filter (DCTDecode, JPXDecode, ...),
+/// whose data is returned as it is.
+pub fn decode_stream(dict: &Dictionary, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let filters: Vec<&str> = match dict.get("Filter") {
+        Some(Object::Name(n)) => vec![n.as_str()],
+        Some(Object::Array(a)) => a.iter().filter_map(|f| f.as_name()).collect(),
+        _ => Vec::new(),
+    };
+    let params: Vec<Option<&Dictionary>> = match dict.get("DecodeParms") {
+        Some(Object::Dictionary(d)) => vec![Some(d)],
+        Some(Object::Array(a)) => a.iter().map(|p| p.as_dict()).collect(),
+        _ => Vec::new(),
+    };
+
+    let mut data = data.to_vec();
+    for (i, filter) in filters.iter().enumerate() {
+        let param = params.get(i).copied().flatten();
+        data = match *filter {
+            "FlateDecode" | "Fl" => predict(flate::decode(&data)?, param)?,
+            "LZWDecode" | "LZW" => {
+                let early = param.and_then(|p| p.get_i64("EarlyChange")).unwrap_or(1);
+                predict(lzw_decode(&data, early != 0)?, param)?
+            }
+            "ASCIIHexDecode" | "AHx" => ascii_hex_decode(&data)?,
+            "ASCII85Decode" | "A85" => ascii85_decode(&data)?,
+            "RunLengthDecode" | "RL" => run_length_decode(&data),
+            _ => return Ok(data),
+        };
+    }
+
+    Ok(data)
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// PDF32000-1:2008 7.4.4.4 PNG and TIFF predictors
+fn predict(data: Vec<u8>, param: Option<&Dictionary>) -> Result<Vec<u8>, Error> {
+    let Some(param) = param else {
+        return Ok(data);
+    };
+    let predictor = param.get_i64("Predictor").unwrap_or(1);
+    if predictor == 1 {
+        return Ok(data);
+    }
+
+    let colors = param.get_i64("Colors").unwrap_or(1).max(1) as usize;
+    let bpc = param.get_i64("BitsPerComponent").unwrap_or(8).max(1) as usize;
+    let columns = param.get_i64("Columns").unwrap_or(1).max(1) as usize;
+    let bpp = (colors * bpc).div_ceil(8);
+    let row_len = (colors * bpc * columns).div_ceil(8);
+
+    if predictor == 2 {
+        if bpc != 8 {
+            return Err(Error::Unsupported(format!("TIFF predictor with {bpc} bits per component")));
+        }
+        let mut data = data;
+        for row in data.chunks_mut(row_len) {
+            for i in bpp..row.len() {
+                row[i] = row[i].wrapping_add(row[i - bpp]);
+            }
+        }
+        return Ok(data);
+    }
+
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev = vec![0u8; row_len];
+    for chunk in data.chunks(row_len + 1) {
+        let kind = chunk[0];
+        let mut row = chunk[1..].to_vec();
+        row.resize(row_len, 0);
+        for i in 0..row_len {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let up = prev[i];
+            let up_left = if i >= bpp { prev[i - bpp] } else { 0 };
+            row[i] = match kind {
+                0 => row[i],
+                1 => row[i].wrapping_add(left),
+                2 => row[i].wrapping_add(up),
+                3 => row[i].wrapping_add(((left as u16 + up as u16) / 2) as u8),
+                4 => row[i].wrapping_add(paeth(left, up, up_left)),
+                _ => return Err(Error::filter("FlateDecode", "invalid PNG predictor")),
+            };
+        }
+        out.extend_from_slice(&row);
+        prev = row;
+    }
+
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn ascii_hex_decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    let mut high: Option<u8> = None;
+    for b in data {
+        let v = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            b'>' => break,
+            _ if b.is_ascii_whitespace() => continue,
+            _ => return Err(Error::filter("ASCIIHexDecode", "invalid character")),
+        };
+        match high.take() {
+            Some(h) => out.push(h << 4 | v),
+            None => high = Some(v),
+        }
+    }
+    if let Some(h) = high {
+        out.push(h << 4);
+    }
+    Ok(out)
+}
+
+fn ascii85_decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    let mut group = [0u8; 5];
+    let mut n = 0;
+    let data = data.strip_prefix(b"<~").unwrap_or(data);
+    for b in data {
+        match b {
+            b'~' => break,
+            b'z' if n == 0 => out.extend_from_slice(&[0, 0, 0, 0]),
+            b'!'..=b'u' => {
+                group[n] = b - b'!';
+                n += 1;
+                if n == 5 {
+                    let v = group.iter().fold(0u64, |acc, d| acc * 85 + *d as u64);
+                    if v > u32::MAX as u64 {
+                        return Err(Error::filter("ASCII85Decode", "group overflow"));
+                    }
+                    out.extend_from_slice(&(v as u32).to_be_bytes());
+                    n = 0;
+                }
+            }
+            _ if b.is_ascii_whitespace() => {}
+            _ => return Err(Error::filter("ASCII85Decode", "invalid character")),
+        }
+    }
+    if n == 1 {
+        return Err(Error::filter("ASCII85Decode", "invalid final group"));
+    }
+    if n > 1 {
+        for d in group.iter_mut().skip(n) {
+            *d = 84;
+        }
+        let v = group.iter().fold(0u64, |acc, d| acc * 85 + *d as u64);
+        out.extend_from_slice(&(v as u32).to_be_bytes()[..n - 1]);
+    }
+    Ok(out)
+}
+
+fn lzw_decode(data: &[u8], early_change: bool) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    let mut table: Vec<Vec<u8>> = (0..=255u16).map(|b| vec![b as u8]).collect();
+    table.push(Vec::new());
+    table.push(Vec::new());
+    let mut code_len = 9;
+    let mut prev: Option<usize> = None;
+    let mut buf = 0u32;
+    let mut count = 0;
+    for b in data {
+        buf = buf << 8 | *b as u32;
+        count += 8;
+        while count >= code_len {
+            let code = ((buf >> (count - code_len)) & ((1 << code_len) - 1)) as usize;
+            count -= code_len;
+            if code == 256 {
+                table.truncate(258);
+                code_len = 9;
+                prev = None;
+                continue;
+            }
+            if code == 257 {
+                return Ok(out);
+            }
+            let entry = if code < table.len() {
+                table[code].clone()
+            } else if let Some(p) = prev && code == table.len() {
+                let mut e = table[p].clone();
+                e.push(table[p][0]);
+                e
+            } else {
+                return Err(Error::filter("LZWDecode", "invalid code"));
+            };
+            if let Some(p) = prev {
+                let mut e = table[p].clone();
+                e.push(entry[0]);
+                table.push(e);
+            }
+            out.extend_from_slice(&entry);
+            prev = Some(code);
+            let limit = table.len() + usize::from(early_change);
+            code_len = match limit {
+                ..512 => 9,
+                512..1024 => 10,
+                1024..2048 => 11,
+                _ => 12,
+            };
+        }
+    }
+    Ok(out)
+}
+
+fn run_length_decode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let len = data[i] as usize;
+        i += 1;
+        if len == 128 {
+            break;
+        } else if len < 128 {
+            let end = (i + len + 1).min(data.len());
+            out.extend_from_slice(&data[i..end]);
+            i = end;
+        } else if let Some(b) = data.get(i) {
+            out.extend(std::iter::repeat_n(*b, 257 - len));
+            i += 1;
+        }
+    }
+    out
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict(filter: &str) -> Dictionary {
+        let mut d = Dictionary::new();
+        d.set("Filter", Object::name(filter));
+        d
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(decode_stream(&dict("ASCIIHexDecode"), b"48 65 6c6C 6f7>").unwrap(), b"Hellop");
+        assert_eq!(decode_stream(&dict("ASCII85Decode"), b"<~87cURDZ~>").unwrap(), b"Hello");
+    }
+
+    #[test]
+    fn lzw() {
+        // Example from PDF32000-1:2008 7.4.4.2
+        let data = [0x80, 0x0b, 0x60, 0x50, 0x22, 0x0c, 0x0c, 0x85, 0x01];
+        assert_eq!(decode_stream(&dict("LZWDecode"), &data).unwrap(), [45, 45, 45, 45, 45, 65, 45, 45, 45, 66]);
+    }
+
+    #[test]
+    fn run_length() {
+        assert_eq!(decode_stream(&dict("RunLengthDecode"), &[1, b'a', b'b', 254, b'c', 128]).unwrap(), b"abccc");
+    }
+
+    #[test]
+    fn png_predictor() {
+        let mut parms = Dictionary::new();
+        parms.set("Predictor", 12i64);
+        parms.set("Columns", 2i64);
+        let data = vec![2, 1, 2, 2, 1, 1];
+        assert_eq!(predict(data, Some(&parms)).unwrap(), [1, 2, 2, 3]);
+    }
+}
@@ -7,6 +7,7 @@
 
 use super::utils::indent;
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Font {
     base_font_name: String
 }
@@ -18,6 +19,10 @@ impl Font {
         }
     }
 
+    pub fn base_font_name(&self) -> &str {
+        &self.base_font_name
+    }
+
     pub fn to_string(&self, indent_size: usize) -> String {
         indent(&format!(concat!(
             "<< /Type /Font\n",
@@ -29,6 +34,11 @@ impl Font {
     }
 }
 
+/// Rough width of a string in Helvetica, used to lay out generated text.
+pub(crate) fn approx_text_width(text: &str, font_size: f64) -> f64 {
+    text.chars().count() as f64 * font_size * 0.5
+}
+
 //------------------------------------------------------------------------------
 // tests
 //------------------------------------------------------------------------------
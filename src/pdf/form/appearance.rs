@@ -0,0 +1,357 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::super::annotation::Justification;
+use super::super::base::Rectangle;
+use super::super::colour::DeviceColour;
+use super::super::contents::Contents;
+use super::super::font::approx_text_width;
+
+/// PDF32000-1:2008 12.7.3.3
+///
+/// The text properties of the default appearance string (`/DA`),
+/// e.g. `/Helv 12 Tf 0 g`. A font size of 0 means auto-sized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    pub font_name: String,
+    pub font_size: f64,
+    pub colour: DeviceColour,
+}
+
+impl TextStyle {
+    pub fn new(font_name: &str, font_size: f64) -> Self {
+        Self {
+            font_name: font_name.to_string(),
+            font_size,
+            colour: DeviceColour::black(),
+        }
+    }
+
+    /// Parses a default appearance string. Operators other than `Tf` and
+    /// the device colour operators are ignored.
+    pub fn parse(da: &str) -> Option<Self> {
+        let mut style: Option<Self> = None;
+        let mut colour = DeviceColour::black();
+        let mut operands: Vec<&str> = Vec::new();
+        for token in da.split_whitespace() {
+            let nums = || -> Vec<f64> {
+                operands.iter().filter_map(|o| o.parse::<f64>().ok()).collect()
+            };
+            match token {
+                "Tf" if operands.len() >= 2 => {
+                    let name = operands[operands.len() - 2].trim_start_matches('/');
+                    let size = operands[operands.len() - 1].parse::<f64>().unwrap_or(0.0);
+                    style = Some(Self::new(name, size));
+                }
+                "g" if !operands.is_empty() => {
+                    colour = DeviceColour::Gray(nums()[0]);
+                }
+                "rg" if operands.len() >= 3 => {
+                    let n = nums();
+                    colour = DeviceColour::Rgb(n[n.len() - 3], n[n.len() - 2], n[n.len() - 1]);
+                }
+                "k" if operands.len() >= 4 => {
+                    let n = nums();
+                    colour = DeviceColour::Cmyk(n[n.len() - 4], n[n.len() - 3], n[n.len() - 2], n[n.len() - 1]);
+                }
+                _ => {
+                    operands.push(token);
+                    continue;
+                }
+            }
+            operands.clear();
+        }
+
+        style.map(|mut s| {
+            s.colour = colour;
+            s
+        })
+    }
+
+    /// Returns the default appearance string without the delimiters.
+    pub fn to_da_string(&self) -> String {
+        format!("/{} {} Tf {}", self.font_name, self.font_size, self.colour.to_fill_operator())
+    }
+}
+
+/// PDF32000-1:2008 12.5.6.19
+///
+/// The border and background of a widget (`/MK` and `/BS`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetStyle {
+    pub border_colour: Option<DeviceColour>,
+    pub background_colour: Option<DeviceColour>,
+    pub border_width: f64,
+}
+
+impl Default for WidgetStyle {
+    fn default() -> Self {
+        Self {
+            border_colour: Some(DeviceColour::black()),
+            background_colour: None,
+            border_width: 1.0,
+        }
+    }
+}
+
+impl WidgetStyle {
+    fn border(&self) -> f64 {
+        if self.border_colour.is_some() { self.border_width } else { 0.0 }
+    }
+}
+
+/// Layout options of text field appearances.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct TextLayout {
+    pub multiline: bool,
+    pub comb: Option<u32>,
+    pub justification: Justification,
+}
+
+const SELECTION_COLOUR: DeviceColour = DeviceColour::Rgb(0.6, 0.75, 0.85);
+
+fn draw_frame(c: &mut Contents, w: f64, h: f64, style: &WidgetStyle, round: bool) {
+    let rect = Rectangle::new(0.0, 0.0, w, h);
+    if let Some(bg) = &style.background_colour {
+        c.set_fill_device_color(bg);
+        if round { c.ellipse(&rect) } else { c.rect(0.0, 0.0, w, h) }
+        c.fill();
+    }
+    if let Some(bc) = &style.border_colour && style.border_width > 0.0 {
+        let bw = style.border_width;
+        c.set_line_width(bw);
+        c.set_stroke_device_color(bc);
+        if round {
+            c.ellipse(&rect.expand(-bw / 2.0));
+        } else {
+            c.rect(bw / 2.0, bw / 2.0, w - bw, h - bw);
+        }
+        c.stroke();
+    }
+}
+
+/// Clips to the area inside the border.
+fn clip_inner(c: &mut Contents, w: f64, h: f64, style: &WidgetStyle) {
+    let b = style.border();
+    c.rect(b, b, (w - b * 2.0).max(0.0), (h - b * 2.0).max(0.0));
+    c.clip();
+    c.end_path();
+}
+
+fn resolve_font_size(text_style: &TextStyle, h: f64, style: &WidgetStyle) -> f64 {
+    if text_style.font_size > 0.0 {
+        text_style.font_size
+    } else {
+        ((h - (style.border() + 2.0) * 2.0) / 1.15).clamp(4.0, 12.0)
+    }
+}
+
+/// Writes the appearance of a text field or a combo box.
+pub(crate) fn text(
+    c: &mut Contents,
+    w: f64,
+    h: f64,
+    text_style: &TextStyle,
+    style: &WidgetStyle,
+    value: &str,
+    layout: &TextLayout,
+) {
+    let font_size = resolve_font_size(text_style, if layout.multiline { 12.0 * 1.15 + 4.0 } else { h }, style);
+    let padding = style.border() + 2.0;
+
+    draw_frame(c, w, h, style, false);
+    if let Some(max_len) = layout.comb.filter(|n| *n > 0) {
+        // cell dividers
+        if let Some(bc) = &style.border_colour {
+            let cell = w / max_len as f64;
+            c.set_stroke_device_color(bc);
+            c.set_line_width(style.border_width);
+            for i in 1..max_len {
+                c.move_to(cell * i as f64, 0.0);
+                c.line_to(cell * i as f64, h);
+            }
+            c.stroke();
+        }
+    }
+
+    c.begin_marked_content("Tx");
+    c.save_state();
+    clip_inner(c, w, h, style);
+    c.begin_text();
+    c.set_font(&text_style.font_name, font_size);
+    c.set_fill_device_color(&text_style.colour);
+
+    if let Some(max_len) = layout.comb.filter(|n| *n > 0) {
+        let cell = w / max_len as f64;
+        let y = (h - font_size * 0.7) / 2.0;
+        let mut prev = (0.0, 0.0);
+        for (i, ch) in value.chars().take(max_len as usize).enumerate() {
+            let s = ch.to_string();
+            let x = cell * i as f64 + (cell - approx_text_width(&s, font_size)) / 2.0;
+            c.move_text(x - prev.0, y - prev.1);
+            c.show_text(&s);
+            prev = (x, y);
+        }
+    } else if layout.multiline {
+        let leading = font_size * 1.15;
+        let mut prev = (0.0, 0.0);
+        let mut y = h - padding - font_size;
+        for line in value.lines() {
+            let x = align(layout.justification, line, font_size, w, padding);
+            c.move_text(x - prev.0, y - prev.1);
+            c.show_text(line);
+            prev = (x, y);
+            y -= leading;
+        }
+    } else {
+        let x = align(layout.justification, value, font_size, w, padding);
+        let y = (h - font_size * 0.7) / 2.0;
+        c.move_text(x, y);
+        c.show_text(value);
+    }
+
+    c.end_text();
+    c.restore_state();
+    c.end_marked_content();
+}
+
+fn align(justification: Justification, text: &str, font_size: f64, w: f64, padding: f64) -> f64 {
+    let tw = approx_text_width(text, font_size);
+    match justification {
+        Justification::Left => padding,
+        Justification::Centre => (w - tw) / 2.0,
+        Justification::Right => w - padding - tw,
+    }
+}
+
+/// Writes the appearance of a check box. The on state has a ZapfDingbats
+/// check mark drawn with the font of the text style.
+pub(crate) fn check_box(c: &mut Contents, w: f64, h: f64, text_style: &TextStyle, style: &WidgetStyle, on: bool) {
+    draw_frame(c, w, h, style, false);
+    if !on {
+        return;
+    }
+
+    let size = if text_style.font_size > 0.0 { text_style.font_size } else { w.min(h) * 0.8 };
+    c.begin_text();
+    c.set_font(&text_style.font_name, size);
+    c.set_fill_device_color(&text_style.colour);
+    c.move_text((w - size * 0.75) / 2.0, (h - size * 0.7) / 2.0);
+    // "4" is the check mark in ZapfDingbats
+    c.show_text("4");
+    c.end_text();
+}
+
+/// Writes the appearance of a radio button. The on state has a dot.
+pub(crate) fn radio(c: &mut Contents, w: f64, h: f64, text_style: &TextStyle, style: &WidgetStyle, on: bool) {
+    let size = w.min(h);
+    let (x, y) = ((w - size) / 2.0, (h - size) / 2.0);
+    c.save_state();
+    c.concat_matrix([1.0, 0.0, 0.0, 1.0, x, y]);
+    draw_frame(c, size, size, style, true);
+    if on {
+        let r = size / 4.0;
+        c.set_fill_device_color(&text_style.colour);
+        c.ellipse(&Rectangle::new(size / 2.0 - r, size / 2.0 - r, size / 2.0 + r, size / 2.0 + r));
+        c.fill();
+    }
+    c.restore_state();
+}
+
+/// Writes the appearance of a push button with the caption centred.
+pub(crate) fn push_button(c: &mut Contents, w: f64, h: f64, text_style: &TextStyle, style: &WidgetStyle, caption: &str) {
+    let style = WidgetStyle {
+        background_colour: style.background_colour.or(Some(DeviceColour::Gray(0.75))),
+        ..style.clone()
+    };
+    draw_frame(c, w, h, &style, false);
+
+    let font_size = resolve_font_size(text_style, h, &style);
+    c.begin_text();
+    c.set_font(&text_style.font_name, font_size);
+    c.set_fill_device_color(&text_style.colour);
+    c.move_text(align(Justification::Centre, caption, font_size, w, 0.0), (h - font_size * 0.7) / 2.0);
+    c.show_text(caption);
+    c.end_text();
+}
+
+/// Writes the appearance of a list box. Each item has whether it is selected.
+pub(crate) fn list_box(
+    c: &mut Contents,
+    w: f64,
+    h: f64,
+    text_style: &TextStyle,
+    style: &WidgetStyle,
+    items: &[(String, bool)],
+) {
+    let font_size = if text_style.font_size > 0.0 { text_style.font_size } else { 12.0 };
+    let leading = font_size * 1.15;
+    let b = style.border();
+    let padding = b + 2.0;
+
+    draw_frame(c, w, h, style, false);
+    c.begin_marked_content("Tx");
+    c.save_state();
+    clip_inner(c, w, h, style);
+
+    for (row, (_, selected)) in items.iter().enumerate() {
+        if *selected {
+            let y = h - b - leading * (row + 1) as f64;
+            c.set_fill_device_color(&SELECTION_COLOUR);
+            c.rect(b, y, w - b * 2.0, leading);
+            c.fill();
+        }
+    }
+
+    c.begin_text();
+    c.set_font(&text_style.font_name, font_size);
+    c.set_fill_device_color(&text_style.colour);
+    let mut prev_y = 0.0;
+    for (row, (item, _)) in items.iter().enumerate() {
+        let y = h - b - leading * (row + 1) as f64 + (leading - font_size * 0.7) / 2.0;
+        if y + font_size < 0.0 {
+            break;
+        }
+        c.move_text(if row == 0 { padding } else { 0.0 }, y - prev_y);
+        c.show_text(item);
+        prev_y = y;
+    }
+    c.end_text();
+    c.restore_state();
+    c.end_marked_content();
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_style() {
+        let s = TextStyle::parse("/Helv 0 Tf 0 0 1 rg").unwrap();
+        assert_eq!(s.font_name, "Helv");
+        assert_eq!(s.font_size, 0.0);
+        assert_eq!(s.colour, DeviceColour::Rgb(0.0, 0.0, 1.0));
+        assert_eq!(s.to_da_string(), "/Helv 0 Tf 0 0 1 rg");
+        assert!(TextStyle::parse("0 g").is_none());
+    }
+
+    #[test]
+    fn comb() {
+        let mut c = Contents::new();
+        let style = WidgetStyle { border_colour: None, background_colour: None, border_width: 0.0 };
+        let layout = TextLayout { multiline: false, comb: Some(4), justification: Justification::Left };
+        text(&mut c, 40.0, 20.0, &TextStyle::new("Helv", 10.0), &style, "123456", &layout);
+
+        let stream = c.get_stream_string(0);
+        assert!(stream.contains("(4) Tj"));
+        assert!(!stream.contains("(5) Tj"));
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::appearance;
+use super::{FieldFlags, TextStyle, Widget};
+use super::super::base::Name;
+use super::super::resources::Resources;
+
+/// PDF32000-1:2008 12.7.4.2.3
+///
+/// ```text
+/// << /FT /Btn
+///    /V /Yes
+///    /DV /Off
+/// >>
+/// ```
+#[derive(Clone, Debug)]
+pub struct CheckBox {
+    export_value: String,
+    checked: bool,
+    default_checked: bool,
+}
+
+impl CheckBox {
+    /// Creates a check box whose on state is named `Yes`.
+    pub fn new() -> Self {
+        Self::with_export_value("Yes")
+    }
+
+    /// Creates a check box with the name of the on state.
+    pub fn with_export_value(export_value: &str) -> Self {
+        Self {
+            export_value: export_value.to_string(),
+            checked: false,
+            default_checked: false,
+        }
+    }
+
+    pub fn export_value(&self) -> &str {
+        &self.export_value
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    pub fn set_default_checked(&mut self, checked: bool) {
+        self.default_checked = checked;
+    }
+
+    fn state_name(&self, on: bool) -> String {
+        if on { Name::new(&self.export_value).to_string() } else { String::from("/Off") }
+    }
+
+    pub(crate) fn flags(&self) -> FieldFlags {
+        FieldFlags::NONE
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("V", self.state_name(self.checked)),
+            ("DV", self.state_name(self.default_checked)),
+        ]
+    }
+
+    pub(crate) fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        for widget in widgets {
+            let states = [true, false].map(|on| {
+                let mut form = super::new_appearance(widget, text_style, dr);
+                let (w, h) = (widget.rect().width(), widget.rect().height());
+                appearance::check_box(form.contents(), w, h, text_style, widget.style(), on);
+                let name = if on { self.export_value.clone() } else { String::from("Off") };
+                (Some(name), form)
+            });
+            let state = if self.checked { self.export_value.clone() } else { String::from("Off") };
+            widget.set_appearances(states.into_iter().collect(), Some(state));
+        }
+    }
+}
+
+impl Default for CheckBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// PDF32000-1:2008 12.7.4.2.4
+///
+/// A set of radio buttons. The n-th widget of the field is the button for
+/// the n-th export value.
+///
+/// ```text
+/// << /FT /Btn
+///    /Ff 49152
+///    /V /Card
+/// >>
+/// ```
+#[derive(Clone, Debug)]
+pub struct RadioGroup {
+    export_values: Vec<String>,
+    selected: Option<usize>,
+    default_selected: Option<usize>,
+}
+
+impl RadioGroup {
+    pub fn new(export_values: Vec<&str>) -> Self {
+        Self {
+            export_values: export_values.iter().map(|e| e.to_string()).collect(),
+            selected: None,
+            default_selected: None,
+        }
+    }
+
+    pub fn export_values(&self) -> &Vec<String> {
+        &self.export_values
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.map(|i| self.export_values[i].as_str())
+    }
+
+    /// Selects the button of the export value. Returns false if there is no such value.
+    pub fn select(&mut self, export_value: &str) -> bool {
+        self.selected = self.export_values.iter().position(|e| e == export_value);
+        self.selected.is_some()
+    }
+
+    pub fn set_default(&mut self, export_value: &str) {
+        self.default_selected = self.export_values.iter().position(|e| e == export_value);
+    }
+
+    fn state_name(&self, index: Option<usize>) -> String {
+        match index {
+            Some(i) => Name::new(&self.export_values[i]).to_string(),
+            None => String::from("/Off"),
+        }
+    }
+
+    pub(crate) fn flags(&self) -> FieldFlags {
+        FieldFlags::RADIO | FieldFlags::NO_TOGGLE_TO_OFF
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("V", self.state_name(self.selected)),
+            ("DV", self.state_name(self.default_selected)),
+        ]
+    }
+
+    pub(crate) fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        for (i, widget) in widgets.iter_mut().enumerate() {
+            let Some(export_value) = self.export_values.get(i) else {
+                continue;
+            };
+            let states = [true, false].map(|on| {
+                let mut form = super::new_appearance(widget, text_style, dr);
+                let (w, h) = (widget.rect().width(), widget.rect().height());
+                appearance::radio(form.contents(), w, h, text_style, widget.style(), on);
+                let name = if on { export_value.clone() } else { String::from("Off") };
+                (Some(name), form)
+            });
+            let state = if self.selected == Some(i) { export_value.clone() } else { String::from("Off") };
+            widget.set_appearances(states.into_iter().collect(), Some(state));
+        }
+    }
+}
+
+/// PDF32000-1:2008 12.7.4.2.2
+#[derive(Clone, Debug)]
+pub struct PushButton {
+    caption: String,
+}
+
+impl PushButton {
+    pub fn new(caption: &str) -> Self {
+        Self { caption: caption.to_string() }
+    }
+
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    pub(crate) fn flags(&self) -> FieldFlags {
+        FieldFlags::PUSHBUTTON
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    pub(crate) fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        for widget in widgets {
+            let mut form = super::new_appearance(widget, text_style, dr);
+            let (w, h) = (widget.rect().width(), widget.rect().height());
+            appearance::push_button(form.contents(), w, h, text_style, widget.style(), &self.caption);
+            widget.set_caption(&self.caption);
+            widget.set_appearances(vec![(None, form)], None);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_box() {
+        let mut c = CheckBox::new();
+        c.set_checked(true);
+        assert_eq!(c.entries(), vec![
+            ("V", String::from("/Yes")),
+            ("DV", String::from("/Off")),
+        ]);
+    }
+
+    #[test]
+    fn radio_group() {
+        let mut r = RadioGroup::new(vec!["Cash", "Credit card"]);
+        assert!(r.select("Credit card"));
+        assert!(!r.select("Cheque"));
+        r.select("Credit card");
+        assert_eq!(r.selected(), Some("Credit card"));
+        assert_eq!(r.entries()[0], ("V", String::from("/Credit#20card")));
+        assert_eq!(r.flags().bits(), 49152);
+    }
+}
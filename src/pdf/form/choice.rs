@@ -0,0 +1,251 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::appearance::{self, TextLayout};
+use super::{FieldFlags, TextStyle, Widget};
+use super::super::annotation::Justification;
+use super::super::resources::Resources;
+use super::super::utils::to_text_string;
+
+/// An item of a choice field (`/Opt`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChoiceOption {
+    pub export_value: String,
+    pub display: String,
+}
+
+impl ChoiceOption {
+    /// Creates an item whose export value is the same as the displayed text.
+    pub fn new(display: &str) -> Self {
+        Self::with_export_value(display, display)
+    }
+
+    pub fn with_export_value(export_value: &str, display: &str) -> Self {
+        Self {
+            export_value: export_value.to_string(),
+            display: display.to_string(),
+        }
+    }
+
+    fn to_pdf_string(&self) -> String {
+        if self.export_value == self.display {
+            to_text_string(&self.display)
+        } else {
+            format!("[{} {}]", to_text_string(&self.export_value), to_text_string(&self.display))
+        }
+    }
+}
+
+/// PDF32000-1:2008 12.7.4.4
+///
+/// A combo box or a list box.
+///
+/// ```text
+/// << /FT /Ch
+///    /Ff 131072
+///    /Opt [(Red) [(G) (Green)]]
+///    /V (G)
+/// >>
+/// ```
+#[derive(Clone, Debug)]
+pub struct Choice {
+    options: Vec<ChoiceOption>,
+    combo: bool,
+    editable: bool,
+    multi_select: bool,
+    selected: Vec<usize>,
+    default_selected: Vec<usize>,
+    /// Text typed in an editable combo box which is not one of the options.
+    custom_value: Option<String>,
+}
+
+impl Choice {
+    pub fn combo_box(options: Vec<ChoiceOption>) -> Self {
+        Self::new(options, true)
+    }
+
+    pub fn list_box(options: Vec<ChoiceOption>) -> Self {
+        Self::new(options, false)
+    }
+
+    fn new(options: Vec<ChoiceOption>, combo: bool) -> Self {
+        Self {
+            options,
+            combo,
+            editable: false,
+            multi_select: false,
+            selected: Vec::new(),
+            default_selected: Vec::new(),
+            custom_value: None,
+        }
+    }
+
+    pub fn options(&self) -> &Vec<ChoiceOption> {
+        &self.options
+    }
+
+    pub fn is_combo(&self) -> bool {
+        self.combo
+    }
+
+    /// Allows typing any text in a combo box.
+    pub fn set_editable(&mut self, editable: bool) {
+        self.editable = editable;
+    }
+
+    /// Allows selecting more than one item in a list box.
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.multi_select = multi_select;
+    }
+
+    /// Returns the export values of the selected items, or the custom text.
+    pub fn values(&self) -> Vec<&str> {
+        match &self.custom_value {
+            Some(v) => vec![v.as_str()],
+            None => self.selected.iter().map(|i| self.options[*i].export_value.as_str()).collect(),
+        }
+    }
+
+    /// Selects the item with the export value. An editable combo box takes
+    /// any text. Returns false if the value is not accepted.
+    pub fn set_value(&mut self, value: &str) -> bool {
+        self.set_values(&[value])
+    }
+
+    /// Selects the items with the export values. Returns false if a value is
+    /// not accepted.
+    pub fn set_values(&mut self, values: &[&str]) -> bool {
+        if values.len() > 1 && !self.multi_select {
+            return false;
+        }
+
+        let indices: Option<Vec<usize>> = values.iter()
+            .map(|v| self.options.iter().position(|o| o.export_value == *v))
+            .collect();
+        match indices {
+            Some(mut indices) => {
+                indices.sort();
+                self.selected = indices;
+                self.custom_value = None;
+                true
+            }
+            None if self.combo && self.editable && values.len() == 1 => {
+                self.selected.clear();
+                self.custom_value = Some(values[0].to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_default_values(&mut self, values: &[&str]) {
+        self.default_selected = values.iter()
+            .filter_map(|v| self.options.iter().position(|o| o.export_value == *v))
+            .collect();
+    }
+
+    fn values_to_pdf_string(&self, selected: &[usize], custom: Option<&String>) -> Option<String> {
+        if let Some(v) = custom {
+            return Some(to_text_string(v));
+        }
+        match selected {
+            [] => None,
+            [i] => Some(to_text_string(&self.options[*i].export_value)),
+            _ => Some(format!("[{}]", selected.iter()
+                .map(|i| to_text_string(&self.options[*i].export_value))
+                .collect::<Vec<String>>()
+                .join(" "))),
+        }
+    }
+
+    pub(crate) fn flags(&self) -> FieldFlags {
+        let mut flags = FieldFlags::NONE;
+        flags.set(FieldFlags::COMBO, self.combo);
+        flags.set(FieldFlags::EDIT, self.combo && self.editable);
+        flags.set(FieldFlags::MULTI_SELECT, !self.combo && self.multi_select);
+        flags
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let opt = self.options.iter().map(|o| o.to_pdf_string()).collect::<Vec<String>>().join(" ");
+        let mut entries = vec![("Opt", format!("[{opt}]"))];
+        if let Some(v) = self.values_to_pdf_string(&self.selected, self.custom_value.as_ref()) {
+            entries.push(("V", v));
+        }
+        if let Some(v) = self.values_to_pdf_string(&self.default_selected, None) {
+            entries.push(("DV", v));
+        }
+        if self.selected.len() > 1 {
+            let i = self.selected.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(" ");
+            entries.push(("I", format!("[{i}]")));
+        }
+        entries
+    }
+
+    pub(crate) fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        for widget in widgets {
+            let mut form = super::new_appearance(widget, text_style, dr);
+            let (w, h) = (widget.rect().width(), widget.rect().height());
+            if self.combo {
+                let text = match &self.custom_value {
+                    Some(v) => v.clone(),
+                    None => self.selected.first().map(|i| self.options[*i].display.clone()).unwrap_or_default(),
+                };
+                let layout = TextLayout { multiline: false, comb: None, justification: Justification::Left };
+                appearance::text(form.contents(), w, h, text_style, widget.style(), &text, &layout);
+            } else {
+                let items: Vec<(String, bool)> = self.options.iter().enumerate()
+                    .map(|(i, o)| (o.display.clone(), self.selected.contains(&i)))
+                    .collect();
+                appearance::list_box(form.contents(), w, h, text_style, widget.style(), &items);
+            }
+            widget.set_appearances(vec![(None, form)], None);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<ChoiceOption> {
+        vec![
+            ChoiceOption::new("Red"),
+            ChoiceOption::with_export_value("G", "Green"),
+        ]
+    }
+
+    #[test]
+    fn combo_box() {
+        let mut c = Choice::combo_box(options());
+        assert!(c.set_value("G"));
+        assert!(!c.set_value("Blue"));
+        assert_eq!(c.entries(), vec![
+            ("Opt", String::from("[(Red) [(G) (Green)]]")),
+            ("V", String::from("(G)")),
+        ]);
+
+        c.set_editable(true);
+        assert!(c.set_value("Blue"));
+        assert_eq!(c.values(), vec!["Blue"]);
+        assert_eq!(c.flags(), FieldFlags::COMBO | FieldFlags::EDIT);
+    }
+
+    #[test]
+    fn list_box() {
+        let mut c = Choice::list_box(options());
+        assert!(!c.set_values(&["Red", "G"]));
+        c.set_multi_select(true);
+        assert!(c.set_values(&["G", "Red"]));
+        assert_eq!(c.entries()[1], ("V", String::from("[(Red) (G)]")));
+        assert_eq!(c.entries()[2], ("I", String::from("[0 1]")));
+    }
+}
@@ -0,0 +1,375 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::{CheckBox, Choice, FieldFlags, PushButton, RadioGroup, TextField, TextStyle, Widget};
+use super::super::base::*;
+use super::super::resources::Resources;
+use super::super::utils::{dict_string, to_text_string};
+
+/// Field types with their type specific entries.
+pub enum FieldKind {
+    Text(TextField),
+    CheckBox(CheckBox),
+    RadioGroup(RadioGroup),
+    PushButton(PushButton),
+    Choice(Choice),
+}
+
+macro_rules! impl_from_kind {
+    ($($t:ident),*) => {
+        $(
+            impl From<$t> for FieldKind {
+                fn from(v: $t) -> Self {
+                    Self::$t(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_kind!(CheckBox, RadioGroup, PushButton, Choice);
+
+impl From<TextField> for FieldKind {
+    fn from(v: TextField) -> Self {
+        Self::Text(v)
+    }
+}
+
+impl FieldKind {
+    /// Returns the `/FT` value.
+    pub fn field_type(&self) -> &str {
+        match self {
+            Self::Text(_) => "Tx",
+            Self::CheckBox(_) | Self::RadioGroup(_) | Self::PushButton(_) => "Btn",
+            Self::Choice(_) => "Ch",
+        }
+    }
+
+    fn flags(&self) -> FieldFlags {
+        match self {
+            Self::Text(v) => v.flags(),
+            Self::CheckBox(v) => v.flags(),
+            Self::RadioGroup(v) => v.flags(),
+            Self::PushButton(v) => v.flags(),
+            Self::Choice(v) => v.flags(),
+        }
+    }
+
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Text(v) => v.entries(),
+            Self::CheckBox(v) => v.entries(),
+            Self::RadioGroup(v) => v.entries(),
+            Self::PushButton(v) => v.entries(),
+            Self::Choice(v) => v.entries(),
+        }
+    }
+
+    fn default_text_style(&self) -> TextStyle {
+        match self {
+            Self::CheckBox(_) => TextStyle::new("ZaDb", 0.0),
+            _ => TextStyle::new("Helv", 0.0),
+        }
+    }
+
+    fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        match self {
+            Self::Text(v) => v.build_appearances(widgets, text_style, dr),
+            Self::CheckBox(v) => v.build_appearances(widgets, text_style, dr),
+            Self::RadioGroup(v) => v.build_appearances(widgets, text_style, dr),
+            Self::PushButton(v) => v.build_appearances(widgets, text_style, dr),
+            Self::Choice(v) => v.build_appearances(widgets, text_style, dr),
+        }
+    }
+}
+
+/// PDF32000-1:2008 12.7.3.1
+///
+/// A node of the field hierarchy. A terminal field has a type and widgets,
+/// a non-terminal field only groups child fields whose fully qualified
+/// names are prefixed with its partial name, e.g. `address.city`.
+///
+/// ```text
+/// 40 0 obj
+/// << /FT /Tx
+///    /T (city)
+///    /Parent 39 0 R
+///    /Kids [41 0 R]
+///    /Ff 2
+///    /DA (/Helv 0 Tf 0 g)
+///    /V (Tokyo)
+/// >>
+/// endobj
+/// ```
+pub struct Field {
+    id: Id,
+    parent_id: Option<Id>,
+    name: String,
+    kind: Option<FieldKind>,
+    flags: FieldFlags,
+    tooltip: Option<String>,
+    text_style: Option<TextStyle>,
+    children: Vec<Field>,
+    widgets: Vec<Widget>,
+}
+
+impl Field {
+    /// Creates a terminal field. The partial name must not contain a period.
+    pub fn new(name: &str, kind: impl Into<FieldKind>) -> Self {
+        Self::with_kind(name, Some(kind.into()))
+    }
+
+    /// Creates a non-terminal field grouping child fields.
+    pub fn new_group(name: &str) -> Self {
+        Self::with_kind(name, None)
+    }
+
+    fn with_kind(name: &str, kind: Option<FieldKind>) -> Self {
+        Self {
+            id: Id::new_0(),
+            parent_id: None,
+            name: name.to_string(),
+            kind,
+            flags: FieldFlags::NONE,
+            tooltip: None,
+            text_style: None,
+            children: Vec::new(),
+            widgets: Vec::new(),
+        }
+    }
+
+    /// Returns the partial name (`/T`).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> Option<&FieldKind> {
+        self.kind.as_ref()
+    }
+
+    pub fn kind_mut(&mut self) -> Option<&mut FieldKind> {
+        self.kind.as_mut()
+    }
+
+    pub fn children(&self) -> &Vec<Field> {
+        &self.children
+    }
+
+    pub fn widgets(&self) -> &Vec<Widget> {
+        &self.widgets
+    }
+
+    /// Adds a child field. Only for non-terminal fields.
+    pub fn add_child(&mut self, field: Field) {
+        self.children.push(field);
+    }
+
+    /// Adds a widget placing the field on a page. Only for terminal fields.
+    pub fn add_widget(&mut self, widget: Widget) {
+        self.widgets.push(widget);
+    }
+
+    /// Returns the descendant field by a name relative to this field, e.g. `address.city`.
+    pub fn find(&self, name: &str) -> Option<&Field> {
+        let (head, rest) = match name.split_once('.') {
+            Some((h, r)) => (h, Some(r)),
+            None => (name, None),
+        };
+        let child = self.children.iter().find(|c| c.name == head)?;
+        match rest {
+            Some(rest) => child.find(rest),
+            None => Some(child),
+        }
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut Field> {
+        let (head, rest) = match name.split_once('.') {
+            Some((h, r)) => (h, Some(r)),
+            None => (name, None),
+        };
+        let child = self.children.iter_mut().find(|c| c.name == head)?;
+        match rest {
+            Some(rest) => child.find_mut(rest),
+            None => Some(child),
+        }
+    }
+
+    /// Returns the flags set by the user. The type specific flags are added when written.
+    pub fn flags(&self) -> FieldFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: FieldFlags) {
+        self.flags = flags;
+    }
+
+    /// Sets the alternate name shown as a tooltip (`/TU`).
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        self.tooltip = Some(tooltip.to_string());
+    }
+
+    /// Sets the font, size and colour of the default appearance (`/DA`).
+    /// The font name must be in the default resources of the form.
+    pub fn set_text_style(&mut self, text_style: TextStyle) {
+        self.text_style = Some(text_style);
+    }
+
+    fn get_text_style(&self) -> Option<TextStyle> {
+        self.text_style.clone().or(self.kind.as_ref().map(|k| k.default_text_style()))
+    }
+
+    pub(crate) fn for_each_widget_mut(&mut self, f: &mut dyn FnMut(&mut Widget)) {
+        for w in &mut self.widgets {
+            f(w);
+        }
+        for c in &mut self.children {
+            c.for_each_widget_mut(f);
+        }
+    }
+
+    pub(crate) fn for_each_widget(&self, f: &mut dyn FnMut(&Widget)) {
+        for w in &self.widgets {
+            f(w);
+        }
+        for c in &self.children {
+            c.for_each_widget(f);
+        }
+    }
+
+    /// Generates the appearance streams of the widgets of this field and the descendants.
+    pub(crate) fn build_appearances(&mut self, dr: &Resources) {
+        if let (Some(kind), Some(text_style)) = (&self.kind, self.get_text_style()) {
+            kind.build_appearances(&mut self.widgets, &text_style, dr);
+        }
+        for c in &mut self.children {
+            c.build_appearances(dr);
+        }
+    }
+
+    fn get_kids_string(&self) -> String {
+        let kids = self.children.iter().map(|c| c.id)
+            .chain(self.widgets.iter().map(|w| *w.id()))
+            .map(|id| id.to_ref_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("[{kids}]")
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let mut entries: Vec<(&str, String)> = Vec::new();
+        if let Some(kind) = &self.kind {
+            entries.push(("FT", format!("/{}", kind.field_type())));
+        }
+        entries.push(("T", to_text_string(&self.name)));
+        if let Some(parent) = self.parent_id {
+            entries.push(("Parent", parent.to_ref_string()));
+        }
+        entries.push(("Kids", self.get_kids_string()));
+        if let Some(kind) = &self.kind {
+            let flags = self.flags | kind.flags();
+            if flags != FieldFlags::NONE {
+                entries.push(("Ff", flags.bits().to_string()));
+            }
+        }
+        if let Some(tu) = &self.tooltip {
+            entries.push(("TU", to_text_string(tu)));
+        }
+        if let Some(kind) = &self.kind {
+            if let Some(ts) = self.get_text_style() {
+                entries.push(("DA", to_text_string(&ts.to_da_string())));
+            }
+            entries.append(&mut kind.entries());
+        }
+
+        format!(concat!(
+            "{} obj\n",
+            "{}\n",
+            "endobj"),
+            self.id,
+            dict_string(&entries),
+        )
+    }
+}
+
+impl PdfObject for Field {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        for c in &mut self.children {
+            c.parent_id = Some(self.id);
+            c.assign_ids(id_factory);
+        }
+        for w in &mut self.widgets {
+            w.set_parent_id(self.id);
+            w.assign_ids(id_factory);
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        for c in &self.children {
+            list.append(&mut c.get_objects());
+        }
+        for w in &self.widgets {
+            list.append(&mut w.get_objects());
+        }
+        list
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find() {
+        let mut address = Field::new_group("address");
+        address.add_child(Field::new("city", TextField::new()));
+        let mut root = Field::new_group("");
+        root.add_child(address);
+
+        assert_eq!(root.find("address.city").unwrap().name(), "city");
+        assert!(root.find("address.zip").is_none());
+    }
+
+    #[test]
+    fn to_string() {
+        let mut t = TextField::new();
+        t.set_value("Tokyo");
+        let mut f = Field::new("city", t);
+        f.set_flags(FieldFlags::REQUIRED);
+        f.add_widget(Widget::new(0, Rectangle::new(0.0, 0.0, 100.0, 20.0)));
+
+        let mut id_factory = IdFactory::new();
+        f.assign_ids(&mut id_factory);
+
+        let ok = concat!(
+            "1 0 obj\n",
+            "<< /FT /Tx\n",
+            "   /T (city)\n",
+            "   /Kids [2 0 R]\n",
+            "   /Ff 2\n",
+            "   /DA (/Helv 0 Tf 0 g)\n",
+            "   /V (Tokyo)\n",
+            ">>\n",
+            "endobj",
+        );
+
+        assert_eq!(f.to_string(0), ok);
+    }
+}
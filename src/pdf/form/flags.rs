@@ -0,0 +1,98 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use std::ops::{BitOr, BitOrAssign};
+
+/// PDF32000-1:2008 12.7.3.1, 12.7.4.2.1, 12.7.4.3, 12.7.4.4
+///
+/// Field flags (`/Ff`). The flags selecting the field type (Radio,
+/// Pushbutton, Combo, ...) are set from the field type when written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FieldFlags(u32);
+
+impl FieldFlags {
+    pub const NONE: Self = Self(0);
+
+    // common
+    pub const READ_ONLY: Self = Self(1);
+    pub const REQUIRED: Self = Self(1 << 1);
+    pub const NO_EXPORT: Self = Self(1 << 2);
+
+    // button
+    pub const NO_TOGGLE_TO_OFF: Self = Self(1 << 14);
+    pub const RADIO: Self = Self(1 << 15);
+    pub const PUSHBUTTON: Self = Self(1 << 16);
+    pub const RADIOS_IN_UNISON: Self = Self(1 << 25);
+
+    // text
+    pub const MULTILINE: Self = Self(1 << 12);
+    pub const PASSWORD: Self = Self(1 << 13);
+    pub const FILE_SELECT: Self = Self(1 << 20);
+    pub const DO_NOT_SPELL_CHECK: Self = Self(1 << 22);
+    pub const DO_NOT_SCROLL: Self = Self(1 << 23);
+    pub const COMB: Self = Self(1 << 24);
+    pub const RICH_TEXT: Self = Self(1 << 25);
+
+    // choice
+    pub const COMBO: Self = Self(1 << 17);
+    pub const EDIT: Self = Self(1 << 18);
+    pub const SORT: Self = Self(1 << 19);
+    pub const MULTI_SELECT: Self = Self(1 << 21);
+    pub const COMMIT_ON_SEL_CHANGE: Self = Self(1 << 26);
+
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl BitOr for FieldFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for FieldFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits() {
+        let mut f = FieldFlags::REQUIRED | FieldFlags::COMB;
+        assert_eq!(f.bits(), 2 | 1 << 24);
+        assert!(f.contains(FieldFlags::COMB));
+        f.set(FieldFlags::COMB, false);
+        assert_eq!(f, FieldFlags::REQUIRED);
+    }
+}
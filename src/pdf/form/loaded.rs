@@ -0,0 +1,760 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use std::collections::BTreeSet;
+
+use super::appearance::{self, TextLayout, TextStyle, WidgetStyle};
+use super::flags::FieldFlags;
+use super::super::annotation::Justification;
+use super::super::base::*;
+use super::super::colour::DeviceColour;
+use super::super::contents::Contents;
+use super::super::error::Error;
+use super::super::loaded_doc::LoadedDoc;
+use super::super::object::*;
+
+/// The type of a field in a loaded form.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    Text,
+    CheckBox,
+    RadioGroup,
+    PushButton,
+    ComboBox,
+    ListBox,
+    Signature,
+}
+
+/// A value to set to a field.
+///
+/// - Text fields and combo boxes take `Text`
+/// - Check boxes take `Checked`
+/// - Radio groups take `Text` with the export value of the button to select
+/// - List boxes take `Text` or, for multiple selection, `List`
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Checked(bool),
+    List(Vec<String>),
+}
+
+/// A terminal field of a loaded form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadedField {
+    /// The fully qualified name, e.g. `applicant.address.city`.
+    pub name: String,
+    pub field_type: FieldType,
+    pub flags: FieldFlags,
+    /// The current value, `None` if not set.
+    pub value: Option<FieldValue>,
+    /// Export values of a choice field, or the on-states of the buttons.
+    pub options: Vec<String>,
+    id: Id,
+    widgets: Vec<Id>,
+}
+
+impl LoadedField {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns the widget annotations of the field.
+    pub fn widget_ids(&self) -> &[Id] {
+        &self.widgets
+    }
+}
+
+/// PDF32000-1:2008 12.7
+///
+/// Fills and flattens the interactive form of a loaded document.
+///
+/// ```no_run
+/// use prspdf::form::{FieldValue, LoadedForm};
+///
+/// let mut doc = prspdf::LoadedDoc::open("application.pdf").unwrap();
+/// let mut form = LoadedForm::new(&mut doc).unwrap();
+/// form.set_value("applicant.name", FieldValue::Text("Jane Doe".to_string())).unwrap();
+/// form.set_value("agree", FieldValue::Checked(true)).unwrap();
+/// form.flatten().unwrap();
+/// doc.write_incremental_to_file("filled.pdf").unwrap();
+/// ```
+pub struct LoadedForm<'a> {
+    doc: &'a mut LoadedDoc,
+    fields: Vec<LoadedField>,
+}
+
+impl<'a> LoadedForm<'a> {
+    /// Reads the form of the document. Fails if the document has no `/AcroForm`.
+    pub fn new(doc: &'a mut LoadedDoc) -> Result<Self, Error> {
+        let mut form = Self { doc, fields: Vec::new() };
+        let Some(acro_form) = form.acro_form() else {
+            return Err(Error::NotFound(String::from("AcroForm")));
+        };
+
+        let roots: Vec<Id> = form.doc.resolve_entry(acro_form, "Fields")
+            .and_then(|f| f.as_array())
+            .map(|a| a.iter().filter_map(|f| f.as_reference()).collect())
+            .unwrap_or_default();
+        let mut fields = Vec::new();
+        let mut visited = BTreeSet::new();
+        for root in roots {
+            form.collect_fields(root, "", &mut fields, &mut visited);
+        }
+        form.fields = fields;
+
+        Ok(form)
+    }
+
+    /// Returns the terminal fields in document order.
+    pub fn fields(&self) -> &[LoadedField] {
+        &self.fields
+    }
+
+    /// Returns the field with the fully qualified name.
+    pub fn field(&self, name: &str) -> Option<&LoadedField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Sets the value of the field and regenerates the appearances of its widgets.
+    pub fn set_value(&mut self, name: &str, value: FieldValue) -> Result<(), Error> {
+        let Some(index) = self.fields.iter().position(|f| f.name == name) else {
+            return Err(Error::NotFound(format!("field {name}")));
+        };
+        let field = self.fields[index].clone();
+
+        let new_value = match (field.field_type, &value) {
+            (FieldType::Text, FieldValue::Text(text)) => {
+                if let Some(max_len) = self.inherited(field.id, "MaxLen").and_then(|v| v.as_i64())
+                    && text.chars().count() as i64 > max_len {
+                    return Err(Error::InvalidValue(format!("{name} is longer than {max_len}")));
+                }
+                Object::text(text)
+            }
+            (FieldType::CheckBox, FieldValue::Checked(checked)) => {
+                let on = field.options.first().map_or("Yes", |s| s.as_str());
+                Object::name(if *checked { on } else { "Off" })
+            }
+            (FieldType::RadioGroup, FieldValue::Text(text)) => {
+                if text != "Off" && !field.options.contains(text) {
+                    return Err(Error::InvalidValue(format!("{text} is not an option of {name}")));
+                }
+                Object::name(text)
+            }
+            (FieldType::ComboBox | FieldType::ListBox, FieldValue::Text(text)) => {
+                self.check_choice(&field, std::slice::from_ref(text))?;
+                Object::text(text)
+            }
+            (FieldType::ListBox, FieldValue::List(values)) => {
+                if values.len() > 1 && !field.flags.contains(FieldFlags::MULTI_SELECT) {
+                    return Err(Error::InvalidValue(format!("{name} is not multiple selection")));
+                }
+                self.check_choice(&field, values)?;
+                Object::Array(values.iter().map(|v| Object::text(v)).collect())
+            }
+            _ => return Err(Error::InvalidValue(format!("{:?} for {:?} field {name}", value, field.field_type))),
+        };
+
+        let dict = self.dict_mut(field.id)?;
+        dict.set("V", new_value);
+        if field.field_type == FieldType::ListBox {
+            let indices: Vec<Object> = selected(&value).iter()
+                .filter_map(|v| field.options.iter().position(|o| o == v))
+                .map(|i| Object::Integer(i as i64))
+                .collect();
+            dict.set("I", indices);
+        }
+        self.fields[index].value = Some(value);
+
+        for widget in &field.widgets {
+            self.update_appearance(&self.fields[index].clone(), *widget)?;
+        }
+
+        // The appearances are up to date
+        if let Some(acro_form) = self.acro_form_mut() {
+            acro_form.remove("NeedAppearances");
+        }
+
+        Ok(())
+    }
+
+    /// Paints the widget appearances into the page contents and removes the form.
+    pub fn flatten(&mut self) -> Result<(), Error> {
+        let widgets: BTreeSet<Id> = self.fields.iter().flat_map(|f| f.widgets.iter().copied()).collect();
+
+        for page_id in self.doc.page_ids() {
+            let Some(page) = self.doc.get(page_id).and_then(|o| o.as_dict()) else {
+                continue;
+            };
+            let annots: Vec<Object> = self.doc.resolve_entry(page, "Annots")
+                .and_then(|a| a.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let (flattened, kept): (Vec<Object>, Vec<Object>) = annots.into_iter()
+                .partition(|a| a.as_reference().is_some_and(|id| widgets.contains(&id)));
+            if flattened.is_empty() {
+                continue;
+            }
+
+            let mut contents = Contents::new();
+            let mut xobjects = Vec::new();
+            for widget in flattened.iter().filter_map(|w| w.as_reference()) {
+                if let Some((ap, matrix)) = self.flatten_widget(widget) {
+                    let name = format!("FlatAP{}", widget.number());
+                    contents.save_state();
+                    contents.concat_matrix(matrix);
+                    contents.draw_xobject(&name);
+                    contents.restore_state();
+                    xobjects.push((name, ap));
+                }
+            }
+            self.append_page_content(page_id, &contents, xobjects, kept)?;
+        }
+
+        // Remove the fields, widgets and the form
+        let mut ids: BTreeSet<Id> = widgets;
+        for field in &self.fields {
+            let mut id = Some(field.id);
+            while let Some(i) = id {
+                ids.insert(i);
+                id = self.doc.get(i).and_then(|o| o.as_dict()).and_then(|d| d.get_reference("Parent"));
+            }
+        }
+        for id in ids {
+            self.doc.remove_object(id);
+        }
+
+        let catalog_id = self.doc.catalog_id();
+        if let Some(acro_form) = self.doc.catalog().and_then(|c| c.get_reference("AcroForm")) {
+            self.doc.remove_object(acro_form);
+        }
+        if let Some(catalog) = self.doc.get_mut(catalog_id).and_then(|o| o.as_dict_mut()) {
+            catalog.remove("AcroForm");
+        }
+        self.fields.clear();
+
+        Ok(())
+    }
+
+    fn acro_form(&self) -> Option<&Dictionary> {
+        let catalog = self.doc.catalog()?;
+        self.doc.resolve_entry(catalog, "AcroForm")?.as_dict()
+    }
+
+    fn acro_form_mut(&mut self) -> Option<&mut Dictionary> {
+        let catalog_id = self.doc.catalog_id();
+        match self.doc.catalog()?.get("AcroForm")? {
+            Object::Reference(id) => {
+                let id = *id;
+                self.doc.get_mut(id)?.as_dict_mut()
+            }
+            _ => self.doc.get_mut(catalog_id)?.as_dict_mut()?.get_mut("AcroForm")?.as_dict_mut(),
+        }
+    }
+
+    fn dict_mut(&mut self, id: Id) -> Result<&mut Dictionary, Error> {
+        self.doc.get_mut(id)
+            .and_then(|o| o.as_dict_mut())
+            .ok_or_else(|| Error::NotFound(format!("object {id}")))
+    }
+
+    /// Returns the entry of the field, inherited from the parents if missing.
+    fn inherited(&self, id: Id, key: &str) -> Option<&Object> {
+        let mut id = id;
+        for _ in 0..64 {
+            let dict = self.doc.get(id)?.as_dict()?;
+            if let Some(v) = self.doc.resolve_entry(dict, key) {
+                return Some(v);
+            }
+            id = dict.get_reference("Parent")?;
+        }
+        None
+    }
+
+    fn collect_fields(&self, id: Id, parent_name: &str, fields: &mut Vec<LoadedField>, visited: &mut BTreeSet<Id>) {
+        if !visited.insert(id) {
+            return;
+        }
+        let Some(dict) = self.doc.get(id).and_then(|o| o.as_dict()) else {
+            return;
+        };
+
+        let name = match dict.get("T").and_then(|t| t.as_text()) {
+            Some(t) if parent_name.is_empty() => t,
+            Some(t) => format!("{parent_name}.{t}"),
+            None => parent_name.to_string(),
+        };
+
+        // Kids with /T are fields, others are the widgets of this field
+        let kids: Vec<Id> = self.doc.resolve_entry(dict, "Kids")
+            .and_then(|k| k.as_array())
+            .map(|a| a.iter().filter_map(|k| k.as_reference()).collect())
+            .unwrap_or_default();
+        let (child_fields, widgets): (Vec<Id>, Vec<Id>) = kids.into_iter().partition(|k| {
+            self.doc.get(*k).and_then(|o| o.as_dict()).is_some_and(|d| d.contains_key("T"))
+        });
+
+        for child in child_fields {
+            self.collect_fields(child, &name, fields, visited);
+        }
+
+        let widgets = if widgets.is_empty() && dict.get_name("Subtype") == Some("Widget") {
+            vec![id]
+        } else {
+            widgets
+        };
+        if widgets.is_empty() {
+            return;
+        }
+
+        let flags = FieldFlags::from_bits(self.inherited(id, "Ff").and_then(|f| f.as_i64()).unwrap_or(0) as u32);
+        let field_type = match self.inherited(id, "FT").and_then(|f| f.as_name()) {
+            Some("Tx") => FieldType::Text,
+            Some("Btn") if flags.contains(FieldFlags::PUSHBUTTON) => FieldType::PushButton,
+            Some("Btn") if flags.contains(FieldFlags::RADIO) => FieldType::RadioGroup,
+            Some("Btn") => FieldType::CheckBox,
+            Some("Ch") if flags.contains(FieldFlags::COMBO) => FieldType::ComboBox,
+            Some("Ch") => FieldType::ListBox,
+            Some("Sig") => FieldType::Signature,
+            _ => return,
+        };
+
+        let options = match field_type {
+            FieldType::CheckBox | FieldType::RadioGroup => {
+                let mut states = Vec::new();
+                for w in &widgets {
+                    if let Some(on) = self.on_state(*w) && !states.contains(&on) {
+                        states.push(on);
+                    }
+                }
+                states
+            }
+            FieldType::ComboBox | FieldType::ListBox => self.choice_options(id).into_iter().map(|o| o.0).collect(),
+            _ => Vec::new(),
+        };
+
+        let value = self.inherited(id, "V").and_then(|v| match (field_type, v) {
+            (FieldType::CheckBox, Object::Name(n)) => Some(FieldValue::Checked(n.as_str() != "Off")),
+            (FieldType::RadioGroup, Object::Name(n)) => Some(FieldValue::Text(n.as_str().to_string())),
+            (_, Object::Array(a)) => Some(FieldValue::List(a.iter().filter_map(|v| v.as_text()).collect())),
+            (_, v) => v.as_text().map(FieldValue::Text),
+        });
+
+        fields.push(LoadedField { name, field_type, flags, value, options, id, widgets });
+    }
+
+    /// Returns the name of the appearance state other than `Off`.
+    fn on_state(&self, widget: Id) -> Option<String> {
+        let dict = self.doc.get(widget)?.as_dict()?;
+        let ap = self.doc.resolve_entry(dict, "AP")?.as_dict()?;
+        let normal = self.doc.resolve_entry(ap, "N")?.as_dict()?;
+        normal.iter().map(|(k, _)| k.as_str()).find(|k| *k != "Off").map(|k| k.to_string())
+    }
+
+    /// Returns the `(export value, display text)` of the options.
+    fn choice_options(&self, id: Id) -> Vec<(String, String)> {
+        let Some(opt) = self.inherited(id, "Opt").and_then(|o| o.as_array()) else {
+            return Vec::new();
+        };
+        opt.iter().filter_map(|o| match self.doc.resolve(o) {
+            Object::Array(pair) if pair.len() == 2 => {
+                let export = self.doc.resolve(&pair[0]).as_text()?;
+                let display = self.doc.resolve(&pair[1]).as_text()?;
+                Some((export, display))
+            }
+            o => o.as_text().map(|t| (t.clone(), t)),
+        }).collect()
+    }
+
+    fn check_choice(&self, field: &LoadedField, values: &[String]) -> Result<(), Error> {
+        if field.flags.contains(FieldFlags::EDIT) {
+            return Ok(());
+        }
+        match values.iter().find(|v| !field.options.contains(v)) {
+            Some(v) => Err(Error::InvalidValue(format!("{v} is not an option of {}", field.name))),
+            None => Ok(()),
+        }
+    }
+
+    fn text_style(&self, field: &LoadedField, widget: Id) -> TextStyle {
+        let da = self.doc.get(widget)
+            .and_then(|o| o.as_dict())
+            .and_then(|d| d.get("DA"))
+            .or_else(|| self.inherited(field.id, "DA"))
+            .or_else(|| self.acro_form().and_then(|f| f.get("DA")))
+            .and_then(|da| da.as_text());
+        da.and_then(|da| TextStyle::parse(&da)).unwrap_or(TextStyle::new("Helv", 0.0))
+    }
+
+    fn widget_style(&self, widget: &Dictionary) -> WidgetStyle {
+        let mk = self.doc.resolve_entry(widget, "MK").and_then(|m| m.as_dict());
+        let colour = |key: &str| mk
+            .and_then(|mk| self.doc.resolve_entry(mk, key))
+            .and_then(|c| c.as_array())
+            .and_then(|c| to_device_colour(c));
+        let border_width = self.doc.resolve_entry(widget, "BS")
+            .and_then(|bs| bs.as_dict())
+            .and_then(|bs| bs.get_f64("W"))
+            .unwrap_or(1.0);
+
+        WidgetStyle {
+            border_colour: colour("BC"),
+            background_colour: colour("BG"),
+            border_width,
+        }
+    }
+
+    /// Regenerates the normal appearance of the widget for the current value.
+    fn update_appearance(&mut self, field: &LoadedField, widget: Id) -> Result<(), Error> {
+        let Some(dict) = self.doc.get(widget).and_then(|o| o.as_dict()) else {
+            return Err(Error::NotFound(format!("widget {widget}")));
+        };
+        let rect = dict.get("Rect").and_then(|r| r.as_rect()).unwrap_or(Rectangle::new(0.0, 0.0, 0.0, 0.0));
+        let (w, h) = (rect.width(), rect.height());
+        let style = self.widget_style(dict);
+        let text_style = self.text_style(field, widget);
+        let mut c = Contents::new();
+
+        match (field.field_type, &field.value) {
+            (FieldType::CheckBox | FieldType::RadioGroup, value) => {
+                let on_state = self.on_state(widget);
+                let on = match value {
+                    Some(FieldValue::Checked(checked)) => *checked,
+                    Some(FieldValue::Text(v)) => on_state.as_ref() == Some(v),
+                    _ => false,
+                };
+                let state = if on { on_state.clone().unwrap_or(String::from("Yes")) } else { String::from("Off") };
+                self.dict_mut(widget)?.set("AS", Object::name(&state));
+
+                // Existing state appearances are kept as they are
+                if on_state.is_some() {
+                    return Ok(());
+                }
+                let mut normal = Dictionary::new();
+                for (state, on) in [("Yes", true), ("Off", false)] {
+                    let mut c = Contents::new();
+                    if field.field_type == FieldType::CheckBox {
+                        appearance::check_box(&mut c, w, h, &text_style, &style, on);
+                    } else {
+                        appearance::radio(&mut c, w, h, &text_style, &style, on);
+                    }
+                    let stream = self.appearance_stream(w, h, &text_style, &c);
+                    normal.set(state, self.doc.add_object(stream));
+                }
+                let mut ap = Dictionary::new();
+                ap.set("N", normal);
+                self.dict_mut(widget)?.set("AP", ap);
+                return Ok(());
+            }
+            (FieldType::ListBox, value) => {
+                let values = value.as_ref().map(selected).unwrap_or_default();
+                let items: Vec<(String, bool)> = self.choice_options(field.id).into_iter()
+                    .map(|(export, display)| (display, values.contains(&export)))
+                    .collect();
+                appearance::list_box(&mut c, w, h, &text_style, &style, &items);
+            }
+            (_, value) => {
+                let mut text = match value {
+                    Some(FieldValue::Text(t)) => t.clone(),
+                    _ => String::new(),
+                };
+                if field.field_type == FieldType::ComboBox
+                    && let Some((_, display)) = self.choice_options(field.id).into_iter().find(|o| o.0 == text) {
+                    text = display;
+                }
+                if field.flags.contains(FieldFlags::PASSWORD) {
+                    text = "*".repeat(text.chars().count());
+                }
+                let justification = match self.inherited(field.id, "Q").and_then(|q| q.as_i64()) {
+                    Some(1) => Justification::Centre,
+                    Some(2) => Justification::Right,
+                    _ => Justification::Left,
+                };
+                let comb = field.flags.contains(FieldFlags::COMB)
+                    .then(|| self.inherited(field.id, "MaxLen").and_then(|m| m.as_i64()))
+                    .flatten()
+                    .map(|m| m as u32);
+                let layout = TextLayout { multiline: field.flags.contains(FieldFlags::MULTILINE), comb, justification };
+                appearance::text(&mut c, w, h, &text_style, &style, &text, &layout);
+            }
+        }
+
+        let stream = self.appearance_stream(w, h, &text_style, &c);
+        let existing = self.doc.get(widget)
+            .and_then(|o| o.as_dict())
+            .and_then(|d| d.get("AP"))
+            .and_then(|ap| self.doc.resolve(ap).as_dict())
+            .and_then(|ap| ap.get_reference("N"));
+        let mut ap = Dictionary::new();
+        match existing {
+            // Replaced in place so that the old stream does not remain unreferenced
+            Some(id) => {
+                self.doc.set(id, stream);
+                ap.set("N", id);
+            }
+            None => ap.set("N", self.doc.add_object(stream)),
+        }
+        self.dict_mut(widget)?.set("AP", ap);
+        Ok(())
+    }
+
+    /// Creates a Form XObject with the font of the text style from `/DR`.
+    fn appearance_stream(&mut self, w: f64, h: f64, text_style: &TextStyle, c: &Contents) -> Stream {
+        let font = self.acro_form()
+            .and_then(|f| self.doc.resolve_entry(f, "DR"))
+            .and_then(|dr| dr.as_dict())
+            .and_then(|dr| self.doc.resolve_entry(dr, "Font"))
+            .and_then(|fonts| fonts.as_dict())
+            .and_then(|fonts| fonts.get(&text_style.font_name))
+            .cloned();
+        let font = match font {
+            Some(font) => font,
+            None => {
+                let base_font = match text_style.font_name.as_str() {
+                    "ZaDb" => "ZapfDingbats",
+                    "HeBo" => "Helvetica-Bold",
+                    "Cour" => "Courier",
+                    "TiRo" => "Times-Roman",
+                    _ => "Helvetica",
+                };
+                let mut dict = Dictionary::new();
+                dict.set("Type", Object::name("Font"));
+                dict.set("Subtype", Object::name("Type1"));
+                dict.set("BaseFont", Object::name(base_font));
+                Object::Reference(self.doc.add_object(dict))
+            }
+        };
+
+        let mut fonts = Dictionary::new();
+        fonts.set(&text_style.font_name, font);
+        let mut resources = Dictionary::new();
+        resources.set("Font", fonts);
+
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::name("XObject"));
+        dict.set("Subtype", Object::name("Form"));
+        dict.set("BBox", Object::rect(&Rectangle::new(0.0, 0.0, w, h)));
+        dict.set("Resources", resources);
+        Stream::new(dict, c.get_stream_string(0).into_bytes())
+    }
+
+    /// Returns the appearance stream to paint and the matrix mapping it onto `/Rect`.
+    fn flatten_widget(&self, widget: Id) -> Option<(Id, [f64; 6])> {
+        let dict = self.doc.get(widget)?.as_dict()?;
+        // Hidden
+        if dict.get_i64("F").unwrap_or(0) & 2 != 0 {
+            return None;
+        }
+        let rect = dict.get("Rect")?.as_rect()?;
+        let normal = self.doc.resolve_entry(dict, "AP")?.as_dict()?.get("N")?;
+        let ap = match normal {
+            Object::Reference(id) if self.doc.get(*id)?.as_stream().is_some() => *id,
+            _ => {
+                let state = dict.get_name("AS")?;
+                self.doc.resolve(normal).as_dict()?.get_reference(state)?
+            }
+        };
+
+        // PDF32000-1:2008 12.5.5 Algorithm: Appearance streams
+        let stream = self.doc.get(ap)?.as_stream()?;
+        let bbox = stream.dict.get("BBox")?.as_rect()?;
+        let m: Vec<f64> = stream.dict.get("Matrix").and_then(|m| m.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_f64()).collect())
+            .filter(|m: &Vec<f64>| m.len() == 6)
+            .unwrap_or(vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        let corners: Vec<(f64, f64)> = [(bbox.llx, bbox.lly), (bbox.urx, bbox.lly), (bbox.llx, bbox.ury), (bbox.urx, bbox.ury)]
+            .iter()
+            .map(|(x, y)| (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]))
+            .collect();
+        let transformed = Rectangle::bounding(&corners)?;
+        if transformed.width() == 0.0 || transformed.height() == 0.0 {
+            return None;
+        }
+        let sx = rect.width() / transformed.width();
+        let sy = rect.height() / transformed.height();
+        Some((ap, [sx, 0.0, 0.0, sy, rect.llx - transformed.llx * sx, rect.lly - transformed.lly * sy]))
+    }
+
+    /// Appends the content to the page, wrapping the existing content in `q`/`Q`.
+    fn append_page_content(
+        &mut self,
+        page_id: Id,
+        contents: &Contents,
+        xobjects: Vec<(String, Id)>,
+        annots: Vec<Object>,
+    ) -> Result<(), Error> {
+        // Resources may be inherited or shared, so the page gets its own copy
+        let mut resources = self.doc.page_attribute(page_id, "Resources")
+            .and_then(|r| r.as_dict())
+            .cloned()
+            .unwrap_or_default();
+        let mut xobject_dict = self.doc.resolve_entry(&resources, "XObject")
+            .and_then(|x| x.as_dict())
+            .cloned()
+            .unwrap_or_default();
+        for (name, id) in xobjects {
+            xobject_dict.set(&name, id);
+        }
+        resources.set("XObject", xobject_dict);
+
+        let existing: Vec<Object> = match self.doc.get(page_id).and_then(|o| o.as_dict()).and_then(|d| d.get("Contents")) {
+            Some(Object::Array(a)) => a.clone(),
+            Some(Object::Reference(id)) => match self.doc.get(*id) {
+                Some(Object::Array(a)) => a.clone(),
+                _ => vec![Object::Reference(*id)],
+            },
+            _ => Vec::new(),
+        };
+        let before = self.doc.add_object(Stream::new(Dictionary::new(), b"q".to_vec()));
+        let after = self.doc.add_object(Stream::new(
+            Dictionary::new(), format!("Q\n{}", contents.get_stream_string(0)).into_bytes()));
+        let mut new_contents = vec![Object::Reference(before)];
+        new_contents.extend(existing);
+        new_contents.push(Object::Reference(after));
+
+        let page = self.dict_mut(page_id)?;
+        page.set("Resources", resources);
+        page.set("Contents", new_contents);
+        if annots.is_empty() {
+            page.remove("Annots");
+        } else {
+            page.set("Annots", annots);
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn selected(value: &FieldValue) -> Vec<String> {
+    match value {
+        FieldValue::Text(t) => vec![t.clone()],
+        FieldValue::List(l) => l.clone(),
+        FieldValue::Checked(_) => Vec::new(),
+    }
+}
+
+fn to_device_colour(components: &[Object]) -> Option<DeviceColour> {
+    let c: Vec<f64> = components.iter().filter_map(|c| c.as_f64()).collect();
+    match c.len() {
+        1 => Some(DeviceColour::Gray(c[0])),
+        3 => Some(DeviceColour::Rgb(c[0], c[1], c[2])),
+        4 => Some(DeviceColour::Cmyk(c[0], c[1], c[2], c[3])),
+        _ => None,
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::form::*;
+    use crate::pdf::page::Page;
+
+    fn generated() -> Vec<u8> {
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(Page::new(MediaBox::A4));
+
+        let mut name = TextField::new();
+        name.set_max_len(10);
+        let mut name = Field::new("name", name);
+        name.add_widget(Widget::new(0, Rectangle::new(160.0, 755.0, 400.0, 775.0)));
+        let mut applicant = Field::new_group("applicant");
+        applicant.add_child(name);
+
+        let mut agree = Field::new("agree", CheckBox::new());
+        agree.add_widget(Widget::new(0, Rectangle::new(72.0, 680.0, 86.0, 694.0)));
+
+        let mut payment = Field::new("payment", RadioGroup::new(vec!["Cash", "Card"]));
+        payment.add_widget(Widget::new(0, Rectangle::new(72.0, 650.0, 86.0, 664.0)));
+        payment.add_widget(Widget::new(0, Rectangle::new(100.0, 650.0, 114.0, 664.0)));
+
+        let mut sizes = Choice::list_box(vec![ChoiceOption::new("S"), ChoiceOption::new("M")]);
+        sizes.set_multi_select(true);
+        let mut sizes = Field::new("sizes", sizes);
+        sizes.add_widget(Widget::new(0, Rectangle::new(220.0, 560.0, 300.0, 620.0)));
+
+        let form = doc.acro_form();
+        form.add_field(applicant);
+        form.add_field(agree);
+        form.add_field(payment);
+        form.add_field(sizes);
+        doc.to_bytes()
+    }
+
+    #[test]
+    fn fields() {
+        let mut doc = LoadedDoc::load(generated()).unwrap();
+        let form = LoadedForm::new(&mut doc).unwrap();
+        let names: Vec<&str> = form.fields().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["applicant.name", "agree", "payment", "sizes"]);
+
+        let payment = form.field("payment").unwrap();
+        assert_eq!(payment.field_type, FieldType::RadioGroup);
+        assert_eq!(payment.options, ["Cash", "Card"]);
+        assert_eq!(payment.widget_ids().len(), 2);
+        assert_eq!(form.field("sizes").unwrap().field_type, FieldType::ListBox);
+    }
+
+    #[test]
+    fn set_value() {
+        let mut doc = LoadedDoc::load(generated()).unwrap();
+        let mut form = LoadedForm::new(&mut doc).unwrap();
+        form.set_value("applicant.name", FieldValue::Text(String::from("Hanako"))).unwrap();
+        form.set_value("agree", FieldValue::Checked(true)).unwrap();
+        form.set_value("payment", FieldValue::Text(String::from("Card"))).unwrap();
+        form.set_value("sizes", FieldValue::List(vec![String::from("M")])).unwrap();
+
+        assert!(form.set_value("applicant.name", FieldValue::Text(String::from("Long long name"))).is_err());
+        assert!(form.set_value("payment", FieldValue::Text(String::from("Cheque"))).is_err());
+        assert!(form.set_value("agree", FieldValue::Text(String::from("Yes"))).is_err());
+
+        let name = form.field("applicant.name").unwrap().clone();
+        let payment = form.field("payment").unwrap().clone();
+
+        let doc = LoadedDoc::load(doc.to_incremental_bytes()).unwrap();
+        let field = doc.get(name.id()).unwrap().as_dict().unwrap();
+        assert_eq!(field.get("V").unwrap().as_text().unwrap(), "Hanako");
+        let widget = doc.get(name.widget_ids()[0]).unwrap().as_dict().unwrap();
+        let ap = widget.get("AP").unwrap().as_dict().unwrap().get_reference("N").unwrap();
+        let ap = doc.get(ap).unwrap().as_stream().unwrap().decoded_data().unwrap();
+        assert!(String::from_utf8(ap).unwrap().contains("(Hanako) Tj"));
+
+        let states: Vec<&str> = payment.widget_ids().iter()
+            .map(|w| doc.get(*w).unwrap().as_dict().unwrap().get_name("AS").unwrap())
+            .collect();
+        assert_eq!(states, ["Off", "Card"]);
+    }
+
+    #[test]
+    fn flatten() {
+        let mut doc = LoadedDoc::load(generated()).unwrap();
+        let mut form = LoadedForm::new(&mut doc).unwrap();
+        form.set_value("applicant.name", FieldValue::Text(String::from("Hanako"))).unwrap();
+        form.flatten().unwrap();
+        let page = doc.page_ids()[0];
+
+        for bytes in [doc.to_bytes(), doc.to_incremental_bytes()] {
+            let mut doc = LoadedDoc::load(bytes).unwrap();
+            assert!(!doc.catalog().unwrap().contains_key("AcroForm"));
+            assert!(LoadedForm::new(&mut doc).is_err());
+            assert!(doc.page_attribute(page, "Annots").is_none());
+
+            let content = String::from_utf8(doc.page_content(page).unwrap()).unwrap();
+            assert!(content.starts_with("q\n"));
+            assert!(content.contains("Q\nq\n1 0 0 1 160 755 cm\n/FlatAP"));
+
+            let resources = doc.page_attribute(page, "Resources").unwrap().as_dict().unwrap();
+            assert_eq!(resources.get("XObject").unwrap().as_dict().unwrap().len(), 5);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+mod appearance;
+mod button;
+mod choice;
+mod field;
+mod flags;
+mod loaded;
+mod text_field;
+mod widget;
+
+pub use appearance::{TextStyle, WidgetStyle};
+pub use button::{CheckBox, PushButton, RadioGroup};
+pub use choice::{Choice, ChoiceOption};
+pub use field::{Field, FieldKind};
+pub use flags::FieldFlags;
+pub use loaded::{FieldType, FieldValue, LoadedField, LoadedForm};
+pub use text_field::TextField;
+pub use widget::Widget;
+
+use super::base::*;
+use super::font::Font;
+use super::resources::Resources;
+use super::utils::to_text_string;
+use super::xobject::Form;
+
+/// PDF32000-1:2008 12.7.2
+///
+/// The interactive form of a document, written inline in the catalog.
+///
+/// ```text
+/// /AcroForm
+/// << /Fields [40 0 R 45 0 R]
+///    /DA (/Helv 0 Tf 0 g)
+///    /DR 12 0 R
+/// >>
+/// ```
+///
+/// The default resources initially have `Helv` (Helvetica) and `ZaDb`
+/// (ZapfDingbats), the fonts used by the default appearances.
+pub struct AcroForm {
+    fields: Vec<Field>,
+    default_resources: Resources,
+    default_appearance: TextStyle,
+}
+
+impl AcroForm {
+    pub fn new() -> Self {
+        let mut default_resources = Resources::new();
+        default_resources.add_font("Helv", Font::new("Helvetica"));
+        default_resources.add_font("ZaDb", Font::new("ZapfDingbats"));
+
+        Self {
+            fields: Vec::new(),
+            default_resources,
+            default_appearance: TextStyle::new("Helv", 0.0),
+        }
+    }
+
+    /// Adds a root field.
+    pub fn add_field(&mut self, field: Field) {
+        self.fields.push(field);
+    }
+
+    pub fn fields(&self) -> &Vec<Field> {
+        &self.fields
+    }
+
+    /// Returns the field by the fully qualified name, e.g. `address.city`.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        let (head, rest) = match name.split_once('.') {
+            Some((h, r)) => (h, Some(r)),
+            None => (name, None),
+        };
+        let field = self.fields.iter().find(|f| f.name() == head)?;
+        match rest {
+            Some(rest) => field.find(rest),
+            None => Some(field),
+        }
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Field> {
+        let (head, rest) = match name.split_once('.') {
+            Some((h, r)) => (h, Some(r)),
+            None => (name, None),
+        };
+        let field = self.fields.iter_mut().find(|f| f.name() == head)?;
+        match rest {
+            Some(rest) => field.find_mut(rest),
+            None => Some(field),
+        }
+    }
+
+    /// Returns the default resources (`/DR`). Fonts added here can be used in
+    /// the text styles of the fields.
+    pub fn resources(&mut self) -> &mut Resources {
+        &mut self.default_resources
+    }
+
+    /// Sets the document-wide default appearance (`/DA`).
+    pub fn set_default_appearance(&mut self, text_style: TextStyle) {
+        self.default_appearance = text_style;
+    }
+
+    /// Updates the page indexes of the widgets after the pages are rearranged.
+    pub(crate) fn remap_pages(&mut self, f: &dyn Fn(usize) -> usize) {
+        for field in &mut self.fields {
+            field.for_each_widget_mut(&mut |w| w.set_page_index(f(w.page_index())));
+        }
+    }
+
+    /// Generates the appearances and assigns the ids. `page_ids` are the ids
+    /// of the pages in the document order, which the widgets refer to.
+    ///
+    /// Returns the page index and the id of every widget.
+    pub(crate) fn assign_ids(&mut self, id_factory: &mut IdFactory, page_ids: &[Id]) -> Vec<(usize, Id)> {
+        for f in &mut self.fields {
+            f.for_each_widget_mut(&mut |w| {
+                let page_id = page_ids.get(w.page_index()).copied().unwrap_or_else(Id::new_0);
+                w.set_page_id(page_id);
+            });
+            f.build_appearances(&self.default_resources);
+        }
+
+        self.default_resources.assign_ids(id_factory);
+        for f in &mut self.fields {
+            f.assign_ids(id_factory);
+        }
+
+        let mut widgets: Vec<(usize, Id)> = Vec::new();
+        for f in &self.fields {
+            f.for_each_widget(&mut |w| widgets.push((w.page_index(), *w.id())));
+        }
+        widgets
+    }
+
+    pub(crate) fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.default_resources.get_objects();
+        for f in &self.fields {
+            list.append(&mut f.get_objects());
+        }
+        list
+    }
+
+    pub fn to_pdf_string(&self) -> String {
+        let fields = self.fields.iter()
+            .map(|f| f.id().to_ref_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        format!(concat!(
+            "<< /Fields [{}]\n",
+            "   /DA {}\n",
+            "   /DR {}\n",
+            ">>"),
+            fields,
+            to_text_string(&self.default_appearance.to_da_string()),
+            self.default_resources.id.to_ref_string(),
+        )
+    }
+}
+
+impl Default for AcroForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates an appearance form of the widget whose resources have the font
+/// of the text style copied from the default resources.
+fn new_appearance(widget: &Widget, text_style: &TextStyle, dr: &Resources) -> Form {
+    let mut form = widget.new_appearance();
+    if let Some(font) = dr.font(&text_style.font_name) {
+        form.resources().add_font(&text_style.font_name, font.clone());
+    }
+    form
+}
@@ -0,0 +1,152 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::appearance::{self, TextLayout};
+use super::{FieldFlags, TextStyle, Widget};
+use super::super::annotation::Justification;
+use super::super::resources::Resources;
+use super::super::utils::to_text_string;
+
+/// PDF32000-1:2008 12.7.4.3
+///
+/// ```text
+/// << /FT /Tx
+///    /V (value)
+///    /DV (default)
+///    /MaxLen 8
+///    /Q 0
+/// >>
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TextField {
+    value: Option<String>,
+    default_value: Option<String>,
+    max_len: Option<u32>,
+    multiline: bool,
+    password: bool,
+    comb: bool,
+    justification: Option<Justification>,
+}
+
+impl TextField {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.value = Some(value.to_string());
+    }
+
+    pub fn set_default_value(&mut self, value: &str) {
+        self.default_value = Some(value.to_string());
+    }
+
+    /// Sets the maximum length of the text (`/MaxLen`).
+    pub fn set_max_len(&mut self, max_len: u32) {
+        self.max_len = Some(max_len);
+    }
+
+    pub fn set_multiline(&mut self, multiline: bool) {
+        self.multiline = multiline;
+    }
+
+    pub fn set_password(&mut self, password: bool) {
+        self.password = password;
+    }
+
+    /// Divides the field into `/MaxLen` equally spaced cells, one per
+    /// character. Only effective with a maximum length and neither
+    /// multiline nor password.
+    pub fn set_comb(&mut self, comb: bool) {
+        self.comb = comb;
+    }
+
+    pub fn set_justification(&mut self, justification: Justification) {
+        self.justification = Some(justification);
+    }
+
+    fn is_comb(&self) -> bool {
+        self.comb && self.max_len.is_some() && !self.multiline && !self.password
+    }
+
+    pub(crate) fn flags(&self) -> FieldFlags {
+        let mut flags = FieldFlags::NONE;
+        flags.set(FieldFlags::MULTILINE, self.multiline);
+        flags.set(FieldFlags::PASSWORD, self.password);
+        flags.set(FieldFlags::COMB, self.is_comb());
+        flags
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = Vec::new();
+        if let Some(v) = &self.value {
+            entries.push(("V", to_text_string(v)));
+        }
+        if let Some(v) = &self.default_value {
+            entries.push(("DV", to_text_string(v)));
+        }
+        if let Some(n) = self.max_len {
+            entries.push(("MaxLen", n.to_string()));
+        }
+        if let Some(q) = self.justification {
+            entries.push(("Q", (q as u8).to_string()));
+        }
+        entries
+    }
+
+    pub(crate) fn build_appearances(&self, widgets: &mut [Widget], text_style: &TextStyle, dr: &Resources) {
+        let mut value = self.value.clone().or(self.default_value.clone()).unwrap_or_default();
+        if let Some(n) = self.max_len {
+            value = value.chars().take(n as usize).collect();
+        }
+        if self.password {
+            value = "*".repeat(value.chars().count());
+        }
+        let layout = TextLayout {
+            multiline: self.multiline,
+            comb: if self.is_comb() { self.max_len } else { None },
+            justification: self.justification.unwrap_or(Justification::Left),
+        };
+
+        for widget in widgets {
+            let mut form = super::new_appearance(widget, text_style, dr);
+            let (w, h) = (widget.rect().width(), widget.rect().height());
+            appearance::text(form.contents(), w, h, text_style, widget.style(), &value, &layout);
+            widget.set_appearances(vec![(None, form)], None);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        let mut t = TextField::new();
+        t.set_value("ABC");
+        t.set_max_len(4);
+        t.set_comb(true);
+
+        assert_eq!(t.flags(), FieldFlags::COMB);
+        assert_eq!(t.entries(), vec![
+            ("V", String::from("(ABC)")),
+            ("MaxLen", String::from("4")),
+        ]);
+
+        t.set_multiline(true);
+        assert_eq!(t.flags(), FieldFlags::MULTILINE);
+    }
+}
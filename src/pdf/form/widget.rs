@@ -0,0 +1,187 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::WidgetStyle;
+use super::super::base::*;
+use super::super::utils::{dict_string, to_text_string};
+use super::super::xobject::Form;
+
+/// PDF32000-1:2008 12.5.6.19
+///
+/// A widget annotation placing a terminal field on a page.
+///
+/// ```text
+/// 30 0 obj
+/// << /Type /Annot
+///    /Subtype /Widget
+///    /Rect [72 700 272 720]
+///    /P 3 0 R
+///    /Parent 29 0 R
+///    /F 4
+///    /MK << /BC [0] >>
+///    /BS << /W 1 >>
+///    /AP << /N << /Yes 31 0 R /Off 32 0 R >> >>
+///    /AS /Off
+/// >>
+/// endobj
+/// ```
+pub struct Widget {
+    id: Id,
+    parent_id: Id,
+    page_id: Id,
+    page_index: usize,
+    rect: Rectangle,
+    style: WidgetStyle,
+    caption: Option<String>,
+    /// Normal appearances. The state name is `None` for stateless fields.
+    appearances: Vec<(Option<String>, Form)>,
+    state: Option<String>,
+}
+
+impl Widget {
+    /// Creates a widget on the page at `page_index` of the document.
+    pub fn new(page_index: usize, rect: Rectangle) -> Self {
+        Self {
+            id: Id::new_0(),
+            parent_id: Id::new_0(),
+            page_id: Id::new_0(),
+            page_index,
+            rect,
+            style: WidgetStyle::default(),
+            caption: None,
+            appearances: Vec::new(),
+            state: None,
+        }
+    }
+
+    pub fn page_index(&self) -> usize {
+        self.page_index
+    }
+
+    pub(crate) fn set_page_index(&mut self, page_index: usize) {
+        self.page_index = page_index;
+    }
+
+    pub fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    pub fn style(&self) -> &WidgetStyle {
+        &self.style
+    }
+
+    pub fn set_style(&mut self, style: WidgetStyle) {
+        self.style = style;
+    }
+
+    pub(crate) fn set_caption(&mut self, caption: &str) {
+        self.caption = Some(caption.to_string());
+    }
+
+    pub(crate) fn set_page_id(&mut self, page_id: Id) {
+        self.page_id = page_id;
+    }
+
+    pub(crate) fn set_parent_id(&mut self, parent_id: Id) {
+        self.parent_id = parent_id;
+    }
+
+    /// Creates an empty appearance form covering the widget.
+    pub(crate) fn new_appearance(&self) -> Form {
+        Form::new(Rectangle::new(0.0, 0.0, self.rect.width(), self.rect.height()))
+    }
+
+    pub(crate) fn set_appearances(&mut self, appearances: Vec<(Option<String>, Form)>, state: Option<String>) {
+        self.appearances = appearances;
+        self.state = state;
+    }
+
+    fn get_mk_string(&self) -> String {
+        let mut mk = String::from("<<");
+        if let Some(bc) = &self.style.border_colour {
+            mk.push_str(&format!(" /BC {}", bc.to_pdf_string()));
+        }
+        if let Some(bg) = &self.style.background_colour {
+            mk.push_str(&format!(" /BG {}", bg.to_pdf_string()));
+        }
+        if let Some(ca) = &self.caption {
+            mk.push_str(&format!(" /CA {}", to_text_string(ca)));
+        }
+        mk.push_str(" >>");
+        mk
+    }
+
+    fn get_ap_string(&self) -> String {
+        match self.appearances.as_slice() {
+            [] => String::new(),
+            [(None, form)] => format!("<< /N {} >>", form.id().to_ref_string()),
+            states => {
+                let n = states.iter()
+                    .map(|(name, form)| format!("{} {}", Name::new(name.as_deref().unwrap_or("Off")), form.id().to_ref_string()))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                format!("<< /N << {n} >> >>")
+            }
+        }
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let mut entries = vec![
+            ("Type", String::from("/Annot")),
+            ("Subtype", String::from("/Widget")),
+            ("Rect", self.rect.to_pdf_string()),
+            ("P", self.page_id.to_ref_string()),
+            ("Parent", self.parent_id.to_ref_string()),
+            ("F", String::from("4")),
+            ("MK", self.get_mk_string()),
+            ("BS", format!("<< /W {} >>", self.style.border_width)),
+        ];
+        // Not placed on any page
+        if self.page_id.number() == 0 {
+            entries.retain(|e| e.0 != "P");
+        }
+        if !self.appearances.is_empty() {
+            entries.push(("AP", self.get_ap_string()));
+        }
+        if let Some(state) = &self.state {
+            entries.push(("AS", Name::new(state).to_string()));
+        }
+
+        format!(concat!(
+            "{} obj\n",
+            "{}\n",
+            "endobj"),
+            self.id,
+            dict_string(&entries),
+        )
+    }
+}
+
+impl PdfObject for Widget {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        for (_, form) in &mut self.appearances {
+            form.assign_ids(id_factory);
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        for (_, form) in &self.appearances {
+            list.append(&mut form.get_objects());
+        }
+        list
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
@@ -7,5 +7,40 @@
 
 mod type0;
 mod type2;
+mod type3;
+mod type4;
 pub use type0::Type0;
 pub use type2::Type2;
+pub use type3::Type3;
+pub use type4::{Operator, Program, Type4};
+
+use super::base::*;
+
+/// PDF32000-1:2008 7.10 Functions
+///
+/// A function used by a colour space or a shading. Dictionary functions are
+/// written inline, stream functions as objects of their own.
+pub trait Function {
+    /// Returns the function dictionary, or the reference to the stream object.
+    fn to_pdf_string(&self, indent_depth: usize) -> String;
+
+    fn assign_ids(&mut self, _id_factory: &mut IdFactory) {
+    }
+
+    /// Returns the objects written for the function.
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        Vec::new()
+    }
+
+    /// Returns the outputs of the function for the inputs. The inputs are
+    /// clipped to the domain and the outputs to the range.
+    fn evaluate(&self, input: &[f64]) -> Vec<f64>;
+
+    fn clone_box(&self) -> Box<dyn Function>;
+}
+
+impl Clone for Box<dyn Function> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
@@ -5,6 +5,7 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+use super::Function;
 use super::super::base::*;
 use super::super::utils::indent;
 use super::super::utils::ToPdfString;
@@ -24,6 +25,7 @@ use super::super::utils::ToPdfString;
 /// ... bytes stream
 /// endstream
 /// ```
+#[derive(Clone)]
 pub struct Type0 {
     id: Id,
     domain: Vec<(f64, f64)>,
@@ -71,6 +73,19 @@ impl Type0 {
         }
     }
 
+    /// Returns the sample of the output at the grid position as 0.0 to 1.0.
+    fn sample(&self, position: usize, output: usize) -> f64 {
+        let bits = self.bits_per_sample as usize;
+        let start = (position * self.range.len() + output) * bits;
+        let mut value: u64 = 0;
+        for i in 0..bits {
+            let byte = self.samples.get((start + i) / 8).copied().unwrap_or(0);
+            let bit = (byte >> (7 - (start + i) % 8)) & 1;
+            value = (value << 1) | bit as u64;
+        }
+        value as f64 / ((1u64 << bits) - 1) as f64
+    }
+
     fn get_stream_bytes(&self, indent_size: usize) -> Vec<u8> {
         let mut bytes: Vec<u8> = Vec::new();
         bytes.append(&mut indent("stream\n", indent_size).into_bytes());
@@ -113,7 +128,7 @@ impl PdfObject for Type0 {
         ), indent_depth);
 
         let mut bytes: Vec<u8> = Vec::new();
-        bytes.append(&mut format!("{} obj\n", self.id.to_string()).into_bytes());
+        bytes.append(&mut format!("{} obj\n", self.id).into_bytes());
         bytes.append(&mut dict.into_bytes());
         bytes.push("\n".as_bytes()[0]);
         bytes.append(&mut self.get_stream_bytes(indent_depth));
@@ -123,6 +138,59 @@ impl PdfObject for Type0 {
     }
 }
 
+impl Function for Type0 {
+    fn to_pdf_string(&self, indent_depth: usize) -> String {
+        indent(&self.id.to_ref_string(), indent_depth)
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        PdfObject::assign_ids(self, id_factory);
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        PdfObject::get_objects(self)
+    }
+
+    /// PDF32000-1:2008 7.10.2, multilinear interpolation with the default
+    /// `/Encode` and `/Decode`.
+    fn evaluate(&self, input: &[f64]) -> Vec<f64> {
+        // The grid position of each input, the lower sample and the fraction
+        let mut cells: Vec<(usize, f64)> = Vec::with_capacity(self.domain.len());
+        for (i, (min, max)) in self.domain.iter().enumerate() {
+            let size = self.size.get(i).copied().unwrap_or(1).max(1) as usize;
+            let x = input.get(i).copied().unwrap_or(*min).clamp(*min, *max);
+            let e = if max > min { (x - min) * (size - 1) as f64 / (max - min) } else { 0.0 };
+            let lower = (e.floor() as usize).min(size.saturating_sub(2));
+            cells.push((lower, if size > 1 { e - lower as f64 } else { 0.0 }));
+        }
+
+        let mut output = vec![0.0; self.range.len()];
+        for corner in 0..1usize << cells.len() {
+            let mut weight = 1.0;
+            let mut position = 0;
+            let mut stride = 1;
+            for (i, (lower, fraction)) in cells.iter().enumerate() {
+                let upper = corner >> i & 1 == 1;
+                weight *= if upper { *fraction } else { 1.0 - fraction };
+                position += (lower + upper as usize) * stride;
+                stride *= self.size.get(i).copied().unwrap_or(1).max(1) as usize;
+            }
+            if weight == 0.0 {
+                continue;
+            }
+            for (j, o) in output.iter_mut().enumerate() {
+                *o += weight * self.sample(position, j);
+            }
+        }
+
+        output.iter().zip(&self.range).map(|(o, (min, max))| min + o * (max - min)).collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn Function> {
+        Box::new(self.clone())
+    }
+}
+
 //------------------------------------------------------------------------------
 // tests
 //------------------------------------------------------------------------------
@@ -142,6 +210,7 @@ mod tests {
 
         let mut ok: Vec<u8> = Vec::new();
         ok.append(&mut concat!(
+            "0 0 obj\n",
             "<<\n",
             "  /FunctionType 0\n",
             "  /Domain [0 1 0 1 0 1 0 1 0 1 0 1 0 1]\n",
@@ -153,8 +222,28 @@ mod tests {
         ).to_string().into_bytes());
         ok.append(&mut "stream\n".to_string().into_bytes());
         ok.append(&mut vec![128u8, 128, 128, 128]);
-        ok.append(&mut "endstream".to_string().into_bytes());
+        ok.append(&mut "\nendstream\nendobj".to_string().into_bytes());
 
         assert_eq!(f.to_bytes(0), ok);
     }
+
+    #[test]
+    fn evaluate() {
+        // Gray to Lab, white to black
+        let f = Type0::new([(0.0, 1.0)], [(0.0, 100.0), (-128.0, 127.0), (-128.0, 127.0)], [2], 8, vec![255, 128, 128, 0, 128, 128]);
+        let lab = f.evaluate(&[0.25]);
+        assert!((lab[0] - 75.0).abs() < 1e-9);
+        assert!(lab[1].abs() < 1e-9);
+
+        // 2x2 grid of 16 bit samples
+        let samples = vec![0x00, 0x00, 0xff, 0xff, 0x00, 0x00, 0xff, 0xff];
+        let f = Type0::new([(0.0, 1.0), (0.0, 2.0)], [(0.0, 1.0)], [2, 2], 16, samples);
+        assert_eq!(f.evaluate(&[0.5, 0.0]), [0.5]);
+        assert_eq!(f.evaluate(&[1.0, 1.0]), [1.0]);
+        assert_eq!(f.evaluate(&[-1.0, 5.0]), [0.0]);
+
+        // A single sample for any input
+        let f = Type0::new([(0.0, 1.0); 2], [(0.0, 1.0); 2], [1, 1], 8, vec![64, 255]);
+        assert_eq!(f.evaluate(&[0.3, 0.7]), [64.0 / 255.0, 1.0]);
+    }
 }
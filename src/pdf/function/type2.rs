@@ -7,6 +7,7 @@
 
 use crate::pdf::utils::ToPdfString;
 
+use super::Function;
 use super::super::utils::indent;
 
 /// PDF32000-1:2008 7.10.3
@@ -19,6 +20,7 @@ use super::super::utils::indent;
 ///   /N 1.0
 /// >>
 /// ```
+#[derive(Clone)]
 pub struct Type2 {
     c0: Vec<f64>,
     c1: Vec<f64>,
@@ -26,6 +28,10 @@ pub struct Type2 {
 }
 
 impl Type2 {
+    pub fn new_with_vec(c0: Vec<f64>, c1: Vec<f64>, n: f64) -> Type2 {
+        Type2 { c0, c1, n }
+    }
+
     pub fn new<
         const OUTPUT_NUM: usize,
     >(
@@ -44,19 +50,33 @@ impl Type2 {
         indent(&format!(concat!(
             "<<\n",
             "  /FunctionType 2\n",
-            "  /Domain [0.0 1.0]\n",
-            "  /Range [0.0 100.0 -128.0 127.0 -128.0 127.0]\n",
+            "  /Domain [0 1]\n",
             "  /C0 {}\n",
             "  /C1 {}\n",
-            "  /N {:.1}\n",
+            "  /N {}\n",
             ">>"),
             self.c0.to_pdf_string(),
             self.c1.to_pdf_string(),
-            self.n,
+            self.n.to_pdf_string(),
         ), indent_size).into_bytes()
     }
 }
 
+impl Function for Type2 {
+    fn to_pdf_string(&self, indent_depth: usize) -> String {
+        String::from_utf8(self.to_bytes(indent_depth)).unwrap()
+    }
+
+    fn evaluate(&self, input: &[f64]) -> Vec<f64> {
+        let x = input.first().copied().unwrap_or(0.0).clamp(0.0, 1.0);
+        let t = x.powf(self.n);
+        self.c0.iter().zip(&self.c1).map(|(c0, c1)| c0 + t * (c1 - c0)).collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn Function> {
+        Box::new(self.clone())
+    }
+}
 
 //------------------------------------------------------------------------------
 // tests
@@ -83,6 +103,7 @@ mod tests {
         let ok = concat!(
             "<<\n",
             "  /FunctionType 2\n",
+            "  /Domain [0 1]\n",
             "  /C0 [100 1 0]\n",
             "  /C1 [65 58 88]\n",
             "  /N 1\n",
@@ -91,4 +112,13 @@ mod tests {
 
         assert_eq!(String::from_utf8(t2.to_bytes(0)).unwrap(), ok.to_string());
     }
+
+    #[test]
+    fn evaluate() {
+        let f = Type2::new([100.0, 0.0, 0.0], [65.0, 58.0, 88.0], 1.0);
+        assert_eq!(f.evaluate(&[0.0]), [100.0, 0.0, 0.0]);
+        assert_eq!(f.evaluate(&[0.5]), [82.5, 29.0, 44.0]);
+        assert_eq!(f.evaluate(&[2.0]), [65.0, 58.0, 88.0]);
+        assert_eq!(Type2::new([0.0], [1.0], 2.0).evaluate(&[0.5]), [0.25]);
+    }
 }
@@ -0,0 +1,169 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::{Function, Type2};
+use super::super::base::*;
+use super::super::error::Error;
+use super::super::utils::{indent, ToPdfString};
+
+/// PDF32000-1:2008 7.10.4
+///
+/// Stitches 1-input functions over the subdomains split at the bounds,
+/// e.g. the colour stops of a gradient.
+///
+/// ```text
+/// <<
+///   /FunctionType 3
+///   /Domain [0 1]
+///   /Functions [
+///     << /FunctionType 2 ... >>
+///     << /FunctionType 2 ... >>
+///   ]
+///   /Bounds [0.5]
+///   /Encode [0 1 0 1]
+/// >>
+/// ```
+#[derive(Clone)]
+pub struct Type3 {
+    domain: (f64, f64),
+    functions: Vec<Box<dyn Function>>,
+    bounds: Vec<f64>,
+    encode: Vec<(f64, f64)>,
+}
+
+impl Type3 {
+    /// Fails if there is not one bound less than functions and one encode
+    /// per function, or the bounds are not increasing within the domain.
+    pub fn new(
+        domain: (f64, f64),
+        functions: Vec<Box<dyn Function>>,
+        bounds: Vec<f64>,
+        encode: Vec<(f64, f64)>,
+    ) -> Result<Type3, Error> {
+        if functions.is_empty() || bounds.len() + 1 != functions.len() || encode.len() != functions.len() {
+            return Err(Error::InvalidValue(format!(
+                "{} functions with {} bounds and {} encode", functions.len(), bounds.len(), encode.len())));
+        }
+        let mut last = domain.0;
+        for b in &bounds {
+            if *b < last || *b > domain.1 {
+                return Err(Error::InvalidValue(format!("Bounds {}", bounds.to_pdf_string())));
+            }
+            last = *b;
+        }
+        Ok(Type3 { domain, functions, bounds, encode })
+    }
+
+    /// Returns the function interpolating linearly between the colours of
+    /// the stops, each of the position and the colour components.
+    pub fn from_stops(stops: &[(f64, Vec<f64>)]) -> Result<Type3, Error> {
+        if stops.len() < 2 {
+            return Err(Error::InvalidValue(String::from("less than 2 colour stops")));
+        }
+        if stops.iter().any(|s| s.1.len() != stops[0].1.len()) {
+            return Err(Error::InvalidValue(String::from("colour stops of different components")));
+        }
+
+        let functions: Vec<Box<dyn Function>> = stops.windows(2)
+            .map(|w| Box::new(Type2::new_with_vec(w[0].1.clone(), w[1].1.clone(), 1.0)) as Box<dyn Function>)
+            .collect();
+        let bounds = stops[1..stops.len() - 1].iter().map(|s| s.0).collect();
+        let encode = vec![(0.0, 1.0); functions.len()];
+        Self::new((stops[0].0, stops[stops.len() - 1].0), functions, bounds, encode)
+    }
+}
+
+impl Function for Type3 {
+    fn to_pdf_string(&self, indent_depth: usize) -> String {
+        let functions: Vec<String> = self.functions.iter().map(|f| f.to_pdf_string(2)).collect();
+        indent(&format!(concat!(
+            "<<\n",
+            "  /FunctionType 3\n",
+            "  /Domain {}\n",
+            "  /Functions [\n",
+            "{}\n",
+            "  ]\n",
+            "  /Bounds {}\n",
+            "  /Encode {}\n",
+            ">>"),
+            [self.domain.0, self.domain.1].to_pdf_string(),
+            functions.join("\n"),
+            self.bounds.to_pdf_string(),
+            self.encode.to_pdf_string(),
+        ), indent_depth)
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        for f in &mut self.functions {
+            f.assign_ids(id_factory);
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        self.functions.iter().flat_map(|f| f.get_objects()).collect()
+    }
+
+    fn evaluate(&self, input: &[f64]) -> Vec<f64> {
+        let x = input.first().copied().unwrap_or(self.domain.0).clamp(self.domain.0, self.domain.1);
+        let k = self.bounds.iter().take_while(|b| x >= **b).count();
+        let low = if k == 0 { self.domain.0 } else { self.bounds[k - 1] };
+        let high = self.bounds.get(k).copied().unwrap_or(self.domain.1);
+        let (e0, e1) = self.encode[k];
+        let t = if high > low { e0 + (x - low) * (e1 - e0) / (high - low) } else { e0 };
+        self.functions[k].evaluate(&[t])
+    }
+
+    fn clone_box(&self) -> Box<dyn Function> {
+        Box::new(self.clone())
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_stops() {
+        let f = Type3::from_stops(&[(0.0, vec![1.0, 0.0, 0.0]), (0.5, vec![1.0, 1.0, 0.0]), (1.0, vec![0.0, 0.0, 1.0])]).unwrap();
+        let ok = concat!(
+            "<<\n",
+            "  /FunctionType 3\n",
+            "  /Domain [0 1]\n",
+            "  /Functions [\n",
+            "    <<\n",
+            "      /FunctionType 2\n",
+            "      /Domain [0 1]\n",
+            "      /C0 [1 0 0]\n",
+            "      /C1 [1 1 0]\n",
+            "      /N 1\n",
+            "    >>\n",
+            "    <<\n",
+            "      /FunctionType 2\n",
+            "      /Domain [0 1]\n",
+            "      /C0 [1 1 0]\n",
+            "      /C1 [0 0 1]\n",
+            "      /N 1\n",
+            "    >>\n",
+            "  ]\n",
+            "  /Bounds [0.5]\n",
+            "  /Encode [0 1 0 1]\n",
+            ">>",
+        );
+        assert_eq!(f.to_pdf_string(0), ok);
+        assert_eq!(f.evaluate(&[0.25]), [1.0, 0.5, 0.0]);
+        assert_eq!(f.evaluate(&[0.75]), [0.5, 0.5, 0.5]);
+        assert_eq!(f.evaluate(&[1.0]), [0.0, 0.0, 1.0]);
+
+        assert!(Type3::from_stops(&[(0.0, vec![1.0])]).is_err());
+        assert!(Type3::from_stops(&[(0.0, vec![1.0]), (1.0, vec![0.0, 1.0])]).is_err());
+        assert!(Type3::from_stops(&[(0.0, vec![1.0]), (0.7, vec![0.0]), (0.5, vec![0.5])]).is_err());
+    }
+}
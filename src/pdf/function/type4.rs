@@ -0,0 +1,725 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::Function;
+use super::super::base::*;
+use super::super::error::Error;
+use super::super::utils::{indent, ToPdfString};
+
+/// PDF32000-1:2008 7.10.5
+///
+/// ```text
+/// <<
+///   /FunctionType 4
+///   /Domain [0 1 0 1]
+///   /Range [0 1 0 1 0 1 0 1]
+///   /Length 17
+/// >>
+/// stream
+/// { 0 0 3 -1 roll }
+/// endstream
+/// ```
+#[derive(Clone)]
+pub struct Type4 {
+    id: Id,
+    domain: Vec<(f64, f64)>,
+    range: Vec<(f64, f64)>,
+    program: Program,
+}
+
+impl Type4 {
+    /// Creates the function of the program, taking the inputs on the
+    /// stack and leaving one output per range.
+    ///
+    /// ```
+    /// use prspdf::function::{Operator, Program, Type4};
+    ///
+    /// // The tints of 2 channels as the magenta and yellow of DeviceCMYK
+    /// let program = Program::new()
+    ///     .number(0.0)
+    ///     .number(3.0)
+    ///     .number(1.0)
+    ///     .op(Operator::Roll)
+    ///     .number(0.0);
+    /// let f = Type4::new(vec![(0.0, 1.0); 2], vec![(0.0, 1.0); 4], program);
+    /// assert_eq!(f.program().to_string(), "{ 0 3 1 roll 0 }");
+    /// assert_eq!(f.run(&[0.5, 0.25]).unwrap(), [0.0, 0.5, 0.25, 0.0]);
+    /// ```
+    pub fn new(domain: Vec<(f64, f64)>, range: Vec<(f64, f64)>, program: Program) -> Type4 {
+        Type4 {
+            id: Id::new_0(),
+            domain,
+            range,
+            program,
+        }
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Returns the outputs of the program clipped to the range, or an error
+    /// if the program fails or leaves other than one value per range.
+    pub fn run(&self, input: &[f64]) -> Result<Vec<f64>, Error> {
+        let mut stack: Vec<Value> = self.domain.iter().enumerate()
+            .map(|(i, (min, max))| Value::Number(input.get(i).copied().unwrap_or(*min).clamp(*min, *max)))
+            .collect();
+        execute(&self.program.tokens, &mut stack)?;
+
+        if stack.len() != self.range.len() {
+            return Err(Error::InvalidValue(format!(
+                "{} values left by the program, {} expected", stack.len(), self.range.len())));
+        }
+        stack.iter().zip(&self.range)
+            .map(|(v, (min, max))| v.number().map(|n| n.clamp(*min, *max)))
+            .collect()
+    }
+}
+
+impl PdfObject for Type4 {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        vec![self]
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        let program = self.program.to_string();
+        indent(&format!(concat!(
+            "{} obj\n",
+            "<<\n",
+            "  /FunctionType 4\n",
+            "  /Domain {}\n",
+            "  /Range {}\n",
+            "  /Length {}\n",
+            ">>\n",
+            "stream\n",
+            "{}\n",
+            "endstream\n",
+            "endobj"),
+            self.id,
+            self.domain.to_pdf_string(),
+            self.range.to_pdf_string(),
+            program.len(),
+            program,
+        ), indent_depth).into_bytes()
+    }
+}
+
+impl Function for Type4 {
+    fn to_pdf_string(&self, indent_depth: usize) -> String {
+        indent(&self.id.to_ref_string(), indent_depth)
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        PdfObject::assign_ids(self, id_factory);
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        PdfObject::get_objects(self)
+    }
+
+    /// Returns the lower ends of the range if the program fails.
+    fn evaluate(&self, input: &[f64]) -> Vec<f64> {
+        self.run(input).unwrap_or_else(|_| self.range.iter().map(|r| r.0).collect())
+    }
+
+    fn clone_box(&self) -> Box<dyn Function> {
+        Box::new(self.clone())
+    }
+}
+
+/// The operators of PostScript calculator functions (PDF32000-1:2008
+/// Table 42), except `true`, `false`, `if` and `ifelse`, which are added
+/// with `Program::boolean`, `Program::if_then` and `Program::if_else`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    // Arithmetic
+    Abs, Add, Atan, Ceiling, Cos, Cvi, Cvr, Div, Exp, Floor, Idiv, Ln, Log,
+    Mod, Mul, Neg, Round, Sin, Sqrt, Sub, Truncate,
+    // Relational, boolean and bitwise
+    And, Bitshift, Eq, Ge, Gt, Le, Lt, Ne, Not, Or, Xor,
+    // Stack
+    Copy, Dup, Exch, Index, Pop, Roll,
+}
+
+impl Operator {
+    const ALL: [Operator; 38] = [
+        Self::Abs, Self::Add, Self::Atan, Self::Ceiling, Self::Cos, Self::Cvi, Self::Cvr,
+        Self::Div, Self::Exp, Self::Floor, Self::Idiv, Self::Ln, Self::Log, Self::Mod,
+        Self::Mul, Self::Neg, Self::Round, Self::Sin, Self::Sqrt, Self::Sub, Self::Truncate,
+        Self::And, Self::Bitshift, Self::Eq, Self::Ge, Self::Gt, Self::Le, Self::Lt,
+        Self::Ne, Self::Not, Self::Or, Self::Xor,
+        Self::Copy, Self::Dup, Self::Exch, Self::Index, Self::Pop, Self::Roll,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Abs => "abs",
+            Self::Add => "add",
+            Self::Atan => "atan",
+            Self::Ceiling => "ceiling",
+            Self::Cos => "cos",
+            Self::Cvi => "cvi",
+            Self::Cvr => "cvr",
+            Self::Div => "div",
+            Self::Exp => "exp",
+            Self::Floor => "floor",
+            Self::Idiv => "idiv",
+            Self::Ln => "ln",
+            Self::Log => "log",
+            Self::Mod => "mod",
+            Self::Mul => "mul",
+            Self::Neg => "neg",
+            Self::Round => "round",
+            Self::Sin => "sin",
+            Self::Sqrt => "sqrt",
+            Self::Sub => "sub",
+            Self::Truncate => "truncate",
+            Self::And => "and",
+            Self::Bitshift => "bitshift",
+            Self::Eq => "eq",
+            Self::Ge => "ge",
+            Self::Gt => "gt",
+            Self::Le => "le",
+            Self::Lt => "lt",
+            Self::Ne => "ne",
+            Self::Not => "not",
+            Self::Or => "or",
+            Self::Xor => "xor",
+            Self::Copy => "copy",
+            Self::Dup => "dup",
+            Self::Exch => "exch",
+            Self::Index => "index",
+            Self::Pop => "pop",
+            Self::Roll => "roll",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Operator> {
+        Self::ALL.iter().find(|op| op.as_str() == name).copied()
+    }
+}
+
+/// The program of a `Type4` function, built operand by operand or parsed
+/// from the stream.
+///
+/// Numbers are evaluated as reals. The integer operators (`idiv`, `mod`,
+/// `bitshift`, and the bitwise `and`, `or`, `xor` and `not`) take integral
+/// values.
+///
+/// ```
+/// use prspdf::function::{Operator, Program};
+///
+/// // 1 - x if x > 0.5, else x
+/// let program = Program::new()
+///     .op(Operator::Dup)
+///     .number(0.5)
+///     .op(Operator::Gt)
+///     .if_then(Program::new().number(1.0).op(Operator::Exch).op(Operator::Sub));
+/// assert_eq!(program.to_string(), "{ dup 0.5 gt { 1 exch sub } if }");
+/// assert_eq!(Program::parse(&program.to_string()).unwrap(), program);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Program {
+    tokens: Vec<Token>,
+}
+
+impl Program {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the program of the operator subset, e.g. `{ 1 exch sub }`.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let mut lexer = Lexer { bytes: s.as_bytes(), pos: 0 };
+        match lexer.next()? {
+            Some((_, Lexeme::Open)) => (),
+            Some((offset, _)) => return Err(Error::parse(offset, "program not beginning with {")),
+            None => return Err(Error::parse(0, "empty program")),
+        }
+        let program = lexer.procedure()?;
+        if let Some((offset, _)) = lexer.next()? {
+            return Err(Error::parse(offset, "data after the program"));
+        }
+        Ok(program)
+    }
+
+    /// Returns the program pushing the number.
+    pub fn number(mut self, value: f64) -> Self {
+        self.tokens.push(Token::Number(value));
+        self
+    }
+
+    /// Returns the program pushing `true` or `false`.
+    pub fn boolean(mut self, value: bool) -> Self {
+        self.tokens.push(Token::Bool(value));
+        self
+    }
+
+    /// Returns the program running the operator.
+    pub fn op(mut self, op: Operator) -> Self {
+        self.tokens.push(Token::Operator(op));
+        self
+    }
+
+    /// Returns the program running the procedure if the boolean on the
+    /// stack is true, `{ ... } if`.
+    pub fn if_then(mut self, then: Program) -> Self {
+        self.tokens.push(Token::If(then.tokens));
+        self
+    }
+
+    /// Returns the program running either procedure with the boolean on the
+    /// stack, `{ ... } { ... } ifelse`.
+    pub fn if_else(mut self, then: Program, otherwise: Program) -> Self {
+        self.tokens.push(Token::IfElse(then.tokens, otherwise.tokens));
+        self
+    }
+}
+
+impl std::fmt::Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", procedure_string(&self.tokens))
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// The operand stack limit of calculator functions
+const STACK_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Bool(bool),
+    Operator(Operator),
+    If(Vec<Token>),
+    IfElse(Vec<Token>, Vec<Token>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn number(&self) -> Result<f64, Error> {
+        match self {
+            Self::Number(n) => Ok(*n),
+            Self::Bool(_) => Err(Error::InvalidValue(String::from("boolean for a number"))),
+        }
+    }
+
+    fn integer(&self) -> Result<i64, Error> {
+        let n = self.number()?;
+        if n.fract() != 0.0 {
+            return Err(Error::InvalidValue(format!("{} for an integer", n)));
+        }
+        Ok(n as i64)
+    }
+}
+
+fn procedure_string(tokens: &[Token]) -> String {
+    let mut s = String::from("{");
+    for token in tokens {
+        s.push(' ');
+        match token {
+            Token::Number(n) => s.push_str(&n.to_pdf_string()),
+            Token::Bool(b) => s.push_str(&b.to_string()),
+            Token::Operator(op) => s.push_str(op.as_str()),
+            Token::If(then) => {
+                s.push_str(&procedure_string(then));
+                s.push_str(" if");
+            }
+            Token::IfElse(then, otherwise) => {
+                s.push_str(&procedure_string(then));
+                s.push(' ');
+                s.push_str(&procedure_string(otherwise));
+                s.push_str(" ifelse");
+            }
+        }
+    }
+    s.push_str(" }");
+    s
+}
+
+fn execute(tokens: &[Token], stack: &mut Vec<Value>) -> Result<(), Error> {
+    fn pop(stack: &mut Vec<Value>) -> Result<Value, Error> {
+        stack.pop().ok_or_else(|| Error::InvalidValue(String::from("stack underflow")))
+    }
+    fn pop_number(stack: &mut Vec<Value>) -> Result<f64, Error> {
+        pop(stack)?.number()
+    }
+    fn pop_integer(stack: &mut Vec<Value>) -> Result<i64, Error> {
+        pop(stack)?.integer()
+    }
+    fn pop_count(stack: &mut Vec<Value>) -> Result<usize, Error> {
+        let n = pop_integer(stack)?;
+        if n < 0 || n as usize > stack.len() {
+            return Err(Error::InvalidValue(format!("count {} of {} values", n, stack.len())));
+        }
+        Ok(n as usize)
+    }
+
+    for token in tokens {
+        match token {
+            Token::Number(n) => stack.push(Value::Number(*n)),
+            Token::Bool(b) => stack.push(Value::Bool(*b)),
+            Token::If(then) => {
+                match pop(stack)? {
+                    Value::Bool(true) => execute(then, stack)?,
+                    Value::Bool(false) => (),
+                    Value::Number(_) => return Err(Error::InvalidValue(String::from("number for if"))),
+                }
+            }
+            Token::IfElse(then, otherwise) => {
+                match pop(stack)? {
+                    Value::Bool(true) => execute(then, stack)?,
+                    Value::Bool(false) => execute(otherwise, stack)?,
+                    Value::Number(_) => return Err(Error::InvalidValue(String::from("number for ifelse"))),
+                }
+            }
+            Token::Operator(op) => {
+                use Operator::*;
+                match op {
+                    Abs | Ceiling | Cos | Cvi | Cvr | Floor | Ln | Log | Neg | Round | Sin | Sqrt | Truncate => {
+                        let x = pop_number(stack)?;
+                        let y = match op {
+                            Abs => x.abs(),
+                            Ceiling => x.ceil(),
+                            Cos => x.to_radians().cos(),
+                            Cvi | Truncate => x.trunc(),
+                            Floor => x.floor(),
+                            Ln => x.ln(),
+                            Log => x.log10(),
+                            Neg => -x,
+                            Round => (x + 0.5).floor(),
+                            Sin => x.to_radians().sin(),
+                            Sqrt => x.sqrt(),
+                            _ => x,
+                        };
+                        if !y.is_finite() {
+                            return Err(Error::InvalidValue(format!("{} {}", x, op.as_str())));
+                        }
+                        stack.push(Value::Number(y));
+                    }
+                    Add | Atan | Div | Exp | Mul | Sub => {
+                        let b = pop_number(stack)?;
+                        let a = pop_number(stack)?;
+                        let y = match op {
+                            Add => a + b,
+                            Atan => a.atan2(b).to_degrees().rem_euclid(360.0),
+                            Div => a / b,
+                            Exp => a.powf(b),
+                            Mul => a * b,
+                            _ => a - b,
+                        };
+                        if !y.is_finite() {
+                            return Err(Error::InvalidValue(format!("{} {} {}", a, b, op.as_str())));
+                        }
+                        stack.push(Value::Number(y));
+                    }
+                    Idiv | Mod | Bitshift => {
+                        let b = pop_integer(stack)?;
+                        let a = pop_integer(stack)?;
+                        let y = match op {
+                            Bitshift if b >= 0 => a.checked_shl(b as u32),
+                            Bitshift => a.checked_shr(-b as u32),
+                            Idiv => a.checked_div(b),
+                            _ => a.checked_rem(b),
+                        };
+                        let y = y.ok_or_else(|| Error::InvalidValue(format!("{} {} {}", a, b, op.as_str())))?;
+                        stack.push(Value::Number(y as f64));
+                    }
+                    And | Or | Xor => {
+                        let b = pop(stack)?;
+                        let a = pop(stack)?;
+                        let y = match (a, b) {
+                            (Value::Bool(a), Value::Bool(b)) => Value::Bool(match op {
+                                And => a & b,
+                                Or => a | b,
+                                _ => a ^ b,
+                            }),
+                            (a, b) => {
+                                let (a, b) = (a.integer()?, b.integer()?);
+                                Value::Number(match op {
+                                    And => a & b,
+                                    Or => a | b,
+                                    _ => a ^ b,
+                                } as f64)
+                            }
+                        };
+                        stack.push(y);
+                    }
+                    Not => {
+                        let y = match pop(stack)? {
+                            Value::Bool(b) => Value::Bool(!b),
+                            v => Value::Number(!v.integer()? as f64),
+                        };
+                        stack.push(y);
+                    }
+                    Eq | Ne => {
+                        let b = pop(stack)?;
+                        let a = pop(stack)?;
+                        stack.push(Value::Bool((a == b) == (*op == Eq)));
+                    }
+                    Ge | Gt | Le | Lt => {
+                        let b = pop_number(stack)?;
+                        let a = pop_number(stack)?;
+                        stack.push(Value::Bool(match op {
+                            Ge => a >= b,
+                            Gt => a > b,
+                            Le => a <= b,
+                            _ => a < b,
+                        }));
+                    }
+                    Copy => {
+                        let n = pop_count(stack)?;
+                        stack.extend_from_within(stack.len() - n..);
+                    }
+                    Dup => {
+                        let v = pop(stack)?;
+                        stack.extend([v, v]);
+                    }
+                    Exch => {
+                        let b = pop(stack)?;
+                        let a = pop(stack)?;
+                        stack.extend([b, a]);
+                    }
+                    Index => {
+                        let n = pop_count(stack)?;
+                        if n >= stack.len() {
+                            return Err(Error::InvalidValue(format!("index {} of {} values", n, stack.len())));
+                        }
+                        stack.push(stack[stack.len() - 1 - n]);
+                    }
+                    Pop => {
+                        pop(stack)?;
+                    }
+                    Roll => {
+                        let j = pop_integer(stack)?;
+                        let n = pop_count(stack)?;
+                        if n > 0 {
+                            let start = stack.len() - n;
+                            stack[start..].rotate_right(j.rem_euclid(n as i64) as usize);
+                        }
+                    }
+                }
+            }
+        }
+        if stack.len() > STACK_LIMIT {
+            return Err(Error::InvalidValue(String::from("stack overflow")));
+        }
+    }
+    Ok(())
+}
+
+enum Lexeme<'a> {
+    Open,
+    Close,
+    Word(&'a str),
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    /// Returns the next lexeme and its offset, skipping white-space and
+    /// comments.
+    fn next(&mut self) -> Result<Option<(usize, Lexeme<'a>)>, Error> {
+        while let Some(c) = self.bytes.get(self.pos) {
+            match c {
+                b'%' => {
+                    while self.bytes.get(self.pos).is_some_and(|c| *c != b'\n' && *c != b'\r') {
+                        self.pos += 1;
+                    }
+                }
+                c if c.is_ascii_whitespace() || *c == 0 => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        let start = self.pos;
+        let lexeme = match self.bytes.get(start) {
+            None => return Ok(None),
+            Some(b'{') => {
+                self.pos += 1;
+                Lexeme::Open
+            }
+            Some(b'}') => {
+                self.pos += 1;
+                Lexeme::Close
+            }
+            Some(_) => {
+                while self.bytes.get(self.pos)
+                    .is_some_and(|c| !c.is_ascii_whitespace() && !b"{}%".contains(c)) {
+                    self.pos += 1;
+                }
+                let word = std::str::from_utf8(&self.bytes[start..self.pos])
+                    .map_err(|_| Error::parse(start, "non-ASCII operator"))?;
+                Lexeme::Word(word)
+            }
+        };
+        Ok(Some((start, lexeme)))
+    }
+
+    /// Parses the procedure after its `{` up to the matching `}`.
+    fn procedure(&mut self) -> Result<Program, Error> {
+        let mut tokens = Vec::new();
+        // The procedures before `if` or `ifelse`
+        let mut pending: Vec<Vec<Token>> = Vec::new();
+        loop {
+            let Some((offset, lexeme)) = self.next()? else {
+                return Err(Error::parse(self.pos, "procedure without }"));
+            };
+            if !pending.is_empty() && !matches!(lexeme, Lexeme::Open | Lexeme::Word("if" | "ifelse")) {
+                return Err(Error::parse(offset, "procedure not followed by if or ifelse"));
+            }
+
+            match lexeme {
+                Lexeme::Open => {
+                    if pending.len() == 2 {
+                        return Err(Error::parse(offset, "3 procedures in a row"));
+                    }
+                    pending.push(self.procedure()?.tokens);
+                }
+                Lexeme::Close => return Ok(Program { tokens }),
+                Lexeme::Word("if") => {
+                    if pending.len() != 1 {
+                        return Err(Error::parse(offset, "if without 1 procedure"));
+                    }
+                    tokens.push(Token::If(pending.remove(0)));
+                }
+                Lexeme::Word("ifelse") => {
+                    if pending.len() != 2 {
+                        return Err(Error::parse(offset, "ifelse without 2 procedures"));
+                    }
+                    let otherwise = pending.remove(1);
+                    tokens.push(Token::IfElse(pending.remove(0), otherwise));
+                }
+                Lexeme::Word("true") => tokens.push(Token::Bool(true)),
+                Lexeme::Word("false") => tokens.push(Token::Bool(false)),
+                Lexeme::Word(word) => {
+                    if let Some(op) = Operator::from_name(word) {
+                        tokens.push(Token::Operator(op));
+                    } else if let Ok(n) = word.parse::<f64>()
+                        && n.is_finite() {
+                        tokens.push(Token::Number(n));
+                    } else {
+                        return Err(Error::parse(offset, &format!("unknown operator {}", word)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::{ColorSpace, DeviceN};
+
+    fn run(program: &str, input: &[f64], outputs: usize) -> Result<Vec<f64>, Error> {
+        let f = Type4::new(vec![(-1000.0, 1000.0); input.len()], vec![(-1000.0, 1000.0); outputs], Program::parse(program)?);
+        f.run(input)
+    }
+
+    #[test]
+    fn to_bytes() {
+        // Cyan and black of 2 channels
+        let program = Program::parse("{ 0 0 % magenta and yellow\n 3 -1 roll }").unwrap();
+        assert_eq!(program.to_string(), "{ 0 0 3 -1 roll }");
+        let mut f = Type4::new(vec![(0.0, 1.0); 2], vec![(0.0, 1.0); 4], program);
+        let mut id_factory = IdFactory::new();
+        Function::assign_ids(&mut f, &mut id_factory);
+
+        let ok = concat!(
+            "1 0 obj\n",
+            "<<\n",
+            "  /FunctionType 4\n",
+            "  /Domain [0 1 0 1]\n",
+            "  /Range [0 1 0 1 0 1 0 1]\n",
+            "  /Length 17\n",
+            ">>\n",
+            "stream\n",
+            "{ 0 0 3 -1 roll }\n",
+            "endstream\n",
+            "endobj",
+        );
+        assert_eq!(String::from_utf8(PdfObject::to_bytes(&f, 0)).unwrap(), ok);
+        assert_eq!(f.to_pdf_string(1), "  1 0 R");
+        assert_eq!(f.evaluate(&[0.25, 2.0]), [0.25, 0.0, 0.0, 1.0]);
+
+        let mut space = ColorSpace::from(DeviceN::new(vec!["Cyan", "Black"], ColorSpace::DeviceCMYK, f));
+        space.assign_ids(&mut IdFactory::new());
+        assert_eq!(space.get_objects().len(), 2);
+    }
+
+    #[test]
+    fn evaluate() {
+        assert_eq!(run("{ 2 copy add 3 1 roll sub }", &[5.0, 3.0], 2).unwrap(), [8.0, 2.0]);
+        assert_eq!(run("{ 7 2 idiv -7 2 mod 1 3 bitshift 8 -2 bitshift }", &[], 4).unwrap(), [3.0, -1.0, 8.0, 2.0]);
+        assert_eq!(run("{ 0 1 atan 90 sin -2.5 round 2.5 round }", &[], 4).unwrap(), [0.0, 1.0, -2.0, 3.0]);
+        assert_eq!(run("{ 1 2 3 2 index }", &[], 4).unwrap(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(run("{ dup 0.5 gt { 1 exch sub } if }", &[0.75], 1).unwrap(), [0.25]);
+        assert_eq!(run("{ 0 lt true and { -1 } { 1 } ifelse }", &[-3.0], 1).unwrap(), [-1.0]);
+        assert_eq!(run("{ 12 10 xor not 1 1.0 eq { 5 } if }", &[], 2).unwrap(), [-7.0, 5.0]);
+
+        assert!(run("{ pop }", &[], 0).is_err());
+        assert!(run("{ 1 0 div }", &[], 1).is_err());
+        assert!(run("{ 1.5 2 idiv }", &[], 1).is_err());
+        assert!(run("{ 1 { } if }", &[], 0).is_err());
+        assert!(run("{ 1 2 }", &[], 1).is_err());
+        let f = Type4::new(vec![(0.0, 1.0)], vec![(0.0, 1.0), (0.5, 1.0)], Program::parse("{ pop }").unwrap());
+        assert_eq!(f.evaluate(&[0.5]), [0.0, 0.5]);
+    }
+
+    #[test]
+    fn parse() {
+        let program = Program::parse("{ dup 0 ge { 2 mul } { neg } ifelse true { } if }").unwrap();
+        let ok = Program::new()
+            .op(Operator::Dup)
+            .number(0.0)
+            .op(Operator::Ge)
+            .if_else(Program::new().number(2.0).op(Operator::Mul), Program::new().op(Operator::Neg))
+            .boolean(true)
+            .if_then(Program::new());
+        assert_eq!(program, ok);
+        assert_eq!(Program::parse(&program.to_string()).unwrap(), ok);
+
+        for (s, offset) in [
+            ("1 2 add", 0),
+            ("{ 1 2 add", 9),
+            ("{ 1 2 add } }", 12),
+            ("{ 1 foo }", 4),
+            ("{ { 1 } 2 }", 8),
+            ("{ { 1 } ifelse }", 8),
+            ("{ { 1 } { 2 } { 3 } ifelse }", 14),
+        ] {
+            match Program::parse(s) {
+                Err(Error::Parse { offset: o, .. }) => assert_eq!(o, offset, "{s}"),
+                _ => panic!("{s}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,675 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::base::*;
+use super::error::Error;
+use super::loaded_doc::{LoadedDoc, INHERITABLE_PAGE_ATTRIBUTES};
+use super::object::{Dictionary, Object};
+use super::page::{Page, Rotation};
+
+/// Page entries that are not copied as they are, but rebuilt from the page.
+const REBUILT_PAGE_ENTRIES: [&str; 10] = [
+    "Type", "Parent", "MediaBox", "CropBox", "BleedBox", "TrimBox", "ArtBox", "Rotate", "UserUnit",
+    // Article beads refer to the threads of the source document
+    "B",
+];
+
+/// The entries of a page copied from a loaded document.
+#[derive(Clone)]
+pub(crate) struct ImportedPage {
+    /// The placeholder the copied objects refer to the page with.
+    pub key: Id,
+    /// `/Resources`, `/Contents`, `/Annots` and the other entries of the
+    /// source page, referring to the copied objects by local ids.
+    entries: Dictionary,
+    /// `entries` with the written ids.
+    written: Dictionary,
+}
+
+impl ImportedPage {
+    /// Returns the local ids the entries refer to.
+    pub fn references(&self) -> Vec<Id> {
+        Object::Dictionary(self.entries.clone()).references()
+    }
+
+    pub fn set_written_ids(&mut self, f: &dyn Fn(Id) -> Id) {
+        let mut written = Object::Dictionary(self.entries.clone());
+        written.map_references(&mut |id| f(id));
+        if let Object::Dictionary(d) = written {
+            self.written = d;
+        }
+    }
+
+    /// Returns the written entry as a PDF string.
+    pub fn entry_string(&self, key: &str) -> Option<String> {
+        self.written.get(key).map(object_string)
+    }
+
+    /// Returns the written references of `/Annots`.
+    pub fn annotation_ids(&self) -> Vec<Id> {
+        self.written.get("Annots")
+            .and_then(|a| a.as_array())
+            .map(|a| a.iter().filter_map(|o| o.as_reference()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the written entries other than `/Resources`, `/Contents` and
+    /// `/Annots` as `(name, value)` PDF strings.
+    pub fn other_entries(&self) -> Vec<(String, String)> {
+        self.written.iter()
+            .filter(|(k, _)| !["Resources", "Contents", "Annots"].contains(&k.as_str()))
+            .map(|(k, v)| (k.to_string(), object_string(v)))
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+enum Slot {
+    Object(Object),
+    /// An imported page, referred to by annotations and destinations.
+    Page,
+    /// The outline (bookmarks) root, generated from the top-level items.
+    OutlineRoot,
+}
+
+/// Objects copied from loaded documents.
+///
+/// The objects are numbered locally when copied and renumbered through the
+/// `IdFactory` when the document is written. Identical objects, e.g. the same
+/// font embedded by several documents, are copied once.
+#[derive(Clone, Default)]
+pub(crate) struct ImportedObjects {
+    slots: Vec<Slot>,
+    /// Local ids of the objects that are not in reference cycles, by hash.
+    hashes: HashMap<u64, Vec<Id>>,
+    /// Local ids of the top-level outline items.
+    outlines: Vec<Id>,
+    outline_root: Option<Id>,
+    /// Written ids by slot, valid while writing.
+    ids: Vec<Id>,
+    written: Vec<ImportedObject>,
+}
+
+impl ImportedObjects {
+    /// Copies the pages at the indexes and the outline items pointing at them.
+    pub fn import_pages(&mut self, src: &LoadedDoc, indexes: &[usize]) -> Result<Vec<Page>, Error> {
+        let page_ids = src.page_ids();
+        let mut sources = Vec::new();
+        for &i in indexes {
+            let id = *page_ids.get(i).ok_or_else(|| Error::NotFound(format!("page {i}")))?;
+            sources.push(id);
+        }
+
+        let mut page_keys: HashMap<Id, Id> = HashMap::new();
+        for id in &sources {
+            if !page_keys.contains_key(id) {
+                let key = self.push(Slot::Page);
+                page_keys.insert(*id, key);
+            }
+        }
+
+        let mut copier = Copier::new(self, src, &page_keys);
+        let mut pages = Vec::new();
+        for id in &sources {
+            pages.push(copier.copy_page(*id));
+        }
+        copier.copy_outlines();
+
+        Ok(pages)
+    }
+
+    /// Copies the objects the object refers to, and returns it with the local
+    /// ids. References to pages are null.
+    pub fn import_object(&mut self, src: &LoadedDoc, object: &Object) -> Object {
+        let page_keys = HashMap::new();
+        Copier::new(self, src, &page_keys).copy(object)
+    }
+
+    fn push(&mut self, slot: Slot) -> Id {
+        self.slots.push(slot);
+        Id::new(self.slots.len() as u32, 0)
+    }
+
+    fn object(&self, id: Id) -> Option<&Object> {
+        match self.slots.get((id.number() as usize).wrapping_sub(1)) {
+            Some(Slot::Object(o)) => Some(o),
+            _ => None,
+        }
+    }
+
+    fn object_mut(&mut self, id: Id) -> Option<&mut Object> {
+        match self.slots.get_mut((id.number() as usize).wrapping_sub(1)) {
+            Some(Slot::Object(o)) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns the id of an identical object already copied, or copies it.
+    fn intern(&mut self, object: Object) -> Id {
+        let mut hasher = DefaultHasher::new();
+        object.to_bytes().hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if let Some(ids) = self.hashes.get(&hash)
+            && let Some(id) = ids.iter().find(|id| self.object(**id) == Some(&object))
+        {
+            return *id;
+        }
+        let id = self.push(Slot::Object(object));
+        self.hashes.entry(hash).or_default().push(id);
+        id
+    }
+
+    /// Appends the items to the top level of the outline.
+    fn append_outlines(&mut self, items: Vec<Id>) {
+        if items.is_empty() {
+            return;
+        }
+        let root = match self.outline_root {
+            Some(id) => id,
+            None => {
+                let id = self.push(Slot::OutlineRoot);
+                self.outline_root = Some(id);
+                id
+            }
+        };
+        if let (Some(last), Some(first)) = (self.outlines.last().copied(), items.first().copied()) {
+            self.set_entry(last, "Next", first);
+            self.set_entry(first, "Prev", last);
+        }
+        for item in &items {
+            self.set_entry(*item, "Parent", root);
+        }
+        self.outlines.extend(items);
+    }
+
+    fn set_entry(&mut self, id: Id, key: &str, value: impl Into<Object>) {
+        if let Some(dict) = self.object_mut(id).and_then(|o| o.as_dict_mut()) {
+            dict.set(key, value);
+        }
+    }
+
+    /// Returns the number of the visible items under the outline item.
+    fn outline_count(&self, id: Id) -> i64 {
+        self.object(id).and_then(|o| o.as_dict()).and_then(|d| d.get_i64("Count")).unwrap_or(0)
+    }
+
+    /// Assigns the ids of the objects reachable from the roots, the local ids
+    /// the imported pages refer to.
+    pub fn assign_ids(&mut self, id_factory: &mut IdFactory, roots: &[Id]) {
+        let mut reachable = vec![false; self.slots.len()];
+        let mut stack: Vec<Id> = roots.to_vec();
+        stack.extend(self.outline_root);
+        stack.extend(self.outlines.iter().copied());
+        while let Some(id) = stack.pop() {
+            let index = (id.number() as usize).wrapping_sub(1);
+            if index >= reachable.len() || reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+            if let Slot::Object(o) = &self.slots[index] {
+                stack.extend(o.references());
+            }
+        }
+
+        self.ids = self.slots.iter().zip(reachable)
+            .map(|(slot, reachable)| match slot {
+                Slot::Page => Id::new_0(),
+                _ if reachable => id_factory.next_id(),
+                _ => Id::new_0(),
+            })
+            .collect();
+    }
+
+    /// Returns the written id of the local id, valid after the ids are assigned.
+    pub fn id(&self, local: Id) -> Id {
+        self.ids.get((local.number() as usize).wrapping_sub(1)).copied().unwrap_or_else(Id::new_0)
+    }
+
+    /// Renumbers the reachable objects with the written ids and the ids of the
+    /// pages, `(placeholder, page id)` pairs. Pages no longer in the document
+    /// are referred to as null.
+    pub fn build(&mut self, pages: &[(Id, Id)]) {
+        for (key, page_id) in pages {
+            if let Some(id) = self.ids.get_mut((key.number() as usize).wrapping_sub(1)) {
+                *id = *page_id;
+            }
+        }
+
+        let mut written = Vec::new();
+        for (i, slot) in self.slots.iter().enumerate() {
+            let id = self.ids[i];
+            if id.number() == 0 {
+                continue;
+            }
+            let mut object = match slot {
+                Slot::Object(o) => o.clone(),
+                Slot::Page => continue,
+                Slot::OutlineRoot => Object::Dictionary(self.outline_root_dict()),
+            };
+            object.map_references(&mut |local| self.id(local));
+            written.push(ImportedObject { id, object });
+        }
+        self.written = written;
+    }
+
+    fn outline_root_dict(&self) -> Dictionary {
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::name("Outlines"));
+        if let (Some(first), Some(last)) = (self.outlines.first(), self.outlines.last()) {
+            dict.set("First", *first);
+            dict.set("Last", *last);
+        }
+        let count: i64 = self.outlines.iter().map(|id| 1 + self.outline_count(*id).max(0)).sum();
+        dict.set("Count", count);
+        dict
+    }
+
+    /// Returns the written id of the outline root if there are outline items.
+    pub fn outline_id(&self) -> Option<Id> {
+        self.outline_root.map(|id| self.id(id)).filter(|id| id.number() != 0)
+    }
+
+    pub fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        self.written.iter().map(|o| o as &dyn PdfObject).collect()
+    }
+}
+
+/// A copied object with the written id.
+#[derive(Clone)]
+struct ImportedObject {
+    id: Id,
+    object: Object,
+}
+
+impl PdfObject for ImportedObject {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, _id_factory: &mut IdFactory) {
+        // Assigned by ImportedObjects
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        vec![self]
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut bytes = format!("{} obj\n", self.id).into_bytes();
+        self.object.write(&mut bytes);
+        bytes.extend_from_slice(b"\nendobj");
+        bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Deep copies the objects of a loaded document into `ImportedObjects`.
+///
+/// The objects are visited in strongly connected components (Tarjan's
+/// algorithm) so that an object is copied after the objects it refers to,
+/// which makes the copies of objects outside reference cycles comparable.
+struct Copier<'a> {
+    pool: &'a mut ImportedObjects,
+    src: &'a LoadedDoc,
+    page_keys: &'a HashMap<Id, Id>,
+    /// Local ids by source id.
+    copied: HashMap<Id, Id>,
+    indexes: HashMap<Id, (usize, usize)>,
+    stack: Vec<Id>,
+    on_stack: HashSet<Id>,
+    /// Visited source objects with the named destinations resolved.
+    visited: HashMap<Id, Object>,
+}
+
+impl<'a> Copier<'a> {
+    fn new(pool: &'a mut ImportedObjects, src: &'a LoadedDoc, page_keys: &'a HashMap<Id, Id>) -> Self {
+        Self {
+            pool,
+            src,
+            page_keys,
+            copied: HashMap::new(),
+            indexes: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            visited: HashMap::new(),
+        }
+    }
+
+    /// Copies the object and the objects it refers to, and returns it with the local ids.
+    fn copy(&mut self, object: &Object) -> Object {
+        let mut object = object.clone();
+        resolve_named_destinations(self.src, &mut object, 0);
+        for id in object.references() {
+            if !self.is_leaf(id) && !self.indexes.contains_key(&id) {
+                self.visit(id);
+            }
+        }
+        object.map_references(&mut |id| self.local_id(id));
+        object
+    }
+
+    fn copy_page(&mut self, id: Id) -> Page {
+        let src = self.src;
+        let mut page = Page::new(src.page_media_box(id));
+        let rect = |key: &str| src.page_attribute(id, key).and_then(|r| r.as_rect());
+        // Boxes not nested as required are dropped
+        if let Some(r) = rect("CropBox") {
+            page.set_crop_box(r).ok();
+        }
+        if let Some(r) = rect("BleedBox") {
+            page.set_bleed_box(r).ok();
+        }
+        if let Some(r) = rect("TrimBox") {
+            page.set_trim_box(r).ok();
+        }
+        if let Some(r) = rect("ArtBox") {
+            page.set_art_box(r).ok();
+        }
+        let rotate = src.page_attribute(id, "Rotate").and_then(|r| r.as_i64());
+        if let Some(rotation) = rotate.and_then(|r| Rotation::from_degrees(r as i32)) {
+            page.set_rotation(rotation);
+        }
+        if let Some(user_unit) = src.page_attribute(id, "UserUnit").and_then(|u| u.as_f64()) {
+            page.set_user_unit(user_unit).ok();
+        }
+
+        let mut entries = Dictionary::new();
+        if let Some(dict) = src.get(id).and_then(|o| o.as_dict()) {
+            for (key, value) in dict.iter().filter(|(k, _)| !REBUILT_PAGE_ENTRIES.contains(&k.as_str())) {
+                entries.set(key.as_str(), value.clone());
+            }
+            // Arrays are merged with the page's own annotations and contents
+            for key in ["Annots", "Contents"] {
+                if let Some(value) = src.resolve_entry(dict, key).filter(|v| v.as_array().is_some()) {
+                    entries.set(key, value.clone());
+                }
+            }
+        }
+        // Kept as references to share the resources with the other pages
+        if !entries.contains_key("Resources")
+            && let Some(resources) = inherited_entry(src, id, "Resources")
+        {
+            entries.set("Resources", resources);
+        }
+
+        let entries = match self.copy(&Object::Dictionary(entries)) {
+            Object::Dictionary(d) => d,
+            _ => Dictionary::new(),
+        };
+        page.set_imported(ImportedPage {
+            key: self.page_keys[&id],
+            entries,
+            written: Dictionary::new(),
+        });
+        page
+    }
+
+    /// Copies the outline items pointing at the copied pages with their ancestors.
+    fn copy_outlines(&mut self) {
+        let src = self.src;
+        let first = src.catalog()
+            .and_then(|c| src.resolve_entry(c, "Outlines"))
+            .and_then(|o| o.as_dict())
+            .and_then(|o| o.get_reference("First"));
+        let items = self.copy_outline_level(first, &mut HashSet::new());
+        self.pool.append_outlines(items);
+    }
+
+    /// Copies the items from `first` and their descendants, and returns the
+    /// local ids of the copied items on the level.
+    fn copy_outline_level(&mut self, first: Option<Id>, visited: &mut HashSet<Id>) -> Vec<Id> {
+        let src = self.src;
+        let mut items: Vec<Id> = Vec::new();
+        let mut next = first;
+        while let Some(id) = next
+            && visited.insert(id)
+            && let Some(item) = src.get(id).and_then(|o| o.as_dict())
+        {
+            next = item.get_reference("Next");
+            let kids = self.copy_outline_level(item.get_reference("First"), visited);
+
+            let to_copied_page = target_page(src, item).is_some_and(|page| self.page_keys.contains_key(&page));
+            let other_action = src.resolve_entry(item, "A")
+                .and_then(|a| a.as_dict())
+                .is_some_and(|a| a.get_name("S") != Some("GoTo"));
+            if kids.is_empty() && !to_copied_page && !other_action {
+                continue;
+            }
+
+            let mut dict = Dictionary::new();
+            for key in ["Title", "C", "F"] {
+                if let Some(value) = src.resolve_entry(item, key) {
+                    dict.set(key, value.clone());
+                }
+            }
+            if to_copied_page || other_action {
+                let mut target = Dictionary::new();
+                for key in ["Dest", "A"] {
+                    if let Some(value) = item.get(key) {
+                        target.set(key, value.clone());
+                    }
+                }
+                if let Object::Dictionary(target) = self.copy(&Object::Dictionary(target)) {
+                    for (key, value) in target.iter() {
+                        dict.set(key.as_str(), value.clone());
+                    }
+                }
+            }
+            if let (Some(first), Some(last)) = (kids.first(), kids.last()) {
+                dict.set("First", *first);
+                dict.set("Last", *last);
+                let visible: i64 = kids.iter().map(|k| 1 + self.pool.outline_count(*k).max(0)).sum();
+                let is_closed = item.get_i64("Count").is_some_and(|c| c < 0);
+                dict.set("Count", if is_closed { -visible } else { visible });
+            }
+
+            let local = self.pool.push(Slot::Object(Object::Dictionary(dict)));
+            for kid in &kids {
+                self.pool.set_entry(*kid, "Parent", local);
+            }
+            if let Some(prev) = items.last() {
+                self.pool.set_entry(*prev, "Next", local);
+                self.pool.set_entry(local, "Prev", *prev);
+            }
+            items.push(local);
+        }
+        items
+    }
+
+    fn visit(&mut self, id: Id) {
+        let index = self.indexes.len();
+        self.indexes.insert(id, (index, index));
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        let mut object = self.src.get(id).cloned().unwrap_or(Object::Null);
+        resolve_named_destinations(self.src, &mut object, 0);
+        for r in object.references() {
+            if self.is_leaf(r) {
+                continue;
+            }
+            match self.indexes.get(&r) {
+                None => {
+                    self.visit(r);
+                    let low = self.indexes[&r].1;
+                    self.lower(id, low);
+                }
+                Some(&(r_index, _)) if self.on_stack.contains(&r) => self.lower(id, r_index),
+                Some(_) => {}
+            }
+        }
+        self.visited.insert(id, object);
+
+        let (index, low) = self.indexes[&id];
+        if index == low {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            self.copy_component(component);
+        }
+    }
+
+    fn lower(&mut self, id: Id, value: usize) {
+        if let Some(entry) = self.indexes.get_mut(&id) {
+            entry.1 = entry.1.min(value);
+        }
+    }
+
+    fn copy_component(&mut self, component: Vec<Id>) {
+        if let [id] = component[..] {
+            let mut object = self.visited.remove(&id).unwrap_or(Object::Null);
+            if !object.references().contains(&id) {
+                object.map_references(&mut |r| self.local_id(r));
+                let local = self.pool.intern(object);
+                self.copied.insert(id, local);
+                return;
+            }
+            self.visited.insert(id, object);
+        }
+
+        // Objects in a cycle are copied as they are
+        for id in &component {
+            let local = self.pool.push(Slot::Object(Object::Null));
+            self.copied.insert(*id, local);
+        }
+        for id in &component {
+            let mut object = self.visited.remove(id).unwrap_or(Object::Null);
+            object.map_references(&mut |r| self.local_id(r));
+            if let Some(slot) = self.pool.object_mut(self.copied[id]) {
+                *slot = object;
+            }
+        }
+    }
+
+    /// Returns true if the object is not copied: pages and page tree nodes.
+    fn is_leaf(&self, id: Id) -> bool {
+        self.copied.contains_key(&id)
+            || self.page_keys.contains_key(&id)
+            || self.src.get(id)
+                .and_then(|o| o.as_dict())
+                .is_some_and(|d| matches!(d.get_name("Type"), Some("Page") | Some("Pages")))
+    }
+
+    /// Returns the local id of the source id. Pages not copied are null.
+    fn local_id(&self, id: Id) -> Id {
+        self.page_keys.get(&id)
+            .or_else(|| self.copied.get(&id))
+            .copied()
+            .unwrap_or_else(Id::new_0)
+    }
+}
+
+/// Returns the inheritable page entry without following the references.
+fn inherited_entry(src: &LoadedDoc, page_id: Id, key: &str) -> Option<Object> {
+    debug_assert!(INHERITABLE_PAGE_ATTRIBUTES.contains(&key));
+    let mut id = page_id;
+    for _ in 0..64 {
+        let dict = src.get(id)?.as_dict()?;
+        if let Some(v) = dict.get(key) {
+            return Some(v.clone());
+        }
+        id = dict.get_reference("Parent")?;
+    }
+    None
+}
+
+/// Replaces the named destinations (PDF32000-1:2008 12.3.2.3) with the
+/// explicit ones, as the names are not copied.
+fn resolve_named_destinations(src: &LoadedDoc, object: &mut Object, depth: usize) {
+    if depth > 32 {
+        return;
+    }
+    match object {
+        Object::Array(a) => a.iter_mut().for_each(|o| resolve_named_destinations(src, o, depth + 1)),
+        Object::Dictionary(d) => {
+            let is_goto = d.get_name("S") == Some("GoTo");
+            for (key, value) in d.iter_mut() {
+                let is_dest = key.as_str() == "Dest" || (is_goto && key.as_str() == "D");
+                if is_dest && matches!(value, Object::Name(_) | Object::String(_)) {
+                    *value = named_destination(src, value).unwrap_or(Object::Null);
+                } else {
+                    resolve_named_destinations(src, value, depth + 1);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Looks up the explicit destination of the name in `/Dests` of the catalog
+/// or the string in the `/Dests` name tree.
+fn named_destination(src: &LoadedDoc, name: &Object) -> Option<Object> {
+    let catalog = src.catalog()?;
+    let found = match name {
+        Object::Name(n) => src.resolve_entry(catalog, "Dests")?.as_dict()
+            .and_then(|d| src.resolve_entry(d, n.as_str())),
+        Object::String(s) => {
+            let names = src.resolve_entry(catalog, "Names")?.as_dict()?;
+            find_in_name_tree(src, src.resolve_entry(names, "Dests")?, s, 0)
+        }
+        _ => None,
+    }?;
+    let dest = match found {
+        Object::Dictionary(d) => src.resolve_entry(d, "D")?,
+        o => o,
+    };
+    dest.as_array().map(|_| dest.clone())
+}
+
+fn find_in_name_tree<'a>(src: &'a LoadedDoc, node: &'a Object, key: &[u8], depth: usize) -> Option<&'a Object> {
+    if depth > 32 {
+        return None;
+    }
+    let node = node.as_dict()?;
+    if let Some(names) = src.resolve_entry(node, "Names").and_then(|n| n.as_array()) {
+        for pair in names.chunks(2) {
+            if let [k, v] = pair && k.as_bytes() == Some(key) {
+                return Some(src.resolve(v));
+            }
+        }
+    }
+    src.resolve_entry(node, "Kids")?.as_array()?.iter()
+        .find_map(|kid| find_in_name_tree(src, src.resolve(kid), key, depth + 1))
+}
+
+/// Returns the page an outline item or a link annotation goes to with
+/// `/Dest` or a GoTo action.
+pub(crate) fn target_page(src: &LoadedDoc, item: &Dictionary) -> Option<Id> {
+    let dest = match src.resolve_entry(item, "A").and_then(|a| a.as_dict()) {
+        Some(a) if a.get_name("S") == Some("GoTo") => src.resolve_entry(a, "D"),
+        Some(_) => None,
+        None => src.resolve_entry(item, "Dest"),
+    };
+    destination_page(src, dest?)
+}
+
+/// Returns the page the explicit or named destination points at.
+fn destination_page(src: &LoadedDoc, dest: &Object) -> Option<Id> {
+    let dest = match dest {
+        Object::Name(_) | Object::String(_) => named_destination(src, dest)?,
+        o => o.clone(),
+    };
+    dest.as_array()?.first()?.as_reference()
+}
+
+fn object_string(object: &Object) -> String {
+    // Strings are written with escapes or in hex, so the bytes are ASCII
+    String::from_utf8_lossy(&object.to_bytes()).into_owned()
+}
@@ -0,0 +1,315 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::base::*;
+use super::colour::DeviceColour;
+use super::contents::Contents;
+use super::doc::Doc;
+use super::error::Error;
+use super::loaded_doc::LoadedDoc;
+use super::marks::{draw_corner_marks, draw_target};
+use super::page::Page;
+use super::xobject::PageForm;
+
+/// Length of the crop marks.
+const MARK_LENGTH: f64 = 12.0;
+/// Distance of the crop marks from the page corners.
+const MARK_OFFSET: f64 = 3.0;
+/// Diameter of the registration targets.
+const TARGET_SIZE: f64 = 12.0;
+
+/// How the pages are arranged on the sheets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// The pages in reading order, `columns` × `rows` per sheet.
+    NUp { columns: usize, rows: usize },
+    /// Saddle-stitch booklet: two pages side by side on each side of the
+    /// sheets, printed duplex, nested and folded. Blank pages are added up
+    /// to a multiple of 4. `creep` shifts the pages towards the spine per
+    /// sheet from the outermost one, usually the paper thickness.
+    Booklet { creep: f64 },
+    /// The pages ordered so that the stacks cut from the sheets are in order,
+    /// i.e. the first cells of all the sheets hold the first pages.
+    CutAndStack { columns: usize, rows: usize },
+    /// Every page repeated `columns` × `rows` times on its own sheet, e.g. labels.
+    StepAndRepeat { columns: usize, rows: usize },
+}
+
+impl Layout {
+    fn grid(&self) -> (usize, usize) {
+        match *self {
+            Self::NUp { columns, rows } => (columns, rows),
+            Self::Booklet { .. } => (2, 1),
+            Self::CutAndStack { columns, rows } => (columns, rows),
+            Self::StepAndRepeat { columns, rows } => (columns, rows),
+        }
+    }
+
+    /// Returns the source page index of every cell of every sheet side.
+    fn sheets(&self, page_count: usize) -> Vec<Vec<Option<usize>>> {
+        let (columns, rows) = self.grid();
+        let cells = columns * rows;
+        let page = |i: usize| (i < page_count).then_some(i);
+        match *self {
+            Self::NUp { .. } => (0..page_count.div_ceil(cells))
+                .map(|s| (0..cells).map(|c| page(s * cells + c)).collect())
+                .collect(),
+            Self::Booklet { .. } => {
+                let n = page_count.div_ceil(4) * 4;
+                (0..n / 4)
+                    .flat_map(|i| [
+                        vec![page(n - 1 - 2 * i), page(2 * i)],
+                        vec![page(2 * i + 1), page(n - 2 - 2 * i)],
+                    ])
+                    .collect()
+            }
+            Self::CutAndStack { .. } => {
+                let sheets = page_count.div_ceil(cells);
+                (0..sheets)
+                    .map(|s| (0..cells).map(|c| page(c * sheets + s)).collect())
+                    .collect()
+            }
+            Self::StepAndRepeat { .. } => (0..page_count)
+                .map(|p| vec![Some(p); cells])
+                .collect(),
+        }
+    }
+}
+
+/// Imposes the pages of a loaded document on sheets.
+///
+/// The pages are drawn as `PageForm`s centred in the cells of a grid,
+/// shrunk to fit but never enlarged. The cells fill the sheet inside the
+/// margin with the gutters between them.
+///
+/// ```no_run
+/// use prspdf::{LoadedDoc, PaperSize};
+/// use prspdf::impose::{Imposition, Layout};
+///
+/// let src = LoadedDoc::open("a5-booklet.pdf").unwrap();
+/// let mut imposition = Imposition::new(Layout::Booklet { creep: 0.1 }, PaperSize::A4.landscape());
+/// imposition.set_crop_marks(true);
+/// imposition.impose(&src).unwrap().write_to_file(String::from("sheets.pdf"));
+/// ```
+pub struct Imposition {
+    layout: Layout,
+    sheet: Rectangle,
+    margin: f64,
+    gutter: (f64, f64),
+    crop_marks: bool,
+    registration_marks: bool,
+}
+
+impl Imposition {
+    pub fn new(layout: Layout, sheet: impl Into<Rectangle>) -> Self {
+        Self {
+            layout,
+            sheet: sheet.into(),
+            margin: 0.0,
+            gutter: (0.0, 0.0),
+            crop_marks: false,
+            registration_marks: false,
+        }
+    }
+
+    /// Sets the space between the sheet edges and the cells.
+    pub fn set_margin(&mut self, margin: f64) {
+        self.margin = margin;
+    }
+
+    /// Sets the space between the columns and between the rows.
+    pub fn set_gutter(&mut self, horizontal: f64, vertical: f64) {
+        self.gutter = (horizontal, vertical);
+    }
+
+    /// Draws crop marks at the corners of every page.
+    pub fn set_crop_marks(&mut self, crop_marks: bool) {
+        self.crop_marks = crop_marks;
+    }
+
+    /// Draws registration targets at the middle of the sheet edges, in the margin.
+    pub fn set_registration_marks(&mut self, registration_marks: bool) {
+        self.registration_marks = registration_marks;
+    }
+
+    /// Returns the cells in reading order.
+    pub fn cells(&self) -> Vec<Rectangle> {
+        let (columns, rows) = self.layout.grid();
+        let (gh, gv) = self.gutter;
+        let width = (self.sheet.width() - 2.0 * self.margin - (columns as f64 - 1.0) * gh) / columns as f64;
+        let height = (self.sheet.height() - 2.0 * self.margin - (rows as f64 - 1.0) * gv) / rows as f64;
+
+        let mut cells = Vec::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let x = self.sheet.llx + self.margin + column as f64 * (width + gh);
+                let y = self.sheet.ury - self.margin - (row + 1) as f64 * height - row as f64 * gv;
+                cells.push(Rectangle::new(x, y, x + width, y + height));
+            }
+        }
+        cells
+    }
+
+    /// Creates the document with the sheets.
+    pub fn impose(&self, src: &LoadedDoc) -> Result<Doc, Error> {
+        let (columns, rows) = self.layout.grid();
+        if columns == 0 || rows == 0 {
+            return Err(Error::InvalidValue(format!("{columns} x {rows} grid")));
+        }
+
+        let page_count = src.page_ids().len();
+        let forms: Vec<PageForm> = (0..page_count)
+            .map(|i| PageForm::new(src, i))
+            .collect::<Result<_, _>>()?;
+        let cells = self.cells();
+
+        let mut doc = Doc::new(Version::V1_7);
+        for (side, sheet) in self.layout.sheets(page_count).iter().enumerate() {
+            let mut page = Page::new(self.sheet);
+            let mut placed = Vec::new();
+            let mut placed_sources = Vec::new();
+            for (column, (cell, source)) in cells.iter().zip(sheet).enumerate() {
+                let Some(source) = *source else {
+                    continue;
+                };
+                let form = &forms[source];
+                let (matrix, rect) = match self.layout {
+                    // Pages abut at the spine, shifted towards it on the inner sheets
+                    Layout::Booklet { creep } => {
+                        let shift = (side / 2) as f64 * creep;
+                        if column == 0 {
+                            place(form, cell, 1.0, shift)
+                        } else {
+                            place(form, cell, 0.0, -shift)
+                        }
+                    }
+                    _ => place(form, cell, 0.5, 0.0),
+                };
+
+                let name = format!("P{source}");
+                if !placed_sources.contains(&source) {
+                    page.resources().add_xobject(&name, form.clone());
+                    placed_sources.push(source);
+                }
+                let contents = page.contents();
+                contents.save_state();
+                contents.concat_matrix(matrix);
+                contents.draw_xobject(&name);
+                contents.restore_state();
+                placed.push(rect);
+            }
+
+            if self.crop_marks || self.registration_marks {
+                let contents = page.contents();
+                contents.save_state();
+                contents.set_stroke_device_color(&DeviceColour::Cmyk(1.0, 1.0, 1.0, 1.0));
+                contents.set_line_width(0.25);
+                if self.crop_marks {
+                    for rect in &placed {
+                        draw_corner_marks(contents, rect, rect, MARK_OFFSET, MARK_LENGTH);
+                    }
+                }
+                if self.registration_marks {
+                    self.draw_registration_marks(contents);
+                }
+                contents.restore_state();
+            }
+            doc.push_page(page);
+        }
+        Ok(doc)
+    }
+
+    fn draw_registration_marks(&self, contents: &mut Contents) {
+        let s = &self.sheet;
+        let d = (self.margin / 2.0).max(TARGET_SIZE / 2.0);
+        let cx = (s.llx + s.urx) / 2.0;
+        let cy = (s.lly + s.ury) / 2.0;
+        for (x, y) in [(cx, s.ury - d), (cx, s.lly + d), (s.llx + d, cy), (s.urx - d, cy)] {
+            draw_target(contents, x, y, TARGET_SIZE);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Returns the matrix to draw the form in the cell and the area it covers.
+/// `align` is the horizontal position of the page in the cell from 0 (left)
+/// to 1 (right).
+fn place(form: &PageForm, cell: &Rectangle, align: f64, shift: f64) -> ([f64; 6], Rectangle) {
+    let bbox = form.placed_bbox();
+    let scale = (cell.width() / bbox.width()).min(cell.height() / bbox.height()).min(1.0);
+    let width = bbox.width() * scale;
+    let height = bbox.height() * scale;
+    let x = cell.llx + (cell.width() - width) * align + shift;
+    let y = cell.lly + (cell.height() - height) / 2.0;
+    let matrix = [scale, 0.0, 0.0, scale, x - bbox.llx * scale, y - bbox.lly * scale];
+    (matrix, Rectangle::new(x, y, x + width, y + height))
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(sheets: &[Vec<Option<usize>>]) -> Vec<Vec<i64>> {
+        sheets.iter()
+            .map(|s| s.iter().map(|p| p.map_or(-1, |p| p as i64)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn sheets() {
+        let n_up = Layout::NUp { columns: 2, rows: 1 };
+        assert_eq!(pages(&n_up.sheets(3)), [vec![0, 1], vec![2, -1]]);
+
+        let booklet = Layout::Booklet { creep: 0.0 };
+        assert_eq!(pages(&booklet.sheets(6)), [vec![-1, 0], vec![1, -1], vec![5, 2], vec![3, 4]]);
+
+        let cut_and_stack = Layout::CutAndStack { columns: 2, rows: 1 };
+        assert_eq!(pages(&cut_and_stack.sheets(5)), [vec![0, 3], vec![1, 4], vec![2, -1]]);
+
+        let repeat = Layout::StepAndRepeat { columns: 1, rows: 2 };
+        assert_eq!(pages(&repeat.sheets(2)), [vec![0, 0], vec![1, 1]]);
+    }
+
+    #[test]
+    fn cells() {
+        let mut imposition = Imposition::new(Layout::NUp { columns: 2, rows: 2 }, Rectangle::new(0.0, 0.0, 220.0, 120.0));
+        imposition.set_margin(10.0);
+        imposition.set_gutter(20.0, 0.0);
+        let cells = imposition.cells();
+        assert_eq!(cells[0], Rectangle::new(10.0, 60.0, 100.0, 110.0));
+        assert_eq!(cells[3], Rectangle::new(120.0, 10.0, 210.0, 60.0));
+    }
+
+    #[test]
+    fn impose() {
+        let mut doc = Doc::new(Version::V1_7);
+        for _ in 0..6 {
+            doc.push_page(Page::new(Rectangle::new(0.0, 0.0, 100.0, 200.0)));
+        }
+        let src = LoadedDoc::load(doc.to_bytes()).unwrap();
+
+        let mut imposition = Imposition::new(Layout::Booklet { creep: 1.0 }, Rectangle::new(0.0, 0.0, 240.0, 240.0));
+        imposition.set_crop_marks(true);
+        imposition.set_registration_marks(true);
+        let sheets = LoadedDoc::load(imposition.impose(&src).unwrap().to_bytes()).unwrap();
+        let ids = sheets.page_ids();
+        assert_eq!(ids.len(), 4);
+
+        // The second sheet is shifted towards the spine
+        let content = String::from_utf8(sheets.page_content(ids[2]).unwrap()).unwrap();
+        assert!(content.contains("1 0 0 1 21 20 cm\n/P5 Do"));
+        assert!(content.contains("1 0 0 1 119 20 cm\n/P2 Do"));
+        assert!(content.contains("\nS\n"));
+    }
+}
@@ -0,0 +1,580 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use super::base::*;
+use super::error::Error;
+use super::object::*;
+use super::operation::{self, Operation};
+use super::parser::*;
+
+/// Page attributes inherited from the ancestor `/Pages` nodes (PDF32000-1:2008 7.7.3.4).
+pub const INHERITABLE_PAGE_ATTRIBUTES: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+
+/// An existing PDF document loaded into memory.
+///
+/// All objects are read when loading. Changed and added objects are tracked,
+/// so the document can be saved either as an incremental update appended to
+/// the original bytes or as a full rewrite.
+///
+/// ```no_run
+/// let mut doc = prspdf::LoadedDoc::open("form.pdf").unwrap();
+/// println!("{} pages", doc.page_ids().len());
+/// doc.write_incremental_to_file("form-updated.pdf").unwrap();
+/// ```
+pub struct LoadedDoc {
+    version: String,
+    original: Vec<u8>,
+    startxref: usize,
+    xref_is_stream: bool,
+    objects: BTreeMap<Id, Object>,
+    trailer: Dictionary,
+    modified: BTreeSet<Id>,
+    removed: BTreeSet<Id>,
+    next_number: u32,
+}
+
+impl LoadedDoc {
+    /// Loads a PDF from the bytes. The cross-reference is rebuilt by scanning
+    /// the file if it is missing or broken. Encrypted files are not supported.
+    pub fn load(data: Vec<u8>) -> Result<Self, Error> {
+        let Some(header) = find(&data[..data.len().min(1024)], b"%PDF-") else {
+            return Err(Error::parse(0, "PDF header not found"));
+        };
+        let version: String = data[header + 5..].iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b'.')
+            .map(|b| *b as char)
+            .collect();
+
+        let (info, objects) = match read_xref(&data).and_then(|info| {
+            let objects = load_objects(&data, &info)?;
+            Ok((info, objects))
+        }) {
+            Ok((info, objects)) if info.trailer.contains_key("Root") => (info, objects),
+            _ => {
+                let info = reconstruct_xref(&data);
+                let objects = load_objects(&data, &info)?;
+                (info, objects)
+            }
+        };
+
+        if info.trailer.contains_key("Encrypt") {
+            return Err(Error::Unsupported(String::from("encrypted PDF")));
+        }
+        if info.trailer.get_reference("Root").is_none() {
+            return Err(Error::NotFound(String::from("document catalog")));
+        }
+
+        let size = info.trailer.get_i64("Size").unwrap_or(0) as u32;
+        let max = objects.keys().map(|id| id.number()).max().unwrap_or(0);
+
+        Ok(Self {
+            version,
+            original: data,
+            startxref: info.startxref,
+            xref_is_stream: info.is_stream,
+            objects,
+            trailer: info.trailer,
+            modified: BTreeSet::new(),
+            removed: BTreeSet::new(),
+            next_number: size.max(max + 1),
+        })
+    }
+
+    /// Loads a PDF file.
+    pub fn open(path: &str) -> Result<Self, Error> {
+        Self::load(fs::read(path)?)
+    }
+
+    /// Returns the version in the header, e.g. "1.7".
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn trailer(&self) -> &Dictionary {
+        &self.trailer
+    }
+
+    /// Returns the trailer for modification. `/Root`, `/Info` and `/ID` are
+    /// written when saving.
+    pub fn trailer_mut(&mut self) -> &mut Dictionary {
+        &mut self.trailer
+    }
+
+    pub fn catalog_id(&self) -> Id {
+        // Checked when loading
+        self.trailer.get_reference("Root").unwrap()
+    }
+
+    pub fn catalog(&self) -> Option<&Dictionary> {
+        self.get(self.catalog_id()).and_then(|o| o.as_dict())
+    }
+
+    /// Returns the ids of all objects.
+    pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.objects.keys().copied()
+    }
+
+    pub fn get(&self, id: Id) -> Option<&Object> {
+        self.objects.get(&id)
+    }
+
+    /// Returns the object for modification. The object is written by the next save.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut Object> {
+        let object = self.objects.get_mut(&id)?;
+        self.modified.insert(id);
+        Some(object)
+    }
+
+    /// Replaces or adds the object with the id.
+    pub fn set(&mut self, id: Id, object: impl Into<Object>) {
+        self.objects.insert(id, object.into());
+        self.removed.remove(&id);
+        self.modified.insert(id);
+        self.next_number = self.next_number.max(id.number() + 1);
+    }
+
+    /// Adds a new object and returns its id.
+    pub fn add_object(&mut self, object: impl Into<Object>) -> Id {
+        let id = Id::new(self.next_number, 0);
+        self.set(id, object);
+        id
+    }
+
+    /// Removes the object. References to it are read as null.
+    pub fn remove_object(&mut self, id: Id) -> Option<Object> {
+        let object = self.objects.remove(&id)?;
+        self.modified.remove(&id);
+        self.removed.insert(id);
+        Some(object)
+    }
+
+    /// Removes the objects not reachable from the trailer, e.g. replaced
+    /// content streams, and returns how many were removed.
+    pub fn remove_unreferenced(&mut self) -> usize {
+        let mut reachable = BTreeSet::new();
+        let mut pending: Vec<Id> = ["Root", "Info"].iter()
+            .filter_map(|key| self.trailer.get_reference(key))
+            .collect();
+        while let Some(id) = pending.pop() {
+            if reachable.insert(id) && let Some(object) = self.objects.get(&id) {
+                pending.extend(object.references());
+            }
+        }
+
+        let unreferenced: Vec<Id> = self.objects.keys().filter(|id| !reachable.contains(id)).copied().collect();
+        for id in &unreferenced {
+            self.remove_object(*id);
+        }
+        unreferenced.len()
+    }
+
+    /// Follows references until a direct object.
+    pub fn resolve<'a>(&'a self, object: &'a Object) -> &'a Object {
+        let mut object = object;
+        // Bounded to survive reference cycles
+        for _ in 0..32 {
+            match object {
+                Object::Reference(id) => match self.objects.get(id) {
+                    Some(o) => object = o,
+                    None => return &Object::Null,
+                },
+                _ => return object,
+            }
+        }
+        &Object::Null
+    }
+
+    /// Returns the entry of the dictionary with references followed.
+    pub fn resolve_entry<'a>(&'a self, dict: &'a Dictionary, key: &str) -> Option<&'a Object> {
+        match self.resolve(dict.get(key)?) {
+            Object::Null => None,
+            o => Some(o),
+        }
+    }
+
+    /// Returns the ids of the pages in order.
+    pub fn page_ids(&self) -> Vec<Id> {
+        let mut ids = Vec::new();
+        if let Some(root) = self.catalog().and_then(|c| c.get_reference("Pages")) {
+            self.collect_pages(root, &mut ids, &mut BTreeSet::new());
+        }
+        ids
+    }
+
+    fn collect_pages(&self, id: Id, ids: &mut Vec<Id>, visited: &mut BTreeSet<Id>) {
+        if !visited.insert(id) {
+            return;
+        }
+        let Some(dict) = self.get(id).and_then(|o| o.as_dict()) else {
+            return;
+        };
+
+        match self.resolve_entry(dict, "Kids").and_then(|k| k.as_array()) {
+            Some(kids) if dict.get_name("Type") != Some("Page") => {
+                for kid in kids.iter().filter_map(|k| k.as_reference()) {
+                    self.collect_pages(kid, ids, visited);
+                }
+            }
+            _ => ids.push(id),
+        }
+    }
+
+    /// Returns the page attribute, looking up the page tree for inheritable ones.
+    pub fn page_attribute(&self, page_id: Id, key: &str) -> Option<&Object> {
+        let mut id = page_id;
+        for _ in 0..64 {
+            let dict = self.get(id)?.as_dict()?;
+            if let Some(v) = self.resolve_entry(dict, key) {
+                return Some(v);
+            }
+            if !INHERITABLE_PAGE_ATTRIBUTES.contains(&key) {
+                return None;
+            }
+            id = dict.get_reference("Parent")?;
+        }
+        None
+    }
+
+    /// Returns the MediaBox of the page, Letter if missing.
+    pub fn page_media_box(&self, page_id: Id) -> Rectangle {
+        self.page_attribute(page_id, "MediaBox")
+            .and_then(|r| r.as_rect())
+            .unwrap_or(Rectangle::new(0.0, 0.0, 612.0, 792.0))
+    }
+
+    /// Returns the decoded and concatenated content streams of the page.
+    pub fn page_content(&self, page_id: Id) -> Result<Vec<u8>, Error> {
+        let mut content = Vec::new();
+        let contents = self.page_attribute(page_id, "Contents");
+        let streams: Vec<&Object> = match contents {
+            Some(Object::Array(a)) => a.iter().map(|o| self.resolve(o)).collect(),
+            Some(o) => vec![o],
+            None => Vec::new(),
+        };
+        for stream in streams.iter().filter_map(|s| s.as_stream()) {
+            content.extend_from_slice(&stream.decoded_data()?);
+            content.push(b'\n');
+        }
+        Ok(content)
+    }
+
+    /// Returns the operations of the page content.
+    pub fn page_operations(&self, page_id: Id) -> Result<Vec<Operation>, Error> {
+        operation::parse(&self.page_content(page_id)?)
+    }
+
+    /// Replaces the page content with the operations, written as a single
+    /// stream. The old content streams are left for the caller to remove.
+    pub fn set_page_operations(&mut self, page_id: Id, operations: &[Operation]) -> Result<(), Error> {
+        if self.get(page_id).and_then(|o| o.as_dict()).is_none() {
+            return Err(Error::NotFound(format!("page {page_id}")));
+        }
+        let stream = Stream::from_decoded(Dictionary::new(), operation::to_bytes(operations));
+        let contents = self.add_object(stream);
+        if let Some(page) = self.get_mut(page_id).and_then(|o| o.as_dict_mut()) {
+            page.set("Contents", Object::Reference(contents));
+        }
+        Ok(())
+    }
+
+    /// Writes the document as a new file with every object.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = format!("%PDF-{}\n%", self.version).into_bytes();
+        bytes.extend_from_slice(&[0x80, 0x80, 0x80, 0x80]);
+
+        let mut offsets: BTreeMap<u32, (usize, u32)> = BTreeMap::new();
+        for (id, object) in &self.objects {
+            bytes.push(b'\n');
+            offsets.insert(id.number(), (bytes.len(), id.generation()));
+            write_object(*id, object, &mut bytes);
+        }
+
+        bytes.push(b'\n');
+        let size = offsets.keys().max().map_or(1, |n| n + 1);
+        let xref_offset = bytes.len();
+        bytes.extend_from_slice(format!("xref\n0 {}\n", size).as_bytes());
+        for number in 0..size {
+            let entry = match offsets.get(&number) {
+                Some((offset, generation)) => format!("{:0>10} {:0>5} n \n", offset, generation),
+                None if number == 0 => String::from("0000000000 65535 f \n"),
+                None => String::from("0000000000 00001 f \n"),
+            };
+            bytes.extend_from_slice(entry.as_bytes());
+        }
+
+        let mut trailer = self.trailer_for_save(size);
+        trailer.remove("Prev");
+        self.write_trailer(trailer, xref_offset, &mut bytes);
+        bytes
+    }
+
+    /// Writes the original bytes followed by an incremental update with the
+    /// changed, added and removed objects (PDF32000-1:2008 7.5.6).
+    pub fn to_incremental_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.original.clone();
+        if !bytes.ends_with(b"\n") {
+            bytes.push(b'\n');
+        }
+
+        let mut entries: BTreeMap<u32, (usize, u32, bool)> = BTreeMap::new();
+        for id in &self.modified {
+            let offset = bytes.len();
+            write_object(*id, &self.objects[id], &mut bytes);
+            bytes.push(b'\n');
+            entries.insert(id.number(), (offset, id.generation(), true));
+        }
+        for id in &self.removed {
+            entries.insert(id.number(), (0, id.generation() + 1, false));
+        }
+
+        let size = self.next_number.max(self.trailer.get_i64("Size").unwrap_or(0) as u32);
+        let mut trailer = self.trailer_for_save(size);
+        trailer.set("Prev", self.startxref as i64);
+
+        let xref_offset = bytes.len();
+        if self.xref_is_stream {
+            // An xref stream must be continued with an xref stream
+            let xref_id = Id::new(size, 0);
+            entries.insert(size, (xref_offset, 0, true));
+            trailer.set("Size", size as i64 + 1);
+            trailer.set("Type", Object::name("XRef"));
+            trailer.set("W", vec![Object::Integer(1), Object::Integer(4), Object::Integer(2)]);
+            trailer.set("Index", subsections(&entries).iter()
+                .flat_map(|(first, count)| [Object::Integer(*first as i64), Object::Integer(*count as i64)])
+                .collect::<Vec<_>>());
+            let mut data = Vec::new();
+            for (offset, generation, in_use) in entries.values() {
+                data.push(if *in_use { 1 } else { 0 });
+                data.extend_from_slice(&(*offset as u32).to_be_bytes());
+                data.extend_from_slice(&(*generation as u16).to_be_bytes());
+            }
+            write_object(xref_id, &Object::Stream(Stream::new(trailer, data)), &mut bytes);
+            bytes.extend_from_slice(format!("\nstartxref\n{}\n%%EOF\n", xref_offset).as_bytes());
+            return bytes;
+        }
+
+        bytes.extend_from_slice(b"xref\n");
+        for (first, count) in subsections(&entries) {
+            bytes.extend_from_slice(format!("{} {}\n", first, count).as_bytes());
+            for number in first..first + count {
+                let (offset, generation, in_use) = entries[&number];
+                let kind = if in_use { 'n' } else { 'f' };
+                bytes.extend_from_slice(format!("{:0>10} {:0>5} {} \n", offset, generation, kind).as_bytes());
+            }
+        }
+        self.write_trailer(trailer, xref_offset, &mut bytes);
+        bytes
+    }
+
+    fn trailer_for_save(&self, size: u32) -> Dictionary {
+        let mut trailer = Dictionary::new();
+        trailer.set("Size", size as i64);
+        for key in ["Root", "Info", "ID"] {
+            if let Some(v) = self.trailer.get(key) {
+                trailer.set(key, v.clone());
+            }
+        }
+        trailer
+    }
+
+    fn write_trailer(&self, trailer: Dictionary, xref_offset: usize, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(b"trailer\n");
+        bytes.extend_from_slice(&Object::Dictionary(trailer).to_bytes());
+        bytes.extend_from_slice(format!("\nstartxref\n{}\n%%EOF\n", xref_offset).as_bytes());
+    }
+
+    /// Write out the full rewrite to the specified path.
+    pub fn write_to_file(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write out the incremental update to the specified path.
+    pub fn write_incremental_to_file(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, self.to_incremental_bytes())?;
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn load_objects(data: &[u8], info: &XrefInfo) -> Result<BTreeMap<Id, Object>, Error> {
+    let mut objects = BTreeMap::new();
+    let mut object_streams: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+
+    for (number, entry) in &info.entries {
+        match entry {
+            XrefEntry::Offset(offset, _) => {
+                let mut p = Parser::new(data, *offset);
+                match p.parse_indirect_object() {
+                    Ok((id, object)) if id.number() == *number => {
+                        objects.insert(id, object);
+                    }
+                    // Wrong offsets are fixed by reconstructing the xref
+                    _ => return Err(Error::parse(*offset, &format!("object {} not found", number))),
+                }
+            }
+            XrefEntry::Compressed(stream, _) => {
+                object_streams.entry(*stream).or_default().push(*number);
+            }
+        }
+    }
+
+    for (stream_number, numbers) in object_streams {
+        let Some(stream) = objects.get(&Id::new(stream_number, 0)).and_then(|o| o.as_stream()) else {
+            continue;
+        };
+        for (number, object) in parse_object_stream(stream)? {
+            if numbers.contains(&number) {
+                objects.entry(Id::new(number, 0)).or_insert(object);
+            }
+        }
+        // The objects are written individually when saving
+        objects.remove(&Id::new(stream_number, 0));
+    }
+
+    // Cross-reference streams are regenerated when saving
+    objects.retain(|_, o| !matches!(o, Object::Stream(s) if s.dict.get_name("Type") == Some("XRef")));
+
+    Ok(objects)
+}
+
+fn write_object(id: Id, object: &Object, bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(format!("{} obj\n", id).as_bytes());
+    object.write(bytes);
+    bytes.extend_from_slice(b"\nendobj");
+}
+
+/// Groups the consecutive object numbers.
+fn subsections<T>(entries: &BTreeMap<u32, T>) -> Vec<(u32, u32)> {
+    let mut sections: Vec<(u32, u32)> = Vec::new();
+    for number in entries.keys() {
+        match sections.last_mut() {
+            Some((first, count)) if *first + *count == *number => *count += 1,
+            _ => sections.push((*number, 1)),
+        }
+    }
+    sections
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::page::Page;
+
+    fn generated() -> Vec<u8> {
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(Page::new(MediaBox::A4));
+        doc.push_page(Page::new(MediaBox::Letter));
+        doc.to_bytes()
+    }
+
+    #[test]
+    fn load() {
+        let doc = LoadedDoc::load(generated()).unwrap();
+        assert_eq!(doc.version(), "1.7");
+        let pages = doc.page_ids();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(doc.page_media_box(pages[0]), Rectangle::new(0.0, 0.0, 595.0, 842.0));
+    }
+
+    #[test]
+    fn reconstruct() {
+        let mut bytes = generated();
+        let pos = find(&bytes, b"startxref").unwrap();
+        bytes.truncate(pos);
+        bytes.extend_from_slice(b"startxref\n5\n%%EOF\n");
+
+        let doc = LoadedDoc::load(bytes).unwrap();
+        assert_eq!(doc.page_ids().len(), 2);
+    }
+
+    #[test]
+    fn save() {
+        let mut doc = LoadedDoc::load(generated()).unwrap();
+        let page = doc.page_ids()[1];
+        doc.get_mut(page).unwrap().as_dict_mut().unwrap().set("Rotate", 90i64);
+        let added = doc.add_object(Object::text("extra"));
+
+        let incremental = doc.to_incremental_bytes();
+        assert!(incremental.starts_with(&generated()));
+        let reloaded = LoadedDoc::load(incremental).unwrap();
+        assert_eq!(reloaded.page_attribute(page, "Rotate"), Some(&Object::Integer(90)));
+        assert_eq!(reloaded.get(added).unwrap().as_text().unwrap(), "extra");
+
+        let reloaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        assert_eq!(reloaded.page_ids().len(), 2);
+        assert_eq!(reloaded.page_attribute(page, "Rotate"), Some(&Object::Integer(90)));
+    }
+
+    #[test]
+    fn xref_stream() {
+        // Objects in an object stream with an xref stream
+        let objstm = b"1 0 2 34 << /Type /Catalog /Pages 2 0 R >> << /Type /Pages /Kids [] /Count 0 >>";
+        let mut bytes = b"%PDF-1.5\n".to_vec();
+        let objstm_offset = bytes.len();
+        bytes.extend_from_slice(format!(
+            "3 0 obj\n<< /Type /ObjStm /N 2 /First 9 /Length {} >>\nstream\n", objstm.len()).as_bytes());
+        bytes.extend_from_slice(objstm);
+        bytes.extend_from_slice(b"\nendstream\nendobj\n");
+        let xref_offset = bytes.len();
+        let mut rows = vec![0, 0, 0, 0, 0xff, 0xff];
+        rows.extend_from_slice(&[2, 0, 0, 3, 0, 0]);
+        rows.extend_from_slice(&[2, 0, 0, 3, 0, 1]);
+        rows.extend_from_slice(&[1, 0, (objstm_offset >> 8) as u8, objstm_offset as u8, 0, 0]);
+        rows.extend_from_slice(&[1, 0, (xref_offset >> 8) as u8, xref_offset as u8, 0, 0]);
+        bytes.extend_from_slice(format!(
+            "4 0 obj\n<< /Type /XRef /Size 5 /W [1 3 2] /Root 1 0 R /Length {} >>\nstream\n", rows.len()).as_bytes());
+        bytes.extend_from_slice(&rows);
+        bytes.extend_from_slice(format!("\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref_offset).as_bytes());
+
+        let mut doc = LoadedDoc::load(bytes).unwrap();
+        assert_eq!(doc.catalog().unwrap().get_name("Type"), Some("Catalog"));
+        assert!(doc.get(Id::new(3, 0)).is_none());
+
+        doc.add_object(Object::Integer(7));
+        let reloaded = LoadedDoc::load(doc.to_incremental_bytes()).unwrap();
+        assert_eq!(reloaded.get(Id::new(5, 0)), Some(&Object::Integer(7)));
+        assert_eq!(reloaded.catalog().unwrap().get_name("Type"), Some("Catalog"));
+    }
+
+    #[test]
+    fn page_operations() {
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.contents().set_fill_color(1.0, 0.0, 0.0);
+        page.contents().rect(0.0, 0.0, 10.0, 10.0);
+        page.contents().fill();
+        doc.push_page(page);
+
+        let mut doc = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let page = doc.page_ids()[0];
+        let mut operations = doc.page_operations(page).unwrap();
+        assert_eq!(operations[0], Operation::SetFillRgb(1.0, 0.0, 0.0));
+        operations[0] = Operation::SetFillGray(0.5);
+        doc.set_page_operations(page, &operations).unwrap();
+
+        let reloaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        assert_eq!(reloaded.page_content(page).unwrap(), b"0.5 g\n0 0 10 10 re\nf\n");
+        assert!(doc.set_page_operations(Id::new(999, 0), &operations).is_err());
+
+        // The replaced stream
+        assert_eq!(doc.remove_unreferenced(), 1);
+        assert_eq!(doc.remove_unreferenced(), 0);
+        assert_eq!(doc.page_content(page).unwrap(), b"0.5 g\n0 0 10 10 re\nf\n");
+    }
+}
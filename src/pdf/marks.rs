@@ -0,0 +1,283 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::base::*;
+use super::colour::space::{ColorSpace, DeviceN, Separation};
+use super::contents::Contents;
+use super::error::Error;
+use super::font::Font;
+use super::page::Page;
+
+/// Resource name of the `/All` separation the marks are painted in.
+const ALL_NAME: &str = "MarksAll";
+/// Resource name of the colour space of the colour bar.
+const COLOUR_BAR_NAME: &str = "MarksBar";
+/// Resource name of the slug font.
+const FONT_NAME: &str = "MarksF0";
+
+const SLUG_FONT_SIZE: f64 = 6.0;
+
+/// Printer's marks drawn outside the TrimBox (and the BleedBox) of a page,
+/// in the space up to the MediaBox.
+///
+/// Every mark is painted in the `/All` separation, i.e. on every plate.
+///
+/// ```
+/// use prspdf::{Date, Page, Rectangle};
+/// use prspdf::marks::Marks;
+///
+/// let mut page = Page::new(Rectangle::new(0.0, 0.0, 667.0, 914.0));
+/// page.set_bleed_box(Rectangle::new(27.0, 27.0, 640.0, 887.0)).unwrap();
+/// page.set_trim_box(Rectangle::new(36.0, 36.0, 631.0, 878.0)).unwrap();
+///
+/// let mut marks = Marks::new();
+/// marks.set_bleed_marks(true);
+/// marks.set_slug("leaflet.pdf", Date::new(2025, 4, 1, 9, 0, 0));
+/// marks.draw(&mut page).unwrap();
+/// ```
+pub struct Marks {
+    trim_marks: bool,
+    bleed_marks: bool,
+    registration_marks: bool,
+    colour_bar: Option<DeviceN>,
+    slug: Option<(String, Date)>,
+    offset: f64,
+    length: f64,
+    line_width: f64,
+}
+
+impl Marks {
+    /// Creates the marks with the trim marks and the registration targets.
+    pub fn new() -> Self {
+        Self {
+            trim_marks: true,
+            bleed_marks: false,
+            registration_marks: true,
+            colour_bar: None,
+            slug: None,
+            offset: 3.0,
+            length: 12.0,
+            line_width: 0.25,
+        }
+    }
+
+    /// Draws the lines extending the edges of the TrimBox.
+    pub fn set_trim_marks(&mut self, trim_marks: bool) {
+        self.trim_marks = trim_marks;
+    }
+
+    /// Draws the lines extending the edges of the BleedBox.
+    pub fn set_bleed_marks(&mut self, bleed_marks: bool) {
+        self.bleed_marks = bleed_marks;
+    }
+
+    /// Draws the targets at the middle of the sides.
+    pub fn set_registration_marks(&mut self, registration_marks: bool) {
+        self.registration_marks = registration_marks;
+    }
+
+    /// Draws a colour control strip under the page with a solid patch per
+    /// colorant of the colour space.
+    pub fn set_colour_bar(&mut self, space: DeviceN) {
+        self.colour_bar = Some(space);
+    }
+
+    /// Writes the file name, the date and the plate names over the page.
+    pub fn set_slug(&mut self, file: &str, date: Date) {
+        self.slug = Some((file.to_string(), date));
+    }
+
+    /// Sets the distance of the marks from the BleedBox and their length.
+    pub fn set_mark_size(&mut self, offset: f64, length: f64) {
+        self.offset = offset;
+        self.length = length;
+    }
+
+    /// Draws the marks on the page. Fails if the MediaBox has no space
+    /// outside the BleedBox.
+    pub fn draw(&self, page: &mut Page) -> Result<(), Error> {
+        let media = page.media_box();
+        let bleed = page.bleed_box();
+        let trim = page.trim_box();
+        let space = (bleed.llx - media.llx)
+            .min(bleed.lly - media.lly)
+            .min(media.urx - bleed.urx)
+            .min(media.ury - bleed.ury);
+        if space <= 0.0 {
+            return Err(Error::InvalidValue(String::from("no space for the marks outside the BleedBox")));
+        }
+
+        let all = ColorSpace::from(Separation::all());
+        let colour_bar = self.colour_bar.clone().map(ColorSpace::from);
+        let resources = page.resources();
+        resources.add_color_space(ALL_NAME, all.clone());
+        if let Some(space) = &colour_bar {
+            resources.add_color_space(COLOUR_BAR_NAME, space.clone());
+        }
+        if self.slug.is_some() {
+            resources.add_font(FONT_NAME, Font::new("Helvetica"));
+        }
+
+        let contents = page.contents();
+        contents.save_state();
+        contents.set_stroke_color_space(ALL_NAME, &all);
+        contents.set_stroke_color_space_color([1.0])?;
+        contents.set_line_width(self.line_width);
+        if self.trim_marks {
+            draw_corner_marks(contents, &trim, &bleed, self.offset, self.length);
+        }
+        if self.bleed_marks {
+            draw_corner_marks(contents, &bleed, &bleed, self.offset, self.length);
+        }
+        if self.registration_marks {
+            let size = (space - self.offset).clamp(self.line_width, 12.0);
+            let d = self.offset + size / 2.0;
+            let cx = (trim.llx + trim.urx) / 2.0;
+            let cy = (trim.lly + trim.ury) / 2.0;
+            for (x, y) in [(cx, bleed.ury + d), (cx, bleed.lly - d), (bleed.llx - d, cy), (bleed.urx + d, cy)] {
+                draw_target(contents, x, y, size);
+            }
+        }
+        if let Some(space) = &colour_bar {
+            self.draw_colour_bar(contents, space, &bleed, media.lly)?;
+        }
+        if let Some((file, date)) = &self.slug {
+            let mut plates: Vec<&str> = vec![];
+            if let Some(space) = &self.colour_bar {
+                plates = space.names();
+            }
+            let mut text = format!("{}  {}", file, date.to_iso8601_string());
+            if !plates.is_empty() {
+                text.push_str(&format!("  Plates: {}", plates.join(", ")));
+            }
+            let y = bleed.ury + (media.ury - bleed.ury - SLUG_FONT_SIZE) / 2.0;
+            contents.set_fill_color_space(ALL_NAME, &all);
+            contents.set_fill_color_space_color([1.0])?;
+            contents.begin_text();
+            contents.set_font(FONT_NAME, SLUG_FONT_SIZE);
+            contents.move_text(trim.llx, y);
+            contents.show_text(&text);
+            contents.end_text();
+        }
+        contents.restore_state();
+        Ok(())
+    }
+
+    /// Draws the patches from the left of the page, in the space between the
+    /// BleedBox and the bottom of the MediaBox.
+    fn draw_colour_bar(&self, contents: &mut Contents, space: &ColorSpace, bleed: &Rectangle, bottom: f64) -> Result<(), Error> {
+        let count = space.components();
+        let size = (bleed.lly - bottom - 2.0 * self.offset).clamp(1.0, 14.0);
+        let y = bleed.lly - self.offset - size;
+        contents.set_fill_color_space(COLOUR_BAR_NAME, space);
+        for i in 0..count {
+            let tints: Vec<f64> = (0..count).map(|j| if i == j { 1.0 } else { 0.0 }).collect();
+            contents.set_fill_color_space_color(tints)?;
+            contents.rect(bleed.llx + i as f64 * size, y, size, size);
+            contents.fill();
+        }
+        Ok(())
+    }
+}
+
+impl Default for Marks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Draws the lines extending the edges of `rect` at the corners, from
+/// `offset` outside `clear` to `length` further.
+pub(crate) fn draw_corner_marks(contents: &mut Contents, rect: &Rectangle, clear: &Rectangle, offset: f64, length: f64) {
+    let left = clear.llx - offset;
+    let right = clear.urx + offset;
+    let bottom = clear.lly - offset;
+    let top = clear.ury + offset;
+    for (x, y, start_x, end_x, start_y, end_y) in [
+        (rect.llx, rect.lly, left, left - length, bottom, bottom - length),
+        (rect.urx, rect.lly, right, right + length, bottom, bottom - length),
+        (rect.llx, rect.ury, left, left - length, top, top + length),
+        (rect.urx, rect.ury, right, right + length, top, top + length),
+    ] {
+        contents.move_to(start_x, y);
+        contents.line_to(end_x, y);
+        contents.move_to(x, start_y);
+        contents.line_to(x, end_y);
+    }
+    contents.stroke();
+}
+
+/// Draws a circle with a cross centred at the point.
+pub(crate) fn draw_target(contents: &mut Contents, x: f64, y: f64, size: f64) {
+    let r = size / 2.0;
+    contents.ellipse(&Rectangle::new(x - r / 2.0, y - r / 2.0, x + r / 2.0, y + r / 2.0));
+    contents.move_to(x - r, y);
+    contents.line_to(x + r, y);
+    contents.move_to(x, y - r);
+    contents.line_to(x, y + r);
+    contents.stroke();
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::pdf::colour::space::{DeviceCMYK, DeviceNAttributes};
+    use crate::pdf::function::Type0;
+
+    fn page() -> Page {
+        let mut page = Page::new(Rectangle::new(0.0, 0.0, 200.0, 200.0));
+        page.set_bleed_box(Rectangle::new(30.0, 30.0, 170.0, 170.0)).unwrap();
+        page.set_trim_box(Rectangle::new(40.0, 40.0, 160.0, 160.0)).unwrap();
+        page
+    }
+
+    #[test]
+    fn draw() {
+        let mut page = page();
+        let mut marks = Marks::new();
+        marks.set_bleed_marks(true);
+        marks.set_slug("a.pdf", Date::new(2025, 4, 1, 9, 0, 0));
+        let nchannel = DeviceNAttributes::nchannel(HashMap::new(), DeviceCMYK {}, vec!["Cyan", "Magenta", "Yellow", "Black"]);
+        let samples = vec![0; 4];
+        let type0 = Type0::new([(0.0, 1.0); 4], [(0.0, 1.0); 4], [1; 4], 8, samples);
+        let mut colour_bar = DeviceN::new(vec!["Cyan", "Magenta", "Yellow", "Black"], DeviceCMYK {}, type0);
+        colour_bar.set_attributes(nchannel);
+        marks.set_colour_bar(colour_bar);
+        marks.draw(&mut page).unwrap();
+
+        let s = page.contents().get_stream_string(0);
+        // Trim mark at the lower left corner, outside the bleed
+        assert!(s.contains("27 40 m\n15 40 l"));
+        // Bleed mark
+        assert!(s.contains("27 30 m\n15 30 l"));
+        assert!(s.contains("/MarksAll CS\n1 SCN"));
+        assert!(s.contains("0 0 1 0 scn\n58 13 14 14 re\nf"));
+        assert!(s.contains("(a.pdf  2025-04-01T09:00:00Z  Plates: Cyan, Magenta, Yellow, Black) Tj"));
+
+        let all = page.resources().color_space(ALL_NAME).unwrap();
+        let objects = all.get_objects();
+        let separation = String::from_utf8(objects.last().unwrap().to_bytes(0)).unwrap();
+        assert!(separation.contains("/Separation\n  /All\n"));
+    }
+
+    #[test]
+    fn no_space() {
+        let mut page = Page::new(MediaBox::A4);
+        page.set_trim_box(Rectangle::new(10.0, 10.0, 100.0, 100.0)).unwrap();
+        assert!(Marks::new().draw(&mut page).is_err());
+    }
+}
@@ -7,13 +7,33 @@
 
 mod base;
 pub use base::*;
+pub mod annotation;
 pub mod contents;
 pub mod doc;
+pub mod error;
+pub mod ext_g_state;
+pub mod filter;
 pub mod font;
+pub mod form;
+mod import;
+pub mod impose;
+pub mod loaded_doc;
+pub mod marks;
+pub mod object;
+pub mod operation;
+pub mod optimize;
 pub mod page;
 pub mod page_list;
+pub mod paper_size;
+pub mod pattern;
+mod parser;
+pub mod redact;
 pub mod resources;
+pub mod shading;
+pub mod split;
+pub mod text;
 pub mod utils;
+pub mod xobject;
 
 pub mod colour;
 pub mod function;
@@ -0,0 +1,495 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::base::*;
+use super::error::Error;
+use super::filter;
+
+/// PDF32000-1:2008 7.3
+///
+/// A generic PDF object, as read from an existing file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Object {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Real(f64),
+    /// String bytes without the delimiters and escapes.
+    String(Vec<u8>),
+    Name(Name),
+    Array(Vec<Object>),
+    Dictionary(Dictionary),
+    Stream(Stream),
+    Reference(Id),
+}
+
+impl Object {
+    pub fn name(name: &str) -> Self {
+        Self::Name(Name::new(name))
+    }
+
+    /// Creates a text string, encoded in UTF-16BE if it is not ASCII.
+    pub fn text(text: &str) -> Self {
+        if text.is_ascii() {
+            Self::String(text.as_bytes().to_vec())
+        } else {
+            let mut bytes = vec![0xfe, 0xff];
+            for u in text.encode_utf16() {
+                bytes.extend_from_slice(&u.to_be_bytes());
+            }
+            Self::String(bytes)
+        }
+    }
+
+    pub fn rect(rect: &Rectangle) -> Self {
+        Self::Array(vec![
+            Self::Real(rect.llx), Self::Real(rect.lly),
+            Self::Real(rect.urx), Self::Real(rect.ury)])
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(v) => Some(*v),
+            Self::Real(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Integer(v) => Some(*v as f64),
+            Self::Real(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_name(&self) -> Option<&str> {
+        match self {
+            Self::Name(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Decodes a text string from UTF-16BE, UTF-8 (with the BOMs) or PDFDocEncoding.
+    pub fn as_text(&self) -> Option<String> {
+        self.as_bytes().map(decode_text_string)
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Object>> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Object>> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the dictionary, or the dictionary of a stream.
+    pub fn as_dict(&self) -> Option<&Dictionary> {
+        match self {
+            Self::Dictionary(v) => Some(v),
+            Self::Stream(v) => Some(&v.dict),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict_mut(&mut self) -> Option<&mut Dictionary> {
+        match self {
+            Self::Dictionary(v) => Some(v),
+            Self::Stream(v) => Some(&mut v.dict),
+            _ => None,
+        }
+    }
+
+    pub fn as_stream(&self) -> Option<&Stream> {
+        match self {
+            Self::Stream(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_stream_mut(&mut self) -> Option<&mut Stream> {
+        match self {
+            Self::Stream(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_reference(&self) -> Option<Id> {
+        match self {
+            Self::Reference(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns a rectangle from an array of four numbers.
+    pub fn as_rect(&self) -> Option<Rectangle> {
+        let a = self.as_array()?;
+        if a.len() != 4 {
+            return None;
+        }
+        let v: Vec<f64> = a.iter().filter_map(|e| e.as_f64()).collect();
+        (v.len() == 4).then(|| Rectangle::new(v[0], v[1], v[2], v[3]))
+    }
+
+    /// Returns the references this object contains directly or nested.
+    pub fn references(&self) -> Vec<Id> {
+        let mut refs = Vec::new();
+        self.collect_references(&mut refs);
+        refs
+    }
+
+    fn collect_references(&self, refs: &mut Vec<Id>) {
+        match self {
+            Self::Reference(id) => refs.push(*id),
+            Self::Array(a) => a.iter().for_each(|e| e.collect_references(refs)),
+            Self::Dictionary(d) => d.iter().for_each(|(_, e)| e.collect_references(refs)),
+            Self::Stream(s) => s.dict.iter().for_each(|(_, e)| e.collect_references(refs)),
+            _ => {}
+        }
+    }
+
+    /// Applies `f` to every reference this object contains.
+    pub fn map_references(&mut self, f: &mut dyn FnMut(Id) -> Id) {
+        match self {
+            Self::Reference(id) => *id = f(*id),
+            Self::Array(a) => a.iter_mut().for_each(|e| e.map_references(f)),
+            Self::Dictionary(d) => d.iter_mut().for_each(|(_, e)| e.map_references(f)),
+            Self::Stream(s) => s.dict.iter_mut().for_each(|(_, e)| e.map_references(f)),
+            _ => {}
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes);
+        bytes
+    }
+
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Null => out.extend_from_slice(b"null"),
+            Self::Boolean(v) => out.extend_from_slice(if *v { b"true" } else { b"false" }),
+            Self::Integer(v) => out.extend_from_slice(v.to_string().as_bytes()),
+            Self::Real(v) => out.extend_from_slice(real_to_string(*v).as_bytes()),
+            Self::String(v) => write_string(v, out),
+            Self::Name(v) => out.extend_from_slice(v.to_string().as_bytes()),
+            Self::Array(a) => {
+                out.push(b'[');
+                for (i, e) in a.iter().enumerate() {
+                    if i > 0 {
+                        out.push(b' ');
+                    }
+                    e.write(out);
+                }
+                out.push(b']');
+            }
+            Self::Dictionary(d) => d.write(out),
+            Self::Stream(s) => s.write(out),
+            Self::Reference(id) => out.extend_from_slice(id.to_ref_string().as_bytes()),
+        }
+    }
+}
+
+impl From<Dictionary> for Object {
+    fn from(v: Dictionary) -> Self {
+        Self::Dictionary(v)
+    }
+}
+
+impl From<Stream> for Object {
+    fn from(v: Stream) -> Self {
+        Self::Stream(v)
+    }
+}
+
+impl From<Id> for Object {
+    fn from(v: Id) -> Self {
+        Self::Reference(v)
+    }
+}
+
+impl From<i64> for Object {
+    fn from(v: i64) -> Self {
+        Self::Integer(v)
+    }
+}
+
+impl From<f64> for Object {
+    fn from(v: f64) -> Self {
+        Self::Real(v)
+    }
+}
+
+impl From<bool> for Object {
+    fn from(v: bool) -> Self {
+        Self::Boolean(v)
+    }
+}
+
+impl From<Vec<Object>> for Object {
+    fn from(v: Vec<Object>) -> Self {
+        Self::Array(v)
+    }
+}
+
+/// PDF32000-1:2008 7.3.7
+///
+/// A dictionary keeping the order of the entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dictionary {
+    entries: Vec<(Name, Object)>,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Object> {
+        self.entries.iter().find(|(k, _)| k.as_str() == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Object> {
+        self.entries.iter_mut().find(|(k, _)| k.as_str() == key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Sets the entry, replacing the value of an existing key in place.
+    pub fn set(&mut self, key: &str, value: impl Into<Object>) {
+        let value = value.into();
+        match self.get_mut(key) {
+            Some(v) => *v = value,
+            None => self.entries.push((Name::new(key), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Object> {
+        let pos = self.entries.iter().position(|(k, _)| k.as_str() == key)?;
+        Some(self.entries.remove(pos).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Name, &Object)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Name, &mut Object)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    pub fn get_name(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|v| v.as_name())
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(|v| v.as_i64())
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(|v| v.as_f64())
+    }
+
+    pub fn get_reference(&self, key: &str) -> Option<Id> {
+        self.get(key).and_then(|v| v.as_reference())
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(b"<<");
+        for (k, v) in &self.entries {
+            out.push(b' ');
+            out.extend_from_slice(k.to_string().as_bytes());
+            out.push(b' ');
+            v.write(out);
+        }
+        out.extend_from_slice(b" >>");
+    }
+}
+
+/// PDF32000-1:2008 7.3.8
+///
+/// A stream with the data as stored in the file, i.e. still encoded with the filters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stream {
+    pub dict: Dictionary,
+    data: Vec<u8>,
+}
+
+impl Stream {
+    /// Creates a stream with encoded data. `/Length` is set from the data.
+    pub fn new(mut dict: Dictionary, data: Vec<u8>) -> Self {
+        dict.set("Length", data.len() as i64);
+        Self { dict, data }
+    }
+
+    /// Creates a stream with unencoded data, removing any filter from the dictionary.
+    pub fn from_decoded(mut dict: Dictionary, data: Vec<u8>) -> Self {
+        dict.remove("Filter");
+        dict.remove("DecodeParms");
+        Self::new(dict, data)
+    }
+
+    /// Returns the data as stored.
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Replaces the data as stored. The filters in the dictionary must match.
+    pub fn set_raw_data(&mut self, data: Vec<u8>) {
+        self.dict.set("Length", data.len() as i64);
+        self.data = data;
+    }
+
+    /// Replaces the data with unencoded data, removing the filters.
+    pub fn set_decoded_data(&mut self, data: Vec<u8>) {
+        self.dict.remove("Filter");
+        self.dict.remove("DecodeParms");
+        self.set_raw_data(data);
+    }
+
+    /// Returns the data with the filters applied. Image filters such as
+    /// DCTDecode are left encoded.
+    pub fn decoded_data(&self) -> Result<Vec<u8>, Error> {
+        filter::decode_stream(&self.dict, &self.data)
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        self.dict.write(out);
+        out.extend_from_slice(b"\nstream\n");
+        out.extend_from_slice(&self.data);
+        out.extend_from_slice(b"\nendstream");
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn real_to_string(v: f64) -> String {
+    if !v.is_finite() {
+        return String::from("0");
+    }
+    let v = (v * 1_000_000.0).round() / 1_000_000.0;
+    if v == 0.0 { String::from("0") } else { v.to_string() }
+}
+
+pub(crate) fn write_string(v: &[u8], out: &mut Vec<u8>) {
+    let printable = v.iter().all(|b| (0x20..0x7f).contains(b) || *b == b'\n' || *b == b'\r' || *b == b'\t');
+    if printable {
+        out.push(b'(');
+        for b in v {
+            match b {
+                b'(' | b')' | b'\\' => {
+                    out.push(b'\\');
+                    out.push(*b);
+                }
+                b'\r' => out.extend_from_slice(b"\\r"),
+                _ => out.push(*b),
+            }
+        }
+        out.push(b')');
+    } else {
+        out.push(b'<');
+        for b in v {
+            out.extend_from_slice(format!("{:02X}", b).as_bytes());
+        }
+        out.push(b'>');
+    }
+}
+
+/// PDFDocEncoding differs from Latin-1 only in 0x18-0x1f and 0x80-0xa0.
+const PDF_DOC_ENCODING_HIGH: [char; 33] = [
+    '•', '†', '‡', '…', '—', '–', 'ƒ', '⁄', '‹', '›', '−', '‰', '„', '“', '”', '‘',
+    '’', '‚', '™', 'ﬁ', 'ﬂ', 'Ł', 'Œ', 'Š', 'Ÿ', 'Ž', 'ı', 'ł', 'œ', 'š', 'ž', '\u{fffd}',
+    '€'];
+
+pub(crate) fn decode_text_string(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xfe, 0xff]) {
+        let units: Vec<u16> = bytes[2..].chunks(2)
+            .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if bytes.starts_with(&[0xef, 0xbb, 0xbf]) {
+        return String::from_utf8_lossy(&bytes[3..]).into_owned();
+    }
+
+    bytes.iter().map(|b| match b {
+        0x80..=0xa0 => PDF_DOC_ENCODING_HIGH[(b - 0x80) as usize],
+        _ => *b as char,
+    }).collect()
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_bytes() {
+        let mut d = Dictionary::new();
+        d.set("Type", Object::name("Annot"));
+        d.set("Rect", Object::rect(&Rectangle::new(0.0, 0.0, 10.5, 20.0)));
+        d.set("T", Object::text("a(b)"));
+        d.set("P", Id::new(3, 0));
+        d.set("Bin", Object::String(vec![0, 255]));
+
+        assert_eq!(
+            String::from_utf8(Object::from(d).to_bytes()).unwrap(),
+            "<< /Type /Annot /Rect [0 0 10.5 20] /T (a\\(b\\)) /P 3 0 R /Bin <00FF> >>");
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(Object::text("é").as_text().unwrap(), "é");
+        assert_eq!(Object::String(vec![0x8d, b'a']).as_text().unwrap(), "“a");
+    }
+
+    #[test]
+    fn dictionary_set_replaces() {
+        let mut d = Dictionary::new();
+        d.set("A", 1i64);
+        d.set("B", 2i64);
+        d.set("A", 3i64);
+        assert_eq!(d.len(), 2);
+        assert_eq!(d.get_i64("A"), Some(3));
+        assert_eq!(d.remove("A"), Some(Object::Integer(3)));
+        assert!(!d.contains_key("A"));
+    }
+}
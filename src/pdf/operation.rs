@@ -0,0 +1,620 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 Annex A - Operator summary.
+
+use super::base::*;
+use super::error::Error;
+use super::object::{write_string, Dictionary, Object};
+use super::parser::parse_content;
+
+/// A content stream operator with its operands.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    // 8.4.4 General graphics state
+    /// `w`
+    SetLineWidth(f64),
+    /// `J`
+    SetLineCap(i64),
+    /// `j`
+    SetLineJoin(i64),
+    /// `M`
+    SetMiterLimit(f64),
+    /// `d`, the dash array and the phase.
+    SetDash(Vec<f64>, f64),
+    /// `ri`
+    SetRenderingIntent(Name),
+    /// `i`
+    SetFlatness(f64),
+    /// `gs`
+    SetExtGState(Name),
+
+    // 8.4.4 Special graphics state
+    /// `q`
+    SaveState,
+    /// `Q`
+    RestoreState,
+    /// `cm`
+    ConcatMatrix([f64; 6]),
+
+    // 8.5.2 Path construction
+    /// `m`
+    MoveTo(f64, f64),
+    /// `l`
+    LineTo(f64, f64),
+    /// `c`
+    CurveTo([f64; 6]),
+    /// `v`, the current point is the first control point.
+    CurveToInitial([f64; 4]),
+    /// `y`, the end point is the second control point.
+    CurveToFinal([f64; 4]),
+    /// `h`
+    ClosePath,
+    /// `re`
+    Rect(f64, f64, f64, f64),
+
+    // 8.5.3 Path painting
+    /// `S`
+    Stroke,
+    /// `s`
+    CloseStroke,
+    /// `f`
+    Fill,
+    /// `F`, same as `f`.
+    FillObsolete,
+    /// `f*`
+    FillEvenOdd,
+    /// `B`
+    FillStroke,
+    /// `B*`
+    FillStrokeEvenOdd,
+    /// `b`
+    CloseFillStroke,
+    /// `b*`
+    CloseFillStrokeEvenOdd,
+    /// `n`
+    EndPath,
+
+    // 8.5.4 Clipping paths
+    /// `W`
+    Clip,
+    /// `W*`
+    ClipEvenOdd,
+
+    // 9.4 Text objects
+    /// `BT`
+    BeginText,
+    /// `ET`
+    EndText,
+
+    // 9.3 Text state
+    /// `Tc`
+    SetCharSpacing(f64),
+    /// `Tw`
+    SetWordSpacing(f64),
+    /// `Tz`, in percent.
+    SetHorizontalScaling(f64),
+    /// `TL`
+    SetLeading(f64),
+    /// `Tf`
+    SetFont(Name, f64),
+    /// `Tr`
+    SetTextRender(i64),
+    /// `Ts`
+    SetTextRise(f64),
+
+    // 9.4.2 Text positioning
+    /// `Td`
+    MoveText(f64, f64),
+    /// `TD`
+    MoveTextSetLeading(f64, f64),
+    /// `Tm`
+    SetTextMatrix([f64; 6]),
+    /// `T*`
+    NextLine,
+
+    // 9.4.3 Text showing
+    /// `Tj`
+    ShowText(Vec<u8>),
+    /// `TJ`
+    ShowTextAdjusted(Vec<TextItem>),
+    /// `'`
+    NextLineShowText(Vec<u8>),
+    /// `"`, the word spacing, the character spacing and the string.
+    NextLineShowTextSpaced(f64, f64, Vec<u8>),
+
+    // 9.6.5 Type 3 fonts
+    /// `d0`
+    SetGlyphWidth(f64, f64),
+    /// `d1`, the width and the bounding box.
+    SetGlyphWidthAndBBox([f64; 6]),
+
+    // 8.6.8 Colour
+    /// `CS`
+    SetStrokeColorSpace(Name),
+    /// `cs`
+    SetFillColorSpace(Name),
+    /// `SC`
+    SetStrokeColor(Vec<f64>),
+    /// `sc`
+    SetFillColor(Vec<f64>),
+    /// `SCN`, the components and the pattern.
+    SetStrokeColorN(Vec<f64>, Option<Name>),
+    /// `scn`
+    SetFillColorN(Vec<f64>, Option<Name>),
+    /// `G`
+    SetStrokeGray(f64),
+    /// `g`
+    SetFillGray(f64),
+    /// `RG`
+    SetStrokeRgb(f64, f64, f64),
+    /// `rg`
+    SetFillRgb(f64, f64, f64),
+    /// `K`
+    SetStrokeCmyk(f64, f64, f64, f64),
+    /// `k`
+    SetFillCmyk(f64, f64, f64, f64),
+
+    // 8.7.4.2 Shading
+    /// `sh`
+    PaintShading(Name),
+
+    // 8.9.7 Inline images
+    /// `BI` ... `ID` ... `EI`, the image dictionary and the data.
+    InlineImage(Dictionary, Vec<u8>),
+
+    // 8.8 External objects
+    /// `Do`
+    DrawXObject(Name),
+
+    // 14.6 Marked content
+    /// `MP`
+    MarkPoint(Name),
+    /// `DP`, the tag and the property list or its name.
+    MarkPointProperties(Name, Object),
+    /// `BMC`
+    BeginMarkedContent(Name),
+    /// `BDC`
+    BeginMarkedContentProperties(Name, Object),
+    /// `EMC`
+    EndMarkedContent,
+
+    // 7.8.2 Compatibility
+    /// `BX`
+    BeginCompatibility,
+    /// `EX`
+    EndCompatibility,
+
+    /// An operator not in the set or with unexpected operands, kept as read.
+    Other(String, Vec<Object>),
+}
+
+/// An element of the `TJ` array.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextItem {
+    Text(Vec<u8>),
+    /// Thousandths of the font size to move the next glyph left.
+    Adjust(f64),
+}
+
+impl From<&str> for TextItem {
+    fn from(text: &str) -> Self {
+        Self::Text(text.as_bytes().to_vec())
+    }
+}
+
+impl Operation {
+    /// Returns the operator, e.g. `Tj`.
+    pub fn keyword(&self) -> &str {
+        match self {
+            Self::SetLineWidth(..) => "w",
+            Self::SetLineCap(..) => "J",
+            Self::SetLineJoin(..) => "j",
+            Self::SetMiterLimit(..) => "M",
+            Self::SetDash(..) => "d",
+            Self::SetRenderingIntent(..) => "ri",
+            Self::SetFlatness(..) => "i",
+            Self::SetExtGState(..) => "gs",
+            Self::SaveState => "q",
+            Self::RestoreState => "Q",
+            Self::ConcatMatrix(..) => "cm",
+            Self::MoveTo(..) => "m",
+            Self::LineTo(..) => "l",
+            Self::CurveTo(..) => "c",
+            Self::CurveToInitial(..) => "v",
+            Self::CurveToFinal(..) => "y",
+            Self::ClosePath => "h",
+            Self::Rect(..) => "re",
+            Self::Stroke => "S",
+            Self::CloseStroke => "s",
+            Self::Fill => "f",
+            Self::FillObsolete => "F",
+            Self::FillEvenOdd => "f*",
+            Self::FillStroke => "B",
+            Self::FillStrokeEvenOdd => "B*",
+            Self::CloseFillStroke => "b",
+            Self::CloseFillStrokeEvenOdd => "b*",
+            Self::EndPath => "n",
+            Self::Clip => "W",
+            Self::ClipEvenOdd => "W*",
+            Self::BeginText => "BT",
+            Self::EndText => "ET",
+            Self::SetCharSpacing(..) => "Tc",
+            Self::SetWordSpacing(..) => "Tw",
+            Self::SetHorizontalScaling(..) => "Tz",
+            Self::SetLeading(..) => "TL",
+            Self::SetFont(..) => "Tf",
+            Self::SetTextRender(..) => "Tr",
+            Self::SetTextRise(..) => "Ts",
+            Self::MoveText(..) => "Td",
+            Self::MoveTextSetLeading(..) => "TD",
+            Self::SetTextMatrix(..) => "Tm",
+            Self::NextLine => "T*",
+            Self::ShowText(..) => "Tj",
+            Self::ShowTextAdjusted(..) => "TJ",
+            Self::NextLineShowText(..) => "'",
+            Self::NextLineShowTextSpaced(..) => "\"",
+            Self::SetGlyphWidth(..) => "d0",
+            Self::SetGlyphWidthAndBBox(..) => "d1",
+            Self::SetStrokeColorSpace(..) => "CS",
+            Self::SetFillColorSpace(..) => "cs",
+            Self::SetStrokeColor(..) => "SC",
+            Self::SetFillColor(..) => "sc",
+            Self::SetStrokeColorN(..) => "SCN",
+            Self::SetFillColorN(..) => "scn",
+            Self::SetStrokeGray(..) => "G",
+            Self::SetFillGray(..) => "g",
+            Self::SetStrokeRgb(..) => "RG",
+            Self::SetFillRgb(..) => "rg",
+            Self::SetStrokeCmyk(..) => "K",
+            Self::SetFillCmyk(..) => "k",
+            Self::PaintShading(..) => "sh",
+            Self::InlineImage(..) => "BI",
+            Self::DrawXObject(..) => "Do",
+            Self::MarkPoint(..) => "MP",
+            Self::MarkPointProperties(..) => "DP",
+            Self::BeginMarkedContent(..) => "BMC",
+            Self::BeginMarkedContentProperties(..) => "BDC",
+            Self::EndMarkedContent => "EMC",
+            Self::BeginCompatibility => "BX",
+            Self::EndCompatibility => "EX",
+            Self::Other(keyword, _) => keyword,
+        }
+    }
+
+    /// Writes the operands and the operator. Numbers are rounded to 4
+    /// decimal places.
+    pub fn write(&self, out: &mut Vec<u8>) {
+        let mut w = Writer(out);
+        match self {
+            Self::SetLineWidth(v) | Self::SetMiterLimit(v) | Self::SetFlatness(v)
+            | Self::SetCharSpacing(v) | Self::SetWordSpacing(v) | Self::SetHorizontalScaling(v)
+            | Self::SetLeading(v) | Self::SetTextRise(v) | Self::SetStrokeGray(v) | Self::SetFillGray(v) => w.num(*v),
+            Self::SetLineCap(v) | Self::SetLineJoin(v) | Self::SetTextRender(v) => w.raw(&v.to_string()),
+            Self::SetDash(array, phase) => {
+                w.raw(&format!("[{}]", nums(array)));
+                w.num(*phase);
+            }
+            Self::SetRenderingIntent(name) | Self::SetExtGState(name) | Self::SetStrokeColorSpace(name)
+            | Self::SetFillColorSpace(name) | Self::PaintShading(name) | Self::DrawXObject(name)
+            | Self::MarkPoint(name) | Self::BeginMarkedContent(name) => w.raw(&name.to_string()),
+            Self::ConcatMatrix(m) | Self::CurveTo(m) | Self::SetTextMatrix(m) | Self::SetGlyphWidthAndBBox(m) => w.nums(m),
+            Self::CurveToInitial(v) | Self::CurveToFinal(v) => w.nums(v),
+            Self::MoveTo(x, y) | Self::LineTo(x, y) | Self::MoveText(x, y)
+            | Self::MoveTextSetLeading(x, y) | Self::SetGlyphWidth(x, y) => w.nums(&[*x, *y]),
+            Self::Rect(x, y, width, height) => w.nums(&[*x, *y, *width, *height]),
+            Self::SetFont(name, size) => {
+                w.raw(&name.to_string());
+                w.num(*size);
+            }
+            Self::ShowText(text) | Self::NextLineShowText(text) => w.string(text),
+            Self::ShowTextAdjusted(items) => {
+                w.0.push(b'[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        w.0.push(b' ');
+                    }
+                    match item {
+                        TextItem::Text(text) => write_string(text, w.0),
+                        TextItem::Adjust(v) => w.0.extend_from_slice(num(*v).as_bytes()),
+                    }
+                }
+                w.0.extend_from_slice(b"] ");
+            }
+            Self::NextLineShowTextSpaced(aw, ac, text) => {
+                w.nums(&[*aw, *ac]);
+                w.string(text);
+            }
+            Self::SetStrokeColor(c) | Self::SetFillColor(c) => w.nums(c),
+            Self::SetStrokeColorN(c, pattern) | Self::SetFillColorN(c, pattern) => {
+                w.nums(c);
+                if let Some(pattern) = pattern {
+                    w.raw(&pattern.to_string());
+                }
+            }
+            Self::SetStrokeRgb(r, g, b) | Self::SetFillRgb(r, g, b) => w.nums(&[*r, *g, *b]),
+            Self::SetStrokeCmyk(c, m, y, k) | Self::SetFillCmyk(c, m, y, k) => w.nums(&[*c, *m, *y, *k]),
+            Self::InlineImage(dict, data) => {
+                w.0.extend_from_slice(b"BI");
+                for (key, value) in dict.iter() {
+                    w.0.push(b' ');
+                    w.0.extend_from_slice(key.to_string().as_bytes());
+                    w.0.push(b' ');
+                    value.write(w.0);
+                }
+                w.0.extend_from_slice(b" ID ");
+                w.0.extend_from_slice(data);
+                w.0.extend_from_slice(b"\nEI");
+                return;
+            }
+            Self::MarkPointProperties(tag, properties) | Self::BeginMarkedContentProperties(tag, properties) => {
+                w.raw(&tag.to_string());
+                w.object(properties);
+            }
+            Self::Other(_, operands) => for operand in operands {
+                w.object(operand);
+            },
+            Self::SaveState | Self::RestoreState | Self::ClosePath | Self::Stroke | Self::CloseStroke
+            | Self::Fill | Self::FillObsolete | Self::FillEvenOdd | Self::FillStroke | Self::FillStrokeEvenOdd
+            | Self::CloseFillStroke | Self::CloseFillStrokeEvenOdd | Self::EndPath | Self::Clip | Self::ClipEvenOdd
+            | Self::BeginText | Self::EndText | Self::NextLine | Self::EndMarkedContent
+            | Self::BeginCompatibility | Self::EndCompatibility => (),
+        }
+        out.extend_from_slice(self.keyword().as_bytes());
+    }
+
+    /// Returns the typed operation, `Other` if the operands do not match.
+    fn from_parsed(operands: Vec<Object>, keyword: String) -> Self {
+        match Self::typed(&operands, &keyword) {
+            Some(operation) => operation,
+            None => Self::Other(keyword, operands),
+        }
+    }
+
+    fn typed(operands: &[Object], keyword: &str) -> Option<Self> {
+        let num = |i: usize| operands.get(i).and_then(|o| o.as_f64());
+        let int = |i: usize| operands.get(i).and_then(|o| o.as_i64());
+        let name = |i: usize| operands.get(i).and_then(|o| o.as_name()).map(Name::new);
+        let string = |i: usize| operands.get(i).and_then(|o| o.as_bytes()).map(|s| s.to_vec());
+        let all_nums = || operands.iter().map(|o| o.as_f64()).collect::<Option<Vec<f64>>>();
+        let count = |n: usize| if operands.len() == n { Some(()) } else { None };
+
+        // The operand counts are checked first, so the closures can index
+        let operation = match keyword {
+            "w" => { count(1)?; Self::SetLineWidth(num(0)?) }
+            "J" => { count(1)?; Self::SetLineCap(int(0)?) }
+            "j" => { count(1)?; Self::SetLineJoin(int(0)?) }
+            "M" => { count(1)?; Self::SetMiterLimit(num(0)?) }
+            "d" => {
+                count(2)?;
+                let array = operands[0].as_array()?.iter().map(|o| o.as_f64()).collect::<Option<Vec<f64>>>()?;
+                Self::SetDash(array, num(1)?)
+            }
+            "ri" => { count(1)?; Self::SetRenderingIntent(name(0)?) }
+            "i" => { count(1)?; Self::SetFlatness(num(0)?) }
+            "gs" => { count(1)?; Self::SetExtGState(name(0)?) }
+            "q" => { count(0)?; Self::SaveState }
+            "Q" => { count(0)?; Self::RestoreState }
+            "cm" => { count(6)?; Self::ConcatMatrix(all_nums()?.try_into().ok()?) }
+            "m" => { count(2)?; Self::MoveTo(num(0)?, num(1)?) }
+            "l" => { count(2)?; Self::LineTo(num(0)?, num(1)?) }
+            "c" => { count(6)?; Self::CurveTo(all_nums()?.try_into().ok()?) }
+            "v" => { count(4)?; Self::CurveToInitial(all_nums()?.try_into().ok()?) }
+            "y" => { count(4)?; Self::CurveToFinal(all_nums()?.try_into().ok()?) }
+            "h" => { count(0)?; Self::ClosePath }
+            "re" => { count(4)?; Self::Rect(num(0)?, num(1)?, num(2)?, num(3)?) }
+            "S" => { count(0)?; Self::Stroke }
+            "s" => { count(0)?; Self::CloseStroke }
+            "f" => { count(0)?; Self::Fill }
+            "F" => { count(0)?; Self::FillObsolete }
+            "f*" => { count(0)?; Self::FillEvenOdd }
+            "B" => { count(0)?; Self::FillStroke }
+            "B*" => { count(0)?; Self::FillStrokeEvenOdd }
+            "b" => { count(0)?; Self::CloseFillStroke }
+            "b*" => { count(0)?; Self::CloseFillStrokeEvenOdd }
+            "n" => { count(0)?; Self::EndPath }
+            "W" => { count(0)?; Self::Clip }
+            "W*" => { count(0)?; Self::ClipEvenOdd }
+            "BT" => { count(0)?; Self::BeginText }
+            "ET" => { count(0)?; Self::EndText }
+            "Tc" => { count(1)?; Self::SetCharSpacing(num(0)?) }
+            "Tw" => { count(1)?; Self::SetWordSpacing(num(0)?) }
+            "Tz" => { count(1)?; Self::SetHorizontalScaling(num(0)?) }
+            "TL" => { count(1)?; Self::SetLeading(num(0)?) }
+            "Tf" => { count(2)?; Self::SetFont(name(0)?, num(1)?) }
+            "Tr" => { count(1)?; Self::SetTextRender(int(0)?) }
+            "Ts" => { count(1)?; Self::SetTextRise(num(0)?) }
+            "Td" => { count(2)?; Self::MoveText(num(0)?, num(1)?) }
+            "TD" => { count(2)?; Self::MoveTextSetLeading(num(0)?, num(1)?) }
+            "Tm" => { count(6)?; Self::SetTextMatrix(all_nums()?.try_into().ok()?) }
+            "T*" => { count(0)?; Self::NextLine }
+            "Tj" => { count(1)?; Self::ShowText(string(0)?) }
+            "TJ" => {
+                count(1)?;
+                let items = operands[0].as_array()?.iter().map(|o| match o {
+                    Object::String(s) => Some(TextItem::Text(s.clone())),
+                    _ => o.as_f64().map(TextItem::Adjust),
+                }).collect::<Option<Vec<TextItem>>>()?;
+                Self::ShowTextAdjusted(items)
+            }
+            "'" => { count(1)?; Self::NextLineShowText(string(0)?) }
+            "\"" => { count(3)?; Self::NextLineShowTextSpaced(num(0)?, num(1)?, string(2)?) }
+            "d0" => { count(2)?; Self::SetGlyphWidth(num(0)?, num(1)?) }
+            "d1" => { count(6)?; Self::SetGlyphWidthAndBBox(all_nums()?.try_into().ok()?) }
+            "CS" => { count(1)?; Self::SetStrokeColorSpace(name(0)?) }
+            "cs" => { count(1)?; Self::SetFillColorSpace(name(0)?) }
+            "SC" => Self::SetStrokeColor(all_nums()?),
+            "sc" => Self::SetFillColor(all_nums()?),
+            "SCN" | "scn" => {
+                let (components, pattern) = match operands.split_last() {
+                    Some((Object::Name(pattern), components)) => (components, Some(pattern.clone())),
+                    _ => (operands, None),
+                };
+                let components = components.iter().map(|o| o.as_f64()).collect::<Option<Vec<f64>>>()?;
+                if keyword == "SCN" {
+                    Self::SetStrokeColorN(components, pattern)
+                } else {
+                    Self::SetFillColorN(components, pattern)
+                }
+            }
+            "G" => { count(1)?; Self::SetStrokeGray(num(0)?) }
+            "g" => { count(1)?; Self::SetFillGray(num(0)?) }
+            "RG" => { count(3)?; Self::SetStrokeRgb(num(0)?, num(1)?, num(2)?) }
+            "rg" => { count(3)?; Self::SetFillRgb(num(0)?, num(1)?, num(2)?) }
+            "K" => { count(4)?; Self::SetStrokeCmyk(num(0)?, num(1)?, num(2)?, num(3)?) }
+            "k" => { count(4)?; Self::SetFillCmyk(num(0)?, num(1)?, num(2)?, num(3)?) }
+            "sh" => { count(1)?; Self::PaintShading(name(0)?) }
+            "BI" => { count(2)?; Self::InlineImage(operands[0].as_dict()?.clone(), string(1)?) }
+            "Do" => { count(1)?; Self::DrawXObject(name(0)?) }
+            "MP" => { count(1)?; Self::MarkPoint(name(0)?) }
+            "DP" => { count(2)?; Self::MarkPointProperties(name(0)?, operands[1].clone()) }
+            "BMC" => { count(1)?; Self::BeginMarkedContent(name(0)?) }
+            "BDC" => { count(2)?; Self::BeginMarkedContentProperties(name(0)?, operands[1].clone()) }
+            "EMC" => { count(0)?; Self::EndMarkedContent }
+            "BX" => { count(0)?; Self::BeginCompatibility }
+            "EX" => { count(0)?; Self::EndCompatibility }
+            _ => return None,
+        };
+        Some(operation)
+    }
+}
+
+/// Parses a content stream into operations.
+///
+/// ```
+/// use prspdf::operation::{self, Operation};
+///
+/// let mut operations = operation::parse(b"0 0 1 rg 0 0 10 10 re f").unwrap();
+/// for op in operations.iter_mut() {
+///     if let Operation::SetFillRgb(..) = op {
+///         *op = Operation::SetFillGray(0.5);
+///     }
+/// }
+/// assert_eq!(operation::to_bytes(&operations), b"0.5 g\n0 0 10 10 re\nf");
+/// ```
+pub fn parse(data: &[u8]) -> Result<Vec<Operation>, Error> {
+    Ok(parse_content(data)?.into_iter()
+        .map(|(operands, keyword)| Operation::from_parsed(operands, keyword))
+        .collect())
+}
+
+/// Writes the operations, one per line.
+pub fn to_bytes(operations: &[Operation]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (i, operation) in operations.iter().enumerate() {
+        if i > 0 {
+            bytes.push(b'\n');
+        }
+        operation.write(&mut bytes);
+    }
+    bytes
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Writes operands, each followed by a space.
+struct Writer<'a>(&'a mut Vec<u8>);
+
+impl Writer<'_> {
+    fn raw(&mut self, s: &str) {
+        self.0.extend_from_slice(s.as_bytes());
+        self.0.push(b' ');
+    }
+
+    fn num(&mut self, v: f64) {
+        self.raw(&num(v));
+    }
+
+    fn nums(&mut self, values: &[f64]) {
+        for v in values {
+            self.num(*v);
+        }
+    }
+
+    fn string(&mut self, s: &[u8]) {
+        write_string(s, self.0);
+        self.0.push(b' ');
+    }
+
+    fn object(&mut self, object: &Object) {
+        match object {
+            Object::Integer(_) | Object::Real(_) => self.num(object.as_f64().unwrap_or(0.0)),
+            _ => {
+                object.write(self.0);
+                self.0.push(b' ');
+            }
+        }
+    }
+}
+
+/// Formats a number for a content stream, rounded to 4 decimal places.
+pub(crate) fn num(v: f64) -> String {
+    let v = (v * 10000.0).round() / 10000.0;
+    if v == 0.0 {
+        // avoids "-0"
+        String::from("0")
+    } else {
+        v.to_string()
+    }
+}
+
+pub(crate) fn nums(values: &[f64]) -> String {
+    values.iter().map(|v| num(*v)).collect::<Vec<String>>().join(" ")
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let data = concat!(
+            "q 0.5 0 0 0.5 10 20 cm [3 1] 0 d 1 J /GS0 gs\n",
+            "0 0 m 10 0 l 10 10 5 10 0 5 c 1 2 3 4 v 1 2 3 4 y h 0 0 5 5 re f* W* n\n",
+            "/P <</MCID 0>> BDC BT /F0 12 Tf 2 Tr 72 700 Td [(A) -120 (B)] TJ 1 2 (x) \" ET EMC\n",
+            "/Pattern cs 0.2 /P0 scn 0.1 0.2 0.3 SC 1 0 0 0 K /Sh0 sh /Im0 Do\n",
+            "BI /W 2 /H 1 /BPC 8 /CS /G ID \u{1}\u{2} EI F BX 1 2 foo EX Q");
+        let operations = parse(data.as_bytes()).unwrap();
+        assert_eq!(operations[0], Operation::SaveState);
+        assert_eq!(operations[1], Operation::ConcatMatrix([0.5, 0.0, 0.0, 0.5, 10.0, 20.0]));
+        assert_eq!(operations[2], Operation::SetDash(vec![3.0, 1.0], 0.0));
+        assert!(operations.contains(&Operation::ShowTextAdjusted(
+            vec![TextItem::from("A"), TextItem::Adjust(-120.0), TextItem::from("B")])));
+        assert!(operations.contains(&Operation::NextLineShowTextSpaced(1.0, 2.0, b"x".to_vec())));
+        assert!(operations.contains(&Operation::SetFillColorN(vec![0.2], Some(Name::new("P0")))));
+        assert!(operations.contains(&Operation::Other(String::from("foo"), vec![Object::Integer(1), Object::Integer(2)])));
+        let image = operations.iter().find(|o| matches!(o, Operation::InlineImage(..))).unwrap();
+        assert_eq!(*image, Operation::InlineImage(
+            {
+                let mut dict = Dictionary::new();
+                dict.set("W", 2);
+                dict.set("H", 1);
+                dict.set("BPC", 8);
+                dict.set("CS", Object::name("G"));
+                dict
+            },
+            vec![1, 2]));
+
+        let bytes = to_bytes(&operations);
+        assert_eq!(parse(&bytes).unwrap(), operations);
+        assert!(bytes.starts_with(b"q\n0.5 0 0 0.5 10 20 cm\n[3 1] 0 d\n1 J\n/GS0 gs\n"));
+    }
+
+    #[test]
+    fn unexpected_operands() {
+        let operations = parse(b"1 2 m /F0 Tf").unwrap();
+        assert_eq!(operations[0], Operation::MoveTo(1.0, 2.0));
+        assert_eq!(operations[1], Operation::Other(String::from("Tf"), vec![Object::name("F0")]));
+        assert_eq!(to_bytes(&operations), b"1 2 m\n/F0 Tf");
+    }
+}
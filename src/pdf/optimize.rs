@@ -0,0 +1,708 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! Size optimization of loaded and generated documents.
+
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use super::base::*;
+use super::doc::Doc;
+use super::error::Error;
+use super::filter::{dct, flate};
+use super::loaded_doc::LoadedDoc;
+use super::object::{Dictionary, Object, Stream};
+use super::operation::{self, Operation};
+use super::text::{concat, to_matrix, Matrix, IDENTITY};
+
+const MAX_FORM_DEPTH: usize = 16;
+/// Quality of the images re-encoded as JPEG without a set quality.
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+const IMAGE_FILTERS: [&str; 6] = ["DCTDecode", "DCT", "JPXDecode", "JBIG2Decode", "CCITTFaxDecode", "CCF"];
+/// Objects never merged, whose identity matters.
+const UNIQUE_TYPES: [&str; 6] = ["Catalog", "Pages", "Page", "Annot", "OCG", "Sig"];
+
+/// Optimization passes over a document, all lossless by default.
+///
+/// In order, the optimizer:
+///
+/// 1. removes the objects not reachable from the trailer,
+/// 2. rewrites the page and form content streams without comments and
+///    redundant white space, when shorter,
+/// 3. downsamples the images drawn above the set resolution (optional),
+/// 4. re-encodes the JPEG images at the set quality, when smaller (optional),
+/// 5. merges identical streams, fonts, font descriptors and colour spaces,
+/// 6. compresses the streams with Flate at the best level, when smaller,
+/// 7. removes the objects left unreachable.
+///
+/// Only 8-bit images that are not indexed are downsampled. JPEG images with
+/// four components are left as they are.
+///
+/// ```no_run
+/// use prspdf::LoadedDoc;
+/// use prspdf::optimize::Optimizer;
+///
+/// let mut doc = LoadedDoc::open("merged.pdf").unwrap();
+/// let mut optimizer = Optimizer::new();
+/// optimizer.set_image_resolution(Some(150.0));
+/// optimizer.set_jpeg_quality(Some(75));
+/// let report = optimizer.optimize(&mut doc).unwrap();
+/// println!("{report}");
+/// doc.write_to_file("merged-optimized.pdf").unwrap();
+/// ```
+pub struct Optimizer {
+    remove_unused: bool,
+    minify_content: bool,
+    image_resolution: Option<f64>,
+    jpeg_quality: Option<u8>,
+    deduplicate: bool,
+    recompress: bool,
+}
+
+/// Result of an optimization.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    /// Size of the whole file written before the optimization
+    pub original_size: usize,
+    /// Size of the whole file written after the optimization
+    pub optimized_size: usize,
+    /// Unreachable objects removed
+    pub removed_objects: usize,
+    /// Duplicates merged into an identical object
+    pub merged_objects: usize,
+    pub minified_contents: usize,
+    pub downsampled_images: usize,
+    pub requantized_images: usize,
+    pub recompressed_streams: usize,
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self {
+            remove_unused: true,
+            minify_content: true,
+            image_resolution: None,
+            jpeg_quality: None,
+            deduplicate: true,
+            recompress: true,
+        }
+    }
+
+    /// Removes the objects not reachable from the trailer `/Root` and `/Info`.
+    pub fn set_remove_unused(&mut self, remove_unused: bool) {
+        self.remove_unused = remove_unused;
+    }
+
+    /// Rewrites the content streams with the shortest operators. Numbers are
+    /// written with up to 4 decimals.
+    pub fn set_minify_content(&mut self, minify_content: bool) {
+        self.minify_content = minify_content;
+    }
+
+    /// Downsamples the images to the resolution in pixels per inch at the
+    /// largest size they are drawn on the pages, `None` to keep them.
+    pub fn set_image_resolution(&mut self, ppi: Option<f64>) {
+        self.image_resolution = ppi;
+    }
+
+    /// Re-encodes the JPEG images at the quality (1-100), `None` to keep them.
+    pub fn set_jpeg_quality(&mut self, quality: Option<u8>) {
+        self.jpeg_quality = quality;
+    }
+
+    /// Merges identical streams, fonts, font descriptors and colour spaces.
+    pub fn set_deduplicate(&mut self, deduplicate: bool) {
+        self.deduplicate = deduplicate;
+    }
+
+    /// Compresses the streams with Flate at the best level.
+    pub fn set_recompress(&mut self, recompress: bool) {
+        self.recompress = recompress;
+    }
+
+    /// Optimizes the document. Save it with [`LoadedDoc::to_bytes`], as an
+    /// incremental update keeps the original objects.
+    pub fn optimize(&self, doc: &mut LoadedDoc) -> Result<Report, Error> {
+        let mut report = Report {
+            original_size: doc.to_bytes().len(),
+            ..Default::default()
+        };
+
+        if self.remove_unused {
+            report.removed_objects += doc.remove_unreferenced();
+        }
+        if self.minify_content {
+            report.minified_contents = minify_contents(doc);
+        }
+        if self.image_resolution.is_some() || self.jpeg_quality.is_some() {
+            let (downsampled, requantized) = self.resample_images(doc)?;
+            report.downsampled_images = downsampled;
+            report.requantized_images = requantized;
+        }
+        if self.deduplicate {
+            report.merged_objects = deduplicate(doc);
+        }
+        if self.recompress {
+            report.recompressed_streams = recompress(doc);
+        }
+        if self.remove_unused {
+            report.removed_objects += doc.remove_unreferenced();
+        }
+
+        report.optimized_size = doc.to_bytes().len();
+        Ok(report)
+    }
+
+    /// Optimizes a generated document, returning it loaded from its bytes.
+    pub fn optimize_doc(&self, doc: &mut Doc) -> Result<(LoadedDoc, Report), Error> {
+        let mut loaded = LoadedDoc::load(doc.to_bytes())?;
+        let report = self.optimize(&mut loaded)?;
+        Ok((loaded, report))
+    }
+
+    /// Returns the number of downsampled and re-encoded images.
+    fn resample_images(&self, doc: &mut LoadedDoc) -> Result<(usize, usize), Error> {
+        let sizes = if self.image_resolution.is_some() { drawn_sizes(doc) } else { HashMap::new() };
+        let mut downsampled = 0;
+        let mut requantized = 0;
+
+        let ids: Vec<Id> = doc.ids().collect();
+        for id in ids {
+            let Some(stream) = doc.get(id).and_then(|o| o.as_stream()) else {
+                continue;
+            };
+            let target = match (self.image_resolution, sizes.get(&id)) {
+                (Some(ppi), Some((width, height))) => Some((
+                    (width / 72.0 * ppi).ceil().max(1.0) as usize,
+                    (height / 72.0 * ppi).ceil().max(1.0) as usize,
+                )),
+                _ => None,
+            };
+            let Some(mut image) = ImageData::read(doc, stream) else {
+                continue;
+            };
+            let jpeg = image.jpeg;
+
+            let resized = target.is_some_and(|(w, h)| image.downsample(w, h));
+            let quality = match (resized, self.jpeg_quality) {
+                (true, quality) => quality.unwrap_or(DEFAULT_JPEG_QUALITY),
+                (false, Some(quality)) if jpeg => quality,
+                _ => continue,
+            };
+            let Some(new) = image.write(stream, quality) else {
+                continue;
+            };
+            if !resized && new.raw_data().len() >= stream.raw_data().len() {
+                continue;
+            }
+            if resized {
+                downsampled += 1;
+            } else {
+                requantized += 1;
+            }
+            doc.set(id, new);
+        }
+        Ok((downsampled, requantized))
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let saved = self.original_size as f64 - self.optimized_size as f64;
+        let percent = if self.original_size > 0 { saved * 100.0 / self.original_size as f64 } else { 0.0 };
+        writeln!(f, "{} -> {} bytes ({:.1}% smaller)", self.original_size, self.optimized_size, percent)?;
+        writeln!(f, "removed objects: {}", self.removed_objects)?;
+        writeln!(f, "merged objects: {}", self.merged_objects)?;
+        writeln!(f, "minified contents: {}", self.minified_contents)?;
+        writeln!(f, "downsampled images: {}", self.downsampled_images)?;
+        writeln!(f, "requantized images: {}", self.requantized_images)?;
+        write!(f, "recompressed streams: {}", self.recompressed_streams)
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn filter_names(dict: &Dictionary) -> Vec<&str> {
+    match dict.get("Filter") {
+        Some(Object::Name(n)) => vec![n.as_str()],
+        Some(Object::Array(a)) => a.iter().filter_map(|f| f.as_name()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn has_image_filter(dict: &Dictionary) -> bool {
+    filter_names(dict).iter().any(|f| IMAGE_FILTERS.contains(f))
+}
+
+/// Returns the content written again from the parsed operations, `None`
+/// unless shorter.
+fn minified(data: &[u8]) -> Option<Vec<u8>> {
+    let bytes = operation::to_bytes(&operation::parse(data).ok()?);
+    (bytes.len() < data.len()).then_some(bytes)
+}
+
+/// Minifies the content streams of the pages and the form XObjects and
+/// returns how many were rewritten.
+fn minify_contents(doc: &mut LoadedDoc) -> usize {
+    let mut count = 0;
+    let mut streams = BTreeSet::new();
+    for page_id in doc.page_ids() {
+        match doc.page_attribute(page_id, "Contents") {
+            Some(Object::Array(a)) if a.len() > 1 => {
+                // Operations may be split between the streams
+                let Ok(content) = doc.page_content(page_id) else {
+                    continue;
+                };
+                if let Some(bytes) = minified(&content)
+                    && let Ok(operations) = operation::parse(&bytes)
+                    && doc.set_page_operations(page_id, &operations).is_ok() {
+                    count += 1;
+                }
+            }
+            Some(Object::Array(a)) => streams.extend(a.iter().filter_map(|c| c.as_reference())),
+            _ => {
+                let contents = doc.get(page_id).and_then(|p| p.as_dict()).and_then(|p| p.get_reference("Contents"));
+                streams.extend(contents);
+            }
+        }
+    }
+    streams.extend(doc.ids().filter(|id| {
+        doc.get(*id).and_then(|o| o.as_stream()).is_some_and(|s| s.dict.get_name("Subtype") == Some("Form"))
+    }));
+
+    for id in streams {
+        let Some(stream) = doc.get(id).and_then(|o| o.as_stream()) else {
+            continue;
+        };
+        if has_image_filter(&stream.dict) {
+            continue;
+        }
+        let Some(bytes) = stream.decoded_data().ok().and_then(|d| minified(&d)) else {
+            continue;
+        };
+        let mut stream = stream.clone();
+        stream.set_decoded_data(bytes);
+        doc.set(id, stream);
+        count += 1;
+    }
+    count
+}
+
+/// Returns the largest width and height in points each image, and its soft
+/// mask, is drawn at on the pages.
+fn drawn_sizes(doc: &LoadedDoc) -> HashMap<Id, (f64, f64)> {
+    let mut sizes = HashMap::new();
+    for page_id in doc.page_ids() {
+        let Ok(operations) = doc.page_operations(page_id) else {
+            continue;
+        };
+        let resources = doc.page_attribute(page_id, "Resources").and_then(|r| r.as_dict());
+        collect_sizes(doc, &operations, resources, IDENTITY, 0, &mut sizes);
+    }
+    sizes
+}
+
+fn collect_sizes(
+    doc: &LoadedDoc,
+    operations: &[Operation],
+    resources: Option<&Dictionary>,
+    ctm: Matrix,
+    depth: usize,
+    sizes: &mut HashMap<Id, (f64, f64)>,
+) {
+    let mut stack = Vec::new();
+    let mut ctm = ctm;
+    for op in operations {
+        match op {
+            Operation::SaveState => stack.push(ctm),
+            Operation::RestoreState => ctm = stack.pop().unwrap_or(ctm),
+            Operation::ConcatMatrix(m) => ctm = concat(m, &ctm),
+            Operation::DrawXObject(name) => {
+                let Some(reference) = resources
+                    .and_then(|r| doc.resolve_entry(r, "XObject"))
+                    .and_then(|x| x.as_dict())
+                    .and_then(|x| x.get(name.as_str()))
+                    .and_then(|x| x.as_reference()) else {
+                    continue;
+                };
+                let Some(stream) = doc.get(reference).and_then(|o| o.as_stream()) else {
+                    continue;
+                };
+                match stream.dict.get_name("Subtype") {
+                    Some("Image") => {
+                        let size = (ctm[0].hypot(ctm[1]), ctm[2].hypot(ctm[3]));
+                        let mask = stream.dict.get_reference("SMask");
+                        for id in std::iter::once(reference).chain(mask) {
+                            let entry = sizes.entry(id).or_insert((0.0, 0.0));
+                            *entry = (entry.0.max(size.0), entry.1.max(size.1));
+                        }
+                    }
+                    Some("Form") if depth < MAX_FORM_DEPTH => {
+                        let matrix = doc.resolve_entry(&stream.dict, "Matrix")
+                            .and_then(|m| m.as_array())
+                            .map_or(IDENTITY, |m| to_matrix(m));
+                        let form_resources = doc.resolve_entry(&stream.dict, "Resources")
+                            .and_then(|r| r.as_dict())
+                            .or(resources);
+                        if let Ok(form_operations) = stream.decoded_data().and_then(|d| operation::parse(&d)) {
+                            collect_sizes(doc, &form_operations, form_resources, concat(&matrix, &ctm), depth + 1, sizes);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Samples of an image XObject with 8 bits per component.
+struct ImageData {
+    width: usize,
+    height: usize,
+    components: usize,
+    samples: Vec<u8>,
+    /// Stored with DCTDecode
+    jpeg: bool,
+}
+
+impl ImageData {
+    fn read(doc: &LoadedDoc, stream: &Stream) -> Option<Self> {
+        let dict = &stream.dict;
+        if dict.get_name("Subtype") != Some("Image")
+            || dict.get("ImageMask").and_then(|m| m.as_bool()) == Some(true) || dict.get_i64("BitsPerComponent") != Some(8) {
+            return None;
+        }
+        let width = usize::try_from(dict.get_i64("Width")?).ok().filter(|w| *w > 0)?;
+        let height = usize::try_from(dict.get_i64("Height")?).ok().filter(|h| *h > 0)?;
+        let components = match dict.get("ColorSpace") {
+            Some(space) => components(doc, space)?,
+            // Soft masks are DeviceGray
+            None => 1,
+        };
+
+        let filters = filter_names(dict);
+        let jpeg = matches!(filters.last(), Some(&"DCTDecode") | Some(&"DCT"));
+        let mut samples = stream.decoded_data().ok()?;
+        if jpeg {
+            if components == 4 || filters[..filters.len() - 1].iter().any(|f| IMAGE_FILTERS.contains(f)) {
+                return None;
+            }
+            let params = match doc.resolve_entry(dict, "DecodeParms") {
+                Some(Object::Array(a)) => a.last().map(|p| doc.resolve(p)),
+                p => p,
+            };
+            let transform = params.and_then(|p| p.as_dict()).and_then(|p| p.get_i64("ColorTransform"));
+            let image = dct::decode(&samples, transform.map(|t| t != 0)).ok()?;
+            if (image.width, image.height, image.components) != (width, height, components) {
+                return None;
+            }
+            samples = image.samples;
+        } else if has_image_filter(dict) || samples.len() < width * height * components {
+            return None;
+        }
+        Some(Self { width, height, components, samples, jpeg })
+    }
+
+    /// Box filters the image down to the size, returning false if it is not
+    /// smaller.
+    fn downsample(&mut self, width: usize, height: usize) -> bool {
+        let (width, height) = (width.min(self.width), height.min(self.height));
+        if width == self.width && height == self.height {
+            return false;
+        }
+        let n = self.components;
+        let mut samples = Vec::with_capacity(width * height * n);
+        for y in 0..height {
+            let (y0, y1) = (y * self.height / height, ((y + 1) * self.height / height).max(y * self.height / height + 1));
+            for x in 0..width {
+                let (x0, x1) = (x * self.width / width, ((x + 1) * self.width / width).max(x * self.width / width + 1));
+                let count = ((y1 - y0) * (x1 - x0)) as u32;
+                for c in 0..n {
+                    let mut sum = 0u32;
+                    for sy in y0..y1 {
+                        for sx in x0..x1 {
+                            sum += self.samples[(sy * self.width + sx) * n + c] as u32;
+                        }
+                    }
+                    samples.push(((sum + count / 2) / count) as u8);
+                }
+            }
+        }
+        self.width = width;
+        self.height = height;
+        self.samples = samples;
+        true
+    }
+
+    /// Returns the image stream with the samples, as JPEG if it was.
+    fn write(&self, original: &Stream, quality: u8) -> Option<Stream> {
+        let mut dict = original.dict.clone();
+        dict.set("Width", self.width as i64);
+        dict.set("Height", self.height as i64);
+        dict.remove("DecodeParms");
+        if self.jpeg {
+            let image = dct::Image {
+                width: self.width,
+                height: self.height,
+                components: self.components,
+                samples: self.samples.clone(),
+            };
+            dict.set("Filter", Object::name("DCTDecode"));
+            Some(Stream::new(dict, dct::encode(&image, quality).ok()?))
+        } else {
+            dict.set("Filter", Object::name("FlateDecode"));
+            Some(Stream::new(dict, flate::encode(&self.samples)))
+        }
+    }
+}
+
+fn components(doc: &LoadedDoc, space: &Object) -> Option<usize> {
+    let space = doc.resolve(space);
+    let family = match space {
+        Object::Array(a) => doc.resolve(a.first()?).as_name()?,
+        _ => space.as_name()?,
+    };
+    match family {
+        "DeviceGray" | "G" | "CalGray" | "Separation" => Some(1),
+        "DeviceRGB" | "RGB" | "CalRGB" | "Lab" => Some(3),
+        "DeviceCMYK" | "CMYK" => Some(4),
+        "ICCBased" => {
+            let profile = doc.resolve(space.as_array()?.get(1)?).as_stream()?;
+            usize::try_from(profile.dict.get_i64("N")?).ok()
+        }
+        "DeviceN" => Some(doc.resolve(space.as_array()?.get(1)?).as_array()?.len()),
+        // Indexed images are not averaged
+        _ => None,
+    }
+}
+
+/// Returns whether the object may be merged with an identical one.
+fn is_mergeable(object: &Object) -> bool {
+    match object {
+        Object::Stream(s) => !s.dict.get_name("Type").is_some_and(|t| UNIQUE_TYPES.contains(&t)),
+        Object::Dictionary(d) => matches!(d.get_name("Type"), Some("Font" | "FontDescriptor" | "Encoding")),
+        Object::Array(a) => matches!(
+            a.first().and_then(|f| f.as_name()),
+            Some("ICCBased" | "Indexed" | "Separation" | "DeviceN" | "CalGray" | "CalRGB" | "Lab" | "Pattern")
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the bytes identifying the object: streams compare by the decoded
+/// data and the dictionary without the filters.
+fn identity(object: &Object) -> Vec<u8> {
+    match object {
+        Object::Stream(s) if !has_image_filter(&s.dict) && let Ok(data) = s.decoded_data() => {
+            let mut dict = s.dict.clone();
+            dict.remove("Length");
+            dict.remove("Filter");
+            dict.remove("DecodeParms");
+            let mut bytes = Object::Dictionary(dict).to_bytes();
+            bytes.extend(data);
+            bytes
+        }
+        _ => object.to_bytes(),
+    }
+}
+
+/// Merges the identical objects until none is left and returns how many
+/// were removed.
+fn deduplicate(doc: &mut LoadedDoc) -> usize {
+    let mut merged = 0;
+    loop {
+        // The first object of each hash and bytes is kept
+        let mut kept: HashMap<u64, Vec<(Vec<u8>, Id)>> = HashMap::new();
+        let mut duplicates: HashMap<Id, Id> = HashMap::new();
+        let ids: Vec<Id> = doc.ids().collect();
+        for id in ids {
+            let Some(object) = doc.get(id).filter(|o| is_mergeable(o)) else {
+                continue;
+            };
+            let bytes = identity(object);
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            let bucket = kept.entry(hasher.finish()).or_default();
+            match bucket.iter().find(|(b, _)| *b == bytes) {
+                Some((_, keeper)) => {
+                    duplicates.insert(id, *keeper);
+                }
+                None => bucket.push((bytes, id)),
+            }
+        }
+        if duplicates.is_empty() {
+            return merged;
+        }
+
+        let mut map = |id: Id| duplicates.get(&id).copied().unwrap_or(id);
+        let ids: Vec<Id> = doc.ids().collect();
+        for id in ids {
+            let refers = doc.get(id).is_some_and(|o| o.references().iter().any(|r| duplicates.contains_key(r)));
+            if refers && let Some(object) = doc.get_mut(id) {
+                object.map_references(&mut map);
+            }
+        }
+        for (_, value) in doc.trailer_mut().iter_mut() {
+            value.map_references(&mut map);
+        }
+        for id in duplicates.keys() {
+            doc.remove_object(*id);
+        }
+        merged += duplicates.len();
+    }
+}
+
+/// Compresses the streams with Flate when smaller and returns how many were
+/// replaced. Metadata is left readable.
+fn recompress(doc: &mut LoadedDoc) -> usize {
+    let mut count = 0;
+    let ids: Vec<Id> = doc.ids().collect();
+    for id in ids {
+        let Some(stream) = doc.get(id).and_then(|o| o.as_stream()) else {
+            continue;
+        };
+        if stream.dict.get_name("Type") == Some("Metadata") || has_image_filter(&stream.dict) {
+            continue;
+        }
+        let Ok(data) = stream.decoded_data() else {
+            continue;
+        };
+        let encoded = flate::encode(&data);
+        if encoded.len() >= stream.raw_data().len() {
+            continue;
+        }
+        let mut dict = stream.dict.clone();
+        dict.remove("DecodeParms");
+        dict.set("Filter", Object::name("FlateDecode"));
+        doc.set(id, Stream::new(dict, encoded));
+        count += 1;
+    }
+    count
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::page::Page;
+    use crate::pdf::font::Font;
+
+    fn doc_with_duplicates() -> LoadedDoc {
+        let mut doc = Doc::new(Version::V1_7);
+        for _ in 0..3 {
+            let mut page = Page::new(MediaBox::A4);
+            page.resources().add_font("F0", Font::new("Helvetica"));
+            for y in 0..10 {
+                page.contents().fill_text("F0", 12, Pos { x: 72, y: 700 - y * 20 }, "Hello, world");
+            }
+            doc.push_page(page);
+        }
+        LoadedDoc::load(doc.to_bytes()).unwrap()
+    }
+
+    fn image_stream(width: usize, height: usize, filter: Option<&str>, data: Vec<u8>) -> Stream {
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::name("XObject"));
+        dict.set("Subtype", Object::name("Image"));
+        dict.set("Width", width as i64);
+        dict.set("Height", height as i64);
+        dict.set("ColorSpace", Object::name("DeviceRGB"));
+        dict.set("BitsPerComponent", 8i64);
+        if let Some(filter) = filter {
+            dict.set("Filter", Object::name(filter));
+        }
+        Stream::new(dict, data)
+    }
+
+    /// Draws the image at 72x72 points on the first page.
+    fn draw_image(doc: &mut LoadedDoc, image: Stream) -> Id {
+        let image = doc.add_object(image);
+        let page_id = doc.page_ids()[0];
+        let mut xobjects = Dictionary::new();
+        xobjects.set("Im0", Object::Reference(image));
+        let mut resources = Dictionary::new();
+        resources.set("XObject", xobjects);
+        let operations = [
+            Operation::SaveState,
+            Operation::ConcatMatrix([72.0, 0.0, 0.0, 72.0, 100.0, 100.0]),
+            Operation::DrawXObject(Name::new("Im0")),
+            Operation::RestoreState,
+        ];
+        doc.set_page_operations(page_id, &operations).unwrap();
+        doc.get_mut(page_id).unwrap().as_dict_mut().unwrap().set("Resources", resources);
+        image
+    }
+
+    #[test]
+    fn lossless() {
+        let mut doc = doc_with_duplicates();
+        let unused = doc.add_object(Object::text("unused"));
+        let text = operation::to_bytes(&doc.page_operations(doc.page_ids()[0]).unwrap());
+
+        let report = Optimizer::new().optimize(&mut doc).unwrap();
+        assert!(doc.get(unused).is_none());
+        assert_eq!(report.removed_objects, 1);
+        // Two of the three content streams
+        assert_eq!(report.merged_objects, 2);
+        assert!(report.recompressed_streams >= 1);
+        assert!(report.optimized_size < report.original_size);
+        assert!(report.to_string().starts_with(&format!("{} -> {} bytes", report.original_size, report.optimized_size)));
+
+        let reloaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let pages = reloaded.page_ids();
+        assert_eq!(pages.len(), 3);
+        for page_id in pages {
+            assert_eq!(operation::to_bytes(&reloaded.page_operations(page_id).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn downsample() {
+        let mut doc = doc_with_duplicates();
+        // 300 ppi at 72x72 points
+        let data: Vec<u8> = (0..300 * 300).flat_map(|i| [(i % 256) as u8, 0, 255]).collect();
+        let image = draw_image(&mut doc, image_stream(300, 300, None, data));
+
+        let mut optimizer = Optimizer::new();
+        optimizer.set_image_resolution(Some(150.0));
+        let report = optimizer.optimize(&mut doc).unwrap();
+        assert_eq!(report.downsampled_images, 1);
+        let image = doc.get(image).unwrap().as_stream().unwrap();
+        assert_eq!(image.dict.get_i64("Width"), Some(150));
+        assert_eq!(image.dict.get_i64("Height"), Some(150));
+        assert_eq!(image.decoded_data().unwrap().len(), 150 * 150 * 3);
+    }
+
+    #[test]
+    fn jpeg_quality() {
+        let mut doc = doc_with_duplicates();
+        let samples: Vec<u8> = (0..64 * 64).flat_map(|i| [(i % 64 * 4) as u8, (i / 64 * 4) as u8, 128]).collect();
+        let jpeg = dct::encode(&dct::Image { width: 64, height: 64, components: 3, samples }, 100).unwrap();
+        let size = jpeg.len();
+        let image = draw_image(&mut doc, image_stream(64, 64, Some("DCTDecode"), jpeg));
+
+        let mut optimizer = Optimizer::new();
+        optimizer.set_jpeg_quality(Some(50));
+        let report = optimizer.optimize(&mut doc).unwrap();
+        assert_eq!(report.requantized_images, 1);
+        let image = doc.get(image).unwrap().as_stream().unwrap();
+        assert!(image.raw_data().len() < size);
+        assert_eq!(image.dict.get_name("Filter"), Some("DCTDecode"));
+    }
+}
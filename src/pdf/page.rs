@@ -5,31 +5,235 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+use std::rc::Rc;
+
+use super::annotation::Annotation;
 use super::base::*;
 use super::contents::Contents;
+use super::error::Error;
+use super::import::ImportedPage;
 use super::resources::Resources;
 use super::utils::*;
 
+/// PDF32000-1:2008 7.7.3.3
+///
+/// The clockwise rotation of the page when displayed or printed (`/Rotate`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    /// Returns the rotation for a multiple of 90 degrees, negative values
+    /// being counterclockwise.
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        if degrees % 90 != 0 {
+            return None;
+        }
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::Deg0),
+            90 => Some(Self::Deg90),
+            180 => Some(Self::Deg180),
+            _ => Some(Self::Deg270),
+        }
+    }
+
+    pub fn degrees(&self) -> i32 {
+        match self {
+            Self::Deg0 => 0,
+            Self::Deg90 => 90,
+            Self::Deg180 => 180,
+            Self::Deg270 => 270,
+        }
+    }
+}
+
+/// PDF32000-1:2008 7.7.3.3
+///
+/// A page. The page boundaries (PDF32000-1:2008 14.11.2) must nest:
+///
+/// - CropBox and BleedBox are inside the MediaBox
+/// - TrimBox and ArtBox are inside the BleedBox
+///
+/// A missing CropBox defaults to the MediaBox, and missing BleedBox, TrimBox
+/// and ArtBox default to the CropBox.
 pub struct Page {
     pub id: Id,
-    parent_id: Id, // PageList id
-    media_box: MediaBox,
-    resources: Resources,
+    parent_id: Id, // Page tree node id
+    /// Attributes written on an ancestor page tree node instead.
+    hoisted: Vec<&'static str>,
+    media_box: Rectangle,
+    crop_box: Option<Rectangle>,
+    bleed_box: Option<Rectangle>,
+    trim_box: Option<Rectangle>,
+    art_box: Option<Rectangle>,
+    rotation: Rotation,
+    user_unit: Option<f64>,
+    /// Shared with the duplicates of the page until modified.
+    resources: Rc<Resources>,
     contents: Contents,
+    annotations: Vec<Annotation>,
+    /// Form field widgets placed on this page, set when the document is written.
+    widget_ids: Vec<Id>,
+    /// The resources and contents copied from a loaded document, written
+    /// instead of `resources` and `contents`.
+    imported: Option<ImportedPage>,
 }
 
 impl Page {
-    pub fn new(media_box: MediaBox) -> Self {
+    /// Creates a page with the MediaBox, e.g. `MediaBox::A4` or `PaperSize::B5.landscape()`.
+    pub fn new(media_box: impl Into<Rectangle>) -> Self {
         Page {
             id: Id::new_0(),
             parent_id: Id::new_0(),
-            media_box: media_box,
-            resources: Resources::new(),
+            hoisted: Vec::new(),
+            media_box: media_box.into(),
+            crop_box: None,
+            bleed_box: None,
+            trim_box: None,
+            art_box: None,
+            rotation: Rotation::Deg0,
+            user_unit: None,
+            resources: Rc::new(Resources::new()),
             contents: Contents::new(),
+            annotations: Vec::new(),
+            widget_ids: Vec::new(),
+            imported: None,
         }
     }
 
+    /// Returns the resources for modification. A page sharing the resources
+    /// with its duplicates gets its own copy.
     pub fn resources(&mut self) -> &mut Resources {
+        Rc::make_mut(&mut self.resources)
+    }
+
+    pub fn media_box(&self) -> Rectangle {
+        self.media_box
+    }
+
+    /// Returns the CropBox, or the MediaBox if not set.
+    pub fn crop_box(&self) -> Rectangle {
+        self.crop_box.unwrap_or(self.media_box)
+    }
+
+    /// Returns the BleedBox, or the CropBox if not set.
+    pub fn bleed_box(&self) -> Rectangle {
+        self.bleed_box.unwrap_or(self.crop_box())
+    }
+
+    /// Returns the TrimBox, or the CropBox if not set.
+    pub fn trim_box(&self) -> Rectangle {
+        self.trim_box.unwrap_or(self.crop_box())
+    }
+
+    /// Returns the ArtBox, or the CropBox if not set.
+    pub fn art_box(&self) -> Rectangle {
+        self.art_box.unwrap_or(self.crop_box())
+    }
+
+    /// Sets the MediaBox. Fails if the other boxes would not be inside.
+    pub fn set_media_box(&mut self, rect: impl Into<Rectangle>) -> Result<(), Error> {
+        self.set_boxes(|p| p.media_box = rect.into())
+    }
+
+    /// Sets the region to display or print.
+    pub fn set_crop_box(&mut self, rect: Rectangle) -> Result<(), Error> {
+        self.set_boxes(|p| p.crop_box = Some(rect))
+    }
+
+    /// Sets the region to clip to in production, including the bleed.
+    pub fn set_bleed_box(&mut self, rect: Rectangle) -> Result<(), Error> {
+        self.set_boxes(|p| p.bleed_box = Some(rect))
+    }
+
+    /// Sets the intended dimensions of the finished page after trimming.
+    pub fn set_trim_box(&mut self, rect: Rectangle) -> Result<(), Error> {
+        self.set_boxes(|p| p.trim_box = Some(rect))
+    }
+
+    /// Sets the extent of the meaningful content.
+    pub fn set_art_box(&mut self, rect: Rectangle) -> Result<(), Error> {
+        self.set_boxes(|p| p.art_box = Some(rect))
+    }
+
+    /// Applies the change if the boxes nest afterwards.
+    fn set_boxes(&mut self, f: impl FnOnce(&mut Self)) -> Result<(), Error> {
+        let saved = (self.media_box, self.crop_box, self.bleed_box, self.trim_box, self.art_box);
+        f(self);
+
+        // Only the boxes set explicitly, as the defaults may exceed the BleedBox
+        let checks = [
+            ("CropBox", self.crop_box, "MediaBox", self.media_box),
+            ("BleedBox", self.bleed_box, "MediaBox", self.media_box),
+            ("TrimBox", self.trim_box, "BleedBox", self.bleed_box()),
+            ("ArtBox", self.art_box, "BleedBox", self.bleed_box()),
+        ];
+        for (inner_name, inner, outer_name, outer) in checks {
+            if let Some(inner) = inner && !outer.contains(&inner) {
+                (self.media_box, self.crop_box, self.bleed_box, self.trim_box, self.art_box) = saved;
+                return Err(Error::InvalidValue(format!(
+                    "{} {} is not inside {} {}", inner_name, inner.to_pdf_string(), outer_name, outer.to_pdf_string())));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the size of default user space units in 1/72 inch.
+    pub fn user_unit(&self) -> f64 {
+        self.user_unit.unwrap_or(1.0)
+    }
+
+    /// Sets the size of default user space units in 1/72 inch (PDF 1.6),
+    /// e.g. 10.0 for a poster larger than the 14,400 unit limit of a page.
+    pub fn set_user_unit(&mut self, user_unit: f64) -> Result<(), Error> {
+        if !(user_unit.is_finite() && user_unit > 0.0) {
+            return Err(Error::InvalidValue(format!("UserUnit {user_unit}")));
+        }
+        self.user_unit = (user_unit != 1.0).then_some(user_unit);
+        Ok(())
+    }
+
+    /// Returns a copy of the page sharing the resources. The contents are
+    /// copied and the annotations are not.
+    pub fn duplicate(&self) -> Page {
+        Page {
+            id: Id::new_0(),
+            parent_id: Id::new_0(),
+            hoisted: Vec::new(),
+            media_box: self.media_box,
+            crop_box: self.crop_box,
+            bleed_box: self.bleed_box,
+            trim_box: self.trim_box,
+            art_box: self.art_box,
+            rotation: self.rotation,
+            user_unit: self.user_unit,
+            resources: Rc::clone(&self.resources),
+            contents: self.contents.clone(),
+            annotations: Vec::new(),
+            widget_ids: Vec::new(),
+            imported: self.imported.clone(),
+        }
+    }
+
+    /// Returns true if the pages share the resources.
+    pub fn shares_resources_with(&self, other: &Page) -> bool {
+        Rc::ptr_eq(&self.resources, &other.resources)
+    }
+
+    pub(crate) fn resources_rc_mut(&mut self) -> &mut Rc<Resources> {
         &mut self.resources
     }
 
@@ -37,32 +241,145 @@ impl Page {
         &mut self.contents
     }
 
+    /// Adds the annotation to the page.
+    pub fn add_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    pub fn annotations(&self) -> &Vec<Annotation> {
+        &self.annotations
+    }
+
+    /// Returns true if the page is copied from a loaded document, see `Doc::import_pages`.
+    pub fn is_imported(&self) -> bool {
+        self.imported.is_some()
+    }
+
+    pub(crate) fn imported(&self) -> Option<&ImportedPage> {
+        self.imported.as_ref()
+    }
+
+    pub(crate) fn imported_mut(&mut self) -> Option<&mut ImportedPage> {
+        self.imported.as_mut()
+    }
+
+    pub(crate) fn set_imported(&mut self, imported: ImportedPage) {
+        self.imported = Some(imported);
+    }
+
+    pub(crate) fn set_widget_ids(&mut self, widget_ids: Vec<Id>) {
+        self.widget_ids = widget_ids;
+    }
+
+    /// Sets the parent page tree node and the attributes the page inherits from it.
+    pub(crate) fn set_parent(&mut self, parent_id: Id, hoisted: Vec<&'static str>) {
+        self.parent_id = parent_id;
+        self.hoisted = hoisted;
+    }
+
+    /// Returns the inheritable attributes (PDF32000-1:2008 7.7.3.4) as PDF strings.
+    /// Valid after the ids are assigned.
+    pub(crate) fn inheritable_attributes(&self) -> Vec<(&'static str, String)> {
+        let resources = match &self.imported {
+            Some(imported) => imported.entry_string("Resources").unwrap_or_else(|| String::from("<< >>")),
+            None => self.resources.id.to_ref_string(),
+        };
+        let mut attributes = vec![
+            ("MediaBox", self.media_box.to_pdf_string()),
+            ("Resources", resources),
+        ];
+        if let Some(crop_box) = &self.crop_box {
+            attributes.push(("CropBox", crop_box.to_pdf_string()));
+        }
+        if self.rotation != Rotation::Deg0 {
+            attributes.push(("Rotate", self.rotation.degrees().to_string()));
+        }
+        attributes
+    }
+
+    fn get_boundaries_string(&self) -> String {
+        let boxes = [("BleedBox", &self.bleed_box), ("TrimBox", &self.trim_box), ("ArtBox", &self.art_box)];
+        let mut s: String = boxes.iter()
+            .filter_map(|(key, rect)| rect.map(|r| format!("   /{} {}\n", key, r.to_pdf_string())))
+            .collect();
+        if let Some(user_unit) = self.user_unit {
+            s.push_str(&format!("   /UserUnit {}\n", user_unit));
+        }
+        s
+    }
+
+    fn get_imported_entries_string(&self) -> String {
+        match &self.imported {
+            Some(imported) => imported.other_entries().iter()
+                .map(|(key, value)| format!("   {} {}\n", key, value))
+                .collect(),
+            None => String::new(),
+        }
+    }
+
     pub fn reassign_ids(&mut self, id_factory: &mut IdFactory) {
         self.id = id_factory.next_id();
-        self.parent_id = id_factory.page_list_id().clone();
-        self.resources.assign_ids(id_factory);
-        self.contents.id = id_factory.next_id();
+        if self.imported.is_none() {
+            // Shared resources are assigned by the page list
+            if let Some(resources) = Rc::get_mut(&mut self.resources) {
+                resources.assign_ids(id_factory);
+            }
+            self.contents.id = id_factory.next_id();
+        }
+        for annot in &mut self.annotations {
+            annot.set_page_id(self.id);
+            annot.assign_ids(id_factory);
+        }
     }
 
     fn get_contents_string(&self) -> String {
-        format!("{}", self.contents.id.to_ref_string())
+        match &self.imported {
+            Some(imported) => imported.entry_string("Contents").unwrap_or_else(|| String::from("[]")),
+            None => self.contents.id.to_ref_string(),
+        }
+    }
+
+    fn get_annots_string(&self) -> String {
+        let imported = self.imported.as_ref().map(|i| i.annotation_ids()).unwrap_or_default();
+        if imported.is_empty() && self.annotations.is_empty() && self.widget_ids.is_empty() {
+            return String::new();
+        }
+
+        let refs = imported.into_iter()
+            .chain(self.annotations.iter().map(|a| *a.id()))
+            .chain(self.widget_ids.iter().copied())
+            .map(|id| id.to_ref_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("   /Annots [{refs}]\n")
+    }
+
+    fn get_attributes_string(&self) -> String {
+        self.inheritable_attributes().iter()
+            .filter(|(key, _)| !self.hoisted.contains(key))
+            .map(|(key, value)| format!("   /{} {}\n", key, value))
+            .collect()
     }
 
     pub fn to_string(&self, indent_size: usize) -> String {
         indent(&format!(concat!(
             "{} obj\n",
             "<< /Type /Page\n",
-            "   /MediaBox {}\n",
-            "   /Resources {}\n",
+            "{}",
+            "{}",
             "   /Parent {}\n",
             "   /Contents {}\n",
+            "{}",
+            "{}",
             ">>\n",
             "endobj"),
-            self.id.to_string(),
-            self.media_box.to_string(),
-            self.resources.id.to_ref_string(),
+            self.id,
+            self.get_attributes_string(),
+            self.get_boundaries_string(),
             self.parent_id.to_ref_string(),
-            self.get_contents_string()),
+            self.get_contents_string(),
+            self.get_annots_string(),
+            self.get_imported_entries_string()),
             indent_size)
     }
 }
@@ -80,8 +397,13 @@ impl PdfObject for Page {
         let mut list: Vec<&dyn PdfObject> = Vec::new();
 
         list.push(self);
-        list.append(&mut self.resources.get_objects());
-        list.append(&mut self.contents.get_objects());
+        if self.imported.is_none() {
+            list.append(&mut self.resources.get_objects());
+            list.append(&mut self.contents.get_objects());
+        }
+        for annot in &self.annotations {
+            list.append(&mut annot.get_objects());
+        }
 
         list
     }
@@ -90,3 +412,46 @@ impl PdfObject for Page {
         self.to_string(indent_depth).into_bytes()
     }
 }
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation() {
+        assert_eq!(Rotation::from_degrees(90), Some(Rotation::Deg90));
+        assert_eq!(Rotation::from_degrees(-90), Some(Rotation::Deg270));
+        assert_eq!(Rotation::from_degrees(540), Some(Rotation::Deg180));
+        assert_eq!(Rotation::from_degrees(45), None);
+    }
+
+    #[test]
+    fn boxes() {
+        let mut page = Page::new(MediaBox::A4);
+        assert_eq!(page.trim_box(), Rectangle::new(0.0, 0.0, 595.0, 842.0));
+
+        page.set_bleed_box(Rectangle::new(10.0, 10.0, 585.0, 832.0)).unwrap();
+        page.set_trim_box(Rectangle::new(19.0, 19.0, 576.0, 823.0)).unwrap();
+        assert!(page.set_art_box(Rectangle::new(0.0, 0.0, 100.0, 100.0)).is_err());
+        assert!(page.set_media_box(Rectangle::new(0.0, 0.0, 500.0, 500.0)).is_err());
+        assert_eq!(page.media_box(), Rectangle::new(0.0, 0.0, 595.0, 842.0));
+        assert_eq!(page.art_box(), page.crop_box());
+
+        page.set_rotation(Rotation::Deg90);
+        assert!(page.set_user_unit(0.0).is_err());
+        page.set_user_unit(2.0).unwrap();
+
+        let s = page.to_string(0);
+        assert!(s.contains("/MediaBox [0 0 595 842]\n"));
+        assert!(s.contains("/Rotate 90\n"));
+        assert!(s.contains("/BleedBox [10 10 585 832]\n"));
+        assert!(s.contains("/TrimBox [19 19 576 823]\n"));
+        assert!(s.contains("/UserUnit 2\n"));
+        assert!(!s.contains("/CropBox"));
+        assert!(!s.contains("/ArtBox"));
+    }
+}
@@ -5,13 +5,30 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::rc::Rc;
+
 use super::base::*;
 use super::page::Page;
+use super::resources::Resources;
 use super::utils::indent;
 
+/// The default maximum number of kids of a page tree node.
+pub const DEFAULT_FAN_OUT: usize = 16;
+
+/// PDF32000-1:2008 7.7.3
+///
+/// The page tree. Pages are written under a balanced tree of `/Pages` nodes
+/// with at most `fan_out` kids each. Inheritable attributes shared by all
+/// the pages under a node are written on the node instead of the pages.
 pub struct PageList {
+    /// The root node id.
     pub id: Id,
     pages: Vec<Page>,
+    fan_out: usize,
+    /// Intermediate nodes built when the ids are assigned, the root first.
+    nodes: Vec<PageTreeNode>,
 }
 
 impl PageList {
@@ -19,6 +36,8 @@ impl PageList {
         PageList {
             id: Id::new_0(),
             pages: Vec::new(),
+            fan_out: DEFAULT_FAN_OUT,
+            nodes: Vec::new(),
         }
     }
 
@@ -26,35 +45,185 @@ impl PageList {
         self.pages.push(page);
     }
 
+    /// Inserts the page at the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, page: Page) {
+        self.pages.insert(index, page);
+    }
+
+    /// Removes the page at the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Page {
+        self.pages.remove(index)
+    }
+
+    /// Swaps the pages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.pages.swap(a, b);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Page> {
+        self.pages.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Page> {
+        self.pages.get_mut(index)
+    }
+
+    /// Sets the maximum number of kids of a node. Values less than 2 are read as 2.
+    pub fn set_fan_out(&mut self, fan_out: usize) {
+        self.fan_out = fan_out.max(2);
+    }
+
+    pub(crate) fn pages(&self) -> &Vec<Page> {
+        &self.pages
+    }
+
+    pub(crate) fn pages_mut(&mut self) -> &mut Vec<Page> {
+        &mut self.pages
+    }
+
     fn reassign_ids(&mut self, id_factory: &mut IdFactory) {
-        self.id = id_factory.page_list_id().clone();
+        self.id = id_factory.page_list_id();
+        self.assign_shared_resource_ids(id_factory);
         for page in &mut self.pages {
             page.assign_ids(id_factory);
         }
+
+        let attributes: Vec<Vec<(&'static str, String)>> = self.pages.iter()
+            .map(|p| p.inheritable_attributes())
+            .collect();
+        let mut nodes = Vec::new();
+        let mut parents = vec![(Id::new_0(), Vec::new()); self.pages.len()];
+        self.build_node(self.id, None, 0..self.pages.len(), &attributes, &[], id_factory, &mut nodes, &mut parents);
+        self.nodes = nodes;
+
+        for (page, (parent_id, hoisted)) in self.pages.iter_mut().zip(parents) {
+            page.set_parent(parent_id, hoisted);
+        }
     }
 
-    fn get_kids_string(&self) -> String {
-        let mut kids = String::new();
-        kids.push_str("[");
-        for page in &self.pages {
-            kids.push_str(&format!("{} ", page.id.to_ref_string()))
+    /// Assigns the ids of the resources shared by several pages once.
+    fn assign_shared_resource_ids(&mut self, id_factory: &mut IdFactory) {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of: HashMap<*const Resources, usize> = HashMap::new();
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            let resources = page.resources_rc_mut();
+            if Rc::strong_count(resources) > 1 {
+                let group = *group_of.entry(Rc::as_ptr(resources)).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[group].push(i);
+            }
         }
-        kids.push_str("]");
-        kids
+
+        for group in groups {
+            // Release the other references to modify the resources in place
+            for j in &group[1..] {
+                *self.pages[*j].resources_rc_mut() = Rc::default();
+            }
+            let resources = self.pages[group[0]].resources_rc_mut();
+            // Copied if a removed page still shares them
+            Rc::make_mut(resources).assign_ids(id_factory);
+            let resources = Rc::clone(resources);
+            for j in &group[1..] {
+                *self.pages[*j].resources_rc_mut() = Rc::clone(&resources);
+            }
+        }
+    }
+
+    /// Builds the node for the pages in the range and its descendants.
+    #[allow(clippy::too_many_arguments)]
+    fn build_node(
+        &self,
+        id: Id,
+        parent_id: Option<Id>,
+        range: Range<usize>,
+        attributes: &[Vec<(&'static str, String)>],
+        inherited: &[(&'static str, String)],
+        id_factory: &mut IdFactory,
+        nodes: &mut Vec<PageTreeNode>,
+        parents: &mut Vec<(Id, Vec<&'static str>)>,
+    ) {
+        // Attributes shared by all the pages and not already inherited with the same value
+        let pages = &attributes[range.clone()];
+        let shared: Vec<(&'static str, String)> = match pages.first() {
+            Some(first) => first.iter()
+                .filter(|a| pages.iter().all(|p| p.contains(a)))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        let hoisted: Vec<(&'static str, String)> = shared.iter()
+            .filter(|a| !inherited.contains(a))
+            .cloned()
+            .collect();
+        let mut inherited: Vec<(&'static str, String)> = inherited.iter()
+            .filter(|a| !hoisted.iter().any(|h| h.0 == a.0))
+            .cloned()
+            .collect();
+        inherited.extend(hoisted.iter().cloned());
+
+        let index = nodes.len();
+        nodes.push(PageTreeNode {
+            id,
+            parent_id,
+            kids: Vec::new(),
+            count: range.len(),
+            attributes: hoisted,
+        });
+
+        let kids = if range.len() <= self.fan_out {
+            for i in range.clone() {
+                // Only the attributes with the same value on the page are omitted
+                let keys = inherited.iter().filter(|a| attributes[i].contains(a)).map(|a| a.0).collect();
+                parents[i] = (id, keys);
+            }
+            self.pages[range].iter().map(|p| p.id).collect()
+        } else {
+            let mut kids = Vec::new();
+            for chunk in split(range, self.fan_out) {
+                let kid_id = id_factory.next_id();
+                kids.push(kid_id);
+                self.build_node(kid_id, Some(id), chunk, attributes, &inherited, id_factory, nodes, parents);
+            }
+            kids
+        };
+        nodes[index].kids = kids;
     }
 
     pub fn to_string(&self, indent_size: usize) -> String {
-        indent(&format!(concat!(
-            "{} obj\n",
-            "<< /Type /Pages\n",
-            "   /Count {}\n",
-            "   /Kids {}\n",
-            ">>\n",
-            "endobj"),
-            self.id.to_string(),
-            self.pages.len(),
-            self.get_kids_string()),
-            indent_size)
+        match self.nodes.first() {
+            Some(root) => root.to_string(indent_size),
+            None => PageTreeNode {
+                id: self.id,
+                parent_id: None,
+                kids: Vec::new(),
+                count: 0,
+                attributes: Vec::new(),
+            }.to_string(indent_size),
+        }
+    }
+}
+
+impl Default for PageList {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -70,11 +239,18 @@ impl PdfObject for PageList {
     fn get_objects(&self) -> Vec<&dyn PdfObject> {
         let mut list: Vec<&dyn PdfObject> = Vec::new();
 
-        list.push(self);
+        // Page tree nodes
+        if self.nodes.is_empty() {
+            list.push(self);
+        }
+        for node in &self.nodes {
+            list.push(node);
+        }
 
-        // Pages
+        // Pages, with the shared resources once
+        let mut ids = HashSet::new();
         for page in &self.pages {
-            list.append(&mut page.get_objects());
+            list.extend(page.get_objects().into_iter().filter(|o| ids.insert(*o.id())));
         }
 
         list
@@ -84,3 +260,172 @@ impl PdfObject for PageList {
         self.to_string(indent_depth).into_bytes()
     }
 }
+
+/// A `/Pages` node.
+struct PageTreeNode {
+    id: Id,
+    parent_id: Option<Id>,
+    kids: Vec<Id>,
+    /// Number of the leaf pages under the node.
+    count: usize,
+    /// Inheritable attributes written on this node.
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl PageTreeNode {
+    fn to_string(&self, indent_size: usize) -> String {
+        let parent = match self.parent_id {
+            Some(id) => format!("   /Parent {}\n", id.to_ref_string()),
+            None => String::new(),
+        };
+        let kids = self.kids.iter().map(|k| k.to_ref_string()).collect::<Vec<String>>().join(" ");
+        let attributes: String = self.attributes.iter()
+            .map(|(key, value)| format!("   /{} {}\n", key, value))
+            .collect();
+
+        indent(&format!(concat!(
+            "{} obj\n",
+            "<< /Type /Pages\n",
+            "{}",
+            "   /Count {}\n",
+            "   /Kids [{}]\n",
+            "{}",
+            ">>\n",
+            "endobj"),
+            self.id,
+            parent,
+            self.count,
+            kids,
+            attributes),
+            indent_size)
+    }
+}
+
+impl PdfObject for PageTreeNode {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, _id_factory: &mut IdFactory) {
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        vec![self]
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Splits the range into the fewest equal parts keeping the tree height minimal.
+fn split(range: Range<usize>, fan_out: usize) -> Vec<Range<usize>> {
+    // Capacity of a subtree one level lower
+    let mut capacity = fan_out;
+    while capacity * fan_out < range.len() {
+        capacity *= fan_out;
+    }
+    let parts = range.len().div_ceil(capacity);
+
+    let mut ranges = Vec::with_capacity(parts);
+    let mut start = range.start;
+    for i in 0..parts {
+        let len = range.len() / parts + usize::from(i < range.len() % parts);
+        ranges.push(start..start + len);
+        start += len;
+    }
+    ranges
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page_list(count: usize, fan_out: usize) -> PageList {
+        let mut list = PageList::new();
+        list.set_fan_out(fan_out);
+        for i in 0..count {
+            list.push(Page::new(if i < count / 2 { MediaBox::A4 } else { MediaBox::Letter }));
+        }
+        list.assign_ids(&mut IdFactory::new());
+        list
+    }
+
+    #[test]
+    fn split_balanced() {
+        assert_eq!(split(0..100, 10), vec![0..10, 10..20, 20..30, 30..40, 40..50, 50..60, 60..70, 70..80, 80..90, 90..100]);
+        assert_eq!(split(0..11, 10), vec![0..6, 6..11]);
+        assert_eq!(split(0..101, 10), vec![0..51, 51..101]);
+    }
+
+    #[test]
+    fn flat() {
+        let list = page_list(3, 4);
+        assert_eq!(list.id.to_string(), "1 0");
+        assert_eq!(list.nodes.len(), 1);
+        assert_eq!(list.nodes[0].kids.len(), 3);
+    }
+
+    #[test]
+    fn balanced() {
+        let list = page_list(20000, 16);
+        let root = &list.nodes[0];
+        assert_eq!(root.count, 20000);
+        assert!(list.nodes.iter().all(|n| n.kids.len() <= 16));
+
+        // All the leaves are at the same depth
+        let depth = |mut id: Id| {
+            let mut depth = 0;
+            while let Some(node) = list.nodes.iter().find(|n| n.kids.contains(&id)) {
+                id = node.id;
+                depth += 1;
+            }
+            depth
+        };
+        let depths: Vec<usize> = [0, 9999, 19999].iter().map(|i| depth(list.pages[*i].id)).collect();
+        assert_eq!(depths, [4, 4, 4]);
+    }
+
+    #[test]
+    fn shared_resources() {
+        let mut list = PageList::new();
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", crate::pdf::font::Font::new("Helvetica"));
+        list.push(page.duplicate());
+        list.push(page.duplicate());
+        list.push(page);
+        list.get_mut(2).unwrap().resources().add_font("F1", crate::pdf::font::Font::new("Courier"));
+        list.assign_ids(&mut IdFactory::new());
+
+        let objects = list.get_objects();
+        let count = |s: &str| objects.iter()
+            .filter(|o| String::from_utf8(o.to_bytes(0)).unwrap().contains(s))
+            .count();
+        assert_eq!(count("/BaseFont /Helvetica"), 2);
+        assert_eq!(count("/BaseFont /Courier"), 1);
+
+        let resources: Vec<String> = list.pages.iter().map(|p| p.inheritable_attributes()[1].1.clone()).collect();
+        assert_eq!(resources[0], resources[1]);
+        assert_ne!(resources[0], resources[2]);
+    }
+
+    #[test]
+    fn hoisting() {
+        let list = page_list(4, 2);
+        let root = list.nodes[0].to_string(0);
+        let a4 = list.nodes[1].to_string(0);
+        let page = list.pages[0].to_string(0);
+        assert!(!root.contains("/MediaBox"));
+        assert!(a4.contains("   /MediaBox [0 0 595 842]\n"));
+        assert!(!page.contains("/MediaBox"));
+        assert!(page.contains("/Resources"));
+    }
+}
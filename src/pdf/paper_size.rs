@@ -0,0 +1,276 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::base::Rectangle;
+
+/// Page orientation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Standard paper and envelope sizes.
+///
+/// ```
+/// use prspdf::{Page, PaperSize};
+///
+/// let page = Page::new(PaperSize::A3.landscape());
+/// assert_eq!(page.media_box().width().round(), 1191.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaperSize {
+    // ISO 216 A series
+    /// 841 x 1189 mm
+    A0,
+    /// 594 x 841 mm
+    A1,
+    /// 420 x 594 mm
+    A2,
+    /// 297 x 420 mm
+    A3,
+    /// 210 x 297 mm
+    A4,
+    /// 148 x 210 mm
+    A5,
+    /// 105 x 148 mm
+    A6,
+    /// 74 x 105 mm
+    A7,
+    /// 52 x 74 mm
+    A8,
+    /// 37 x 52 mm
+    A9,
+    /// 26 x 37 mm
+    A10,
+    // ISO 216 B series
+    /// 1000 x 1414 mm
+    B0,
+    /// 707 x 1000 mm
+    B1,
+    /// 500 x 707 mm
+    B2,
+    /// 353 x 500 mm
+    B3,
+    /// 250 x 353 mm
+    B4,
+    /// 176 x 250 mm
+    B5,
+    /// 125 x 176 mm
+    B6,
+    /// 88 x 125 mm
+    B7,
+    /// 62 x 88 mm
+    B8,
+    /// 44 x 62 mm
+    B9,
+    /// 31 x 44 mm
+    B10,
+    // ISO 269 C (envelopes and folders) series
+    /// 917 x 1297 mm
+    C0,
+    /// 648 x 917 mm
+    C1,
+    /// 458 x 648 mm
+    C2,
+    /// 324 x 458 mm
+    C3,
+    /// 229 x 324 mm
+    C4,
+    /// 162 x 229 mm
+    C5,
+    /// 114 x 162 mm
+    C6,
+    /// 81 x 114 mm
+    C7,
+    /// 57 x 81 mm
+    C8,
+    /// 40 x 57 mm
+    C9,
+    /// 28 x 40 mm
+    C10,
+    // JIS P 0138 B series
+    /// 1030 x 1456 mm
+    JisB0,
+    /// 728 x 1030 mm
+    JisB1,
+    /// 515 x 728 mm
+    JisB2,
+    /// 364 x 515 mm
+    JisB3,
+    /// 257 x 364 mm
+    JisB4,
+    /// 182 x 257 mm
+    JisB5,
+    /// 128 x 182 mm
+    JisB6,
+    /// 91 x 128 mm
+    JisB7,
+    /// 64 x 91 mm
+    JisB8,
+    /// 45 x 64 mm
+    JisB9,
+    /// 32 x 45 mm
+    JisB10,
+    // North American sizes
+    /// ANSI A, 8.5 x 11 in
+    Letter,
+    /// 8.5 x 14 in
+    Legal,
+    /// ANSI B, 11 x 17 in
+    Tabloid,
+    /// 17 x 22 in
+    AnsiC,
+    /// 22 x 34 in
+    AnsiD,
+    /// 34 x 44 in
+    AnsiE,
+    /// No. 9 envelope, 3.875 x 8.875 in
+    Envelope9,
+    /// No. 10 envelope, 4.125 x 9.5 in
+    Envelope10,
+    /// Monarch envelope, 3.875 x 7.5 in
+    Monarch,
+    // Envelopes
+    /// DL envelope, 110 x 220 mm
+    Dl,
+    /// Japanese Choukei 3 (長形3号) envelope, 120 x 235 mm
+    Choukei3,
+    /// Japanese Kakugata 2 (角形2号) envelope, 240 x 332 mm
+    Kakugata2,
+}
+
+impl PaperSize {
+    /// Returns the width and the height in points in portrait orientation.
+    pub fn size(&self) -> (f64, f64) {
+        match self {
+            Self::A0 => mm(841.0, 1189.0),
+            Self::A1 => mm(594.0, 841.0),
+            Self::A2 => mm(420.0, 594.0),
+            Self::A3 => mm(297.0, 420.0),
+            Self::A4 => mm(210.0, 297.0),
+            Self::A5 => mm(148.0, 210.0),
+            Self::A6 => mm(105.0, 148.0),
+            Self::A7 => mm(74.0, 105.0),
+            Self::A8 => mm(52.0, 74.0),
+            Self::A9 => mm(37.0, 52.0),
+            Self::A10 => mm(26.0, 37.0),
+            Self::B0 => mm(1000.0, 1414.0),
+            Self::B1 => mm(707.0, 1000.0),
+            Self::B2 => mm(500.0, 707.0),
+            Self::B3 => mm(353.0, 500.0),
+            Self::B4 => mm(250.0, 353.0),
+            Self::B5 => mm(176.0, 250.0),
+            Self::B6 => mm(125.0, 176.0),
+            Self::B7 => mm(88.0, 125.0),
+            Self::B8 => mm(62.0, 88.0),
+            Self::B9 => mm(44.0, 62.0),
+            Self::B10 => mm(31.0, 44.0),
+            Self::C0 => mm(917.0, 1297.0),
+            Self::C1 => mm(648.0, 917.0),
+            Self::C2 => mm(458.0, 648.0),
+            Self::C3 => mm(324.0, 458.0),
+            Self::C4 => mm(229.0, 324.0),
+            Self::C5 => mm(162.0, 229.0),
+            Self::C6 => mm(114.0, 162.0),
+            Self::C7 => mm(81.0, 114.0),
+            Self::C8 => mm(57.0, 81.0),
+            Self::C9 => mm(40.0, 57.0),
+            Self::C10 => mm(28.0, 40.0),
+            Self::JisB0 => mm(1030.0, 1456.0),
+            Self::JisB1 => mm(728.0, 1030.0),
+            Self::JisB2 => mm(515.0, 728.0),
+            Self::JisB3 => mm(364.0, 515.0),
+            Self::JisB4 => mm(257.0, 364.0),
+            Self::JisB5 => mm(182.0, 257.0),
+            Self::JisB6 => mm(128.0, 182.0),
+            Self::JisB7 => mm(91.0, 128.0),
+            Self::JisB8 => mm(64.0, 91.0),
+            Self::JisB9 => mm(45.0, 64.0),
+            Self::JisB10 => mm(32.0, 45.0),
+            Self::Letter => inch(8.5, 11.0),
+            Self::Legal => inch(8.5, 14.0),
+            Self::Tabloid => inch(11.0, 17.0),
+            Self::AnsiC => inch(17.0, 22.0),
+            Self::AnsiD => inch(22.0, 34.0),
+            Self::AnsiE => inch(34.0, 44.0),
+            Self::Envelope9 => inch(3.875, 8.875),
+            Self::Envelope10 => inch(4.125, 9.5),
+            Self::Monarch => inch(3.875, 7.5),
+            Self::Dl => mm(110.0, 220.0),
+            Self::Choukei3 => mm(120.0, 235.0),
+            Self::Kakugata2 => mm(240.0, 332.0),
+        }
+    }
+
+    pub fn portrait(&self) -> Rectangle {
+        self.with_orientation(Orientation::Portrait)
+    }
+
+    pub fn landscape(&self) -> Rectangle {
+        self.with_orientation(Orientation::Landscape)
+    }
+
+    /// Returns the rectangle at the origin in the orientation.
+    pub fn with_orientation(&self, orientation: Orientation) -> Rectangle {
+        let (w, h) = self.size();
+        match orientation {
+            Orientation::Portrait => Rectangle::new(0.0, 0.0, w, h),
+            Orientation::Landscape => Rectangle::new(0.0, 0.0, h, w),
+        }
+    }
+}
+
+impl From<PaperSize> for Rectangle {
+    /// Converts to the rectangle in portrait orientation.
+    fn from(size: PaperSize) -> Self {
+        size.portrait()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+const POINTS_PER_INCH: f64 = 72.0;
+
+fn mm(w: f64, h: f64) -> (f64, f64) {
+    (w * POINTS_PER_INCH / 25.4, h * POINTS_PER_INCH / 25.4)
+}
+
+fn inch(w: f64, h: f64) -> (f64, f64) {
+    (w * POINTS_PER_INCH, h * POINTS_PER_INCH)
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size() {
+        let (w, h) = PaperSize::A4.size();
+        assert_eq!((w.round(), h.round()), (595.0, 842.0));
+        assert_eq!(PaperSize::Letter.size(), (612.0, 792.0));
+        assert_eq!(PaperSize::Tabloid.landscape(), Rectangle::new(0.0, 0.0, 1224.0, 792.0));
+        assert_eq!(Rectangle::from(PaperSize::Envelope10), Rectangle::new(0.0, 0.0, 297.0, 684.0));
+    }
+
+    #[test]
+    fn series_halves() {
+        // Each size is the half of the previous one, rounded down to mm
+        for (a, b) in [(PaperSize::A3, PaperSize::A4), (PaperSize::B4, PaperSize::B5), (PaperSize::JisB4, PaperSize::JisB5)] {
+            let (aw, ah) = a.size();
+            let (bw, bh) = b.size();
+            assert_eq!(aw, bh);
+            assert!((ah / 2.0 - bw).abs() < 3.0);
+        }
+    }
+}
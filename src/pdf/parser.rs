@@ -0,0 +1,718 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 7.2 - 7.5 Lexical conventions, objects and file structure.
+
+use std::collections::BTreeMap;
+
+use super::base::*;
+use super::error::Error;
+use super::object::*;
+
+/// A cross-reference entry of an in-use object.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum XrefEntry {
+    Offset(usize, u32),
+    /// Object stream number and index in the stream.
+    Compressed(u32, usize),
+}
+
+/// The result of reading the cross-reference sections of a file.
+pub(crate) struct XrefInfo {
+    pub entries: BTreeMap<u32, XrefEntry>,
+    pub trailer: Dictionary,
+    pub startxref: usize,
+    pub is_stream: bool,
+}
+
+pub(crate) fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n' | 0x0c | 0x00)
+}
+
+pub(crate) fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+/// Object parser over the bytes of a file or an object stream.
+pub(crate) struct Parser<'a> {
+    data: &'a [u8],
+    pub pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos: pos.min(data.len()) }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while let Some(b) = self.peek() && b != b'\r' && b != b'\n' {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Reads a regular-character token such as a number or a keyword.
+    pub fn read_token(&mut self) -> &'a [u8] {
+        self.skip_whitespace();
+        let start = self.pos;
+        while let Some(b) = self.peek() && !is_whitespace(b) && !is_delimiter(b) {
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    /// Reads the keyword if it comes next.
+    pub fn accept(&mut self, keyword: &[u8]) -> bool {
+        let pos = self.pos;
+        if self.read_token() == keyword {
+            true
+        } else {
+            self.pos = pos;
+            false
+        }
+    }
+
+    fn expect(&mut self, keyword: &[u8]) -> Result<(), Error> {
+        if self.accept(keyword) {
+            Ok(())
+        } else {
+            Err(Error::parse(self.pos, &format!("expected {}", String::from_utf8_lossy(keyword))))
+        }
+    }
+
+    fn read_unsigned(&mut self) -> Option<u32> {
+        let pos = self.pos;
+        let token = self.read_token();
+        match std::str::from_utf8(token).ok().and_then(|s| s.parse().ok()) {
+            Some(v) => Some(v),
+            None => {
+                self.pos = pos;
+                None
+            }
+        }
+    }
+
+    /// Reads a direct object. `R` references are recognised.
+    pub fn parse_object(&mut self) -> Result<Object, Error> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let Some(b) = self.peek() else {
+            return Err(Error::parse(start, "unexpected end of data"));
+        };
+
+        match b {
+            b'/' => {
+                self.pos += 1;
+                Ok(Object::Name(self.read_name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                Ok(Object::String(self.read_literal_string()))
+            }
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let dict = self.read_dictionary()?;
+                Ok(Object::Dictionary(dict))
+            }
+            b'<' => {
+                self.pos += 1;
+                Ok(Object::String(self.read_hex_string()))
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut array = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Object::Array(array));
+                        }
+                        None => return Err(Error::parse(start, "unterminated array")),
+                        _ => array.push(self.parse_object()?),
+                    }
+                }
+            }
+            _ => {
+                let token = self.read_token();
+                match token {
+                    b"" => {
+                        self.pos += 1;
+                        Err(Error::parse(start, "unexpected delimiter"))
+                    }
+                    b"null" => Ok(Object::Null),
+                    b"true" => Ok(Object::Boolean(true)),
+                    b"false" => Ok(Object::Boolean(false)),
+                    _ => self.parse_number(token, start),
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self, token: &[u8], start: usize) -> Result<Object, Error> {
+        let s = std::str::from_utf8(token).map_err(|_| Error::parse(start, "invalid token"))?;
+        if let Ok(v) = s.parse::<i64>() {
+            // "n g R"
+            let pos = self.pos;
+            if v >= 0 && let Some(generation) = self.read_unsigned() && self.accept(b"R") {
+                return Ok(Object::Reference(Id::new(v as u32, generation)));
+            }
+            self.pos = pos;
+            return Ok(Object::Integer(v));
+        }
+
+        // Reals such as "-.5", "4." and the occasional "--1" of broken writers
+        let s = s.trim_start_matches('-');
+        let negative = token.len() - s.len() > 0;
+        match s.parse::<f64>() {
+            Ok(v) => Ok(Object::Real(if negative { -v } else { v })),
+            Err(_) => Err(Error::parse(start, &format!("unexpected token {}", String::from_utf8_lossy(token)))),
+        }
+    }
+
+    fn read_name(&mut self) -> Name {
+        let mut bytes = Vec::new();
+        while let Some(b) = self.peek() && !is_whitespace(b) && !is_delimiter(b) {
+            self.pos += 1;
+            if b == b'#' && let Some(v) = self.data.get(self.pos..self.pos + 2)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                bytes.push(v);
+                self.pos += 2;
+            } else {
+                bytes.push(b);
+            }
+        }
+        Name::new(&String::from_utf8_lossy(&bytes))
+    }
+
+    fn read_literal_string(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut depth = 0;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    bytes.push(b);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    bytes.push(b);
+                }
+                b'\\' => {
+                    let Some(e) = self.peek() else { break };
+                    self.pos += 1;
+                    match e {
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'0'..=b'7' => {
+                            let mut v = (e - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        v = v * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            bytes.push(v as u8);
+                        }
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        _ => bytes.push(e),
+                    }
+                }
+                b'\r' => {
+                    // End-of-line markers are read as a single LF
+                    if self.peek() == Some(b'\n') {
+                        self.pos += 1;
+                    }
+                    bytes.push(b'\n');
+                }
+                _ => bytes.push(b),
+            }
+        }
+        bytes
+    }
+
+    fn read_hex_string(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut high: Option<u8> = None;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            let v = match b {
+                b'>' => break,
+                b'0'..=b'9' => b - b'0',
+                b'a'..=b'f' => b - b'a' + 10,
+                b'A'..=b'F' => b - b'A' + 10,
+                _ => continue,
+            };
+            match high.take() {
+                Some(h) => bytes.push(h << 4 | v),
+                None => high = Some(v),
+            }
+        }
+        if let Some(h) = high {
+            bytes.push(h << 4);
+        }
+        bytes
+    }
+
+    fn read_dictionary(&mut self) -> Result<Dictionary, Error> {
+        let start = self.pos;
+        let mut dict = Dictionary::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'>') => {
+                    self.pos += 1;
+                    if self.peek() == Some(b'>') {
+                        self.pos += 1;
+                    }
+                    return Ok(dict);
+                }
+                Some(b'/') => {
+                    self.pos += 1;
+                    let key = self.read_name();
+                    let value = self.parse_object()?;
+                    // A null value is equivalent to an absent entry
+                    if value != Object::Null {
+                        dict.set(key.as_str(), value);
+                    }
+                }
+                None => return Err(Error::parse(start, "unterminated dictionary")),
+                _ => {
+                    // Skip a stray token rather than failing the whole file
+                    self.parse_object()?;
+                }
+            }
+        }
+    }
+
+    /// Reads "n g obj ... endobj" at the current position, including the stream data.
+    pub fn parse_indirect_object(&mut self) -> Result<(Id, Object), Error> {
+        let start = self.pos;
+        let (Some(number), Some(generation)) = (self.read_unsigned(), self.read_unsigned()) else {
+            return Err(Error::parse(start, "expected an indirect object"));
+        };
+        self.expect(b"obj")?;
+        let object = self.parse_object()?;
+
+        let object = match object {
+            Object::Dictionary(dict) if self.accept(b"stream") => {
+                let data = self.read_stream_data(&dict)?;
+                Object::Stream(Stream::new(dict, data))
+            }
+            object => object,
+        };
+
+        Ok((Id::new(number, generation), object))
+    }
+
+    fn read_stream_data(&mut self, dict: &Dictionary) -> Result<Vec<u8>, Error> {
+        // "stream" is followed by CRLF or LF
+        if self.peek() == Some(b'\r') {
+            self.pos += 1;
+        }
+        if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+        let start = self.pos;
+
+        // Trust /Length when "endstream" follows, otherwise search for it.
+        // An indirect /Length is always searched.
+        if let Some(len) = dict.get_i64("Length")
+            && len >= 0
+            && let Some(end) = start.checked_add(len as usize)
+            && end <= self.data.len() {
+            let mut p = Parser::new(self.data, end);
+            if p.accept(b"endstream") {
+                self.pos = p.pos;
+                return Ok(self.data[start..end].to_vec());
+            }
+        }
+
+        let Some(found) = find(&self.data[start..], b"endstream") else {
+            return Err(Error::parse(start, "missing endstream"));
+        };
+        let mut end = start + found;
+        if end > start && self.data[end - 1] == b'\n' {
+            end -= 1;
+        }
+        if end > start && self.data[end - 1] == b'\r' {
+            end -= 1;
+        }
+        self.pos = start + found + b"endstream".len();
+        Ok(self.data[start..end].to_vec())
+    }
+}
+
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+//------------------------------------------------------------------------------
+// Cross-reference
+//------------------------------------------------------------------------------
+
+/// Reads the cross-reference sections starting from the last `startxref`,
+/// following `/Prev` and `/XRefStm`.
+pub(crate) fn read_xref(data: &[u8]) -> Result<XrefInfo, Error> {
+    let tail_start = data.len().saturating_sub(1024);
+    let Some(pos) = rfind(&data[tail_start..], b"startxref") else {
+        return Err(Error::parse(data.len(), "startxref not found"));
+    };
+    let mut p = Parser::new(data, tail_start + pos + b"startxref".len());
+    let Some(startxref) = p.read_unsigned() else {
+        return Err(Error::parse(p.pos, "invalid startxref"));
+    };
+
+    let mut info = XrefInfo {
+        entries: BTreeMap::new(),
+        trailer: Dictionary::new(),
+        startxref: startxref as usize,
+        is_stream: false,
+    };
+
+    let mut visited = Vec::new();
+    let mut next = Some(startxref as usize);
+    let mut first = true;
+    while let Some(offset) = next.take() {
+        if visited.contains(&offset) || offset >= data.len() {
+            break;
+        }
+        visited.push(offset);
+
+        let mut p = Parser::new(data, offset);
+        let trailer = if p.accept(b"xref") {
+            let trailer = read_xref_table(&mut p, &mut info.entries)?;
+            // Hybrid-reference file: the stream entries take precedence over /Prev
+            if let Some(stm) = trailer.get_i64("XRefStm") {
+                let mut p = Parser::new(data, stm as usize);
+                let _ = read_xref_stream(&mut p, &mut info.entries);
+            }
+            trailer
+        } else {
+            if first {
+                info.is_stream = true;
+            }
+            read_xref_stream(&mut p, &mut info.entries)?
+        };
+
+        next = trailer.get_i64("Prev").map(|v| v as usize);
+        if first {
+            info.trailer = trailer;
+            info.trailer.remove("Prev");
+            info.trailer.remove("XRefStm");
+            first = false;
+        }
+    }
+
+    Ok(info)
+}
+
+fn read_xref_table(p: &mut Parser, entries: &mut BTreeMap<u32, XrefEntry>) -> Result<Dictionary, Error> {
+    loop {
+        if p.accept(b"trailer") {
+            return match p.parse_object()? {
+                Object::Dictionary(d) => Ok(d),
+                _ => Err(Error::parse(p.pos, "invalid trailer")),
+            };
+        }
+
+        let (Some(first), Some(count)) = (p.read_unsigned(), p.read_unsigned()) else {
+            return Err(Error::parse(p.pos, "invalid xref subsection"));
+        };
+        for number in first..first + count {
+            let (Some(offset), Some(generation)) = (p.read_unsigned(), p.read_unsigned()) else {
+                return Err(Error::parse(p.pos, "invalid xref entry"));
+            };
+            let in_use = p.read_token() == b"n";
+            // Sections are read from the newest, so existing entries win
+            if in_use && !entries.contains_key(&number) {
+                entries.insert(number, XrefEntry::Offset(offset as usize, generation));
+            }
+        }
+    }
+}
+
+fn read_xref_stream(p: &mut Parser, entries: &mut BTreeMap<u32, XrefEntry>) -> Result<Dictionary, Error> {
+    let (_, object) = p.parse_indirect_object()?;
+    let Object::Stream(stream) = object else {
+        return Err(Error::parse(p.pos, "expected a cross-reference stream"));
+    };
+    let data = stream.decoded_data()?;
+    let dict = stream.dict;
+
+    let widths: Vec<usize> = dict.get("W").and_then(|w| w.as_array())
+        .map(|a| a.iter().map(|v| v.as_i64().unwrap_or(0) as usize).collect())
+        .unwrap_or_default();
+    if widths.len() != 3 {
+        return Err(Error::parse(p.pos, "invalid /W"));
+    }
+    let size = dict.get_i64("Size").unwrap_or(0);
+    let index: Vec<i64> = dict.get("Index").and_then(|w| w.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_i64()).collect())
+        .unwrap_or_else(|| vec![0, size]);
+
+    let row_len: usize = widths.iter().sum();
+    let read = |row: &[u8], from: usize, width: usize| -> u64 {
+        row[from..from + width].iter().fold(0u64, |acc, b| acc << 8 | *b as u64)
+    };
+
+    let mut rows = data.chunks_exact(row_len.max(1));
+    for range in index.chunks(2) {
+        let [first, count] = range else { break };
+        for number in *first..*first + *count {
+            let Some(row) = rows.next() else { break };
+            let kind = if widths[0] == 0 { 1 } else { read(row, 0, widths[0]) };
+            let f2 = read(row, widths[0], widths[1]);
+            let f3 = read(row, widths[0] + widths[1], widths[2]);
+            let entry = match kind {
+                1 => XrefEntry::Offset(f2 as usize, f3 as u32),
+                2 => XrefEntry::Compressed(f2 as u32, f3 as usize),
+                _ => continue,
+            };
+            entries.entry(number as u32).or_insert(entry);
+        }
+    }
+
+    Ok(dict)
+}
+
+/// Rebuilds the cross-reference by scanning for "n g obj" when the table is broken.
+pub(crate) fn reconstruct_xref(data: &[u8]) -> XrefInfo {
+    let mut entries = BTreeMap::new();
+    let mut trailer = Dictionary::new();
+
+    let mut pos = 0;
+    while let Some(found) = find(&data[pos..], b"obj") {
+        let obj_pos = pos + found;
+        pos = obj_pos + 3;
+
+        // Walk back over "n g "
+        let mut start = obj_pos;
+        let mut fields = 0;
+        while fields < 2 && start > 0 {
+            while start > 0 && is_whitespace(data[start - 1]) {
+                start -= 1;
+            }
+            let end = start;
+            while start > 0 && data[start - 1].is_ascii_digit() {
+                start -= 1;
+            }
+            if start == end {
+                break;
+            }
+            fields += 1;
+        }
+        if fields != 2 {
+            continue;
+        }
+
+        let mut p = Parser::new(data, start);
+        if let Ok((id, object)) = p.parse_indirect_object() {
+            // Later definitions replace earlier ones, as in incremental updates
+            entries.insert(id.number(), XrefEntry::Offset(start, id.generation()));
+            if let Some(dict) = object.as_dict()
+                && dict.get_name("Type") == Some("XRef") {
+                merge_trailer(&mut trailer, dict);
+            }
+            pos = pos.max(p.pos);
+        }
+    }
+
+    let mut pos = 0;
+    while let Some(found) = find(&data[pos..], b"trailer") {
+        let mut p = Parser::new(data, pos + found + b"trailer".len());
+        if let Ok(Object::Dictionary(dict)) = p.parse_object() {
+            merge_trailer(&mut trailer, &dict);
+        }
+        pos += found + 1;
+    }
+
+    XrefInfo { entries, trailer, startxref: 0, is_stream: false }
+}
+
+fn merge_trailer(trailer: &mut Dictionary, dict: &Dictionary) {
+    for key in ["Root", "Info", "ID", "Encrypt"] {
+        if let Some(v) = dict.get(key) {
+            trailer.set(key, v.clone());
+        }
+    }
+}
+
+/// Parses the objects of an object stream (PDF32000-1:2008 7.5.7).
+pub(crate) fn parse_object_stream(stream: &Stream) -> Result<Vec<(u32, Object)>, Error> {
+    let data = stream.decoded_data()?;
+    let n = stream.dict.get_i64("N").unwrap_or(0) as usize;
+    let first = stream.dict.get_i64("First").unwrap_or(0) as usize;
+
+    let mut p = Parser::new(&data, 0);
+    let mut header = Vec::with_capacity(n);
+    for _ in 0..n {
+        let (Some(number), Some(offset)) = (p.read_unsigned(), p.read_unsigned()) else {
+            return Err(Error::parse(p.pos, "invalid object stream header"));
+        };
+        header.push((number, offset as usize));
+    }
+
+    let mut objects = Vec::with_capacity(n);
+    for (number, offset) in header {
+        let mut p = Parser::new(&data, first + offset);
+        objects.push((number, p.parse_object()?));
+    }
+    Ok(objects)
+}
+
+/// An operation of a content stream as read, the operands and the keyword.
+pub(crate) type ContentOperation = (Vec<Object>, String);
+
+/// Splits a content stream into operations (PDF32000-1:2008 7.8.2).
+///
+/// An inline image is a `BI` operation with the image dictionary and the
+/// data as the operands.
+pub(crate) fn parse_content(data: &[u8]) -> Result<Vec<ContentOperation>, Error> {
+    let mut p = Parser::new(data, 0);
+    let mut operations = Vec::new();
+    let mut operands = Vec::new();
+    loop {
+        p.skip_whitespace();
+        let Some(b) = p.peek() else {
+            break;
+        };
+        // PostScript procedures of CMaps
+        if b == b'{' || b == b'}' {
+            p.pos += 1;
+            continue;
+        }
+        if is_delimiter(b) || b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.') {
+            operands.push(p.parse_object()?);
+            continue;
+        }
+
+        let start = p.pos;
+        match p.read_token() {
+            b"true" => operands.push(Object::Boolean(true)),
+            b"false" => operands.push(Object::Boolean(false)),
+            b"null" => operands.push(Object::Null),
+            b"BI" => {
+                let mut dict = Dictionary::new();
+                while !p.accept(b"ID") {
+                    let Object::Name(key) = p.parse_object()? else {
+                        return Err(Error::parse(start, "invalid inline image"));
+                    };
+                    dict.set(key.as_str(), p.parse_object()?);
+                }
+                // A single white-space character follows ID
+                let begin = (p.pos + 1).min(data.len());
+                let end = (begin..data.len().saturating_sub(1))
+                    .find(|i| data[*i..].starts_with(b"EI")
+                        && (*i == 0 || is_whitespace(data[i - 1]))
+                        && data.get(i + 2).is_none_or(|b| is_whitespace(*b) || is_delimiter(*b)))
+                    .ok_or_else(|| Error::parse(start, "unterminated inline image"))?;
+                // and one precedes EI
+                let image_data = &data[begin..end.saturating_sub(1).max(begin)];
+                operations.push((vec![Object::Dictionary(dict), Object::String(image_data.to_vec())], String::from("BI")));
+                operands.clear();
+                p.pos = end + 2;
+            }
+            token => {
+                let keyword = String::from_utf8_lossy(token).into_owned();
+                operations.push((std::mem::take(&mut operands), keyword));
+            }
+        }
+    }
+    Ok(operations)
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Object {
+        Parser::new(s.as_bytes(), 0).parse_object().unwrap()
+    }
+
+    #[test]
+    fn objects() {
+        assert_eq!(parse("12"), Object::Integer(12));
+        assert_eq!(parse("-.5"), Object::Real(-0.5));
+        assert_eq!(parse("/A#20B"), Object::name("A B"));
+        assert_eq!(parse("(a\\(b\\)\\101\\\nc)"), Object::String(b"a(b)Ac".to_vec()));
+        assert_eq!(parse("<48 656>"), Object::String(b"He`".to_vec()));
+        assert_eq!(parse("[1 0 R 2 /N]"), Object::Array(vec![
+            Object::Reference(Id::new(1, 0)), Object::Integer(2), Object::name("N")]));
+
+        let dict = parse("<< /Type /Page % comment\n /Parent 3 0 R /X null >>");
+        let dict = dict.as_dict().unwrap();
+        assert_eq!(dict.get_name("Type"), Some("Page"));
+        assert_eq!(dict.get_reference("Parent"), Some(Id::new(3, 0)));
+        assert!(!dict.contains_key("X"));
+    }
+
+    #[test]
+    fn indirect_stream() {
+        // Wrong /Length falls back to searching for endstream
+        let data = b"4 0 obj\n<< /Length 99 >>\nstream\nabc\nendstream\nendobj";
+        let (id, object) = Parser::new(data, 0).parse_indirect_object().unwrap();
+        assert_eq!(id, Id::new(4, 0));
+        assert_eq!(object.as_stream().unwrap().raw_data(), b"abc");
+    }
+
+    #[test]
+    fn content() {
+        let data = b"q 1 0 0 1 72 700 cm BT /F0 12 Tf [(A) -250 (B)] TJ ET\nBI /W 1 /H 1 /BPC 8 /CS /G ID \x80 EI Q";
+        let operations = parse_content(data).unwrap();
+        let keywords: Vec<&str> = operations.iter().map(|o| o.1.as_str()).collect();
+        assert_eq!(keywords, ["q", "cm", "BT", "Tf", "TJ", "ET", "BI", "Q"]);
+        assert_eq!(operations[1].0.len(), 6);
+        assert_eq!(operations[3].0, [Object::name("F0"), Object::Integer(12)]);
+        assert_eq!(operations[4].0[0].as_array().unwrap().len(), 3);
+        assert_eq!(operations[6].0[1], Object::String(vec![0x80]));
+    }
+
+    #[test]
+    fn object_stream() {
+        let data = b"1 0 2 5 (ab) << /A 1 >>";
+        let mut dict = Dictionary::new();
+        dict.set("N", 2i64);
+        dict.set("First", 8i64);
+        let objects = parse_object_stream(&Stream::new(dict, data.to_vec())).unwrap();
+        assert_eq!(objects[0], (1, Object::String(b"ab".to_vec())));
+        assert_eq!(objects[1].0, 2);
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 8.7 Patterns
+//!
+//! Patterns are painted with the `/Pattern` colour space. A coloured
+//! pattern is selected with its name only, an uncoloured one with the colour
+//! in the underlying space of the pattern space.
+//!
+//! ```
+//! use prspdf::{MediaBox, Page, Rectangle};
+//! use prspdf::colour::space::ColorSpace;
+//! use prspdf::pattern::{PaintType, TilingPattern};
+//!
+//! // Diagonal hatching painted in the colour of the fill
+//! let mut hatch = TilingPattern::new(PaintType::Uncoloured, Rectangle::new(0.0, 0.0, 8.0, 8.0), 8.0, 8.0);
+//! hatch.contents().move_to(0.0, 0.0);
+//! hatch.contents().line_to(8.0, 8.0);
+//! hatch.contents().stroke();
+//!
+//! let space = ColorSpace::Pattern(Some(Box::new(ColorSpace::DeviceCMYK)));
+//! let mut page = Page::new(MediaBox::A4);
+//! page.resources().add_pattern("P0", hatch);
+//! page.resources().add_color_space("CS0", space.clone());
+//! page.contents().set_fill_color_space("CS0", &space);
+//! page.contents().set_fill_pattern("P0", [0.0, 1.0, 1.0, 0.0]).unwrap();
+//! page.contents().fill_rect(72, 600, 200, 100);
+//! ```
+
+use super::base::*;
+use super::contents::Contents;
+use super::resources::Resources;
+use super::shading::ShadingPattern;
+use super::utils::{dict_string, ToPdfString};
+
+/// A pattern in resources, selected with `Contents::set_fill_pattern`.
+#[derive(Clone)]
+pub enum Pattern {
+    Tiling(TilingPattern),
+    Shading(ShadingPattern),
+}
+
+impl From<TilingPattern> for Pattern {
+    fn from(pattern: TilingPattern) -> Self {
+        Self::Tiling(pattern)
+    }
+}
+
+impl From<ShadingPattern> for Pattern {
+    fn from(pattern: ShadingPattern) -> Self {
+        Self::Shading(pattern)
+    }
+}
+
+impl PdfObject for Pattern {
+    fn id(&self) -> &Id {
+        match self {
+            Self::Tiling(p) => p.id(),
+            Self::Shading(p) => p.id(),
+        }
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        match self {
+            Self::Tiling(p) => p.assign_ids(id_factory),
+            Self::Shading(p) => p.assign_ids(id_factory),
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        match self {
+            Self::Tiling(p) => p.get_objects(),
+            Self::Shading(p) => p.get_objects(),
+        }
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        match self {
+            Self::Tiling(p) => p.to_bytes(indent_depth),
+            Self::Shading(p) => p.to_bytes(indent_depth),
+        }
+    }
+}
+
+/// `/PaintType` of a tiling pattern.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaintType {
+    /// The cell specifies its colours.
+    Coloured = 1,
+    /// The cell is a stencil painted in the colour given when selecting the
+    /// pattern. Its contents shall not set colours.
+    Uncoloured = 2,
+}
+
+/// `/TilingType`, how the cells are spaced on the device.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TilingType {
+    /// Cells spaced exactly, distorting them by up to a device pixel.
+    #[default]
+    ConstantSpacing = 1,
+    /// Cells not distorted, the spacing varying by up to a device pixel.
+    NoDistortion = 2,
+    /// Like `ConstantSpacing` with faster tiling.
+    ConstantSpacingFaster = 3,
+}
+
+/// PDF32000-1:2008 8.7.3
+///
+/// ```text
+/// 12 0 obj
+/// << /Type /Pattern
+///    /PatternType 1
+///    /PaintType 1
+///    /TilingType 1
+///    /BBox [0 0 10 10]
+///    /XStep 10
+///    /YStep 10
+///    /Resources 13 0 R
+///    /Length 35
+/// >>
+/// stream
+/// ...
+/// endstream
+/// endobj
+/// ```
+#[derive(Clone)]
+pub struct TilingPattern {
+    id: Id,
+    paint_type: PaintType,
+    tiling_type: TilingType,
+    bbox: Rectangle,
+    x_step: f64,
+    y_step: f64,
+    matrix: Option<[f64; 6]>,
+    resources: Resources,
+    contents: Contents,
+}
+
+impl TilingPattern {
+    /// Creates the pattern of the cell in the BBox, repeated at the steps in
+    /// the pattern space.
+    pub fn new(paint_type: PaintType, bbox: Rectangle, x_step: f64, y_step: f64) -> Self {
+        Self {
+            id: Id::new_0(),
+            paint_type,
+            tiling_type: TilingType::default(),
+            bbox,
+            x_step,
+            y_step,
+            matrix: None,
+            resources: Resources::new(),
+            contents: Contents::new(),
+        }
+    }
+
+    pub fn paint_type(&self) -> PaintType {
+        self.paint_type
+    }
+
+    pub fn set_tiling_type(&mut self, tiling_type: TilingType) {
+        self.tiling_type = tiling_type;
+    }
+
+    /// Sets the matrix from the pattern space to the default coordinate
+    /// space of the page, or of the form the pattern is used in.
+    pub fn set_matrix(&mut self, matrix: [f64; 6]) {
+        self.matrix = Some(matrix);
+    }
+
+    pub fn resources(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Returns the contents drawing a cell.
+    pub fn contents(&mut self) -> &mut Contents {
+        &mut self.contents
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let stream = self.contents.get_stream_string(0);
+
+        let mut entries = vec![
+            ("Type", String::from("/Pattern")),
+            ("PatternType", String::from("1")),
+            ("PaintType", (self.paint_type as i32).to_string()),
+            ("TilingType", (self.tiling_type as i32).to_string()),
+            ("BBox", self.bbox.to_pdf_string()),
+            ("XStep", self.x_step.to_pdf_string()),
+            ("YStep", self.y_step.to_pdf_string()),
+        ];
+        if let Some(m) = &self.matrix {
+            entries.push(("Matrix", m.to_pdf_string()));
+        }
+        entries.push(("Resources", self.resources.id.to_ref_string()));
+        entries.push(("Length", stream.len().to_string()));
+
+        format!(concat!(
+            "{} obj\n",
+            "{}\n",
+            "stream\n",
+            "{}\n",
+            "endstream\n",
+            "endobj"),
+            self.id,
+            dict_string(&entries),
+            stream,
+        )
+    }
+}
+
+impl PdfObject for TilingPattern {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        self.resources.assign_ids(id_factory);
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        list.append(&mut self.resources.get_objects());
+        list
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::ColorSpace;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::loaded_doc::LoadedDoc;
+    use crate::pdf::page::Page;
+
+    #[test]
+    fn to_string() {
+        let mut dots = TilingPattern::new(PaintType::Coloured, Rectangle::new(0.0, 0.0, 4.0, 4.0), 5.0, 5.0);
+        dots.set_tiling_type(TilingType::NoDistortion);
+        dots.set_matrix([0.5, 0.0, 0.0, 0.5, 0.0, 0.0]);
+        dots.contents().set_fill_color(1.0, 0.0, 0.0);
+        dots.contents().fill_rect(1, 1, 2, 2);
+
+        let ok = concat!(
+            "0 0 obj\n",
+            "<< /Type /Pattern\n",
+            "   /PatternType 1\n",
+            "   /PaintType 1\n",
+            "   /TilingType 2\n",
+            "   /BBox [0 0 4 4]\n",
+            "   /XStep 5\n",
+            "   /YStep 5\n",
+            "   /Matrix [0.5 0 0 0.5 0 0]\n",
+            "   /Resources 0 0 R\n",
+            "   /Length 21\n",
+            ">>\n",
+            "stream\n",
+            "1 0 0 rg\n",
+            "1 1 2 2 re\n",
+            "f\n",
+            "endstream\n",
+            "endobj",
+        );
+        assert_eq!(dots.to_string(0), ok);
+    }
+
+    #[test]
+    fn paint() {
+        let mut logo = TilingPattern::new(PaintType::Coloured, Rectangle::new(0.0, 0.0, 20.0, 20.0), 30.0, 30.0);
+        logo.contents().fill_rect(0, 0, 20, 20);
+        let mut hatch = TilingPattern::new(PaintType::Uncoloured, Rectangle::new(0.0, 0.0, 8.0, 8.0), 8.0, 8.0);
+        hatch.contents().move_to(0.0, 0.0);
+        hatch.contents().line_to(8.0, 8.0);
+        hatch.contents().stroke();
+
+        let coloured = ColorSpace::Pattern(None);
+        let uncoloured = ColorSpace::Pattern(Some(Box::new(ColorSpace::DeviceGray)));
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_pattern("P0", logo);
+        page.resources().add_pattern("P1", hatch);
+        page.resources().add_color_space("CS0", coloured.clone());
+        page.resources().add_color_space("CS1", uncoloured.clone());
+        page.contents().set_fill_color_space("CS0", &coloured);
+        page.contents().set_fill_pattern("P0", []).unwrap();
+        assert!(page.contents().set_fill_color_space_color([0.5]).is_err());
+        page.contents().set_stroke_color_space("CS1", &uncoloured);
+        page.contents().set_stroke_pattern("P1", [0.5]).unwrap();
+        assert!(page.contents().set_stroke_pattern("P1", []).is_err());
+        page.contents().set_stroke_color_space("CS2", &ColorSpace::DeviceGray);
+        assert!(page.contents().set_stroke_pattern("P1", [0.5]).is_err());
+        assert_eq!(page.contents().get_stream_string(0), "/CS0 cs\n/P0 scn\n/CS1 CS\n0.5 /P1 SCN\n/CS2 CS");
+
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(page);
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let page_id = loaded.page_ids()[0];
+        let resources = loaded.page_attribute(page_id, "Resources").and_then(|r| r.as_dict()).unwrap();
+        let patterns = loaded.resolve_entry(resources, "Pattern").and_then(|p| p.as_dict()).unwrap();
+        let hatch = loaded.resolve_entry(patterns, "P1").and_then(|p| p.as_stream()).unwrap();
+        assert_eq!(hatch.dict.get_i64("PaintType"), Some(2));
+        assert_eq!(hatch.decoded_data().unwrap(), b"0 0 m\n8 8 l\nS");
+    }
+}
@@ -0,0 +1,983 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! Redaction of loaded documents: the content under the areas is removed
+//! from the file, not only covered.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use super::base::*;
+use super::colour::DeviceColour;
+use super::error::Error;
+use super::loaded_doc::LoadedDoc;
+use super::object::{Dictionary, Object, Stream};
+use super::operation::{self, Operation, TextItem};
+use super::text::decoder::FontDecoder;
+use super::text::{self, concat, text_box, to_matrix, transform, translate, Matrix, IDENTITY};
+
+/// Resource name of the overlay text font.
+const FONT_NAME: &str = "RedactF0";
+const MAX_FORM_DEPTH: usize = 16;
+/// The clipping paths excluding the areas start from this square.
+const OUTSIDE: f64 = 32767.0;
+
+/// Areas of pages to redact and how to mark them.
+///
+/// Applying the redaction:
+///
+/// - removes the glyphs inside the areas from the `Tj`, `TJ`, `'` and `"`
+///   strings, keeping the positions of the others,
+/// - clears the pixels of the images inside the areas, or removes an image
+///   whose data cannot be decoded (e.g. DCTDecode),
+/// - clips the areas out of the paths and shadings painted over them,
+/// - removes inline images and annotations over the areas,
+/// - fills the areas, black by default, with an optional overlay text.
+///
+/// Form XObjects are copied for the page and redacted the same way. By
+/// default the document information, the XMP metadata and the content of the
+/// optional content groups (layers) hidden by default are removed too.
+///
+/// Save the document with [`LoadedDoc::to_bytes`]. An incremental update
+/// keeps the original content in the earlier revision.
+///
+/// ```no_run
+/// use prspdf::{LoadedDoc, Rectangle};
+/// use prspdf::redact::Redaction;
+///
+/// let mut doc = LoadedDoc::open("statement.pdf").unwrap();
+/// let mut redaction = Redaction::new();
+/// redaction.add_text(&doc, "4111 1111 1111 1111").unwrap();
+/// redaction.add_area(0, Rectangle::new(72.0, 700.0, 300.0, 720.0));
+/// redaction.set_overlay_text("REDACTED", 8.0);
+/// redaction.apply(&mut doc).unwrap();
+/// doc.write_to_file("statement-redacted.pdf").unwrap();
+/// ```
+pub struct Redaction {
+    areas: Vec<(usize, Rectangle)>,
+    fill: Option<DeviceColour>,
+    overlay_text: Option<(String, f64)>,
+    scrub_metadata: bool,
+    remove_hidden_layers: bool,
+}
+
+impl Redaction {
+    pub fn new() -> Self {
+        Self {
+            areas: Vec::new(),
+            fill: Some(DeviceColour::black()),
+            overlay_text: None,
+            scrub_metadata: true,
+            remove_hidden_layers: true,
+        }
+    }
+
+    /// Adds an area of the page in the default user space.
+    pub fn add_area(&mut self, page_index: usize, area: Rectangle) {
+        self.areas.push((page_index, area));
+    }
+
+    /// Adds the boxes of the occurrences of the text on every page and
+    /// returns how many were found. See [`text::find_text`].
+    pub fn add_text(&mut self, src: &LoadedDoc, text: &str) -> Result<usize, Error> {
+        let mut count = 0;
+        for page_index in 0..src.page_ids().len() {
+            for bbox in text::find_text(src, page_index, text)? {
+                self.add_area(page_index, bbox);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Sets the colour the areas are filled with, `None` to leave them blank.
+    pub fn set_fill(&mut self, fill: Option<DeviceColour>) {
+        self.fill = fill;
+    }
+
+    /// Paints the text in Helvetica in each area, white on the fill or black
+    /// without it.
+    pub fn set_overlay_text(&mut self, text: &str, font_size: f64) {
+        self.overlay_text = Some((text.to_string(), font_size));
+    }
+
+    /// Removes the document information dictionary and the `/Metadata` and
+    /// `/PieceInfo` of the catalog and the pages.
+    pub fn set_scrub_metadata(&mut self, scrub_metadata: bool) {
+        self.scrub_metadata = scrub_metadata;
+    }
+
+    /// Removes the content of the optional content groups off in the
+    /// default configuration and the optional content properties.
+    pub fn set_remove_hidden_layers(&mut self, remove_hidden_layers: bool) {
+        self.remove_hidden_layers = remove_hidden_layers;
+    }
+
+    /// Redacts the document and removes the objects no longer used.
+    pub fn apply(&self, doc: &mut LoadedDoc) -> Result<(), Error> {
+        let page_ids = doc.page_ids();
+        if let Some((page_index, _)) = self.areas.iter().find(|(i, _)| *i >= page_ids.len()) {
+            return Err(Error::NotFound(format!("page {}", page_index)));
+        }
+
+        let hidden = if self.remove_hidden_layers { hidden_groups(doc) } else { HashSet::new() };
+        for (page_index, page_id) in page_ids.iter().enumerate() {
+            let areas: Vec<Rectangle> = self.areas.iter()
+                .filter(|(i, _)| *i == page_index)
+                .map(|(_, area)| *area)
+                .collect();
+            if !areas.is_empty() || !hidden.is_empty() {
+                self.redact_page(doc, *page_id, &areas, &hidden)?;
+            }
+        }
+
+        let catalog_id = doc.catalog_id();
+        if self.remove_hidden_layers && let Some(catalog) = doc.get_mut(catalog_id).and_then(|c| c.as_dict_mut()) {
+            catalog.remove("OCProperties");
+        }
+        if self.scrub_metadata {
+            scrub_metadata(doc);
+        }
+        doc.remove_unreferenced();
+        Ok(())
+    }
+
+    fn redact_page(&self, doc: &mut LoadedDoc, page_id: Id, areas: &[Rectangle], hidden: &HashSet<Id>) -> Result<(), Error> {
+        let operations = doc.page_operations(page_id)?;
+        let mut resources = doc.page_attribute(page_id, "Resources")
+            .and_then(|r| r.as_dict())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut redactor = Redactor { doc, areas, hidden, fonts: HashMap::new(), changed: false };
+        let mut operations = redactor.redact(operations, &mut resources, IDENTITY, 0)?;
+        let changed = redactor.changed;
+        redactor.remove_annotations(page_id);
+        if !areas.is_empty() {
+            operations.insert(0, Operation::SaveState);
+            operations.push(Operation::RestoreState);
+            operations.extend(self.overlay(doc, areas, &mut resources));
+        } else if !changed {
+            return Ok(());
+        }
+
+        doc.set_page_operations(page_id, &operations)?;
+        if let Some(page) = doc.get_mut(page_id).and_then(|p| p.as_dict_mut()) {
+            page.set("Resources", resources);
+        }
+        Ok(())
+    }
+
+    fn overlay(&self, doc: &LoadedDoc, areas: &[Rectangle], resources: &mut Dictionary) -> Vec<Operation> {
+        let mut operations = Vec::new();
+        for area in areas {
+            operations.push(Operation::SaveState);
+            if let Some(fill) = &self.fill {
+                operations.push(fill.to_fill_operation());
+                operations.push(Operation::Rect(area.llx, area.lly, area.width(), area.height()));
+                operations.push(Operation::Fill);
+            }
+            if let Some((text, size)) = &self.overlay_text {
+                let colour = if self.fill.is_some() { DeviceColour::white() } else { DeviceColour::black() };
+                let bytes = text.chars().map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' }).collect();
+                operations.extend([
+                    Operation::Rect(area.llx, area.lly, area.width(), area.height()),
+                    Operation::Clip,
+                    Operation::EndPath,
+                    Operation::BeginText,
+                    colour.to_fill_operation(),
+                    Operation::SetFont(Name::new(FONT_NAME), *size),
+                    Operation::MoveText(area.llx + size * 0.2, area.lly + (area.height() - size * 0.7) / 2.0),
+                    Operation::ShowText(bytes),
+                    Operation::EndText,
+                ]);
+            }
+            operations.push(Operation::RestoreState);
+        }
+
+        if self.overlay_text.is_some() {
+            let mut font = Dictionary::new();
+            font.set("Type", Object::name("Font"));
+            font.set("Subtype", Object::name("Type1"));
+            font.set("BaseFont", Object::name("Helvetica"));
+            font.set("Encoding", Object::name("WinAnsiEncoding"));
+            let mut fonts = doc.resolve_entry(resources, "Font").and_then(|f| f.as_dict()).cloned().unwrap_or_default();
+            fonts.set(FONT_NAME, font);
+            resources.set("Font", fonts);
+        }
+        operations
+    }
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// The parts of the graphics state and the text state used for the positions.
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    line_width: f64,
+    char_spacing: f64,
+    word_spacing: f64,
+    /// `Tz` / 100
+    scale: f64,
+    leading: f64,
+    font: Option<Rc<FontDecoder>>,
+    font_size: f64,
+    rise: f64,
+}
+
+struct Redactor<'a> {
+    doc: &'a mut LoadedDoc,
+    areas: &'a [Rectangle],
+    hidden: &'a HashSet<Id>,
+    fonts: HashMap<Id, Rc<FontDecoder>>,
+    /// Set when anything is removed or replaced.
+    changed: bool,
+}
+
+impl Redactor<'_> {
+    /// Returns the operations with the content under the areas removed. The
+    /// XObjects redacted are replaced in the resources.
+    fn redact(&mut self, operations: Vec<Operation>, resources: &mut Dictionary, ctm: Matrix, depth: usize) -> Result<Vec<Operation>, Error> {
+        let mut state = GraphicsState {
+            ctm,
+            line_width: 1.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            scale: 1.0,
+            leading: 0.0,
+            font: None,
+            font_size: 0.0,
+            rise: 0.0,
+        };
+        let mut stack = Vec::new();
+        let mut tm = IDENTITY;
+        let mut tlm = IDENTITY;
+        let mut path: Vec<Operation> = Vec::new();
+        let mut points: Vec<(f64, f64)> = Vec::new();
+        // Nesting of the hidden marked content being removed
+        let mut hidden_depth = 0;
+        let mut out = Vec::with_capacity(operations.len());
+
+        for operation in operations {
+            if hidden_depth > 0 {
+                match operation {
+                    Operation::BeginMarkedContent(_) | Operation::BeginMarkedContentProperties(..) => hidden_depth += 1,
+                    Operation::EndMarkedContent => hidden_depth -= 1,
+                    _ => (),
+                }
+                continue;
+            }
+
+            match &operation {
+                Operation::SaveState => stack.push(state.clone()),
+                Operation::RestoreState => if let Some(saved) = stack.pop() {
+                    state = saved;
+                },
+                Operation::ConcatMatrix(m) => state.ctm = concat(m, &state.ctm),
+                Operation::SetLineWidth(w) => state.line_width = *w,
+                Operation::BeginText => {
+                    tm = IDENTITY;
+                    tlm = IDENTITY;
+                }
+                Operation::SetCharSpacing(v) => state.char_spacing = *v,
+                Operation::SetWordSpacing(v) => state.word_spacing = *v,
+                Operation::SetHorizontalScaling(v) => state.scale = v / 100.0,
+                Operation::SetLeading(v) => state.leading = *v,
+                Operation::SetTextRise(v) => state.rise = *v,
+                Operation::SetFont(name, size) => {
+                    state.font = self.font(resources, name.as_str());
+                    state.font_size = *size;
+                }
+                Operation::MoveText(tx, ty) => {
+                    tlm = concat(&translate(*tx, *ty), &tlm);
+                    tm = tlm;
+                }
+                Operation::MoveTextSetLeading(tx, ty) => {
+                    state.leading = -ty;
+                    tlm = concat(&translate(*tx, *ty), &tlm);
+                    tm = tlm;
+                }
+                Operation::SetTextMatrix(m) => {
+                    tlm = *m;
+                    tm = tlm;
+                }
+                Operation::NextLine => {
+                    tlm = concat(&translate(0.0, -state.leading), &tlm);
+                    tm = tlm;
+                }
+                Operation::ShowText(text) => {
+                    if let Some(items) = self.show(&state, &mut tm, &[TextItem::Text(text.clone())]) {
+                        out.push(Operation::ShowTextAdjusted(items));
+                        continue;
+                    }
+                }
+                Operation::ShowTextAdjusted(items) => {
+                    if let Some(items) = self.show(&state, &mut tm, items) {
+                        out.push(Operation::ShowTextAdjusted(items));
+                        continue;
+                    }
+                }
+                Operation::NextLineShowText(text) => {
+                    tlm = concat(&translate(0.0, -state.leading), &tlm);
+                    tm = tlm;
+                    if let Some(items) = self.show(&state, &mut tm, &[TextItem::Text(text.clone())]) {
+                        out.extend([Operation::NextLine, Operation::ShowTextAdjusted(items)]);
+                        continue;
+                    }
+                }
+                Operation::NextLineShowTextSpaced(aw, ac, text) => {
+                    state.word_spacing = *aw;
+                    state.char_spacing = *ac;
+                    tlm = concat(&translate(0.0, -state.leading), &tlm);
+                    tm = tlm;
+                    if let Some(items) = self.show(&state, &mut tm, &[TextItem::Text(text.clone())]) {
+                        out.extend([
+                            Operation::SetWordSpacing(*aw),
+                            Operation::SetCharSpacing(*ac),
+                            Operation::NextLine,
+                            Operation::ShowTextAdjusted(items),
+                        ]);
+                        continue;
+                    }
+                }
+
+                Operation::MoveTo(x, y) | Operation::LineTo(x, y) => {
+                    points.push(transform(&state.ctm, *x, *y));
+                    path.push(operation);
+                    continue;
+                }
+                Operation::CurveTo(c) => {
+                    points.extend(c.chunks(2).map(|p| transform(&state.ctm, p[0], p[1])));
+                    path.push(operation);
+                    continue;
+                }
+                Operation::CurveToInitial(c) | Operation::CurveToFinal(c) => {
+                    points.extend(c.chunks(2).map(|p| transform(&state.ctm, p[0], p[1])));
+                    path.push(operation);
+                    continue;
+                }
+                Operation::Rect(x, y, w, h) => {
+                    points.extend([(*x, *y), (x + w, *y), (*x, y + h), (x + w, y + h)]
+                        .map(|(px, py)| transform(&state.ctm, px, py)));
+                    path.push(operation);
+                    continue;
+                }
+                Operation::ClosePath | Operation::Clip | Operation::ClipEvenOdd => {
+                    path.push(operation);
+                    continue;
+                }
+                Operation::Stroke | Operation::CloseStroke | Operation::Fill | Operation::FillObsolete
+                | Operation::FillEvenOdd | Operation::FillStroke | Operation::FillStrokeEvenOdd
+                | Operation::CloseFillStroke | Operation::CloseFillStrokeEvenOdd | Operation::EndPath => {
+                    let path = std::mem::take(&mut path);
+                    let bbox = Rectangle::bounding(&std::mem::take(&mut points));
+                    // Half the line width in the default user space
+                    let half_width = state.line_width.max(1.0) * state.ctm[0].hypot(state.ctm[1]).max(state.ctm[2].hypot(state.ctm[3])) / 2.0;
+                    let painted = operation != Operation::EndPath;
+                    if !painted || !bbox.is_some_and(|b| self.covers(&b.expand(half_width))) {
+                        out.extend(path);
+                        out.push(operation);
+                        continue;
+                    }
+
+                    self.changed = true;
+                    let (clip, shape): (Vec<Operation>, Vec<Operation>) = path.into_iter()
+                        .partition(|o| matches!(o, Operation::Clip | Operation::ClipEvenOdd));
+                    if let Some(exclusion) = self.exclusion(&state.ctm) {
+                        out.push(Operation::SaveState);
+                        out.extend(exclusion);
+                        out.extend(shape.iter().cloned());
+                        out.push(operation);
+                        out.push(Operation::RestoreState);
+                    }
+                    // The clipping path set with the painting is kept
+                    if !clip.is_empty() {
+                        out.extend(shape);
+                        out.extend(clip);
+                        out.push(Operation::EndPath);
+                    }
+                    continue;
+                }
+
+                Operation::PaintShading(_) if !self.areas.is_empty() => {
+                    self.changed = true;
+                    if let Some(exclusion) = self.exclusion(&state.ctm) {
+                        out.push(Operation::SaveState);
+                        out.extend(exclusion);
+                        out.push(operation);
+                        out.push(Operation::RestoreState);
+                    }
+                    continue;
+                }
+                Operation::InlineImage(..) if self.covers(&unit_square(&state.ctm)) => {
+                    self.changed = true;
+                    continue;
+                }
+                Operation::DrawXObject(name) if !self.draw_xobject(resources, name.as_str(), &state.ctm, depth)? => {
+                    self.changed = true;
+                    continue;
+                }
+                Operation::BeginMarkedContentProperties(tag, properties)
+                    if tag.as_str() == "OC" && self.is_hidden_properties(resources, properties) =>
+                {
+                    self.changed = true;
+                    hidden_depth = 1;
+                    continue;
+                }
+                _ => (),
+            }
+            out.push(operation);
+        }
+        out.extend(path);
+        self.remove_hidden_properties(resources);
+        Ok(out)
+    }
+
+    /// Removes the hidden optional content from the `/Properties` of the
+    /// resources.
+    fn remove_hidden_properties(&mut self, resources: &mut Dictionary) {
+        let Some(properties) = self.doc.resolve_entry(resources, "Properties").and_then(|p| p.as_dict()) else {
+            return;
+        };
+        let mut kept = Dictionary::new();
+        for (name, value) in properties.iter() {
+            if !self.is_hidden(value) {
+                kept.set(name.as_str(), value.clone());
+            }
+        }
+        if kept.len() != properties.len() {
+            self.changed = true;
+            resources.set("Properties", kept);
+        }
+    }
+
+    /// Returns the items with the glyphs under the areas replaced with
+    /// adjustments, `None` if none is. Moves the text matrix.
+    fn show(&mut self, state: &GraphicsState, tm: &mut Matrix, items: &[TextItem]) -> Option<Vec<TextItem>> {
+        let font = state.font.as_ref()?;
+        let start = *tm;
+        let size = state.font_size;
+        let m = concat(&start, &state.ctm);
+        let bottom = state.rise + font.descent / 1000.0 * size;
+        let top = state.rise + font.ascent / 1000.0 * size;
+        let mut x = 0.0;
+        let mut removed = false;
+        let mut out = Vec::new();
+        for item in items {
+            match item {
+                TextItem::Text(bytes) => {
+                    let mut kept = Vec::new();
+                    let mut offset = 0;
+                    for glyph in font.decode(bytes) {
+                        let code = &bytes[offset.min(bytes.len())..(offset + glyph.length).min(bytes.len())];
+                        offset += glyph.length;
+                        let word_spacing = if glyph.is_space { state.word_spacing } else { 0.0 };
+                        let advance = (glyph.width / 1000.0 * size + state.char_spacing + word_spacing) * state.scale;
+                        let glyph_box = text_box(&m, x, x + glyph.width / 1000.0 * size * state.scale, bottom, top);
+                        if self.covers(&glyph_box) {
+                            removed = true;
+                            if !kept.is_empty() {
+                                out.push(TextItem::Text(std::mem::take(&mut kept)));
+                            }
+                            if size * state.scale != 0.0 {
+                                push_adjustment(&mut out, -advance / (size * state.scale) * 1000.0);
+                            }
+                        } else {
+                            kept.extend_from_slice(code);
+                        }
+                        x += advance;
+                    }
+                    if !kept.is_empty() {
+                        out.push(TextItem::Text(kept));
+                    }
+                }
+                TextItem::Adjust(adjustment) => {
+                    push_adjustment(&mut out, *adjustment);
+                    x -= adjustment / 1000.0 * size * state.scale;
+                }
+            }
+        }
+        *tm = concat(&translate(x, 0.0), &start);
+        if removed {
+            self.changed = true;
+            Some(out)
+        } else {
+            None
+        }
+    }
+
+    /// Returns false if the `Do` is to be removed. Redacted XObjects are
+    /// added as new objects and replace the ones in the resources.
+    fn draw_xobject(&mut self, resources: &mut Dictionary, name: &str, ctm: &Matrix, depth: usize) -> Result<bool, Error> {
+        let stream = self.doc.resolve_entry(resources, "XObject")
+            .and_then(|x| x.as_dict())
+            .and_then(|x| self.doc.resolve_entry(x, name))
+            .and_then(|x| x.as_stream())
+            .cloned();
+        let Some(stream) = stream else {
+            return Ok(true);
+        };
+        if stream.dict.get("OC").is_some_and(|oc| self.is_hidden(oc)) {
+            return Ok(false);
+        }
+
+        match stream.dict.get_name("Subtype") {
+            Some("Image") => {
+                if !self.covers(&unit_square(ctm)) {
+                    return Ok(true);
+                }
+                self.changed = true;
+                match self.redact_image(&stream, ctm) {
+                    Some(image) => {
+                        let id = self.doc.add_object(image);
+                        self.set_xobject(resources, name, id);
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                }
+            }
+            Some("Form") => {
+                let matrix = self.doc.resolve_entry(&stream.dict, "Matrix")
+                    .and_then(|m| m.as_array())
+                    .map_or(IDENTITY, |m| to_matrix(m));
+                let form_ctm = concat(&matrix, ctm);
+                let bbox = self.doc.resolve_entry(&stream.dict, "BBox").and_then(|b| b.as_rect()).and_then(|b| {
+                    Rectangle::bounding(&[(b.llx, b.lly), (b.urx, b.lly), (b.llx, b.ury), (b.urx, b.ury)]
+                        .map(|(x, y)| transform(&form_ctm, x, y)))
+                });
+                if self.hidden.is_empty() && !bbox.is_none_or(|b| self.covers(&b)) {
+                    return Ok(true);
+                }
+                if depth >= MAX_FORM_DEPTH {
+                    return Ok(false);
+                }
+
+                let mut form_resources = self.doc.resolve_entry(&stream.dict, "Resources")
+                    .and_then(|r| r.as_dict())
+                    .unwrap_or(resources)
+                    .clone();
+                let changed = std::mem::replace(&mut self.changed, false);
+                let operations = operation::parse(&stream.decoded_data()?)?;
+                let operations = self.redact(operations, &mut form_resources, form_ctm, depth + 1)?;
+                if !std::mem::replace(&mut self.changed, changed) {
+                    return Ok(true);
+                }
+
+                self.changed = true;
+                let mut dict = stream.dict.clone();
+                dict.set("Resources", form_resources);
+                let id = self.doc.add_object(Stream::from_decoded(dict, operation::to_bytes(&operations)));
+                self.set_xobject(resources, name, id);
+                Ok(true)
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn set_xobject(&self, resources: &mut Dictionary, name: &str, id: Id) {
+        let mut xobjects = self.doc.resolve_entry(resources, "XObject")
+            .and_then(|x| x.as_dict())
+            .cloned()
+            .unwrap_or_default();
+        xobjects.set(name, Object::Reference(id));
+        resources.set("XObject", xobjects);
+    }
+
+    /// Returns a copy of the image with the pixels inside the areas set to
+    /// zero (unpainted for a stencil mask), `None` if the data cannot be
+    /// decoded.
+    fn redact_image(&self, image: &Stream, ctm: &Matrix) -> Option<Stream> {
+        let dict = &image.dict;
+        let decodable = filter_names(dict).iter()
+            .all(|f| ["FlateDecode", "Fl", "LZWDecode", "LZW", "ASCIIHexDecode", "AHx", "ASCII85Decode", "A85", "RunLengthDecode", "RL"].contains(f));
+        if !decodable {
+            return None;
+        }
+        let width = usize::try_from(dict.get_i64("Width")?).ok().filter(|w| *w > 0)?;
+        let height = usize::try_from(dict.get_i64("Height")?).ok().filter(|h| *h > 0)?;
+        let mask = dict.get("ImageMask").and_then(|m| m.as_bool()) == Some(true);
+        let bits = if mask {
+            1
+        } else {
+            let bpc = usize::try_from(dict.get_i64("BitsPerComponent")?).ok()?;
+            self.components(dict.get("ColorSpace")?)? * bpc
+        };
+        // A stencil mask paints the samples 0 unless /Decode is [1 0]
+        let set = mask && self.doc.resolve_entry(dict, "Decode")
+            .and_then(|d| d.as_array())
+            .and_then(|d| d.first())
+            .and_then(|d| d.as_f64()) != Some(1.0);
+
+        let mut data = image.decoded_data().ok()?;
+        let row_bits = (width * bits).div_ceil(8) * 8;
+        if data.len() * 8 < row_bits * height {
+            return None;
+        }
+        for row in 0..height {
+            for column in 0..width {
+                let u = (column as f64 + 0.5) / width as f64;
+                let v = 1.0 - (row as f64 + 0.5) / height as f64;
+                let (x, y) = transform(ctm, u, v);
+                if !self.areas.iter().any(|a| a.llx < x && x < a.urx && a.lly < y && y < a.ury) {
+                    continue;
+                }
+                let start = row * row_bits + column * bits;
+                for bit in start..start + bits {
+                    let byte = &mut data[bit / 8];
+                    let flag = 0x80 >> (bit % 8);
+                    if set { *byte |= flag } else { *byte &= !flag }
+                }
+            }
+        }
+        Some(Stream::from_decoded(dict.clone(), data))
+    }
+
+    /// Returns the number of colour components of an image colour space.
+    fn components(&self, space: &Object) -> Option<usize> {
+        let space = self.doc.resolve(space);
+        let family = match space {
+            Object::Array(a) => self.doc.resolve(a.first()?).as_name()?,
+            _ => space.as_name()?,
+        };
+        match family {
+            "DeviceGray" | "G" | "CalGray" | "Indexed" | "I" | "Separation" => Some(1),
+            "DeviceRGB" | "RGB" | "CalRGB" | "Lab" => Some(3),
+            "DeviceCMYK" | "CMYK" => Some(4),
+            "ICCBased" => {
+                let profile = self.doc.resolve(space.as_array()?.get(1)?).as_stream()?;
+                usize::try_from(profile.dict.get_i64("N")?).ok()
+            }
+            "DeviceN" => Some(self.doc.resolve(space.as_array()?.get(1)?).as_array()?.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns the operations intersecting the clipping path with the
+    /// outside of each area, `None` if the CTM cannot be inverted.
+    fn exclusion(&self, ctm: &Matrix) -> Option<Vec<Operation>> {
+        let mut operations = vec![Operation::ConcatMatrix(invert(ctm)?)];
+        for area in self.areas {
+            operations.extend([
+                Operation::Rect(-OUTSIDE, -OUTSIDE, OUTSIDE * 2.0, OUTSIDE * 2.0),
+                Operation::Rect(area.llx, area.lly, area.width(), area.height()),
+                Operation::ClipEvenOdd,
+                Operation::EndPath,
+            ]);
+        }
+        operations.push(Operation::ConcatMatrix(*ctm));
+        Some(operations)
+    }
+
+    fn covers(&self, bbox: &Rectangle) -> bool {
+        self.areas.iter().any(|area| overlaps(area, bbox))
+    }
+
+    fn font(&mut self, resources: &Dictionary, name: &str) -> Option<Rc<FontDecoder>> {
+        let fonts = self.doc.resolve_entry(resources, "Font")?.as_dict()?;
+        match fonts.get(name)? {
+            Object::Reference(id) => {
+                if !self.fonts.contains_key(id) {
+                    let font = self.doc.get(*id)?.as_dict()?;
+                    self.fonts.insert(*id, Rc::new(FontDecoder::new(self.doc, font)));
+                }
+                self.fonts.get(id).cloned()
+            }
+            Object::Dictionary(font) => Some(Rc::new(FontDecoder::new(self.doc, font))),
+            _ => None,
+        }
+    }
+
+    /// Returns true for the properties of a `BDC /OC` hidden by default.
+    fn is_hidden_properties(&self, resources: &Dictionary, properties: &Object) -> bool {
+        match properties {
+            Object::Name(name) => self.doc.resolve_entry(resources, "Properties")
+                .and_then(|p| p.as_dict())
+                .and_then(|p| p.get(name.as_str()))
+                .is_some_and(|p| self.is_hidden(p)),
+            _ => self.is_hidden(properties),
+        }
+    }
+
+    /// Returns true for an optional content group or membership dictionary
+    /// hidden by default.
+    fn is_hidden(&self, oc: &Object) -> bool {
+        if self.hidden.is_empty() {
+            return false;
+        }
+        if let Object::Reference(id) = oc && self.hidden.contains(id) {
+            return true;
+        }
+        let Some(membership) = self.doc.resolve(oc).as_dict().filter(|d| d.get_name("Type") == Some("OCMD")) else {
+            return false;
+        };
+        let groups: Vec<Id> = match membership.get("OCGs") {
+            Some(Object::Reference(id)) => match self.doc.get(*id) {
+                Some(Object::Array(a)) => a.iter().filter_map(|g| g.as_reference()).collect(),
+                _ => vec![*id],
+            },
+            Some(Object::Array(a)) => a.iter().filter_map(|g| g.as_reference()).collect(),
+            _ => Vec::new(),
+        };
+        if groups.is_empty() {
+            return false;
+        }
+        let off = groups.iter().filter(|g| self.hidden.contains(g)).count();
+        match membership.get_name("P").unwrap_or("AnyOn") {
+            "AllOn" => off > 0,
+            "AnyOff" => off == 0,
+            "AllOff" => off < groups.len(),
+            _ => off == groups.len(),
+        }
+    }
+
+    /// Removes the annotations over the areas or in hidden layers from the
+    /// page.
+    fn remove_annotations(&mut self, page_id: Id) {
+        let Some(annots) = self.doc.get(page_id)
+            .and_then(|p| p.as_dict())
+            .and_then(|p| self.doc.resolve_entry(p, "Annots"))
+            .and_then(|a| a.as_array())
+        else {
+            return;
+        };
+        let kept: Vec<Object> = annots.iter().filter(|annot| {
+            let Some(annot) = self.doc.resolve(annot).as_dict() else {
+                return true;
+            };
+            let covered = annot.get("Rect").and_then(|r| self.doc.resolve(r).as_rect()).is_some_and(|r| self.covers(&r));
+            let hidden = annot.get("OC").is_some_and(|oc| self.is_hidden(oc));
+            !covered && !hidden
+        }).cloned().collect();
+        if kept.len() != annots.len() && let Some(page) = self.doc.get_mut(page_id).and_then(|p| p.as_dict_mut()) {
+            page.set("Annots", kept);
+        }
+    }
+}
+
+/// Appends the adjustment, adding it to the previous one if any.
+fn push_adjustment(items: &mut Vec<TextItem>, adjustment: f64) {
+    match items.last_mut() {
+        Some(TextItem::Adjust(previous)) => *previous += adjustment,
+        _ => items.push(TextItem::Adjust(adjustment)),
+    }
+}
+
+/// Returns true if the rectangles share an area, not only an edge.
+fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+    a.llx < b.urx && b.llx < a.urx && a.lly < b.ury && b.lly < a.ury
+        // an empty box inside
+        || (b.width() == 0.0 || b.height() == 0.0) && a.llx <= b.llx && b.urx <= a.urx && a.lly <= b.lly && b.ury <= a.ury
+}
+
+/// Returns the box of the unit square an image is painted in.
+fn unit_square(ctm: &Matrix) -> Rectangle {
+    text_box(ctm, 0.0, 1.0, 0.0, 1.0)
+}
+
+fn invert(m: &Matrix) -> Option<Matrix> {
+    let det = m[0] * m[3] - m[1] * m[2];
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    Some([
+        m[3] / det,
+        -m[1] / det,
+        -m[2] / det,
+        m[0] / det,
+        (m[2] * m[5] - m[3] * m[4]) / det,
+        (m[1] * m[4] - m[0] * m[5]) / det,
+    ])
+}
+
+fn filter_names(dict: &Dictionary) -> Vec<&str> {
+    match dict.get("Filter") {
+        Some(Object::Name(n)) => vec![n.as_str()],
+        Some(Object::Array(a)) => a.iter().filter_map(|f| f.as_name()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the optional content groups off in the default configuration.
+fn hidden_groups(doc: &LoadedDoc) -> HashSet<Id> {
+    let Some(properties) = doc.catalog()
+        .and_then(|c| doc.resolve_entry(c, "OCProperties"))
+        .and_then(|p| p.as_dict())
+    else {
+        return HashSet::new();
+    };
+    let Some(config) = doc.resolve_entry(properties, "D").and_then(|d| d.as_dict()) else {
+        return HashSet::new();
+    };
+    let refs = |dict: &Dictionary, key: &str| -> HashSet<Id> {
+        doc.resolve_entry(dict, key)
+            .and_then(|a| a.as_array())
+            .map(|a| a.iter().filter_map(|g| g.as_reference()).collect())
+            .unwrap_or_default()
+    };
+    if config.get_name("BaseState") == Some("OFF") {
+        let on = refs(config, "ON");
+        refs(properties, "OCGs").into_iter().filter(|g| !on.contains(g)).collect()
+    } else {
+        refs(config, "OFF")
+    }
+}
+
+fn scrub_metadata(doc: &mut LoadedDoc) {
+    doc.trailer_mut().remove("Info");
+    let mut ids = doc.page_ids();
+    ids.push(doc.catalog_id());
+    for id in ids {
+        if let Some(dict) = doc.get_mut(id).and_then(|o| o.as_dict_mut()) {
+            dict.remove("Metadata");
+            dict.remove("PieceInfo");
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::font::Font;
+    use crate::pdf::page::Page;
+    use crate::pdf::text::extract_text;
+
+    fn find(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn text() {
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        let c = page.contents();
+        c.begin_text();
+        c.set_font("F0", 10.0);
+        c.move_text(100.0, 700.0);
+        c.show_text("Name: Jane Roe, Ref 7");
+        c.end_text();
+        doc.push_page(page);
+        doc.push_page(Page::new(MediaBox::A4));
+        let mut doc = LoadedDoc::load(doc.to_bytes()).unwrap();
+
+        let mut redaction = Redaction::new();
+        assert_eq!(redaction.add_text(&doc, "Jane Roe").unwrap(), 1);
+        redaction.set_overlay_text("X", 8.0);
+        redaction.apply(&mut doc).unwrap();
+
+        let bytes = doc.to_bytes();
+        assert!(!find(&bytes, b"Jane"));
+        let doc = LoadedDoc::load(bytes).unwrap();
+        assert_eq!(extract_text(&doc, 0).unwrap(), "Name: , Ref 7X");
+        let operations = doc.page_operations(doc.page_ids()[0]).unwrap();
+        // 8 glyphs 500 wide
+        assert!(operations.contains(&Operation::ShowTextAdjusted(
+            vec![TextItem::from("Name: "), TextItem::Adjust(-4000.0), TextItem::from(", Ref 7")])));
+        assert!(operations.contains(&Operation::Rect(130.0, 697.5, 40.0, 10.0)));
+        assert!(doc.trailer().get("Info").is_none());
+
+        let mut redaction = Redaction::new();
+        redaction.add_area(2, Rectangle::new(0.0, 0.0, 1.0, 1.0));
+        assert!(redaction.apply(&mut LoadedDoc::load(doc.to_bytes()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn image_and_path() {
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(Page::new(MediaBox::A4));
+        let mut doc = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let page = doc.page_ids()[0];
+
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::name("XObject"));
+        dict.set("Subtype", Object::name("Image"));
+        dict.set("Width", 2);
+        dict.set("Height", 2);
+        dict.set("BitsPerComponent", 8);
+        dict.set("ColorSpace", Object::name("DeviceGray"));
+        let image = doc.add_object(Stream::new(dict, vec![255; 4]));
+        let mut xobjects = Dictionary::new();
+        xobjects.set("Im0", Object::Reference(image));
+        let mut resources = Dictionary::new();
+        resources.set("XObject", xobjects);
+        doc.get_mut(page).unwrap().as_dict_mut().unwrap().set("Resources", resources);
+        let operations = operation::parse(b"q 100 0 0 100 0 0 cm /Im0 Do Q 0 0 60 60 re f 200 200 10 10 re f").unwrap();
+        doc.set_page_operations(page, &operations).unwrap();
+
+        let mut redaction = Redaction::new();
+        redaction.add_area(0, Rectangle::new(0.0, 50.0, 50.0, 100.0));
+        redaction.apply(&mut doc).unwrap();
+
+        // The top-left pixel
+        let resources = doc.page_attribute(page, "Resources").unwrap().as_dict().unwrap();
+        let xobjects = resources.get("XObject").unwrap().as_dict().unwrap();
+        let image = doc.resolve_entry(xobjects, "Im0").unwrap().as_stream().unwrap();
+        assert_eq!(image.decoded_data().unwrap(), [0, 255, 255, 255]);
+
+        let operations = doc.page_operations(page).unwrap();
+        let clipped = operations.iter().position(|o| *o == Operation::ClipEvenOdd).unwrap();
+        assert_eq!(operations[clipped - 1], Operation::Rect(0.0, 50.0, 50.0, 50.0));
+        assert_eq!(operations[clipped + 3..clipped + 6],
+            [Operation::Rect(0.0, 0.0, 60.0, 60.0), Operation::Fill, Operation::RestoreState]);
+        // Only the path under the area is clipped
+        assert_eq!(operations.iter().filter(|o| **o == Operation::ClipEvenOdd).count(), 1);
+        // The original image
+        assert_eq!(doc.remove_unreferenced(), 0);
+        assert!(doc.ids().all(|id| doc.get(id).and_then(|o| o.as_stream()).is_none_or(|s| s.raw_data() != [255; 4])));
+    }
+
+    #[test]
+    fn hidden_layers() {
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        doc.push_page(page);
+        let mut doc = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let page = doc.page_ids()[0];
+
+        let mut group = Dictionary::new();
+        group.set("Type", Object::name("OCG"));
+        group.set("Name", Object::text("Notes"));
+        let group = doc.add_object(group);
+        let mut config = Dictionary::new();
+        config.set("OFF", vec![Object::Reference(group)]);
+        let mut properties = Dictionary::new();
+        properties.set("OCGs", vec![Object::Reference(group)]);
+        properties.set("D", config);
+        let catalog = doc.catalog_id();
+        doc.get_mut(catalog).unwrap().as_dict_mut().unwrap().set("OCProperties", properties);
+
+        let mut properties = Dictionary::new();
+        properties.set("MC0", Object::Reference(group));
+        let mut resources = doc.page_attribute(page, "Resources").unwrap().as_dict().unwrap().clone();
+        resources.set("Properties", properties);
+        doc.get_mut(page).unwrap().as_dict_mut().unwrap().set("Resources", resources);
+        let operations = operation::parse(
+            b"BT /F0 10 Tf 100 700 Td (Shown) Tj ET /OC /MC0 BDC /Span BMC BT /F0 10 Tf (Secret) Tj ET EMC EMC").unwrap();
+        doc.set_page_operations(page, &operations).unwrap();
+        assert_eq!(extract_text(&doc, 0).unwrap(), "Shown\nSecret");
+
+        Redaction::new().apply(&mut doc).unwrap();
+        assert_eq!(extract_text(&doc, 0).unwrap(), "Shown");
+        assert!(!doc.catalog().unwrap().contains_key("OCProperties"));
+        assert!(doc.get(group).is_none());
+    }
+}
@@ -6,16 +6,25 @@
 
 
 use super::base::*;
-use super::colour::space::DeviceN;
+use super::colour::space::ColorSpace;
+use super::ext_g_state::ExtGState;
 use super::font::Font;
-use super::utils::{indent, ToPdfString};
+use super::pattern::Pattern;
+use super::shading::Shading;
+use super::utils::dict_string;
+use super::xobject::XObject;
 
 use std::collections::HashMap;
 
+#[derive(Clone)]
 pub struct Resources {
     pub id: Id,
     fonts: HashMap<String, Font>,
-    color_spaces: HashMap<Name, DeviceN>,
+    color_spaces: HashMap<Name, ColorSpace>,
+    ext_g_states: HashMap<String, ExtGState>,
+    patterns: HashMap<String, Pattern>,
+    shadings: HashMap<String, Shading>,
+    xobjects: HashMap<String, XObject>,
 }
 
 impl Resources {
@@ -24,6 +33,10 @@ impl Resources {
             id: Id::new_0(),
             fonts: HashMap::new(),
             color_spaces: HashMap::new(),
+            ext_g_states: HashMap::new(),
+            patterns: HashMap::new(),
+            shadings: HashMap::new(),
+            xobjects: HashMap::new(),
         }
     }
 
@@ -31,52 +44,134 @@ impl Resources {
         self.fonts.insert(name.to_string(), font);
     }
 
-    pub fn add_color_space(&mut self, name: &str, space: DeviceN) {
-        self.color_spaces.insert(Name::new(name), space);
+    pub fn font(&self, name: &str) -> Option<&Font> {
+        self.fonts.get(name)
     }
 
-    pub fn to_string(&self, indent: usize) -> String {
+    /// Adds the colour space, e.g. `DeviceN`, `Separation` or `Lab`.
+    pub fn add_color_space(&mut self, name: &str, space: impl Into<ColorSpace>) {
+        self.color_spaces.insert(Name::new(name), space.into());
+    }
+
+    pub fn color_space(&self, name: &str) -> Option<&ColorSpace> {
+        self.color_spaces.get(&Name::new(name))
+    }
+
+    pub fn add_ext_g_state(&mut self, name: &str, gs: ExtGState) {
+        self.ext_g_states.insert(name.to_string(), gs);
+    }
+
+    /// Adds the pattern, e.g. `TilingPattern` or `ShadingPattern`.
+    pub fn add_pattern(&mut self, name: &str, pattern: impl Into<Pattern>) {
+        self.patterns.insert(name.to_string(), pattern.into());
+    }
+
+    /// Adds the shading painted with `Contents::paint_shading`.
+    pub fn add_shading(&mut self, name: &str, shading: Shading) {
+        self.shadings.insert(name.to_string(), shading);
+    }
+
+    /// Adds the XObject, e.g. `Form` or `PageForm`.
+    pub fn add_xobject(&mut self, name: &str, xobject: impl Into<XObject>) {
+        self.xobjects.insert(name.to_string(), xobject.into());
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let mut entries: Vec<(&str, String)> = Vec::new();
+        if !self.fonts.is_empty() {
+            entries.push(("Font", self.fonts_to_string()));
+        }
+        if !self.color_spaces.is_empty() {
+            entries.push(("ColorSpace", self.get_color_space_string()));
+        }
+        if !self.ext_g_states.is_empty() {
+            entries.push(("ExtGState", self.get_ext_g_state_string()));
+        }
+        if !self.patterns.is_empty() {
+            entries.push(("Pattern", self.get_pattern_string()));
+        }
+        if !self.shadings.is_empty() {
+            entries.push(("Shading", self.get_shading_string()));
+        }
+        if !self.xobjects.is_empty() {
+            entries.push(("XObject", self.get_xobject_string()));
+        }
+
         format!(concat!(
             "{} obj\n",
-            "<<\n",
-            "  /Font {}\n",
-            "  /ColorSpace {}\n",
-            "\n",
-            ">>\n",
+            "{}\n",
             "endobj"),
-            self.id.to_string(),
-            self.fonts_to_string(indent),
-            self.get_color_space_string(indent),
+            self.id,
+            dict_string(&entries),
         )
     }
 
-    fn get_color_space_string(&self, indent_depth: usize) -> String {
-        let mut dict = String::new();
-        dict.push_str("<<\n");
-        for space in &self.color_spaces {
-            dict.push_str(&format!("{} {}", space.0.to_pdf_string(), space.1.id().to_ref_string()));
-        }
-        dict.push_str(">>");
+    fn get_color_space_string(&self) -> String {
+        let mut spaces: Vec<(String, String)> = self.color_spaces.iter()
+            .map(|(name, space)| (name.to_string(), space.to_pdf_string(0)))
+            .collect();
+        spaces.sort();
 
-        dict
+        let entries: Vec<(&str, String)> = spaces.iter()
+            .map(|(name, r)| (&name[1..], r.clone()))
+            .collect();
+        dict_string(&entries)
     }
 
-    fn fonts_to_string(&self, indent_size: usize) -> String {
-        let dict_elms = (|| {
-            let mut dict = String::new();
-            for (name, font) in &self.fonts {
-                dict.push_str(&indent(&format!(concat!(
-                    "<< /{}\n",
-                    "{}\n",
-                    ">>"),
-                    name,
-                    font.to_string(1)),
-                    indent_size + 1));
-            }
-            dict
-        })();
+    fn get_ext_g_state_string(&self) -> String {
+        let mut names: Vec<&String> = self.ext_g_states.keys().collect();
+        names.sort();
 
-        dict_elms
+        let entries: Vec<(&str, String)> = names.iter()
+            .map(|name| (name.as_str(), self.ext_g_states[*name].to_pdf_string()))
+            .collect();
+        dict_string(&entries)
+    }
+
+    fn get_pattern_string(&self) -> String {
+        let mut names: Vec<&String> = self.patterns.keys().collect();
+        names.sort();
+
+        let entries: Vec<(&str, String)> = names.iter()
+            .map(|name| (name.as_str(), self.patterns[*name].id().to_ref_string()))
+            .collect();
+        dict_string(&entries)
+    }
+
+    fn get_shading_string(&self) -> String {
+        let mut names: Vec<&String> = self.shadings.keys().collect();
+        names.sort();
+
+        let entries: Vec<(&str, String)> = names.iter()
+            .map(|name| (name.as_str(), self.shadings[*name].id().to_ref_string()))
+            .collect();
+        dict_string(&entries)
+    }
+
+    fn get_xobject_string(&self) -> String {
+        let mut names: Vec<&String> = self.xobjects.keys().collect();
+        names.sort();
+
+        let entries: Vec<(&str, String)> = names.iter()
+            .map(|name| (name.as_str(), self.xobjects[*name].id().to_ref_string()))
+            .collect();
+        dict_string(&entries)
+    }
+
+    fn fonts_to_string(&self) -> String {
+        let mut names: Vec<&String> = self.fonts.keys().collect();
+        names.sort();
+
+        let entries: Vec<(&str, String)> = names.iter()
+            .map(|name| (name.as_str(), self.fonts[*name].to_string(0)))
+            .collect();
+        dict_string(&entries)
+    }
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -90,6 +185,15 @@ impl PdfObject for Resources {
         for cs in &mut self.color_spaces {
             cs.1.assign_ids(id_factory);
         }
+        for pattern in self.patterns.values_mut() {
+            pattern.assign_ids(id_factory);
+        }
+        for shading in self.shadings.values_mut() {
+            shading.assign_ids(id_factory);
+        }
+        for xobject in self.xobjects.values_mut() {
+            xobject.assign_ids(id_factory);
+        }
     }
 
     fn get_objects(&self) -> Vec<&dyn PdfObject> {
@@ -100,6 +204,15 @@ impl PdfObject for Resources {
         for cs in &self.color_spaces {
             list.append(&mut cs.1.get_objects());
         }
+        for pattern in self.patterns.values() {
+            list.append(&mut pattern.get_objects());
+        }
+        for shading in self.shadings.values() {
+            list.append(&mut shading.get_objects());
+        }
+        for xobject in self.xobjects.values() {
+            list.append(&mut xobject.get_objects());
+        }
 
         list
     }
@@ -136,4 +249,26 @@ mod tests {
 
         assert_eq!(r.to_string(0), ok);
     }
+
+    #[test]
+    fn to_string_ext_g_state() {
+        let mut r = Resources::new();
+        let mut gs = ExtGState::new();
+        gs.set_alpha(0.5);
+        r.add_ext_g_state("GS0", gs);
+
+        let ok = concat!(
+            "0 0 obj\n",
+            "<< /ExtGState\n",
+            "  << /GS0\n",
+            "    << /Type /ExtGState\n",
+            "       /CA 0.5\n",
+            "       /ca 0.5\n",
+            "    >>\n",
+            "  >>\n",
+            ">>\n",
+            "endobj",);
+
+        assert_eq!(r.to_string(0), ok);
+    }
 }
@@ -0,0 +1,666 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 8.7.4 Shading Patterns
+//!
+//! A shading is painted directly with `Contents::paint_shading` (`sh`), or
+//! used as the paint of fills and strokes through a `ShadingPattern`.
+//!
+//! ```
+//! use prspdf::{MediaBox, Page};
+//! use prspdf::colour::space::ColorSpace;
+//! use prspdf::function::Type3;
+//! use prspdf::shading::Shading;
+//!
+//! // Red, yellow and blue from left to right
+//! let stops = Type3::from_stops(&[
+//!     (0.0, vec![1.0, 0.0, 0.0]),
+//!     (0.5, vec![1.0, 1.0, 0.0]),
+//!     (1.0, vec![0.0, 0.0, 1.0]),
+//! ]).unwrap();
+//! let mut gradient = Shading::axial(ColorSpace::DeviceRGB, [72.0, 0.0, 272.0, 0.0], stops);
+//! gradient.set_extend(true, true);
+//!
+//! let mut page = Page::new(MediaBox::A4);
+//! page.resources().add_shading("Sh0", gradient);
+//! page.contents().save_state();
+//! page.contents().rect(72.0, 600.0, 200.0, 100.0);
+//! page.contents().clip();
+//! page.contents().end_path();
+//! page.contents().paint_shading("Sh0");
+//! page.contents().restore_state();
+//! ```
+
+use super::base::*;
+use super::colour::space::ColorSpace;
+use super::error::Error;
+use super::function::Function;
+use super::utils::{dict_string, ToPdfString};
+
+/// PDF32000-1:2008 8.7.4.3
+#[derive(Clone)]
+pub struct Shading {
+    id: Id,
+    color_space: ColorSpace,
+    background: Option<Vec<f64>>,
+    bbox: Option<Rectangle>,
+    anti_alias: bool,
+    function: Option<Box<dyn Function>>,
+    kind: Kind,
+}
+
+/// The entries of each shading type
+#[derive(Clone)]
+enum Kind {
+    /// Type 1
+    FunctionBased { domain: [f64; 4], matrix: Option<[f64; 6]> },
+    /// Type 2 and 3
+    Gradient { shading_type: u8, coords: Vec<f64>, domain: (f64, f64), extend: (bool, bool) },
+    /// Type 4 to 7
+    Mesh(Mesh),
+}
+
+impl Shading {
+    /// Creates the type 1 shading of the colour `function(x, y)` over the
+    /// domain `[0 1 0 1]`.
+    pub fn function_based(color_space: impl Into<ColorSpace>, function: impl Function + 'static) -> Self {
+        Self::new(color_space.into(), Some(Box::new(function)), Kind::FunctionBased { domain: [0.0, 1.0, 0.0, 1.0], matrix: None })
+    }
+
+    /// Creates the type 2 shading varying along the axis `[x0 y0 x1 y1]`,
+    /// the colour being `function(t)` for t from 0.0 to 1.0.
+    pub fn axial(color_space: impl Into<ColorSpace>, coords: [f64; 4], function: impl Function + 'static) -> Self {
+        let kind = Kind::Gradient { shading_type: 2, coords: coords.to_vec(), domain: (0.0, 1.0), extend: (false, false) };
+        Self::new(color_space.into(), Some(Box::new(function)), kind)
+    }
+
+    /// Creates the type 3 shading varying between the circles
+    /// `[x0 y0 r0 x1 y1 r1]`, the colour being `function(t)` for t from 0.0
+    /// to 1.0.
+    pub fn radial(color_space: impl Into<ColorSpace>, coords: [f64; 6], function: impl Function + 'static) -> Self {
+        let kind = Kind::Gradient { shading_type: 3, coords: coords.to_vec(), domain: (0.0, 1.0), extend: (false, false) };
+        Self::new(color_space.into(), Some(Box::new(function)), kind)
+    }
+
+    /// Creates the type 4 to 7 shading of the mesh. The colours of the mesh
+    /// are in the colour space, or a parametric value if a function is set.
+    ///
+    /// Fails if the colours of the mesh have neither the components of the
+    /// space nor 1, or a lattice has less than 2 vertices per row or an
+    /// incomplete row.
+    pub fn mesh(color_space: impl Into<ColorSpace>, mesh: Mesh) -> Result<Self, Error> {
+        let color_space = color_space.into();
+        let components = mesh.decode.len() - 2;
+        if components != color_space.components() && components != 1 {
+            return Err(Error::InvalidValue(format!(
+                "mesh colours of {} components for {} colour space", components, color_space.family())));
+        }
+        if mesh.mesh_type == MeshType::LatticeForm {
+            let per_row = mesh.vertices_per_row as usize;
+            if per_row < 2 || !mesh.count.is_multiple_of(per_row) || mesh.count / per_row < 2 {
+                return Err(Error::InvalidValue(format!(
+                    "lattice of {} vertices with {} per row", mesh.count, per_row)));
+            }
+        }
+        Ok(Self::new(color_space, None, Kind::Mesh(mesh)))
+    }
+
+    fn new(color_space: ColorSpace, function: Option<Box<dyn Function>>, kind: Kind) -> Self {
+        Self {
+            id: Id::new_0(),
+            color_space,
+            background: None,
+            bbox: None,
+            anti_alias: false,
+            function,
+            kind,
+        }
+    }
+
+    /// Returns the `/ShadingType`, 1 to 7.
+    pub fn shading_type(&self) -> u8 {
+        match &self.kind {
+            Kind::FunctionBased { .. } => 1,
+            Kind::Gradient { shading_type, .. } => *shading_type,
+            Kind::Mesh(mesh) => mesh.mesh_type as u8,
+        }
+    }
+
+    pub fn color_space(&self) -> &ColorSpace {
+        &self.color_space
+    }
+
+    /// Sets the colour of the area outside the shading when it is painted
+    /// as a pattern. Fails if the components do not match the space.
+    pub fn set_background(&mut self, color: Vec<f64>) -> Result<(), Error> {
+        if color.len() != self.color_space.components() {
+            return Err(Error::InvalidValue(format!(
+                "Background of {} components for {} colour space", color.len(), self.color_space.family())));
+        }
+        self.background = Some(color);
+        Ok(())
+    }
+
+    /// Sets the box the shading is clipped to, in the shading space.
+    pub fn set_bbox(&mut self, bbox: Rectangle) {
+        self.bbox = Some(bbox);
+    }
+
+    pub fn set_anti_alias(&mut self, anti_alias: bool) {
+        self.anti_alias = anti_alias;
+    }
+
+    /// Sets the function of the colour, replacing the one of a type 1 to 3
+    /// shading or mapping the parametric values of a mesh.
+    pub fn set_function(&mut self, function: impl Function + 'static) {
+        self.function = Some(Box::new(function));
+    }
+
+    /// Sets the domain of a type 1 shading, `[x0 x1 y0 y1]`.
+    pub fn set_function_domain(&mut self, domain: [f64; 4]) {
+        if let Kind::FunctionBased { domain: d, .. } = &mut self.kind {
+            *d = domain;
+        }
+    }
+
+    /// Sets the matrix from the domain of a type 1 shading to the shading
+    /// space.
+    pub fn set_matrix(&mut self, matrix: [f64; 6]) {
+        if let Kind::FunctionBased { matrix: m, .. } = &mut self.kind {
+            *m = Some(matrix);
+        }
+    }
+
+    /// Sets the range of t of a type 2 or 3 shading, `[0 1]` by default.
+    pub fn set_domain(&mut self, t0: f64, t1: f64) {
+        if let Kind::Gradient { domain, .. } = &mut self.kind {
+            *domain = (t0, t1);
+        }
+    }
+
+    /// Sets whether a type 2 or 3 shading extends beyond the start and the
+    /// end.
+    pub fn set_extend(&mut self, start: bool, end: bool) {
+        if let Kind::Gradient { extend, .. } = &mut self.kind {
+            *extend = (start, end);
+        }
+    }
+
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("ShadingType", self.shading_type().to_string()),
+            ("ColorSpace", self.color_space.to_pdf_string(0)),
+        ];
+        if let Some(background) = &self.background {
+            entries.push(("Background", background.to_pdf_string()));
+        }
+        if let Some(bbox) = &self.bbox {
+            entries.push(("BBox", bbox.to_pdf_string()));
+        }
+        if self.anti_alias {
+            entries.push(("AntiAlias", String::from("true")));
+        }
+
+        match &self.kind {
+            Kind::FunctionBased { domain, matrix } => {
+                entries.push(("Domain", domain.to_pdf_string()));
+                if let Some(matrix) = matrix {
+                    entries.push(("Matrix", matrix.to_pdf_string()));
+                }
+            }
+            Kind::Gradient { coords, domain, extend, .. } => {
+                entries.push(("Coords", coords.to_pdf_string()));
+                if *domain != (0.0, 1.0) {
+                    entries.push(("Domain", [domain.0, domain.1].to_pdf_string()));
+                }
+                if *extend != (false, false) {
+                    entries.push(("Extend", format!("[{} {}]", extend.0, extend.1)));
+                }
+            }
+            Kind::Mesh(mesh) => {
+                entries.push(("BitsPerCoordinate", mesh.bits_per_coordinate.to_string()));
+                entries.push(("BitsPerComponent", mesh.bits_per_component.to_string()));
+                if mesh.mesh_type == MeshType::LatticeForm {
+                    entries.push(("VerticesPerRow", mesh.vertices_per_row.to_string()));
+                } else {
+                    entries.push(("BitsPerFlag", String::from("8")));
+                }
+                entries.push(("Decode", mesh.decode.to_pdf_string()));
+            }
+        }
+
+        if let Some(function) = &self.function {
+            entries.push(("Function", function.to_pdf_string(0)));
+        }
+        entries
+    }
+}
+
+impl PdfObject for Shading {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.color_space.assign_ids(id_factory);
+        if let Some(function) = &mut self.function {
+            function.assign_ids(id_factory);
+        }
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.color_space.get_objects();
+        if let Some(function) = &self.function {
+            list.append(&mut function.get_objects());
+        }
+        list.push(self);
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut entries = self.entries();
+        match &self.kind {
+            Kind::Mesh(mesh) => {
+                entries.push(("Length", mesh.data.len().to_string()));
+                let mut bytes = format!("{} obj\n{}\nstream\n", self.id, dict_string(&entries)).into_bytes();
+                bytes.extend_from_slice(&mesh.data);
+                bytes.extend_from_slice(b"\nendstream\nendobj");
+                bytes
+            }
+            _ => format!("{} obj\n{}\nendobj", self.id, dict_string(&entries)).into_bytes(),
+        }
+    }
+}
+
+/// The mesh shading types
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeshType {
+    /// Type 4, triangles of vertices with edge flags
+    FreeForm = 4,
+    /// Type 5, triangles of a lattice of vertices
+    LatticeForm = 5,
+    /// Type 6, Coons patches of 12 control points
+    Coons = 6,
+    /// Type 7, tensor-product patches of 16 control points
+    TensorProduct = 7,
+}
+
+/// The binary data of a mesh shading (PDF32000-1:2008 8.7.4.5.5 to
+/// 8.7.4.5.8).
+///
+/// Coordinates and colour components are quantised with
+/// `BitsPerCoordinate` and `BitsPerComponent` into the ranges of `/Decode`.
+/// Every vertex or patch starts at a byte boundary and flags take 8 bits.
+#[derive(Clone)]
+pub struct Mesh {
+    mesh_type: MeshType,
+    bits_per_coordinate: u8,
+    bits_per_component: u8,
+    /// The ranges of x, y and each colour component
+    decode: Vec<(f64, f64)>,
+    vertices_per_row: u32,
+    /// The number of vertices or patches
+    count: usize,
+    data: Vec<u8>,
+}
+
+impl Mesh {
+    /// Creates the mesh with the ranges of the coordinates and of each
+    /// colour component, one if a function maps the colours.
+    ///
+    /// Fails if the bits are not 1, 2, 4, 8, 12, 16, 24 or 32 per coordinate
+    /// and 1, 2, 4, 8, 12 or 16 per component, or there is no colour range.
+    pub fn new(
+        mesh_type: MeshType,
+        bits_per_coordinate: u8,
+        bits_per_component: u8,
+        x_range: (f64, f64),
+        y_range: (f64, f64),
+        color_ranges: Vec<(f64, f64)>,
+    ) -> Result<Self, Error> {
+        if !matches!(bits_per_coordinate, 1 | 2 | 4 | 8 | 12 | 16 | 24 | 32) {
+            return Err(Error::InvalidValue(format!("BitsPerCoordinate {}", bits_per_coordinate)));
+        }
+        if !matches!(bits_per_component, 1 | 2 | 4 | 8 | 12 | 16) {
+            return Err(Error::InvalidValue(format!("BitsPerComponent {}", bits_per_component)));
+        }
+        if color_ranges.is_empty() {
+            return Err(Error::InvalidValue(String::from("mesh without colour ranges")));
+        }
+
+        let mut decode = vec![x_range, y_range];
+        decode.extend(color_ranges);
+        Ok(Self {
+            mesh_type,
+            bits_per_coordinate,
+            bits_per_component,
+            decode,
+            vertices_per_row: 0,
+            count: 0,
+            data: Vec::new(),
+        })
+    }
+
+    /// Sets the number of vertices in each row of a lattice.
+    pub fn set_vertices_per_row(&mut self, vertices_per_row: u32) {
+        self.vertices_per_row = vertices_per_row;
+    }
+
+    /// Adds a vertex of a free-form mesh. The flag is 0 to start a new
+    /// triangle, 1 or 2 to make one with the last two or the last and the
+    /// first vertices of the previous triangle.
+    pub fn add_triangle_vertex(&mut self, flag: u8, point: (f64, f64), color: &[f64]) -> Result<(), Error> {
+        self.check_type(&[MeshType::FreeForm])?;
+        if flag > 2 || (self.count < 3 && flag != 0) {
+            return Err(Error::InvalidValue(format!("edge flag {} of vertex {}", flag, self.count)));
+        }
+        self.check_color(color)?;
+        self.push_record(Some(flag), &[point], &[color]);
+        Ok(())
+    }
+
+    /// Adds the next vertex of a lattice, row by row.
+    pub fn add_lattice_vertex(&mut self, point: (f64, f64), color: &[f64]) -> Result<(), Error> {
+        self.check_type(&[MeshType::LatticeForm])?;
+        self.check_color(color)?;
+        self.push_record(None, &[point], &[color]);
+        Ok(())
+    }
+
+    /// Adds a patch of a Coons or tensor-product mesh. A patch with flag 0
+    /// has 12 (Coons) or 16 (tensor) points and 4 colours, one sharing an
+    /// edge of the previous patch (flag 1 to 3) 4 points and 2 colours less.
+    pub fn add_patch(&mut self, flag: u8, points: &[(f64, f64)], colors: &[&[f64]]) -> Result<(), Error> {
+        self.check_type(&[MeshType::Coons, MeshType::TensorProduct])?;
+        if flag > 3 || (self.count == 0 && flag != 0) {
+            return Err(Error::InvalidValue(format!("edge flag {} of patch {}", flag, self.count)));
+        }
+        let full = if self.mesh_type == MeshType::Coons { 12 } else { 16 };
+        let (n_points, n_colors) = if flag == 0 { (full, 4) } else { (full - 4, 2) };
+        if points.len() != n_points || colors.len() != n_colors {
+            return Err(Error::InvalidValue(format!(
+                "patch of {} points and {} colours, {} and {} expected", points.len(), colors.len(), n_points, n_colors)));
+        }
+        for color in colors {
+            self.check_color(color)?;
+        }
+        self.push_record(Some(flag), points, colors);
+        Ok(())
+    }
+
+    fn check_type(&self, types: &[MeshType]) -> Result<(), Error> {
+        if !types.contains(&self.mesh_type) {
+            return Err(Error::InvalidValue(format!("vertex of {:?} mesh", self.mesh_type)));
+        }
+        Ok(())
+    }
+
+    fn check_color(&self, color: &[f64]) -> Result<(), Error> {
+        if color.len() != self.decode.len() - 2 {
+            return Err(Error::InvalidValue(format!(
+                "mesh colour of {} components, {} expected", color.len(), self.decode.len() - 2)));
+        }
+        Ok(())
+    }
+
+    fn push_record(&mut self, flag: Option<u8>, points: &[(f64, f64)], colors: &[&[f64]]) {
+        let mut writer = BitWriter::default();
+        if let Some(flag) = flag {
+            writer.push(flag as u64, 8);
+        }
+        for (x, y) in points {
+            writer.push(quantise(*x, self.decode[0], self.bits_per_coordinate), self.bits_per_coordinate);
+            writer.push(quantise(*y, self.decode[1], self.bits_per_coordinate), self.bits_per_coordinate);
+        }
+        for color in colors {
+            for (c, range) in color.iter().zip(&self.decode[2..]) {
+                writer.push(quantise(*c, *range, self.bits_per_component), self.bits_per_component);
+            }
+        }
+        self.data.append(&mut writer.finish());
+        self.count += 1;
+    }
+}
+
+/// PDF32000-1:2008 8.7.3.3
+///
+/// ```text
+/// 12 0 obj
+/// << /Type /Pattern
+///    /PatternType 2
+///    /Shading 13 0 R
+///    /Matrix [1 0 0 1 0 0]
+/// >>
+/// endobj
+/// ```
+#[derive(Clone)]
+pub struct ShadingPattern {
+    id: Id,
+    shading: Shading,
+    matrix: Option<[f64; 6]>,
+}
+
+impl ShadingPattern {
+    pub fn new(shading: Shading) -> Self {
+        Self { id: Id::new_0(), shading, matrix: None }
+    }
+
+    /// Sets the matrix from the shading space to the default coordinate
+    /// space of the page, or of the form the pattern is used in.
+    pub fn set_matrix(&mut self, matrix: [f64; 6]) {
+        self.matrix = Some(matrix);
+    }
+
+    pub fn shading(&self) -> &Shading {
+        &self.shading
+    }
+}
+
+impl PdfObject for ShadingPattern {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.shading.assign_ids(id_factory);
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.shading.get_objects();
+        list.push(self);
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut entries = vec![
+            ("Type", String::from("/Pattern")),
+            ("PatternType", String::from("2")),
+            ("Shading", self.shading.id.to_ref_string()),
+        ];
+        if let Some(m) = &self.matrix {
+            entries.push(("Matrix", m.to_pdf_string()));
+        }
+        format!("{} obj\n{}\nendobj", self.id, dict_string(&entries)).into_bytes()
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Returns the value in the range as an integer of the bits.
+fn quantise(value: f64, range: (f64, f64), bits: u8) -> u64 {
+    let max = ((1u64 << bits) - 1) as f64;
+    if range.1 == range.0 {
+        return 0;
+    }
+    ((value - range.0) * max / (range.1 - range.0)).round().clamp(0.0, max) as u64
+}
+
+/// Writes bits MSB first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u64, bits: u8) {
+        self.buffer = (self.buffer << bits) | value;
+        self.bits += bits as u32;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.buffer >> self.bits) as u8);
+        }
+        self.buffer &= (1 << self.bits) - 1;
+    }
+
+    /// Returns the bytes padded to the byte boundary.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push((self.buffer << (8 - self.bits)) as u8);
+        }
+        self.bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::colour::space::{DeviceCMYK, Separation};
+    use crate::pdf::doc::Doc;
+    use crate::pdf::function::{Type2, Type3};
+    use crate::pdf::loaded_doc::LoadedDoc;
+    use crate::pdf::page::Page;
+
+    #[test]
+    fn axial() {
+        let stops = Type3::from_stops(&[(0.0, vec![0.0]), (1.0, vec![1.0])]).unwrap();
+        let mut shading = Shading::axial(ColorSpace::DeviceGray, [0.0, 0.0, 100.0, 0.0], stops);
+        shading.set_extend(true, false);
+        shading.set_domain(0.0, 2.0);
+        assert!(shading.set_background(vec![0.0, 0.0]).is_err());
+        shading.set_background(vec![1.0]).unwrap();
+        assert_eq!(shading.shading_type(), 2);
+
+        let ok = concat!(
+            "0 0 obj\n",
+            "<< /ShadingType 2\n",
+            "   /ColorSpace /DeviceGray\n",
+            "   /Background [1]\n",
+            "   /Coords [0 0 100 0]\n",
+            "   /Domain [0 2]\n",
+            "   /Extend [true false]\n",
+            "   /Function\n",
+            "  <<\n",
+            "    /FunctionType 3\n",
+        );
+        let s = String::from_utf8(shading.to_bytes(0)).unwrap();
+        assert!(s.starts_with(ok), "{s}");
+        assert!(s.ends_with("  >>\n>>\nendobj"), "{s}");
+    }
+
+    #[test]
+    fn spot_vignette() {
+        // Tint 0 to 1 of a spot colour in a circle, painted as a pattern
+        let spot = Separation::new("PANTONE 185 C", DeviceCMYK {}, Type2::new([0.0; 4], [0.0, 0.91, 0.76, 0.0], 1.0));
+        let space = ColorSpace::from(spot);
+        let shading = Shading::radial(space.clone(), [50.0, 50.0, 0.0, 50.0, 50.0, 50.0], Type2::new([0.0], [1.0], 1.0));
+        let mut pattern = ShadingPattern::new(shading.clone());
+        pattern.set_matrix([1.0, 0.0, 0.0, 1.0, 72.0, 72.0]);
+
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_shading("Sh0", shading);
+        page.resources().add_pattern("P0", pattern);
+        let pattern_space = ColorSpace::Pattern(None);
+        page.resources().add_color_space("CS0", pattern_space.clone());
+        page.contents().set_fill_color_space("CS0", &pattern_space);
+        page.contents().set_fill_pattern("P0", []).unwrap();
+        page.contents().fill_rect(72, 72, 100, 100);
+        page.contents().paint_shading("Sh0");
+        assert_eq!(page.contents().get_stream_string(0), "/CS0 cs\n/P0 scn\n72 72 100 100 re\nf\n/Sh0 sh");
+
+        let mut doc = Doc::new(Version::V1_7);
+        doc.push_page(page);
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+        let page_id = loaded.page_ids()[0];
+        let resources = loaded.page_attribute(page_id, "Resources").and_then(|r| r.as_dict()).unwrap();
+        let shadings = loaded.resolve_entry(resources, "Shading").and_then(|s| s.as_dict()).unwrap();
+        let shading = loaded.resolve_entry(shadings, "Sh0").and_then(|s| s.as_dict()).unwrap();
+        assert_eq!(shading.get_i64("ShadingType"), Some(3));
+        let space = loaded.resolve_entry(shading, "ColorSpace").and_then(|s| s.as_array()).unwrap();
+        assert_eq!(space[0].as_name(), Some("Separation"));
+
+        let patterns = loaded.resolve_entry(resources, "Pattern").and_then(|p| p.as_dict()).unwrap();
+        let pattern = loaded.resolve_entry(patterns, "P0").and_then(|p| p.as_dict()).unwrap();
+        assert_eq!(pattern.get_i64("PatternType"), Some(2));
+        assert!(loaded.resolve_entry(pattern, "Shading").and_then(|s| s.as_dict()).is_some());
+    }
+
+    #[test]
+    fn free_form() {
+        let mut mesh = Mesh::new(MeshType::FreeForm, 8, 8, (0.0, 255.0), (0.0, 255.0), vec![(0.0, 1.0); 3]).unwrap();
+        mesh.add_triangle_vertex(0, (0.0, 0.0), &[1.0, 0.0, 0.0]).unwrap();
+        mesh.add_triangle_vertex(0, (255.0, 0.0), &[0.0, 1.0, 0.0]).unwrap();
+        assert!(mesh.add_triangle_vertex(1, (0.0, 255.0), &[0.0, 0.0, 1.0]).is_err());
+        mesh.add_triangle_vertex(0, (0.0, 255.0), &[0.0, 0.0, 1.0]).unwrap();
+        mesh.add_triangle_vertex(1, (255.0, 255.0), &[1.0, 1.0, 1.0]).unwrap();
+        assert!(mesh.add_triangle_vertex(0, (0.0, 0.0), &[1.0]).is_err());
+        assert!(mesh.add_lattice_vertex((0.0, 0.0), &[1.0, 0.0, 0.0]).is_err());
+
+        let shading = Shading::mesh(ColorSpace::DeviceRGB, mesh.clone()).unwrap();
+        assert!(Shading::mesh(ColorSpace::DeviceCMYK, mesh).is_err());
+        let bytes = shading.to_bytes(0);
+        let s = String::from_utf8_lossy(&bytes);
+        assert!(s.contains("   /BitsPerCoordinate 8\n   /BitsPerComponent 8\n   /BitsPerFlag 8\n   /Decode [0 255 0 255 0 1 0 1 0 1]\n   /Length 24\n"), "{s}");
+        let start = bytes.windows(7).position(|w| w == b"stream\n").unwrap() + 7;
+        assert_eq!(&bytes[start..start + 12], [0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 255, 0]);
+    }
+
+    #[test]
+    fn lattice_and_patches() {
+        // Parametric colours with 4 bit components
+        let mut mesh = Mesh::new(MeshType::LatticeForm, 16, 4, (0.0, 100.0), (0.0, 100.0), vec![(0.0, 1.0)]).unwrap();
+        mesh.set_vertices_per_row(2);
+        for (point, t) in [((0.0, 0.0), 0.0), ((100.0, 0.0), 1.0), ((0.0, 100.0), 0.0)] {
+            mesh.add_lattice_vertex(point, &[t]).unwrap();
+        }
+        assert!(Shading::mesh(ColorSpace::DeviceRGB, mesh.clone()).is_err());
+        mesh.add_lattice_vertex((100.0, 100.0), &[1.0]).unwrap();
+        let mut shading = Shading::mesh(ColorSpace::DeviceRGB, mesh).unwrap();
+        shading.set_function(Type2::new([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], 1.0));
+        let bytes = shading.to_bytes(0);
+        let s = String::from_utf8_lossy(&bytes);
+        assert!(s.contains("   /VerticesPerRow 2\n"), "{s}");
+        assert!(s.contains("   /Function\n  <<\n    /FunctionType 2\n"), "{s}");
+        // x and y of 2 bytes and the colour padded to a byte
+        assert!(s.contains("/Length 20\n"), "{s}");
+
+        let mut coons = Mesh::new(MeshType::Coons, 8, 8, (0.0, 255.0), (0.0, 255.0), vec![(0.0, 1.0)]).unwrap();
+        let square: Vec<(f64, f64)> = (0..12).map(|i| (i as f64, i as f64)).collect();
+        assert!(coons.add_patch(1, &square[..8], &[&[0.0], &[1.0]]).is_err());
+        coons.add_patch(0, &square, &[&[0.0], &[1.0], &[0.0], &[1.0]]).unwrap();
+        coons.add_patch(2, &square[..8], &[&[0.0], &[1.0]]).unwrap();
+        assert!(coons.add_patch(0, &square[..8], &[&[0.0], &[1.0]]).is_err());
+        let shading = Shading::mesh(DeviceCMYK {}, coons).unwrap();
+        assert_eq!(shading.shading_type(), 6);
+        // 1 + 24 + 4 and 1 + 16 + 2 bytes
+        assert!(String::from_utf8_lossy(&shading.to_bytes(0)).contains("/Length 48\n"));
+
+        let mut tensor = Mesh::new(MeshType::TensorProduct, 32, 16, (-1.0, 1.0), (-1.0, 1.0), vec![(0.0, 1.0)]).unwrap();
+        let points = vec![(0.0, 0.0); 16];
+        tensor.add_patch(0, &points, &[&[0.0], &[1.0], &[0.0], &[1.0]]).unwrap();
+        assert!(Mesh::new(MeshType::TensorProduct, 3, 8, (0.0, 1.0), (0.0, 1.0), vec![(0.0, 1.0)]).is_err());
+    }
+}
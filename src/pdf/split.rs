@@ -0,0 +1,281 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use super::base::*;
+use super::doc::Doc;
+use super::error::Error;
+use super::import::target_page;
+use super::loaded_doc::LoadedDoc;
+use super::object::Object;
+
+/// How to split a document.
+pub enum SplitBy {
+    /// The page ranges, e.g. `[0..2, 5..8]`. Pages outside the ranges are dropped.
+    Ranges(Vec<Range<usize>>),
+    /// Every N pages.
+    EveryN(usize),
+    /// At the pages the top-level outline items go to. The pages before the
+    /// first item are a part without a title.
+    TopLevelOutlines,
+    /// Parts as long as possible with the written size up to the bytes.
+    /// A page larger than the limit by itself is a part anyway.
+    MaxBytes(usize),
+}
+
+/// A document split off.
+pub struct Part {
+    /// The range of the pages in the source document.
+    pub pages: Range<usize>,
+    /// The title of the outline item the part starts at.
+    pub title: Option<String>,
+    /// The document with only the objects the pages refer to.
+    pub doc: Doc,
+}
+
+/// Splits the loaded document into documents.
+///
+/// ```no_run
+/// use prspdf::LoadedDoc;
+/// use prspdf::split::{split, SplitBy};
+///
+/// let src = LoadedDoc::open("catalogue.pdf").unwrap();
+/// for (i, mut part) in split(&src, &SplitBy::MaxBytes(10_000_000)).unwrap().into_iter().enumerate() {
+///     part.doc.write_to_file(format!("catalogue-{}.pdf", i + 1));
+/// }
+/// ```
+pub fn split(src: &LoadedDoc, by: &SplitBy) -> Result<Vec<Part>, Error> {
+    let page_count = src.page_ids().len();
+    let ranges: Vec<TitledRange> = match by {
+        SplitBy::Ranges(ranges) => {
+            if let Some(r) = ranges.iter().find(|r| r.is_empty() || r.end > page_count) {
+                return Err(Error::InvalidValue(format!("page range {:?} of {} pages", r, page_count)));
+            }
+            ranges.iter().map(|r| (r.clone(), None)).collect()
+        }
+        SplitBy::EveryN(0) => return Err(Error::InvalidValue(String::from("every 0 pages"))),
+        SplitBy::EveryN(n) => (0..page_count).step_by(*n)
+            .map(|start| (start..(start + n).min(page_count), None))
+            .collect(),
+        SplitBy::TopLevelOutlines => outline_ranges(src, page_count)?,
+        SplitBy::MaxBytes(max) => return split_by_size(src, *max),
+    };
+
+    ranges.into_iter()
+        .map(|(pages, title)| Ok(Part { doc: new_doc(src, pages.clone())?, pages, title }))
+        .collect()
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// Pages and the title of the outline item they start at.
+type TitledRange = (Range<usize>, Option<String>);
+
+/// Bytes of the object header, trailer and cross-reference entry.
+const OBJECT_OVERHEAD: usize = 40;
+
+fn new_doc(src: &LoadedDoc, pages: Range<usize>) -> Result<Doc, Error> {
+    let version = if src.version() <= "1.4" { Version::V1_4 } else { Version::V1_7 };
+    let mut doc = Doc::new(version);
+    let indexes: Vec<usize> = pages.collect();
+    for page in doc.import_pages(src, &indexes)? {
+        doc.push_page(page);
+    }
+    Ok(doc)
+}
+
+fn outline_ranges(src: &LoadedDoc, page_count: usize) -> Result<Vec<TitledRange>, Error> {
+    let page_ids = src.page_ids();
+    let outlines = src.catalog()
+        .and_then(|c| src.resolve_entry(c, "Outlines"))
+        .and_then(|o| o.as_dict())
+        .ok_or_else(|| Error::NotFound(String::from("Outlines")))?;
+
+    let mut starts: Vec<(usize, Option<String>)> = Vec::new();
+    let mut visited = HashSet::new();
+    let mut next = outlines.get_reference("First");
+    while let Some(id) = next
+        && visited.insert(id)
+        && let Some(item) = src.get(id).and_then(|o| o.as_dict())
+    {
+        next = item.get_reference("Next");
+        let index = target_page(src, item).and_then(|page| page_ids.iter().position(|p| *p == page));
+        if let Some(index) = index && !starts.iter().any(|s| s.0 == index) {
+            starts.push((index, src.resolve_entry(item, "Title").and_then(|t| t.as_text())));
+        }
+    }
+    starts.sort_by_key(|s| s.0);
+    if starts.first().is_none_or(|s| s.0 != 0) {
+        starts.insert(0, (0, None));
+    }
+
+    let ends: Vec<usize> = starts.iter().skip(1).map(|s| s.0).chain([page_count]).collect();
+    Ok(starts.into_iter().zip(ends)
+        .filter(|((start, _), end)| start < end)
+        .map(|((start, title), end)| (start..end, title))
+        .collect())
+}
+
+/// Groups the pages by the estimated sizes of the objects they refer to,
+/// then halves the parts still written larger than the limit.
+fn split_by_size(src: &LoadedDoc, max: usize) -> Result<Vec<Part>, Error> {
+    let page_ids = src.page_ids();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut objects: HashSet<Id> = HashSet::new();
+    let mut size = 0;
+    let mut start = 0;
+    for (i, page) in page_ids.iter().enumerate() {
+        let page_objects = reachable_objects(src, *page);
+        let added: usize = page_objects.iter()
+            .filter(|id| !objects.contains(id))
+            .map(|id| object_size(src, *id))
+            .sum();
+        if i > start && size + added > max {
+            ranges.push(start..i);
+            start = i;
+            objects.clear();
+            size = page_objects.iter().map(|id| object_size(src, *id)).sum();
+        } else {
+            size += added;
+        }
+        objects.extend(page_objects);
+    }
+    if start < page_ids.len() {
+        ranges.push(start..page_ids.len());
+    }
+
+    let mut parts = Vec::new();
+    while let Some(pages) = ranges.pop() {
+        let mut doc = new_doc(src, pages.clone())?;
+        if pages.len() > 1 && doc.to_bytes().len() > max {
+            let middle = pages.start + pages.len() / 2;
+            ranges.push(middle..pages.end);
+            ranges.push(pages.start..middle);
+            continue;
+        }
+        parts.push(Part { pages, title: None, doc });
+    }
+    parts.reverse();
+    Ok(parts)
+}
+
+/// Returns the objects the page refers to, except the other pages.
+fn reachable_objects(src: &LoadedDoc, page_id: Id) -> HashSet<Id> {
+    let mut objects = HashSet::from([page_id]);
+    let mut stack: Vec<Id> = Vec::new();
+    if let Some(page) = src.get(page_id).and_then(|o| o.as_dict()) {
+        for (_, value) in page.iter().filter(|(k, _)| k.as_str() != "Parent") {
+            stack.extend(value.references());
+        }
+    }
+    if let Some(resources) = src.page_attribute(page_id, "Resources") {
+        stack.extend(resources.references());
+    }
+
+    while let Some(id) = stack.pop() {
+        let Some(object) = src.get(id) else {
+            continue;
+        };
+        let is_page = object.as_dict()
+            .is_some_and(|d| matches!(d.get_name("Type"), Some("Page") | Some("Pages")));
+        if !is_page && objects.insert(id) {
+            stack.extend(object.references());
+        }
+    }
+    objects
+}
+
+fn object_size(src: &LoadedDoc, id: Id) -> usize {
+    src.get(id).map(Object::to_bytes).map_or(0, |b| b.len()) + OBJECT_OVERHEAD
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::font::Font;
+    use crate::pdf::object::Dictionary;
+    use crate::pdf::page::Page;
+
+    fn source(page_count: usize) -> LoadedDoc {
+        let mut doc = Doc::new(Version::V1_7);
+        for i in 0..page_count {
+            let mut page = Page::new(MediaBox::A4);
+            page.resources().add_font("F0", Font::new("Helvetica"));
+            page.contents().fill_text("F0", 12, Pos::new(72, 720), &format!("Page {} {}", i, "x".repeat(1000)));
+            doc.push_page(page);
+        }
+        LoadedDoc::load(doc.to_bytes()).unwrap()
+    }
+
+    fn page_ranges(parts: &[Part]) -> Vec<Range<usize>> {
+        parts.iter().map(|p| p.pages.clone()).collect()
+    }
+
+    #[test]
+    fn ranges() {
+        let src = source(5);
+        let parts = split(&src, &SplitBy::Ranges(vec![0..2, 3..5])).unwrap();
+        assert_eq!(page_ranges(&parts), [0..2, 3..5]);
+        assert_eq!(parts[1].doc.page_count(), 2);
+        assert!(split(&src, &SplitBy::Ranges(vec![0..1, 4..6])).is_err());
+
+        let parts = split(&src, &SplitBy::EveryN(2)).unwrap();
+        assert_eq!(page_ranges(&parts), [0..2, 2..4, 4..5]);
+        assert!(split(&src, &SplitBy::EveryN(0)).is_err());
+    }
+
+    #[test]
+    fn outlines() {
+        let mut src = source(5);
+        assert!(split(&src, &SplitBy::TopLevelOutlines).is_err());
+
+        let pages = src.page_ids();
+        let outlines = src.add_object(Dictionary::new());
+        let mut items = Vec::new();
+        for (title, page) in [("Second", 3), ("First", 1)] {
+            let mut item = Dictionary::new();
+            item.set("Title", Object::text(title));
+            item.set("Parent", outlines);
+            item.set("Dest", Object::Array(vec![Object::Reference(pages[page]), Object::name("Fit")]));
+            items.push(src.add_object(item));
+        }
+        src.get_mut(items[0]).unwrap().as_dict_mut().unwrap().set("Next", items[1]);
+        let dict = src.get_mut(outlines).unwrap().as_dict_mut().unwrap();
+        dict.set("First", items[0]);
+        dict.set("Last", items[1]);
+        let catalog = src.catalog_id();
+        src.get_mut(catalog).unwrap().as_dict_mut().unwrap().set("Outlines", outlines);
+
+        let parts = split(&src, &SplitBy::TopLevelOutlines).unwrap();
+        assert_eq!(page_ranges(&parts), [0..1, 1..3, 3..5]);
+        let titles: Vec<Option<&str>> = parts.iter().map(|p| p.title.as_deref()).collect();
+        assert_eq!(titles, [None, Some("First"), Some("Second")]);
+    }
+
+    #[test]
+    fn max_bytes() {
+        let src = source(10);
+        let mut whole = split(&src, &SplitBy::EveryN(10)).unwrap();
+        let size = whole[0].doc.to_bytes().len();
+
+        let mut parts = split(&src, &SplitBy::MaxBytes(size / 3)).unwrap();
+        assert!(parts.len() > 3);
+        assert_eq!(parts.first().unwrap().pages.start, 0);
+        assert_eq!(parts.last().unwrap().pages.end, 10);
+        for part in &mut parts {
+            assert!(part.doc.to_bytes().len() <= size / 3);
+        }
+    }
+}
@@ -0,0 +1,536 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 9.6 - 9.10 Fonts, the part to get the text and the widths.
+
+use std::collections::HashMap;
+
+use super::encoding::{glyph_to_char, BaseEncoding};
+use crate::pdf::loaded_doc::LoadedDoc;
+use crate::pdf::object::{Dictionary, Object};
+use crate::pdf::parser::parse_content;
+
+/// Width of the glyphs of a simple font without `/Widths`, e.g. one of the
+/// standard 14 fonts.
+const DEFAULT_SIMPLE_WIDTH: f64 = 500.0;
+const DEFAULT_ASCENT: f64 = 750.0;
+const DEFAULT_DESCENT: f64 = -250.0;
+
+/// A character code shown.
+pub(crate) struct Glyph {
+    pub text: String,
+    /// Horizontal displacement in thousandths of the font size.
+    pub width: f64,
+    /// The single-byte code 32, which gets the word spacing.
+    pub is_space: bool,
+    /// Bytes of the code.
+    pub length: usize,
+}
+
+/// Maps the strings shown with a font to the text and the glyph widths.
+pub(crate) struct FontDecoder {
+    pub name: String,
+    /// In thousandths of the font size.
+    pub ascent: f64,
+    pub descent: f64,
+    to_unicode: Option<CMap>,
+    kind: Kind,
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    width_scale: f64,
+}
+
+enum Kind {
+    Simple(Box<[Option<char>; 256]>),
+    Composite {
+        /// The code to CID mapping, `None` for Identity-H/V.
+        cmap: Option<Box<CMap>>,
+        /// Codes of a predefined Unicode CMap are the UTF-16 characters.
+        ucs2: bool,
+        /// `None` for the Identity CIDToGIDMap.
+        cid_to_gid: Option<Vec<u16>>,
+        gid_chars: HashMap<u16, char>,
+    },
+}
+
+impl FontDecoder {
+    pub fn new(src: &LoadedDoc, font: &Dictionary) -> Self {
+        let name = src.resolve_entry(font, "BaseFont").and_then(|n| n.as_name()).unwrap_or("").to_string();
+        let to_unicode = src.resolve_entry(font, "ToUnicode")
+            .and_then(|s| s.as_stream())
+            .and_then(|s| s.decoded_data().ok())
+            .map(|data| CMap::parse(&data));
+        let subtype = font.get_name("Subtype").unwrap_or("");
+        let width_scale = match subtype {
+            "Type3" => src.resolve_entry(font, "FontMatrix")
+                .and_then(|m| m.as_array())
+                .and_then(|m| m.first())
+                .and_then(|a| a.as_f64())
+                .map_or(1.0, |a| a * 1000.0),
+            _ => 1.0,
+        };
+
+        if subtype == "Type0" {
+            let descendant = src.resolve_entry(font, "DescendantFonts")
+                .and_then(|a| a.as_array())
+                .and_then(|a| a.first())
+                .and_then(|d| src.resolve(d).as_dict());
+            let (cmap, ucs2) = match src.resolve_entry(font, "Encoding") {
+                Some(Object::Stream(s)) => (s.decoded_data().ok().map(|data| Box::new(CMap::parse(&data))), false),
+                Some(Object::Name(n)) => (None, n.as_str().starts_with("Uni")
+                    && (n.as_str().contains("UCS2") || n.as_str().contains("UTF16"))),
+                _ => (None, false),
+            };
+            let mut decoder = Self {
+                name,
+                ascent: DEFAULT_ASCENT,
+                descent: DEFAULT_DESCENT,
+                to_unicode,
+                kind: Kind::Composite { cmap, ucs2, cid_to_gid: None, gid_chars: HashMap::new() },
+                widths: HashMap::new(),
+                default_width: 1000.0,
+                width_scale,
+            };
+            if let Some(descendant) = descendant {
+                decoder.read_cid_font(src, descendant);
+            }
+            return decoder;
+        }
+
+        let mut decoder = Self {
+            name,
+            ascent: DEFAULT_ASCENT,
+            descent: DEFAULT_DESCENT,
+            to_unicode,
+            kind: Kind::Simple(Box::new(simple_encoding(src, font))),
+            widths: HashMap::new(),
+            default_width: DEFAULT_SIMPLE_WIDTH,
+            width_scale,
+        };
+        let first_char = font.get_i64("FirstChar").unwrap_or(0);
+        if let Some(widths) = src.resolve_entry(font, "Widths").and_then(|w| w.as_array()) {
+            for (i, w) in widths.iter().enumerate() {
+                if let Some(w) = src.resolve(w).as_f64() {
+                    decoder.widths.insert((first_char + i as i64) as u32, w);
+                }
+            }
+            decoder.default_width = 0.0;
+        }
+        if let Some(descriptor) = src.resolve_entry(font, "FontDescriptor").and_then(|d| d.as_dict()) {
+            decoder.read_descriptor(src, descriptor);
+        }
+        decoder
+    }
+
+    /// Splits the string into the codes and decodes them.
+    pub fn decode(&self, bytes: &[u8]) -> Vec<Glyph> {
+        let mut glyphs = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let len = self.code_length(&bytes[i..]);
+            let code = bytes[i..i + len].iter().fold(0u32, |code, b| (code << 8) | *b as u32);
+            i += len;
+
+            let (text, cid) = match &self.kind {
+                Kind::Simple(chars) => (chars[code as usize & 0xff].map(String::from), code),
+                Kind::Composite { cmap, ucs2, cid_to_gid, gid_chars } => {
+                    let cid = cmap.as_ref().map_or(Some(code), |c| c.cid(code)).unwrap_or(0);
+                    let text = if *ucs2 {
+                        char::from_u32(code).map(String::from)
+                    } else {
+                        let gid = match cid_to_gid {
+                            Some(map) => map.get(cid as usize).copied().unwrap_or(0),
+                            None => cid as u16,
+                        };
+                        gid_chars.get(&gid).map(|c| c.to_string())
+                    };
+                    (text, cid)
+                }
+            };
+            let text = self.to_unicode.as_ref()
+                .and_then(|c| c.unicode(code))
+                .or(text)
+                .unwrap_or_else(|| String::from('\u{fffd}'));
+            let width = self.widths.get(&cid).copied().unwrap_or(self.default_width) * self.width_scale;
+            glyphs.push(Glyph { text, width, is_space: len == 1 && code == 32, length: len });
+        }
+        glyphs
+    }
+
+    fn code_length(&self, bytes: &[u8]) -> usize {
+        let codespaces = match &self.kind {
+            Kind::Simple(_) => return 1,
+            Kind::Composite { cmap: Some(cmap), .. } if !cmap.codespaces.is_empty() => &cmap.codespaces,
+            _ => match &self.to_unicode {
+                Some(cmap) if !cmap.codespaces.is_empty() => &cmap.codespaces,
+                _ => return 2.min(bytes.len()),
+            },
+        };
+        codespaces.iter()
+            .find(|(low, high)| low.len() <= bytes.len()
+                && low.iter().zip(high).zip(bytes).all(|((l, h), b)| l <= b && b <= h))
+            .map_or(1, |(low, _)| low.len())
+    }
+
+    fn read_cid_font(&mut self, src: &LoadedDoc, font: &Dictionary) {
+        self.default_width = font.get_f64("DW").unwrap_or(1000.0);
+        if let Some(w) = src.resolve_entry(font, "W").and_then(|w| w.as_array()) {
+            let w: Vec<&Object> = w.iter().map(|o| src.resolve(o)).collect();
+            let mut i = 0;
+            while i + 1 < w.len() {
+                let Some(first) = w[i].as_i64() else {
+                    break;
+                };
+                // "c [w1 w2 ...]" or "c_first c_last w"
+                if let Some(widths) = w[i + 1].as_array() {
+                    for (j, width) in widths.iter().enumerate() {
+                        if let Some(width) = src.resolve(width).as_f64() {
+                            self.widths.insert((first + j as i64) as u32, width);
+                        }
+                    }
+                    i += 2;
+                } else if let (Some(last), Some(width)) = (w[i + 1].as_i64(), w.get(i + 2).and_then(|w| w.as_f64())) {
+                    for cid in first..=last.min(first + 0xffff) {
+                        self.widths.insert(cid as u32, width);
+                    }
+                    i += 3;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let Some(descriptor) = src.resolve_entry(font, "FontDescriptor").and_then(|d| d.as_dict()) else {
+            return;
+        };
+        self.read_descriptor(src, descriptor);
+        if let Kind::Composite { cid_to_gid, gid_chars, .. } = &mut self.kind {
+            if let Some(map) = src.resolve_entry(font, "CIDToGIDMap").and_then(|m| m.as_stream())
+                && let Ok(data) = map.decoded_data()
+            {
+                *cid_to_gid = Some(data.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect());
+            }
+            if let Some(file) = src.resolve_entry(descriptor, "FontFile2").and_then(|f| f.as_stream())
+                && let Ok(data) = file.decoded_data()
+            {
+                *gid_chars = truetype_glyph_chars(&data);
+            }
+        }
+    }
+
+    fn read_descriptor(&mut self, src: &LoadedDoc, descriptor: &Dictionary) {
+        if let Some(w) = descriptor.get_f64("MissingWidth") {
+            self.default_width = w;
+        }
+        let bbox = src.resolve_entry(descriptor, "FontBBox").and_then(|b| b.as_rect());
+        match descriptor.get_f64("Ascent") {
+            Some(a) if a != 0.0 => self.ascent = a,
+            _ => if let Some(b) = bbox { self.ascent = b.ury },
+        }
+        match descriptor.get_f64("Descent") {
+            Some(d) if d != 0.0 => self.descent = d,
+            _ => if let Some(b) = bbox { self.descent = b.lly },
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// The mappings of a CMap stream (PDF32000-1:2008 9.7.5, 9.10.3).
+#[derive(Default)]
+struct CMap {
+    codespaces: Vec<(Vec<u8>, Vec<u8>)>,
+    chars: HashMap<u32, String>,
+    /// First code, last code and the first destination, either the UTF-16
+    /// string or the CID.
+    char_ranges: Vec<(u32, u32, Vec<u16>)>,
+    cids: HashMap<u32, u32>,
+    cid_ranges: Vec<(u32, u32, u32)>,
+}
+
+impl CMap {
+    fn parse(data: &[u8]) -> Self {
+        let mut cmap = CMap::default();
+        // The operands of e.g. "endbfchar" are the entries of the section
+        let Ok(operations) = parse_content(data) else {
+            return cmap;
+        };
+        for (operands, keyword) in operations {
+            match keyword.as_str() {
+                "endcodespacerange" => for pair in operands.chunks_exact(2) {
+                    if let (Some(low), Some(high)) = (pair[0].as_bytes(), pair[1].as_bytes()) {
+                        cmap.codespaces.push((low.to_vec(), high.to_vec()));
+                    }
+                },
+                "endbfchar" => for pair in operands.chunks_exact(2) {
+                    if let (Some(code), Some(dst)) = (pair[0].as_bytes(), pair[1].as_bytes()) {
+                        cmap.chars.insert(to_code(code), String::from_utf16_lossy(&utf16(dst)));
+                    }
+                },
+                "endbfrange" => for range in operands.chunks_exact(3) {
+                    let (Some(low), Some(high)) = (range[0].as_bytes(), range[1].as_bytes()) else {
+                        continue;
+                    };
+                    let (low, high) = (to_code(low), to_code(high));
+                    match &range[2] {
+                        Object::String(dst) => cmap.char_ranges.push((low, high, utf16(dst))),
+                        Object::Array(dsts) => for (i, dst) in dsts.iter().enumerate() {
+                            if let Some(dst) = dst.as_bytes() {
+                                cmap.chars.insert(low + i as u32, String::from_utf16_lossy(&utf16(dst)));
+                            }
+                        },
+                        _ => (),
+                    }
+                },
+                "endcidchar" => for pair in operands.chunks_exact(2) {
+                    if let (Some(code), Some(cid)) = (pair[0].as_bytes(), pair[1].as_i64()) {
+                        cmap.cids.insert(to_code(code), cid as u32);
+                    }
+                },
+                "endcidrange" => for range in operands.chunks_exact(3) {
+                    if let (Some(low), Some(high), Some(cid)) = (range[0].as_bytes(), range[1].as_bytes(), range[2].as_i64()) {
+                        cmap.cid_ranges.push((to_code(low), to_code(high), cid as u32));
+                    }
+                },
+                _ => (),
+            }
+        }
+        cmap
+    }
+
+    fn unicode(&self, code: u32) -> Option<String> {
+        if let Some(s) = self.chars.get(&code) {
+            return Some(s.clone());
+        }
+        let (low, _, dst) = self.char_ranges.iter().find(|(low, high, _)| (*low..=*high).contains(&code))?;
+        // The last unit is incremented within the range
+        let mut dst = dst.clone();
+        if let Some(last) = dst.last_mut() {
+            *last = last.wrapping_add((code - low) as u16);
+        }
+        Some(String::from_utf16_lossy(&dst))
+    }
+
+    fn cid(&self, code: u32) -> Option<u32> {
+        self.cids.get(&code).copied().or_else(|| self.cid_ranges.iter()
+            .find(|(low, high, _)| (*low..=*high).contains(&code))
+            .map(|(low, _, cid)| cid + code - low))
+    }
+}
+
+fn to_code(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |code, b| (code << 8) | *b as u32)
+}
+
+fn utf16(bytes: &[u8]) -> Vec<u16> {
+    bytes.chunks(2).map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])).collect()
+}
+
+/// Returns the characters of the codes of a simple font (9.6.6).
+fn simple_encoding(src: &LoadedDoc, font: &Dictionary) -> [Option<char>; 256] {
+    let default = match font.get_name("Subtype") {
+        Some("TrueType") => BaseEncoding::WinAnsi,
+        _ => BaseEncoding::Standard,
+    };
+    let encoding = src.resolve_entry(font, "Encoding");
+    let base = match encoding {
+        Some(Object::Name(n)) => BaseEncoding::from_name(n.as_str()),
+        Some(Object::Dictionary(d)) => d.get_name("BaseEncoding").and_then(BaseEncoding::from_name),
+        _ => None,
+    }.unwrap_or(default);
+
+    let mut chars = [None; 256];
+    for (code, c) in chars.iter_mut().enumerate() {
+        *c = base.decode(code as u8);
+    }
+    let differences = encoding.and_then(|e| e.as_dict()).and_then(|d| src.resolve_entry(d, "Differences"));
+    if let Some(differences) = differences.and_then(|d| d.as_array()) {
+        let mut code = 0;
+        for item in differences {
+            match item {
+                Object::Integer(i) => code = *i as usize,
+                Object::Name(name) => {
+                    if let Some(c) = chars.get_mut(code) {
+                        *c = glyph_to_char(name.as_str());
+                    }
+                    code += 1;
+                }
+                _ => (),
+            }
+        }
+    }
+    chars
+}
+
+/// Returns the characters of the glyphs from the `cmap` table of a TrueType
+/// font, the first one for a glyph used by more characters.
+fn truetype_glyph_chars(data: &[u8]) -> HashMap<u16, char> {
+    let u16_at = |pos: usize| data.get(pos..pos + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    let u32_at = |pos: usize| data.get(pos..pos + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+    let mut glyphs = HashMap::new();
+    let num_tables = u16_at(4).unwrap_or(0) as usize;
+    let Some(cmap) = (0..num_tables)
+        .map(|i| 12 + i * 16)
+        .find(|pos| data.get(*pos..pos + 4) == Some(b"cmap"))
+        .and_then(|pos| u32_at(pos + 8))
+        .map(|offset| offset as usize) else {
+        return glyphs;
+    };
+
+    // Unicode subtables, the full repertoire first
+    let mut subtables: Vec<(u16, usize)> = Vec::new();
+    for i in 0..u16_at(cmap + 2).unwrap_or(0) as usize {
+        let record = cmap + 4 + i * 8;
+        let (Some(platform), Some(encoding), Some(offset)) = (u16_at(record), u16_at(record + 2), u32_at(record + 4)) else {
+            break;
+        };
+        let unicode = platform == 0 || (platform == 3 && matches!(encoding, 1 | 10));
+        if let Some(format) = u16_at(cmap + offset as usize) && unicode {
+            subtables.push((format, cmap + offset as usize));
+        }
+    }
+    subtables.sort_by_key(|(format, _)| std::cmp::Reverse(*format == 12));
+
+    let mut add = |c: u32, gid: u16| {
+        if gid != 0 && let Some(c) = char::from_u32(c) {
+            glyphs.entry(gid).or_insert(c);
+        }
+    };
+    let Some((format, table)) = subtables.first().copied() else {
+        return glyphs;
+    };
+    match format {
+        4 => {
+            let seg_count = u16_at(table + 6).unwrap_or(0) as usize / 2;
+            let ends = table + 14;
+            let starts = ends + seg_count * 2 + 2;
+            let deltas = starts + seg_count * 2;
+            let range_offsets = deltas + seg_count * 2;
+            for i in 0..seg_count {
+                let (Some(end), Some(start), Some(delta), Some(range_offset)) = (
+                    u16_at(ends + i * 2), u16_at(starts + i * 2), u16_at(deltas + i * 2), u16_at(range_offsets + i * 2)) else {
+                    break;
+                };
+                for c in start..=end.min(0xfffe) {
+                    let gid = if range_offset == 0 {
+                        Some(c.wrapping_add(delta))
+                    } else {
+                        let pos = range_offsets + i * 2 + range_offset as usize + (c - start) as usize * 2;
+                        u16_at(pos).filter(|g| *g != 0).map(|g| g.wrapping_add(delta))
+                    };
+                    if let Some(gid) = gid {
+                        add(c as u32, gid);
+                    }
+                }
+            }
+        }
+        12 => {
+            for i in 0..u32_at(table + 12).unwrap_or(0) as usize {
+                let group = table + 16 + i * 12;
+                let (Some(start), Some(end), Some(gid)) = (u32_at(group), u32_at(group + 4), u32_at(group + 8)) else {
+                    break;
+                };
+                for c in start..=end.min(start + 0xffff) {
+                    add(c, (gid + c - start) as u16);
+                }
+            }
+        }
+        _ => (),
+    }
+    glyphs
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::object::Stream;
+    use crate::pdf::Version;
+
+    fn source() -> LoadedDoc {
+        LoadedDoc::load(Doc::new(Version::V1_7).to_bytes()).unwrap()
+    }
+
+    fn texts(decoder: &FontDecoder, bytes: &[u8]) -> Vec<String> {
+        decoder.decode(bytes).into_iter().map(|g| g.text).collect()
+    }
+
+    #[test]
+    fn simple() {
+        let src = source();
+        let mut encoding = Dictionary::new();
+        encoding.set("BaseEncoding", Object::name("WinAnsiEncoding"));
+        encoding.set("Differences", Object::Array(vec![Object::Integer(65), Object::name("Adieresis"), Object::name("uni2022")]));
+        let mut font = Dictionary::new();
+        font.set("Subtype", Object::name("Type1"));
+        font.set("Encoding", encoding);
+        font.set("FirstChar", Object::Integer(65));
+        font.set("Widths", Object::Array(vec![Object::Integer(600), Object::Integer(700)]));
+
+        let decoder = FontDecoder::new(&src, &font);
+        assert_eq!(texts(&decoder, b"AB C\x80"), ["Ä", "•", " ", "C", "€"]);
+        let widths: Vec<f64> = decoder.decode(b"AB ").iter().map(|g| g.width).collect();
+        assert_eq!(widths, [600.0, 700.0, 0.0]);
+        assert!(decoder.decode(b" ")[0].is_space);
+    }
+
+    #[test]
+    fn to_unicode() {
+        let mut src = source();
+        let cmap = concat!(
+            "/CIDInit /ProcSet findresource begin 12 dict begin begincmap\n",
+            "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n",
+            "1 begincodespacerange <0000> <FFFF> endcodespacerange\n",
+            "2 beginbfchar <0003> <0020> <0024> <00660069> endbfchar\n",
+            "1 beginbfrange <0010> <0012> <3042> endbfrange\n",
+            "endcmap CMapName currentdict /CMap defineresource pop end end");
+        let cmap = src.add_object(Stream::new(Dictionary::new(), cmap.as_bytes().to_vec()));
+        let mut descendant = Dictionary::new();
+        descendant.set("Subtype", Object::name("CIDFontType2"));
+        descendant.set("W", Object::Array(vec![
+            Object::Integer(3), Object::Array(vec![Object::Integer(250)]),
+            Object::Integer(16), Object::Integer(18), Object::Integer(900)]));
+        let mut font = Dictionary::new();
+        font.set("Subtype", Object::name("Type0"));
+        font.set("Encoding", Object::name("Identity-H"));
+        font.set("DescendantFonts", Object::Array(vec![Object::Dictionary(descendant)]));
+        font.set("ToUnicode", cmap);
+
+        let decoder = FontDecoder::new(&src, &font);
+        let glyphs = decoder.decode(b"\x00\x10\x00\x12\x00\x03\x00\x24\x00\x99");
+        let texts: Vec<&str> = glyphs.iter().map(|g| g.text.as_str()).collect();
+        assert_eq!(texts, ["あ", "い", " ", "fi", "\u{fffd}"]);
+        let widths: Vec<f64> = glyphs.iter().map(|g| g.width).collect();
+        assert_eq!(widths, [900.0, 900.0, 250.0, 1000.0, 1000.0]);
+        assert!(!glyphs[2].is_space);
+    }
+
+    #[test]
+    fn truetype_cmap() {
+        // A format 4 subtable mapping U+0041 - U+0043 to glyphs 1 - 3
+        let mut data = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(b"cmap");
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0, 0, 0, 1, 0, 3, 0, 1, 0, 0, 0, 12]);
+        data.extend_from_slice(&[0, 4, 0, 32, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0]);
+        // endCode, pad, startCode, idDelta, idRangeOffset
+        data.extend_from_slice(&[0x00, 0x43, 0xff, 0xff, 0, 0, 0x00, 0x41, 0xff, 0xff]);
+        data.extend_from_slice(&[0xff, 0xc0, 0x00, 0x01, 0, 0, 0, 0]);
+
+        let glyphs = truetype_glyph_chars(&data);
+        assert_eq!(glyphs.get(&1), Some(&'A'));
+        assert_eq!(glyphs.get(&3), Some(&'C'));
+        assert_eq!(glyphs.len(), 3);
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! PDF32000-1:2008 Annex D - Character sets and encodings.
+
+/// The simple font encodings of Annex D.2.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BaseEncoding {
+    Standard,
+    WinAnsi,
+    MacRoman,
+}
+
+impl BaseEncoding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "StandardEncoding" => Some(Self::Standard),
+            "WinAnsiEncoding" => Some(Self::WinAnsi),
+            "MacRomanEncoding" => Some(Self::MacRoman),
+            _ => None,
+        }
+    }
+
+    /// Returns the character of the code, `None` if the code is not used.
+    pub fn decode(self, code: u8) -> Option<char> {
+        match (self, code) {
+            (Self::Standard, 0x27) => Some('’'),
+            (Self::Standard, 0x60) => Some('‘'),
+            (_, 0x20..=0x7e) => Some(code as char),
+            (Self::Standard, 0xa1..=0xff) => STANDARD_HIGH[(code - 0xa1) as usize],
+            (Self::WinAnsi, 0x80..=0x9f) => WIN_ANSI_80[(code - 0x80) as usize],
+            (Self::WinAnsi, 0xa0..=0xff) => char::from_u32(code as u32),
+            (Self::MacRoman, 0x80..=0xff) => Some(MAC_ROMAN_HIGH[(code - 0x80) as usize]),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the character of a glyph name, e.g. `Adieresis`, `uni00C4` or
+/// `u1F600` (Adobe Glyph List Specification, simplified).
+pub(crate) fn glyph_to_char(name: &str) -> Option<char> {
+    // "a.sc", "one.oldstyle"
+    let name = name.split('.').next().unwrap_or(name);
+    if name.len() == 1 && name.as_bytes()[0].is_ascii_alphabetic() {
+        return name.chars().next();
+    }
+    if let Some(i) = ASCII_NAMES.iter().position(|n| *n == name) {
+        return char::from_u32(0x20 + i as u32);
+    }
+    if let Some(i) = LATIN1_NAMES.iter().position(|n| *n == name) {
+        return char::from_u32(0xa1 + i as u32);
+    }
+    if let Some((_, c)) = OTHER_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(*c);
+    }
+    let hex = name.strip_prefix("uni")
+        .filter(|h| h.len() == 4)
+        .or_else(|| name.strip_prefix('u').filter(|h| (4..=6).contains(&h.len())))?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// StandardEncoding 0xA1 - 0xFF.
+const STANDARD_HIGH: [Option<char>; 95] = [
+    Some('¡'), Some('¢'), Some('£'), Some('⁄'), Some('¥'), Some('ƒ'), Some('§'),
+    Some('¤'), Some('\''), Some('“'), Some('«'), Some('‹'), Some('›'), Some('ﬁ'), Some('ﬂ'),
+    None, Some('–'), Some('†'), Some('‡'), Some('·'), None, Some('¶'), Some('•'),
+    Some('‚'), Some('„'), Some('”'), Some('»'), Some('…'), Some('‰'), None, Some('¿'),
+    None, Some('`'), Some('´'), Some('ˆ'), Some('˜'), Some('¯'), Some('˘'), Some('˙'),
+    Some('¨'), None, Some('˚'), Some('¸'), None, Some('˝'), Some('˛'), Some('ˇ'),
+    Some('—'), None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None, None,
+    None, Some('Æ'), None, Some('ª'), None, None, None, None,
+    Some('Ł'), Some('Ø'), Some('Œ'), Some('º'), None, None, None, None,
+    None, Some('æ'), None, None, None, Some('ı'), None, None,
+    Some('ł'), Some('ø'), Some('œ'), Some('ß'), None, None, None, None,
+];
+
+/// WinAnsiEncoding 0x80 - 0x9F, the other codes are Latin-1.
+const WIN_ANSI_80: [Option<char>; 32] = [
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ'),
+];
+
+/// MacRomanEncoding 0x80 - 0xFF.
+const MAC_ROMAN_HIGH: [char; 128] = [
+    'Ä', 'Å', 'Ç', 'É', 'Ñ', 'Ö', 'Ü', 'á', 'à', 'â', 'ä', 'ã', 'å', 'ç', 'é', 'è',
+    'ê', 'ë', 'í', 'ì', 'î', 'ï', 'ñ', 'ó', 'ò', 'ô', 'ö', 'õ', 'ú', 'ù', 'û', 'ü',
+    '†', '°', '¢', '£', '§', '•', '¶', 'ß', '®', '©', '™', '´', '¨', '≠', 'Æ', 'Ø',
+    '∞', '±', '≤', '≥', '¥', 'µ', '∂', '∑', '∏', 'π', '∫', 'ª', 'º', 'Ω', 'æ', 'ø',
+    '¿', '¡', '¬', '√', 'ƒ', '≈', '∆', '«', '»', '…', '\u{a0}', 'À', 'Ã', 'Õ', 'Œ', 'œ',
+    '–', '—', '“', '”', '‘', '’', '÷', '◊', 'ÿ', 'Ÿ', '⁄', '¤', '‹', '›', 'ﬁ', 'ﬂ',
+    '‡', '·', '‚', '„', '‰', 'Â', 'Ê', 'Á', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', 'Ó', 'Ô',
+    '\u{f8ff}', 'Ò', 'Ú', 'Û', 'Ù', 'ı', 'ˆ', '˜', '¯', '˘', '˙', '˚', '¸', '˝', '˛', 'ˇ',
+];
+
+/// Glyph names of U+0020 - U+007E.
+const ASCII_NAMES: [&str; 95] = [
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+    "bracketleft", "backslash", "bracketright", "asciicircum", "underscore", "grave",
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z",
+    "braceleft", "bar", "braceright", "asciitilde",
+];
+
+/// Glyph names of U+00A1 - U+00FF.
+const LATIN1_NAMES: [&str; 95] = [
+    "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "dieresis", "copyright", "ordfeminine", "guillemotleft", "logicalnot", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "ordmasculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adieresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Edieresis", "Igrave", "Iacute", "Icircumflex", "Idieresis",
+    "Eth", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odieresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udieresis", "Yacute", "Thorn", "germandbls",
+    "agrave", "aacute", "acircumflex", "atilde", "adieresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "edieresis", "igrave", "iacute", "icircumflex", "idieresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odieresis", "divide",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udieresis", "yacute", "thorn", "ydieresis",
+];
+
+const OTHER_NAMES: [(&str, char); 40] = [
+    ("quoteleft", '‘'), ("quoteright", '’'), ("quotesinglbase", '‚'), ("quotedblleft", '“'),
+    ("quotedblright", '”'), ("quotedblbase", '„'), ("guilsinglleft", '‹'), ("guilsinglright", '›'),
+    ("endash", '–'), ("emdash", '—'), ("bullet", '•'), ("ellipsis", '…'),
+    ("dagger", '†'), ("daggerdbl", '‡'), ("perthousand", '‰'), ("trademark", '™'),
+    ("Euro", '€'), ("florin", 'ƒ'), ("fraction", '⁄'), ("minus", '−'),
+    ("fi", 'ﬁ'), ("fl", 'ﬂ'), ("dotlessi", 'ı'), ("Lslash", 'Ł'),
+    ("lslash", 'ł'), ("OE", 'Œ'), ("oe", 'œ'), ("Scaron", 'Š'),
+    ("scaron", 'š'), ("Zcaron", 'Ž'), ("zcaron", 'ž'), ("Ydieresis", 'Ÿ'),
+    ("circumflex", 'ˆ'), ("tilde", '˜'), ("breve", '˘'), ("dotaccent", '˙'),
+    ("ring", '˚'), ("ogonek", '˛'), ("caron", 'ˇ'), ("hungarumlaut", '˝'),
+];
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(BaseEncoding::WinAnsi.decode(b'A'), Some('A'));
+        assert_eq!(BaseEncoding::WinAnsi.decode(0x80), Some('€'));
+        assert_eq!(BaseEncoding::WinAnsi.decode(0xe9), Some('é'));
+        assert_eq!(BaseEncoding::Standard.decode(0x27), Some('’'));
+        assert_eq!(BaseEncoding::Standard.decode(0xe1), Some('Æ'));
+        assert_eq!(BaseEncoding::MacRoman.decode(0x8e), Some('é'));
+        assert_eq!(BaseEncoding::MacRoman.decode(0x10), None);
+    }
+
+    #[test]
+    fn glyph_names() {
+        assert_eq!(glyph_to_char("Adieresis"), Some('Ä'));
+        assert_eq!(glyph_to_char("space"), Some(' '));
+        assert_eq!(glyph_to_char("emdash"), Some('—'));
+        assert_eq!(glyph_to_char("a.sc"), Some('a'));
+        assert_eq!(glyph_to_char("uni00C4"), Some('Ä'));
+        assert_eq!(glyph_to_char("u1F600"), Some('😀'));
+        assert_eq!(glyph_to_char("g123"), None);
+    }
+}
@@ -0,0 +1,451 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+//! Text extraction from loaded documents, PDF32000-1:2008 9.4 Text objects.
+
+pub(crate) mod decoder;
+mod encoding;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use decoder::FontDecoder;
+use super::base::*;
+use super::error::Error;
+use super::loaded_doc::LoadedDoc;
+use super::object::{Dictionary, Object};
+use super::operation::{self, Operation, TextItem};
+
+/// The text shown by a text-showing operator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    /// The area from the descent to the ascent of the glyphs, in the default
+    /// user space of the page (before `/Rotate`).
+    pub bbox: Rectangle,
+    /// The BaseFont.
+    pub font: String,
+    /// The font size scaled by the text matrix and the CTM.
+    pub font_size: f64,
+    /// The box of each character of `text`. A space put for a `TJ`
+    /// adjustment has an empty box.
+    pub char_boxes: Vec<Rectangle>,
+}
+
+/// Returns the text runs of the page in the order they are painted.
+///
+/// The glyphs are mapped to characters with `/ToUnicode`, the encoding of a
+/// simple font, a predefined Unicode CMap or the `cmap` table of an embedded
+/// TrueType font, in this order. The text shown by Form XObjects is included.
+///
+/// ```no_run
+/// use prspdf::LoadedDoc;
+/// use prspdf::text::extract_runs;
+///
+/// let src = LoadedDoc::open("archive.pdf").unwrap();
+/// for run in extract_runs(&src, 0).unwrap() {
+///     println!("{:?} {}", run.bbox, run.text);
+/// }
+/// ```
+pub fn extract_runs(src: &LoadedDoc, page_index: usize) -> Result<Vec<TextRun>, Error> {
+    let page_id = *src.page_ids().get(page_index)
+        .ok_or_else(|| Error::NotFound(format!("page {}", page_index)))?;
+    let content = src.page_content(page_id)?;
+    let resources = src.page_attribute(page_id, "Resources").and_then(|r| r.as_dict());
+
+    let mut interpreter = Interpreter { src, fonts: HashMap::new(), runs: Vec::new() };
+    interpreter.run(&content, resources, IDENTITY, 0)?;
+    Ok(interpreter.runs)
+}
+
+/// Returns the text of the page in reading order, lines from the top and
+/// runs from the left.
+pub fn extract_text(src: &LoadedDoc, page_index: usize) -> Result<String, Error> {
+    Ok(plain_text(&extract_runs(src, page_index)?))
+}
+
+/// Joins the runs into lines by their vertical overlap. A space is put
+/// between runs apart on a line.
+pub fn plain_text(runs: &[TextRun]) -> String {
+    lines(runs).into_iter().map(|(text, _)| text).collect::<Vec<String>>().join("\n")
+}
+
+/// Returns the box of each occurrence of the text on the page. The text is
+/// searched in the lines of [`extract_text`], so an occurrence may span runs.
+///
+/// ```no_run
+/// use prspdf::LoadedDoc;
+/// use prspdf::text::find_text;
+///
+/// let src = LoadedDoc::open("contract.pdf").unwrap();
+/// for bbox in find_text(&src, 0, "John Smith").unwrap() {
+///     println!("{:?}", bbox);
+/// }
+/// ```
+pub fn find_text(src: &LoadedDoc, page_index: usize, text: &str) -> Result<Vec<Rectangle>, Error> {
+    let needle: Vec<char> = text.chars().collect();
+    let mut found = Vec::new();
+    if needle.is_empty() {
+        return Ok(found);
+    }
+    for (line, boxes) in lines(&extract_runs(src, page_index)?) {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i + needle.len() <= chars.len() {
+            if chars[i..i + needle.len()] != needle[..] {
+                i += 1;
+                continue;
+            }
+            let corners: Vec<(f64, f64)> = boxes[i..i + needle.len()].iter()
+                .filter(|b| b.width() > 0.0)
+                .flat_map(|b| [(b.llx, b.lly), (b.urx, b.ury)])
+                .collect();
+            found.extend(Rectangle::bounding(&corners));
+            i += needle.len();
+        }
+    }
+    Ok(found)
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+/// The text of a line and the box of each character.
+type Line = (String, Vec<Rectangle>);
+
+pub(crate) type Matrix = [f64; 6];
+
+pub(crate) const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// A `TJ` adjustment over this many thousandths of the font size is a space.
+const SPACE_ADJUSTMENT: f64 = 200.0;
+/// A gap between runs over this many times the font size is a space.
+const WORD_GAP: f64 = 0.15;
+const MAX_FORM_DEPTH: usize = 16;
+
+/// The parts of the graphics state (8.4) and the text state (9.3) used for
+/// the positions.
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    char_spacing: f64,
+    word_spacing: f64,
+    /// `Tz` / 100
+    scale: f64,
+    leading: f64,
+    font: Option<Rc<FontDecoder>>,
+    font_size: f64,
+    rise: f64,
+}
+
+struct Interpreter<'a> {
+    src: &'a LoadedDoc,
+    fonts: HashMap<Id, Rc<FontDecoder>>,
+    runs: Vec<TextRun>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn run(&mut self, content: &[u8], resources: Option<&'a Dictionary>, ctm: Matrix, depth: usize) -> Result<(), Error> {
+        let mut state = GraphicsState {
+            ctm,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            scale: 1.0,
+            leading: 0.0,
+            font: None,
+            font_size: 0.0,
+            rise: 0.0,
+        };
+        let mut stack = Vec::new();
+        let mut tm = IDENTITY;
+        let mut tlm = IDENTITY;
+
+        for operation in operation::parse(content)? {
+            match operation {
+                Operation::SaveState => stack.push(state.clone()),
+                Operation::RestoreState => if let Some(saved) = stack.pop() {
+                    state = saved;
+                },
+                Operation::ConcatMatrix(m) => state.ctm = concat(&m, &state.ctm),
+                Operation::BeginText => {
+                    tm = IDENTITY;
+                    tlm = IDENTITY;
+                }
+                Operation::SetCharSpacing(v) => state.char_spacing = v,
+                Operation::SetWordSpacing(v) => state.word_spacing = v,
+                Operation::SetHorizontalScaling(v) => state.scale = v / 100.0,
+                Operation::SetLeading(v) => state.leading = v,
+                Operation::SetTextRise(v) => state.rise = v,
+                Operation::SetFont(name, size) => {
+                    state.font = self.font(resources, name.as_str());
+                    state.font_size = size;
+                }
+                Operation::MoveText(tx, ty) => {
+                    tlm = concat(&translate(tx, ty), &tlm);
+                    tm = tlm;
+                }
+                Operation::MoveTextSetLeading(tx, ty) => {
+                    state.leading = -ty;
+                    tlm = concat(&translate(tx, ty), &tlm);
+                    tm = tlm;
+                }
+                Operation::SetTextMatrix(m) => {
+                    tlm = m;
+                    tm = tlm;
+                }
+                Operation::NextLine | Operation::NextLineShowText(_) | Operation::NextLineShowTextSpaced(..) => {
+                    let text = match operation {
+                        Operation::NextLineShowText(text) => Some(text),
+                        Operation::NextLineShowTextSpaced(aw, ac, text) => {
+                            state.word_spacing = aw;
+                            state.char_spacing = ac;
+                            Some(text)
+                        }
+                        _ => None,
+                    };
+                    tlm = concat(&translate(0.0, -state.leading), &tlm);
+                    tm = tlm;
+                    if let Some(text) = text {
+                        self.show(&state, &mut tm, &[TextItem::Text(text)]);
+                    }
+                }
+                Operation::ShowText(text) => self.show(&state, &mut tm, &[TextItem::Text(text)]),
+                Operation::ShowTextAdjusted(items) => self.show(&state, &mut tm, &items),
+                Operation::DrawXObject(name) => self.draw_form(resources, name.as_str(), &state.ctm, depth)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn font(&mut self, resources: Option<&'a Dictionary>, name: &str) -> Option<Rc<FontDecoder>> {
+        let fonts = self.src.resolve_entry(resources?, "Font")?.as_dict()?;
+        match fonts.get(name)? {
+            Object::Reference(id) => {
+                if !self.fonts.contains_key(id) {
+                    let font = self.src.get(*id)?.as_dict()?;
+                    self.fonts.insert(*id, Rc::new(FontDecoder::new(self.src, font)));
+                }
+                self.fonts.get(id).cloned()
+            }
+            Object::Dictionary(font) => Some(Rc::new(FontDecoder::new(self.src, font))),
+            _ => None,
+        }
+    }
+
+    /// Shows the strings of a `Tj` or `TJ` as a run and moves the text matrix.
+    fn show(&mut self, state: &GraphicsState, tm: &mut Matrix, items: &[TextItem]) {
+        let Some(font) = &state.font else {
+            return;
+        };
+        let start = *tm;
+        let size = state.font_size;
+        let m = concat(&start, &state.ctm);
+        let bottom = state.rise + font.descent / 1000.0 * size;
+        let top = state.rise + font.ascent / 1000.0 * size;
+        let mut x = 0.0;
+        let mut text = String::new();
+        let mut char_boxes = Vec::new();
+        for item in items {
+            match item {
+                TextItem::Text(bytes) => for glyph in font.decode(bytes) {
+                    let word_spacing = if glyph.is_space { state.word_spacing } else { 0.0 };
+                    let advance = (glyph.width / 1000.0 * size + state.char_spacing + word_spacing) * state.scale;
+                    let glyph_box = text_box(&m, x, x + glyph.width / 1000.0 * size * state.scale, bottom, top);
+                    text.push_str(&glyph.text);
+                    char_boxes.extend(glyph.text.chars().map(|_| glyph_box));
+                    x += advance;
+                },
+                TextItem::Adjust(adjustment) => {
+                    if *adjustment < -SPACE_ADJUSTMENT && !text.is_empty() && !text.ends_with(' ') {
+                        text.push(' ');
+                        char_boxes.push(text_box(&m, x, x, bottom, top));
+                    }
+                    x -= adjustment / 1000.0 * size * state.scale;
+                }
+            }
+        }
+        *tm = concat(&translate(x, 0.0), &start);
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let bbox = text_box(&m, 0.0, x, bottom, top);
+        self.runs.push(TextRun { text, bbox, font: font.name.clone(), font_size: size * m[2].hypot(m[3]), char_boxes });
+    }
+
+    fn draw_form(&mut self, resources: Option<&'a Dictionary>, name: &str, ctm: &Matrix, depth: usize) -> Result<(), Error> {
+        let src = self.src;
+        let form = resources
+            .and_then(|r| src.resolve_entry(r, "XObject"))
+            .and_then(|x| x.as_dict())
+            .and_then(|x| src.resolve_entry(x, name))
+            .and_then(|x| x.as_stream());
+        let Some(form) = form.filter(|f| f.dict.get_name("Subtype") == Some("Form")) else {
+            return Ok(());
+        };
+        if depth >= MAX_FORM_DEPTH {
+            return Ok(());
+        }
+        let matrix = src.resolve_entry(&form.dict, "Matrix")
+            .and_then(|m| m.as_array())
+            .map_or(IDENTITY, |m| to_matrix(m));
+        let form_resources = src.resolve_entry(&form.dict, "Resources").and_then(|r| r.as_dict()).or(resources);
+        self.run(&form.decoded_data()?, form_resources, concat(&matrix, ctm), depth + 1)
+    }
+}
+
+/// Groups the runs into lines from the top and sorts each line from the left.
+fn lines(runs: &[TextRun]) -> Vec<Line> {
+    let mut runs: Vec<&TextRun> = runs.iter().collect();
+    runs.sort_by(|a, b| b.bbox.ury.total_cmp(&a.bbox.ury));
+    let mut lines: Vec<Vec<&TextRun>> = Vec::new();
+    for run in runs {
+        match lines.iter_mut().find(|line| same_line(&line[0].bbox, &run.bbox)) {
+            Some(line) => line.push(run),
+            None => lines.push(vec![run]),
+        }
+    }
+
+    lines.into_iter().map(|mut line| {
+        line.sort_by(|a, b| a.bbox.llx.total_cmp(&b.bbox.llx));
+        let mut text = String::new();
+        let mut boxes = Vec::new();
+        for (i, run) in line.iter().enumerate() {
+            if i > 0 {
+                let gap = run.bbox.llx - line[i - 1].bbox.urx;
+                if gap > run.font_size * WORD_GAP && !text.ends_with(' ') && !run.text.starts_with(' ') {
+                    text.push(' ');
+                    boxes.push(Rectangle::new(run.bbox.llx, run.bbox.lly, run.bbox.llx, run.bbox.ury));
+                }
+            }
+            text.push_str(&run.text);
+            boxes.extend_from_slice(&run.char_boxes);
+        }
+        (text, boxes)
+    }).collect()
+}
+
+pub(crate) fn to_matrix(operands: &[Object]) -> Matrix {
+    let mut m = IDENTITY;
+    if operands.len() >= 6 {
+        for (v, o) in m.iter_mut().zip(operands) {
+            *v = o.as_f64().unwrap_or(0.0);
+        }
+    }
+    m
+}
+
+pub(crate) fn translate(x: f64, y: f64) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, x, y]
+}
+
+/// Returns `m` × `n`, i.e. `m` applied first.
+pub(crate) fn concat(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+/// Returns the box from `x0` to `x1` and from `bottom` to `top` in text space
+/// mapped by `m`.
+pub(crate) fn text_box(m: &Matrix, x0: f64, x1: f64, bottom: f64, top: f64) -> Rectangle {
+    let points = [(x0, bottom), (x1, bottom), (x0, top), (x1, top)].map(|(x, y)| transform(m, x, y));
+    Rectangle::bounding(&points).unwrap_or(Rectangle::new(0.0, 0.0, 0.0, 0.0))
+}
+
+pub(crate) fn transform(m: &Matrix, x: f64, y: f64) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+fn same_line(a: &Rectangle, b: &Rectangle) -> bool {
+    let overlap = a.ury.min(b.ury) - a.lly.max(b.lly);
+    overlap > a.height().min(b.height()) / 2.0
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::doc::Doc;
+    use crate::pdf::font::Font;
+    use crate::pdf::page::Page;
+
+    fn source() -> LoadedDoc {
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        let c = page.contents();
+        c.begin_text();
+        c.set_font("F0", 10.0);
+        c.set_text_matrix([1.0, 0.0, 0.0, 1.0, 100.0, 700.0]);
+        c.show_text("Hello");
+        c.set_char_spacing(1.0);
+        c.set_horizontal_scaling(50.0);
+        c.move_text(0.0, -20.0);
+        c.show_text_adjusted(&[TextItem::from("A"), TextItem::Adjust(-500.0), TextItem::from("B")]);
+        c.end_text();
+        c.save_state();
+        c.concat_matrix([2.0, 0.0, 0.0, 2.0, 0.0, 0.0]);
+        c.begin_text();
+        c.set_font("F0", 10.0);
+        c.move_text(50.0, 100.0);
+        c.show_text("Big");
+        c.end_text();
+        c.restore_state();
+        c.begin_text();
+        c.set_font("F0", 10.0);
+        c.move_text(200.0, 700.0);
+        c.show_text("World");
+        c.end_text();
+        doc.push_page(page);
+        LoadedDoc::load(doc.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn runs() {
+        let src = source();
+        let runs = extract_runs(&src, 0).unwrap();
+        let texts: Vec<&str> = runs.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, ["Hello", "A B", "Big", "World"]);
+
+        assert_eq!(runs[0].bbox, Rectangle::new(100.0, 697.5, 125.0, 707.5));
+        assert_eq!(runs[0].font, "Helvetica");
+        // (5 + 1) * 0.5 + 2.5 + (5 + 1) * 0.5
+        assert_eq!(runs[1].bbox, Rectangle::new(100.0, 677.5, 108.5, 687.5));
+        // The text state is kept after ET: (5 + 1) * 0.5 * 3 * 2
+        assert_eq!(runs[2].bbox, Rectangle::new(100.0, 195.0, 118.0, 215.0));
+        assert_eq!(runs[2].font_size, 20.0);
+        assert!(extract_runs(&src, 1).is_err());
+    }
+
+    #[test]
+    fn find() {
+        let src = source();
+        // Tc 1 and Tz 50 still apply: glyphs 2.5 wide, 3 apart
+        assert_eq!(find_text(&src, 0, "World").unwrap(), [Rectangle::new(200.0, 697.5, 214.5, 707.5)]);
+        // Across runs on a line
+        assert_eq!(find_text(&src, 0, "o W").unwrap(), [Rectangle::new(120.0, 697.5, 202.5, 707.5)]);
+        assert_eq!(find_text(&src, 0, "l").unwrap().len(), 3);
+        assert!(find_text(&src, 0, "Bye").unwrap().is_empty());
+    }
+
+    #[test]
+    fn text() {
+        let src = source();
+        assert_eq!(extract_text(&src, 0).unwrap(), "Hello World\nA B\nBig");
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::indent;
+
+/// Formats dictionary entries in the layout used throughout the writer.
+///
+/// ```text
+/// << /Key1 value
+///    /Key2
+///   <<
+///     ... multi-line value
+///   >>
+/// >>
+/// ```
+///
+/// Keys are given without the leading slash. Single-line values are placed
+/// next to their key, multi-line values are indented on the following lines.
+pub fn dict_string(entries: &[(&str, String)]) -> String {
+    if entries.is_empty() {
+        return String::from("<< >>");
+    }
+
+    let mut s = String::new();
+    for (i, (key, value)) in entries.iter().enumerate() {
+        s.push_str(if i == 0 { "<< /" } else { "   /" });
+        s.push_str(key);
+        if value.contains('\n') {
+            s.push('\n');
+            s.push_str(&indent(value, 1));
+        } else {
+            s.push(' ');
+            s.push_str(value);
+        }
+        s.push('\n');
+    }
+    s.push_str(">>");
+
+    s
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dict_string_layout() {
+        let entries = [
+            ("Type", String::from("/Annot")),
+            ("BS", String::from("<< /W 1\n>>")),
+        ];
+
+        let ok = concat!(
+            "<< /Type /Annot\n",
+            "   /BS\n",
+            "  << /W 1\n",
+            "  >>\n",
+            ">>",
+        );
+
+        assert_eq!(dict_string(&entries), ok);
+        assert_eq!(dict_string(&[]), "<< >>");
+    }
+}
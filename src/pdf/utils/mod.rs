@@ -5,8 +5,14 @@
 // http://www.boost.org/LICENSE_1_0.txt)
 
 
+mod dict;
+pub use dict::dict_string;
+
 mod indent;
 pub use indent::indent;
 
 mod to_pdf_string;
 pub use to_pdf_string::ToPdfString;
+
+mod text_string;
+pub use text_string::to_text_string;
@@ -0,0 +1,60 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+/// Escapes `\`, `(` and `)` so the string can be put in a literal string.
+pub fn escape_literal(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// PDF32000-1:2008 7.9.2.2
+///
+/// Converts to a text string including the delimiters.
+/// ASCII text is written as a literal string, anything else as a UTF-16BE
+/// hexadecimal string with the byte order marker.
+pub fn to_text_string(s: &str) -> String {
+    if s.chars().all(|c| c.is_ascii() && (!c.is_ascii_control() || c == '\n' || c == '\r')) {
+        return format!("({})", escape_literal(s));
+    }
+
+    let mut hex = String::from("<FEFF");
+    for u in s.encode_utf16() {
+        hex.push_str(&format!("{:04X}", u));
+    }
+    hex.push('>');
+    hex
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal() {
+        assert_eq!(to_text_string("a(b)\\c"), "(a\\(b\\)\\\\c)");
+    }
+
+    #[test]
+    fn utf16() {
+        assert_eq!(to_text_string("é"), "<FEFF00E9>");
+    }
+}
@@ -20,7 +20,7 @@ impl<T: Display> ToPdfString<T> for T {
 
 impl<T: Display> ToPdfString<T> for (T, T) {
     fn to_pdf_string(&self) -> String {
-        format!("{:.1} {:.1}", self.0, self.1)
+        format!("{} {}", self.0, self.1)
     }
 }
 
@@ -52,11 +52,11 @@ fn ite_to_pdf_string<T: IntoIterator>(arr: T, to_string: fn(v: &T::Item) -> Stri
         let mut is_first = true;
         for v in arr {
             if !is_first {
-                s.push_str(" ");
+                s.push(' ');
             }
             s.push_str(&to_string(&v));
             is_first = false;
         }
-        s.push_str("]");
+        s.push(']');
         s
 }
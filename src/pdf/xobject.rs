@@ -0,0 +1,776 @@
+// Copyright (C) 2025 kkAyataka
+//
+// Distributed under the Boost Software License, Version 1.0.
+// (See accompanying file LICENSE_1_0.txt or copy at
+// http://www.boost.org/LICENSE_1_0.txt)
+
+
+use super::base::*;
+use super::colour::space::{ColorSpace, DeviceGray, DeviceRGB, Indexed};
+use super::contents::Contents;
+use super::error::Error;
+use super::filter;
+use super::import::ImportedObjects;
+use super::loaded_doc::LoadedDoc;
+use super::object::{Dictionary, Object, Stream};
+use super::page::Rotation;
+use super::resources::Resources;
+use super::utils::{dict_string, ToPdfString};
+
+/// An XObject in resources, drawn with `Contents::draw_xobject`.
+#[derive(Clone)]
+pub enum XObject {
+    Form(Form),
+    Page(PageForm),
+    Image(Image),
+}
+
+impl From<Form> for XObject {
+    fn from(form: Form) -> Self {
+        Self::Form(form)
+    }
+}
+
+impl From<PageForm> for XObject {
+    fn from(form: PageForm) -> Self {
+        Self::Page(form)
+    }
+}
+
+impl From<Image> for XObject {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
+
+impl PdfObject for XObject {
+    fn id(&self) -> &Id {
+        match self {
+            Self::Form(f) => f.id(),
+            Self::Page(f) => f.id(),
+            Self::Image(i) => i.id(),
+        }
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        match self {
+            Self::Form(f) => f.assign_ids(id_factory),
+            Self::Page(f) => f.assign_ids(id_factory),
+            Self::Image(i) => i.assign_ids(id_factory),
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        match self {
+            Self::Form(f) => f.get_objects(),
+            Self::Page(f) => f.get_objects(),
+            Self::Image(i) => i.get_objects(),
+        }
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        match self {
+            Self::Form(f) => f.to_bytes(indent_depth),
+            Self::Page(f) => f.to_bytes(indent_depth),
+            Self::Image(i) => i.to_bytes(indent_depth),
+        }
+    }
+}
+
+/// PDF32000-1:2008 8.10
+///
+/// ```text
+/// 12 0 obj
+/// << /Type /XObject
+///    /Subtype /Form
+///    /BBox [0 0 100 50]
+///    /Resources 13 0 R
+///    /Length 35
+/// >>
+/// stream
+/// ...
+/// endstream
+/// endobj
+/// ```
+#[derive(Clone)]
+pub struct Form {
+    id: Id,
+    bbox: Rectangle,
+    matrix: Option<[f64; 6]>,
+    resources: Resources,
+    contents: Contents,
+}
+
+impl Form {
+    pub fn new(bbox: Rectangle) -> Self {
+        Self {
+            id: Id::new_0(),
+            bbox,
+            matrix: None,
+            resources: Resources::new(),
+            contents: Contents::new(),
+        }
+    }
+
+    pub fn bbox(&self) -> &Rectangle {
+        &self.bbox
+    }
+
+    pub fn set_matrix(&mut self, matrix: [f64; 6]) {
+        self.matrix = Some(matrix);
+    }
+
+    pub fn resources(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    pub fn contents(&mut self) -> &mut Contents {
+        &mut self.contents
+    }
+
+    pub fn to_string(&self, _indent_depth: usize) -> String {
+        let stream = self.contents.get_stream_string(0);
+
+        let mut entries = vec![
+            ("Type", String::from("/XObject")),
+            ("Subtype", String::from("/Form")),
+            ("BBox", self.bbox.to_pdf_string()),
+        ];
+        if let Some(m) = &self.matrix {
+            entries.push(("Matrix", m.to_pdf_string()));
+        }
+        entries.push(("Resources", self.resources.id.to_ref_string()));
+        entries.push(("Length", stream.len().to_string()));
+
+        format!(concat!(
+            "{} obj\n",
+            "{}\n",
+            "stream\n",
+            "{}\n",
+            "endstream\n",
+            "endobj"),
+            self.id,
+            dict_string(&entries),
+            stream,
+        )
+    }
+}
+
+impl PdfObject for Form {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        self.resources.assign_ids(id_factory);
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        list.append(&mut self.resources.get_objects());
+        list
+    }
+
+    fn to_bytes(&self, indent_depth: usize) -> Vec<u8> {
+        self.to_string(indent_depth).into_bytes()
+    }
+}
+
+/// PDF32000-1:2008 8.10
+///
+/// A page of a loaded document as a Form XObject, e.g. for underlays and
+/// n-up imposition. The content streams are concatenated and the resources
+/// are copied with every object they refer to. The BBox is the CropBox, and
+/// the matrix turns the page upright if it has `/Rotate`.
+///
+/// ```no_run
+/// use prspdf::{LoadedDoc, MediaBox, Page, Rectangle};
+/// use prspdf::xobject::PageForm;
+///
+/// let src = LoadedDoc::open("letterhead.pdf").unwrap();
+/// let form = PageForm::new(&src, 0).unwrap();
+/// let matrix = form.fit_matrix(&Rectangle::new(0.0, 0.0, 595.0, 842.0));
+///
+/// let mut page = Page::new(MediaBox::A4);
+/// page.resources().add_xobject("Letterhead", form);
+/// page.contents().save_state();
+/// page.contents().concat_matrix(matrix);
+/// page.contents().draw_xobject("Letterhead");
+/// page.contents().restore_state();
+/// ```
+#[derive(Clone)]
+pub struct PageForm {
+    id: Id,
+    bbox: Rectangle,
+    matrix: [f64; 6],
+    /// `/Resources` and `/Group` referring to the copied objects by local ids.
+    entries: Dictionary,
+    /// `entries` with the written ids.
+    written: Dictionary,
+    data: Vec<u8>,
+    objects: ImportedObjects,
+}
+
+impl PageForm {
+    /// Creates the form from the page at the index.
+    pub fn new(src: &LoadedDoc, index: usize) -> Result<Self, Error> {
+        let page_id = *src.page_ids().get(index).ok_or_else(|| Error::NotFound(format!("page {index}")))?;
+        let bbox = src.page_attribute(page_id, "CropBox")
+            .and_then(|r| r.as_rect())
+            .unwrap_or_else(|| src.page_media_box(page_id));
+        let rotation = src.page_attribute(page_id, "Rotate")
+            .and_then(|r| r.as_i64())
+            .and_then(|r| Rotation::from_degrees(r as i32))
+            .unwrap_or_default();
+
+        let mut entries = Dictionary::new();
+        for key in ["Resources", "Group"] {
+            if let Some(value) = src.page_attribute(page_id, key) {
+                entries.set(key, value.clone());
+            }
+        }
+        let mut objects = ImportedObjects::default();
+        let entries = match objects.import_object(src, &Object::Dictionary(entries)) {
+            Object::Dictionary(d) => d,
+            _ => Dictionary::new(),
+        };
+
+        Ok(Self {
+            id: Id::new_0(),
+            bbox,
+            matrix: rotation_matrix(rotation),
+            entries,
+            written: Dictionary::new(),
+            data: src.page_content(page_id)?,
+            objects,
+        })
+    }
+
+    pub fn bbox(&self) -> &Rectangle {
+        &self.bbox
+    }
+
+    /// Replaces the matrix, which initially turns the page upright.
+    pub fn set_matrix(&mut self, matrix: [f64; 6]) {
+        self.matrix = matrix;
+    }
+
+    /// Returns the BBox transformed with the matrix, the area the form covers
+    /// when drawn without any other transformation.
+    pub fn placed_bbox(&self) -> Rectangle {
+        let [a, b, c, d, e, f] = self.matrix;
+        let r = &self.bbox;
+        let points = [(r.llx, r.lly), (r.urx, r.lly), (r.llx, r.ury), (r.urx, r.ury)]
+            .map(|(x, y)| (a * x + c * y + e, b * x + d * y + f));
+        let xs = points.map(|p| p.0);
+        let ys = points.map(|p| p.1);
+        Rectangle::new(
+            xs.into_iter().fold(f64::INFINITY, f64::min),
+            ys.into_iter().fold(f64::INFINITY, f64::min),
+            xs.into_iter().fold(f64::NEG_INFINITY, f64::max),
+            ys.into_iter().fold(f64::NEG_INFINITY, f64::max))
+    }
+
+    /// Returns the matrix for `Contents::concat_matrix` that scales the form
+    /// to fit in the rectangle keeping the aspect ratio, centred.
+    pub fn fit_matrix(&self, rect: &Rectangle) -> [f64; 6] {
+        let placed = self.placed_bbox();
+        let scale = (rect.width() / placed.width()).min(rect.height() / placed.height());
+        let x = rect.llx + (rect.width() - placed.width() * scale) / 2.0 - placed.llx * scale;
+        let y = rect.lly + (rect.height() - placed.height() * scale) / 2.0 - placed.lly * scale;
+        [scale, 0.0, 0.0, scale, x, y]
+    }
+
+    fn to_object(&self) -> Object {
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::name("XObject"));
+        dict.set("Subtype", Object::name("Form"));
+        dict.set("BBox", Object::rect(&self.bbox));
+        if self.matrix != rotation_matrix(Rotation::Deg0) {
+            dict.set("Matrix", Object::Array(self.matrix.iter().map(|v| Object::Real(*v)).collect()));
+        }
+        for (key, value) in self.written.iter() {
+            dict.set(key.as_str(), value.clone());
+        }
+        Object::Stream(Stream::new(dict, self.data.clone()))
+    }
+}
+
+impl PdfObject for PageForm {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.id = id_factory.next_id();
+        let mut entries = Object::Dictionary(self.entries.clone());
+        self.objects.assign_ids(id_factory, &entries.references());
+        self.objects.build(&[]);
+        entries.map_references(&mut |id| self.objects.id(id));
+        if let Object::Dictionary(d) = entries {
+            self.written = d;
+        }
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list: Vec<&dyn PdfObject> = vec![self];
+        list.append(&mut self.objects.get_objects());
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut bytes = format!("{} obj\n", self.id).into_bytes();
+        self.to_object().write(&mut bytes);
+        bytes.extend_from_slice(b"\nendobj");
+        bytes
+    }
+}
+
+/// PDF32000-1:2008 8.9.5
+///
+/// The samples are written Flate compressed. PNG images are embedded as they
+/// are with the PNG predictor, palette images in an `Indexed` space without
+/// expanding them to RGB.
+///
+/// ```no_run
+/// use prspdf::{MediaBox, Page};
+/// use prspdf::xobject::Image;
+///
+/// let image = Image::from_png(&std::fs::read("chart.png").unwrap()).unwrap();
+///
+/// let mut page = Page::new(MediaBox::A4);
+/// page.resources().add_xobject("Im0", image);
+/// page.contents().save_state();
+/// page.contents().concat_matrix([200.0, 0.0, 0.0, 100.0, 72.0, 600.0]);
+/// page.contents().draw_xobject("Im0");
+/// page.contents().restore_state();
+/// ```
+#[derive(Clone)]
+pub struct Image {
+    id: Id,
+    width: u32,
+    height: u32,
+    bits_per_component: u8,
+    color_space: ColorSpace,
+    /// The `/DecodeParms` of the PNG predictor
+    predictor_colors: Option<u8>,
+    /// The colour key ranges of `/Mask`
+    color_key: Option<Vec<u16>>,
+    smask: Option<Box<Image>>,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Creates the image of the samples, the rows starting at byte
+    /// boundaries.
+    ///
+    /// Fails if the bits per component is not 1, 2, 4, 8 or 16 (up to 8 for
+    /// `Indexed`), the space is a pattern space or the number of samples does
+    /// not match the size.
+    pub fn new(width: u32, height: u32, bits_per_component: u8, color_space: impl Into<ColorSpace>, samples: Vec<u8>) -> Result<Self, Error> {
+        let color_space = color_space.into();
+        check_bits_per_component(&color_space, bits_per_component)?;
+        let row_len = (width as usize * color_space.components() * bits_per_component as usize).div_ceil(8);
+        if samples.len() != row_len * height as usize {
+            return Err(Error::InvalidValue(format!(
+                "{} bytes of samples for {}x{} image, {} expected", samples.len(), width, height, row_len * height as usize)));
+        }
+
+        Ok(Self {
+            id: Id::new_0(),
+            width,
+            height,
+            bits_per_component,
+            color_space,
+            predictor_colors: None,
+            color_key: None,
+            smask: None,
+            data: filter::flate::encode(&samples),
+        })
+    }
+
+    /// Creates the image of a non-interlaced grayscale, RGB or palette PNG.
+    /// The transparency of a palette becomes the soft mask, the colour of
+    /// `tRNS` in a grayscale or RGB image the colour key mask.
+    pub fn from_png(data: &[u8]) -> Result<Self, Error> {
+        let png = Png::parse(data)?;
+        let (color_space, colors): (ColorSpace, u8) = match png.color_type {
+            0 => (DeviceGray {}.into(), 1),
+            2 => (DeviceRGB {}.into(), 3),
+            3 => {
+                let plte = png.plte.ok_or_else(|| Error::NotFound(String::from("PNG PLTE chunk")))?;
+                if plte.is_empty() || plte.len() % 3 != 0 || plte.len() > 3 * 256 {
+                    return Err(Error::InvalidValue(format!("PNG palette of {} bytes", plte.len())));
+                }
+                let hival = (plte.len() / 3 - 1) as u8;
+                (Indexed::new(DeviceRGB {}, hival, plte.to_vec())?.into(), 1)
+            }
+            4 | 6 => return Err(Error::Unsupported(String::from("PNG with an alpha channel"))),
+            t => return Err(Error::InvalidValue(format!("PNG colour type {t}"))),
+        };
+        check_bits_per_component(&color_space, png.bit_depth)?;
+
+        let mut image = Self {
+            id: Id::new_0(),
+            width: png.width,
+            height: png.height,
+            bits_per_component: png.bit_depth,
+            color_space,
+            predictor_colors: Some(colors),
+            color_key: None,
+            smask: None,
+            data: png.idat,
+        };
+
+        if let Some(trns) = png.trns {
+            if png.color_type == 3 {
+                image.smask = Some(Box::new(image.palette_alpha(trns)?));
+            } else {
+                let key: Vec<u16> = trns.chunks(2).take(colors as usize).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+                image.color_key = Some(key.iter().flat_map(|k| [*k, *k]).collect());
+            }
+        }
+        Ok(image)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn bits_per_component(&self) -> u8 {
+        self.bits_per_component
+    }
+
+    pub fn color_space(&self) -> &ColorSpace {
+        &self.color_space
+    }
+
+    /// Sets the image of the alpha values, a DeviceGray image.
+    pub fn set_smask(&mut self, smask: Image) -> Result<(), Error> {
+        if !matches!(smask.color_space, ColorSpace::DeviceGray) {
+            return Err(Error::InvalidValue(format!("{} soft mask", smask.color_space.family())));
+        }
+        self.smask = Some(Box::new(smask));
+        Ok(())
+    }
+
+    fn decode_parms_string(&self) -> Option<String> {
+        self.predictor_colors.map(|colors| dict_string(&[
+            ("Predictor", String::from("15")),
+            ("Colors", colors.to_string()),
+            ("BitsPerComponent", self.bits_per_component.to_string()),
+            ("Columns", self.width.to_string()),
+        ]))
+    }
+
+    /// Returns the 8 bit soft mask of the alpha values of the palette entries.
+    fn palette_alpha(&self, trns: &[u8]) -> Result<Image, Error> {
+        let mut dict = Dictionary::new();
+        dict.set("Filter", Object::name("FlateDecode"));
+        let mut parms = Dictionary::new();
+        parms.set("Predictor", 15);
+        parms.set("Colors", 1);
+        parms.set("BitsPerComponent", self.bits_per_component as i64);
+        parms.set("Columns", self.width as i64);
+        dict.set("DecodeParms", parms);
+        let indexes = filter::decode_stream(&dict, &self.data)?;
+
+        let bpc = self.bits_per_component as usize;
+        let row_len = (self.width as usize * bpc).div_ceil(8);
+        let mut alpha = Vec::with_capacity(self.width as usize * self.height as usize);
+        for row in indexes.chunks(row_len).take(self.height as usize) {
+            for x in 0..self.width as usize {
+                let bit = x * bpc;
+                let index = (row.get(bit / 8).copied().unwrap_or(0) >> (8 - bpc - bit % 8)) & ((1u16 << bpc) - 1) as u8;
+                alpha.push(trns.get(index as usize).copied().unwrap_or(255));
+            }
+        }
+        alpha.resize(self.width as usize * self.height as usize, 255);
+        Image::new(self.width, self.height, 8, DeviceGray {}, alpha)
+    }
+}
+
+impl PdfObject for Image {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn assign_ids(&mut self, id_factory: &mut IdFactory) {
+        self.color_space.assign_ids(id_factory);
+        if let Some(smask) = &mut self.smask {
+            smask.assign_ids(id_factory);
+        }
+        self.id = id_factory.next_id();
+    }
+
+    fn get_objects(&self) -> Vec<&dyn PdfObject> {
+        let mut list = self.color_space.get_objects();
+        if let Some(smask) = &self.smask {
+            list.append(&mut smask.get_objects());
+        }
+        list.push(self);
+        list
+    }
+
+    fn to_bytes(&self, _indent_depth: usize) -> Vec<u8> {
+        let mut entries = vec![
+            ("Type", String::from("/XObject")),
+            ("Subtype", String::from("/Image")),
+            ("Width", self.width.to_string()),
+            ("Height", self.height.to_string()),
+            ("ColorSpace", self.color_space.to_pdf_string(0)),
+            ("BitsPerComponent", self.bits_per_component.to_string()),
+        ];
+        if let Some(key) = &self.color_key {
+            entries.push(("Mask", key.iter().map(|k| *k as i64).collect::<Vec<i64>>().to_pdf_string()));
+        }
+        if let Some(smask) = &self.smask {
+            entries.push(("SMask", smask.id.to_ref_string()));
+        }
+        entries.push(("Filter", String::from("/FlateDecode")));
+        if let Some(parms) = self.decode_parms_string() {
+            entries.push(("DecodeParms", parms));
+        }
+        entries.push(("Length", self.data.len().to_string()));
+
+        let mut bytes = format!("{} obj\n{}\nstream\n", self.id, dict_string(&entries)).into_bytes();
+        bytes.extend_from_slice(&self.data);
+        bytes.extend_from_slice(b"\nendstream\nendobj");
+        bytes
+    }
+}
+
+//------------------------------------------------------------------------------
+// details
+//------------------------------------------------------------------------------
+
+fn check_bits_per_component(color_space: &ColorSpace, bits_per_component: u8) -> Result<(), Error> {
+    let max = if matches!(color_space, ColorSpace::Indexed(_)) { 8 } else { 16 };
+    if !matches!(bits_per_component, 1 | 2 | 4 | 8 | 16) || bits_per_component > max {
+        return Err(Error::InvalidValue(format!(
+            "{} bits per component in {}", bits_per_component, color_space.family())));
+    }
+    if color_space.components() == 0 {
+        return Err(Error::InvalidValue(format!("{} image", color_space.family())));
+    }
+    Ok(())
+}
+
+/// The chunks of a PNG file needed to embed it (PNG 11.2).
+struct Png<'a> {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    plte: Option<&'a [u8]>,
+    trns: Option<&'a [u8]>,
+    /// The concatenated zlib stream of the IDAT chunks
+    idat: Vec<u8>,
+}
+
+impl<'a> Png<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        if !data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return Err(Error::InvalidValue(String::from("not a PNG file")));
+        }
+
+        let mut png = Png { width: 0, height: 0, bit_depth: 0, color_type: 0, plte: None, trns: None, idat: Vec::new() };
+        let mut header = None;
+        let mut pos = 8;
+        while pos + 8 <= data.len() {
+            let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+            let kind = &data[pos + 4..pos + 8];
+            let body = data.get(pos + 8..pos + 8 + len)
+                .ok_or_else(|| Error::InvalidValue(String::from("truncated PNG chunk")))?;
+            match kind {
+                b"IHDR" => header = Some(body),
+                b"PLTE" => png.plte = Some(body),
+                b"tRNS" => png.trns = Some(body),
+                b"IDAT" => png.idat.extend_from_slice(body),
+                b"IEND" => break,
+                _ => (),
+            }
+            pos += len + 12;
+        }
+
+        let header = header.filter(|h| h.len() == 13)
+            .ok_or_else(|| Error::NotFound(String::from("PNG IHDR chunk")))?;
+        if header[12] != 0 {
+            return Err(Error::Unsupported(String::from("interlaced PNG")));
+        }
+        png.width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        png.height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        png.bit_depth = header[8];
+        png.color_type = header[9];
+        if png.idat.is_empty() {
+            return Err(Error::NotFound(String::from("PNG IDAT chunk")));
+        }
+        Ok(png)
+    }
+}
+
+/// Returns the matrix that turns a page with `/Rotate` upright.
+fn rotation_matrix(rotation: Rotation) -> [f64; 6] {
+    match rotation {
+        Rotation::Deg0 => [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        Rotation::Deg90 => [0.0, -1.0, 1.0, 0.0, 0.0, 0.0],
+        Rotation::Deg180 => [-1.0, 0.0, 0.0, -1.0, 0.0, 0.0],
+        Rotation::Deg270 => [0.0, 1.0, -1.0, 0.0, 0.0, 0.0],
+    }
+}
+
+//------------------------------------------------------------------------------
+// tests
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string() {
+        let mut f = Form::new(Rectangle::new(0.0, 0.0, 10.0, 20.0));
+        f.contents().rect(0.0, 0.0, 10.0, 20.0);
+        f.contents().fill();
+
+        let ok = concat!(
+            "0 0 obj\n",
+            "<< /Type /XObject\n",
+            "   /Subtype /Form\n",
+            "   /BBox [0 0 10 20]\n",
+            "   /Resources 0 0 R\n",
+            "   /Length 14\n",
+            ">>\n",
+            "stream\n",
+            "0 0 10 20 re\n",
+            "f\n",
+            "endstream\n",
+            "endobj",
+        );
+
+        assert_eq!(f.to_string(0), ok);
+    }
+
+    #[test]
+    fn page_form() {
+        use crate::pdf::doc::Doc;
+        use crate::pdf::font::Font;
+        use crate::pdf::page::Page;
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.set_crop_box(Rectangle::new(0.0, 0.0, 400.0, 200.0)).unwrap();
+        page.set_rotation(Rotation::Deg90);
+        page.resources().add_font("F0", Font::new("Helvetica"));
+        page.contents().fill_text("F0", 12, Pos::new(72, 100), "Artwork");
+        doc.push_page(page);
+        let src = LoadedDoc::load(doc.to_bytes()).unwrap();
+
+        let form = PageForm::new(&src, 0).unwrap();
+        assert!(PageForm::new(&src, 1).is_err());
+        assert_eq!(form.bbox(), &Rectangle::new(0.0, 0.0, 400.0, 200.0));
+        assert_eq!(form.placed_bbox(), Rectangle::new(0.0, -400.0, 200.0, 0.0));
+        assert_eq!(form.fit_matrix(&Rectangle::new(0.0, 0.0, 100.0, 100.0)), [0.25, 0.0, 0.0, 0.25, 25.0, 100.0]);
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_xobject("P0", form);
+        page.contents().draw_xobject("P0");
+        doc.push_page(page);
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+
+        let page_id = loaded.page_ids()[0];
+        let resources = loaded.page_attribute(page_id, "Resources").and_then(|r| r.as_dict()).unwrap();
+        let xobjects = loaded.resolve_entry(resources, "XObject").and_then(|x| x.as_dict()).unwrap();
+        let form = loaded.resolve_entry(xobjects, "P0").and_then(|f| f.as_stream()).unwrap();
+        assert_eq!(form.dict.get("Matrix").unwrap().to_bytes(), b"[0 -1 1 0 0 0]");
+        assert_eq!(form.decoded_data().unwrap(), src.page_content(src.page_ids()[0]).unwrap());
+        let form_resources = loaded.resolve_entry(&form.dict, "Resources").and_then(|r| r.as_dict()).unwrap();
+        assert!(form_resources.contains_key("Font"));
+    }
+
+    /// Returns a PNG file of the rows of palette indexes.
+    fn png(width: u32, bit_depth: u8, palette: &[u8], trns: Option<&[u8]>, rows: &[&[u8]]) -> Vec<u8> {
+        fn chunk(png: &mut Vec<u8>, kind: &[u8], body: &[u8]) {
+            png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            png.extend_from_slice(kind);
+            png.extend_from_slice(body);
+            png.extend_from_slice(&[0; 4]); // CRC, not checked
+        }
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend_from_slice(&(rows.len() as u32).to_be_bytes());
+        header.extend_from_slice(&[bit_depth, 3, 0, 0, 0]);
+        chunk(&mut png, b"IHDR", &header);
+        chunk(&mut png, b"PLTE", palette);
+        if let Some(trns) = trns {
+            chunk(&mut png, b"tRNS", trns);
+        }
+        // Filter type Sub for the first row, None for the others
+        let mut filtered = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            filtered.push(if i == 0 { 1 } else { 0 });
+            for (j, b) in row.iter().enumerate() {
+                let left = if i == 0 && j > 0 { row[j - 1] } else { 0 };
+                filtered.push(b.wrapping_sub(left));
+            }
+        }
+        chunk(&mut png, b"IDAT", &filter::flate::encode(&filtered));
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn image() {
+        assert!(Image::new(2, 2, 8, DeviceRGB {}, vec![0; 12]).is_ok());
+        assert!(Image::new(2, 2, 8, DeviceRGB {}, vec![0; 11]).is_err());
+        assert!(Image::new(3, 1, 1, DeviceGray {}, vec![0]).is_ok());
+        assert!(Image::new(1, 1, 3, DeviceGray {}, vec![0]).is_err());
+        assert!(Image::new(1, 1, 8, ColorSpace::Pattern(None), Vec::new()).is_err());
+        let indexed = Indexed::new(DeviceRGB {}, 0, vec![0; 3]).unwrap();
+        assert!(Image::new(1, 1, 16, indexed, vec![0; 2]).is_err());
+
+        let mut image = Image::new(1, 1, 8, DeviceRGB {}, vec![0; 3]).unwrap();
+        assert!(image.set_smask(Image::new(1, 1, 8, DeviceRGB {}, vec![0; 3]).unwrap()).is_err());
+        assert!(image.set_smask(Image::new(1, 1, 8, DeviceGray {}, vec![0]).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn palette_png() {
+        use crate::pdf::doc::Doc;
+        use crate::pdf::page::Page;
+
+        let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255];
+        let data = png(5, 2, &palette, Some(&[255, 128, 0]), &[&[0b00011011, 0b00000000], &[0b11100100, 0b01000000]]);
+        let image = Image::from_png(&data).unwrap();
+        assert_eq!((image.width(), image.height(), image.bits_per_component()), (5, 2, 2));
+        assert_eq!(image.color_space().family(), "Indexed");
+        assert!(Image::from_png(&data[..20]).is_err());
+        assert!(Image::from_png(b"GIF89a").is_err());
+
+        let mut doc = Doc::new(Version::V1_7);
+        let mut page = Page::new(MediaBox::A4);
+        page.resources().add_xobject("Im0", image);
+        page.contents().draw_xobject("Im0");
+        doc.push_page(page);
+        let loaded = LoadedDoc::load(doc.to_bytes()).unwrap();
+
+        let page_id = loaded.page_ids()[0];
+        let resources = loaded.page_attribute(page_id, "Resources").and_then(|r| r.as_dict()).unwrap();
+        let xobjects = loaded.resolve_entry(resources, "XObject").and_then(|x| x.as_dict()).unwrap();
+        let image = loaded.resolve_entry(xobjects, "Im0").and_then(|i| i.as_stream()).unwrap();
+        // The indexes as they are, not expanded to RGB
+        assert_eq!(image.decoded_data().unwrap(), [0b00011011, 0b00000000, 0b11100100, 0b01000000]);
+        let space = image.dict.get("ColorSpace").unwrap().to_bytes();
+        assert_eq!(space, b"[/Indexed /DeviceRGB 3 <FF000000FF000000FFFFFFFF>]");
+
+        let smask = loaded.resolve_entry(&image.dict, "SMask").and_then(|s| s.as_stream()).unwrap();
+        assert_eq!(smask.decoded_data().unwrap(), [255, 128, 0, 255, 255, 255, 0, 128, 255, 128]);
+    }
+}